@@ -0,0 +1,75 @@
+//! Browses the records of a word page by page.
+//!
+//! The records are kept in memory, so no database is required.
+
+use ipdis_api::common::{memory::IpdisMemory, GetWords, GetWordsParent, Ipdis};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::{
+        anyhow::Result,
+        value::{hash::Hash, text::Text},
+    },
+    path::Path,
+    tokio,
+    word::{Word, WordHash, WordKey},
+};
+
+const NAMESPACE: &str = "ipdis-example-browse";
+const PAGE_SIZE: u32 = 4;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let client = IpdisMemory::new(IpiisClient::genesis(None).await?);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put a word referring to some documents
+    let key = WordKey {
+        namespace: NAMESPACE.to_string(),
+        text: Text::with_en_us("hello world"),
+    };
+    let parent = Hash::with_str("");
+    for index in 0..10 {
+        let document = format!("document #{index}");
+        let word: WordHash = Word {
+            key: key.clone(),
+            kind: "ipdis-example".to_string(),
+            relpath: false,
+            path: Path {
+                value: Hash::with_str(&document),
+                len: document.len().try_into()?,
+            },
+        }
+        .into();
+        client
+            .put_word_unchecked(&parent, &ipiis.sign(account, word)?)
+            .await?;
+    }
+
+    // follow the cursors until the last page
+    let word = key.into();
+    let mut start_index = 0;
+    loop {
+        let page = client
+            .get_word_page_unchecked(
+                None,
+                &GetWords {
+                    word,
+                    parent: GetWordsParent::None,
+                    start_index,
+                    end_index: start_index + PAGE_SIZE,
+                    with_total: false,
+                },
+            )
+            .await?;
+        for record in &page.items {
+            println!("{start_index}: {}", record.data.data.data.path.value);
+            start_index += 1;
+        }
+
+        match page.next_cursor {
+            Some(next_cursor) => start_index = next_cursor,
+            None => break Ok(()),
+        }
+    }
+}
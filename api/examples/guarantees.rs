@@ -0,0 +1,65 @@
+//! The lifecycle of a guarantee: registered, renewed before it expires, and revoked.
+//!
+//! Run with `DATABASE_URL` pointing to a migrated database,
+//! as the in-memory backend does not expire the guarantees.
+
+use std::time::Duration;
+
+use ipdis_api::{
+    client::IpdisClient,
+    common::Ipdis,
+    config::{GuaranteeExpiry, IpdisConfig},
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::anyhow::{Context, Result},
+    env::Infer,
+    tokio,
+};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let client = IpdisClient::try_infer()
+        .await
+        .context("the example requires a migrated PostgreSQL through DATABASE_URL")?;
+    let config = IpdisConfig {
+        guarantee_expiry: Some(GuaranteeExpiry {
+            notice: 7 * DAY,
+            renewal: 30 * DAY,
+        }),
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // add a guarantee, signed by its guarantor
+    let guarantee = IpiisClient::genesis(None).await?;
+    let guarantee = guarantee.account_me().account_ref();
+    client
+        .add_guarantee_unchecked(&ipiis.sign(account, guarantee)?)
+        .await?;
+    client.ensure_registered(&guarantee, &account).await?;
+    println!("added the guarantee: {guarantee}");
+
+    // renew it automatically whenever it is about to expire
+    client
+        .set_guarantee_auto_renew_unchecked(&guarantee, true)
+        .await?;
+    let sweep = client.sweep_guarantee_expiry_unchecked().await?;
+    println!(
+        "swept the guarantees: {} notified, {} renewed",
+        sweep.notified, sweep.renewed,
+    );
+
+    // revoke it
+    client.delete_guarantee_unchecked(&guarantee).await?;
+    assert!(client
+        .ensure_registered(&guarantee, &account)
+        .await
+        .is_err());
+    println!("revoked the guarantee: {guarantee}");
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Indexes the words of a document in a batch, and counts them back.
+//!
+//! The records are kept in memory, so no database is required.
+
+use futures::future::try_join_all;
+use ipdis_api::common::{memory::IpdisMemory, tokenize::tokenize, Ipdis};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::{
+        anyhow::Result,
+        value::{hash::Hash, text::Text},
+    },
+    path::Path,
+    tokio,
+    word::{Word, WordHash, WordKey},
+};
+
+const NAMESPACE: &str = "ipdis-example-index";
+const DOCUMENT: &str = "the quick brown fox jumps over the lazy dog";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let client = IpdisMemory::new(IpiisClient::genesis(None).await?);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // the stored document, which every word refers to
+    let path = Path {
+        value: Hash::with_str(DOCUMENT),
+        len: DOCUMENT.len().try_into()?,
+    };
+    let parent = Hash::with_str("");
+
+    // sign the words first, then put them concurrently
+    let words = tokenize(&Text::with_en_us(DOCUMENT))
+        .into_iter()
+        .map(|token| {
+            let word: WordHash = Word {
+                key: WordKey {
+                    namespace: NAMESPACE.to_string(),
+                    text: Text::with_en_us(token),
+                },
+                kind: "ipdis-example".to_string(),
+                relpath: false,
+                path,
+            }
+            .into();
+            ipiis.sign(account, word)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    try_join_all(
+        words
+            .iter()
+            .map(|word| client.put_word_unchecked(&parent, word)),
+    )
+    .await?;
+    println!("indexed {} words", words.len());
+
+    // the duplicated words are counted for each occurrence
+    let the = WordKey {
+        namespace: NAMESPACE.to_string(),
+        text: Text::with_en_us("the"),
+    };
+    let count = client
+        .get_word_count_unchecked(None, &the.into(), false)
+        .await?;
+    println!("the word \"the\" has been indexed {count} times");
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Consumes the events of the writes through the outbox, e.g. to feed the subscribers.
+//!
+//! Run with `DATABASE_URL` pointing to a migrated database,
+//! as the in-memory backend does not keep the outbox.
+
+use ipdis_api::{
+    client::IpdisClient,
+    common::Ipdis,
+    config::IpdisConfig,
+    outbox::{OutboxEvent, OutboxPublisher},
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        anyhow::{Context, Result},
+        value::{hash::Hash, text::Text},
+    },
+    env::Infer,
+    path::Path,
+    tokio,
+    word::{Word, WordHash, WordKey},
+};
+
+/// the maximum number of the events delivered at once
+const BATCH_SIZE: u32 = 16;
+
+/// Prints the events, standing in for a message broker or a webhook.
+struct Printer;
+
+#[async_trait]
+impl OutboxPublisher for Printer {
+    async fn publish(&self, event: &OutboxEvent) -> Result<()> {
+        println!("#{} [{}] {}", event.id, event.topic, event.payload);
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let client = IpdisClient::try_infer()
+        .await
+        .context("the example requires a migrated PostgreSQL through DATABASE_URL")?;
+    let config = IpdisConfig {
+        outbox_enabled: true,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // every write pushes an event in the same transaction
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-example-outbox".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-example".to_string(),
+        relpath: false,
+        path: Path {
+            value: Hash::with_str("hello world"),
+            len: 11,
+        },
+    }
+    .into();
+    client
+        .put_word_unchecked(&Hash::with_str(""), &ipiis.sign(account, word)?)
+        .await?;
+
+    // drain the pending events, which are marked delivered once published
+    loop {
+        let delivered = client
+            .dispatch_outbox_unchecked(&Printer, BATCH_SIZE)
+            .await?;
+        if delivered < BATCH_SIZE {
+            break Ok(());
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Feeds arbitrary backups to the decoders of the restore and the import.
+
+#![no_main]
+
+use ipdis_api_postgres::backup::{BackupObject, BackupRecords};
+use ipis::core::chrono::NaiveDateTime;
+use libfuzzer_sys::fuzz_target;
+
+fn restore(records: BackupRecords) {
+    // the accepted records should be convertible into the rows
+    for metadata in records
+        .dyn_paths
+        .into_iter()
+        .map(|record| record.metadata)
+        .chain(records.words.into_iter().map(|record| record.metadata))
+    {
+        let _ = NaiveDateTime::from(metadata.created_date);
+        let _ = metadata.expiration_date.map(NaiveDateTime::from);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(backup) = BackupObject::from_bytes(data) {
+        restore(backup.records);
+    }
+    if let Ok(records) = BackupRecords::from_bytes(data) {
+        restore(records);
+    }
+});
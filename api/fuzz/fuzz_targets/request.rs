@@ -0,0 +1,104 @@
+//! Feeds arbitrary payloads to the decoders of the server's requests.
+//!
+//! The first byte selects the field of the request, and the rest is its archived payload,
+//! which may be malformed or truncated.
+
+#![no_main]
+
+use bytecheck::CheckBytes;
+use ipdis_common::{
+    AcquireWriterLease, DescribeKind, GetAccountChain, GetAccountStats, GetDynPathsByTarget,
+    GetDynPathsMany, GetIdfVector, GetInclusionProof, GetKind, GetKinds, GetMembers, GetOplog,
+    GetPathReferenceCount, GetRecordByNonce, GetServerDiagnostics, GetSimilarDocuments,
+    GetWordCountAllLangs, GetWordCountDelta, GetWordFrequencyHistogram, GetWords, GetWordsCounts,
+    GetWordsCountsBatch, LinkAccountSuccessor, Normalization, QueryWords, SetReadOnly, WordQuery,
+};
+use ipis::{
+    core::{
+        account::{AccountRef, GuaranteeSigned},
+        value::hash::Hash,
+    },
+    path::{DynPath, Path},
+    word::{WordHash, WordKeyHash},
+};
+use libfuzzer_sys::fuzz_target;
+use rkyv::{
+    validation::validators::DefaultValidator, AlignedVec, Archive, Deserialize, Infallible,
+};
+
+/// Decodes the payload as the server does, i.e. validating the archive before reading it.
+fn decode<T>(bytes: &[u8]) -> Option<T>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<DefaultValidator<'a>> + Deserialize<T, Infallible>,
+{
+    let mut aligned = AlignedVec::with_capacity(bytes.len());
+    aligned.extend_from_slice(bytes);
+
+    ::rkyv::check_archived_root::<T>(&aligned)
+        .ok()
+        .map(|archived| archived.deserialize(&mut Infallible).expect("infallible"))
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (field, payload) = match data.split_first() {
+        Some(data) => data,
+        None => return,
+    };
+
+    match field % 39 {
+        // the signed requests
+        0 => drop(decode::<GuaranteeSigned<SetReadOnly>>(payload)),
+        1 => drop(decode::<GuaranteeSigned<GetServerDiagnostics>>(payload)),
+        2 => drop(decode::<GuaranteeSigned<GetAccountStats>>(payload)),
+        3 => drop(decode::<GuaranteeSigned<DescribeKind>>(payload)),
+        4 => drop(decode::<GuaranteeSigned<GetKind>>(payload)),
+        5 => drop(decode::<GuaranteeSigned<GetKinds>>(payload)),
+        6 => drop(decode::<GuaranteeSigned<AccountRef>>(payload)),
+        7 => drop(decode::<GuaranteeSigned<DynPath<()>>>(payload)),
+        8 => drop(decode::<GuaranteeSigned<GetDynPathsByTarget>>(payload)),
+        9 => drop(decode::<GuaranteeSigned<GetPathReferenceCount>>(payload)),
+        10 => drop(decode::<GuaranteeSigned<GetRecordByNonce>>(payload)),
+        11 => drop(decode::<GuaranteeSigned<DynPath<Path>>>(payload)),
+        12 => drop(decode::<GuaranteeSigned<GetWords>>(payload)),
+        13 => drop(decode::<GuaranteeSigned<WordHash>>(payload)),
+        14 => drop(decode::<GuaranteeSigned<GetWordsCounts>>(payload)),
+        15 => drop(decode::<GuaranteeSigned<GetWordsCountsBatch>>(payload)),
+        16 => drop(decode::<GuaranteeSigned<GetWordCountAllLangs>>(payload)),
+        17 => drop(decode::<GuaranteeSigned<GetIdfVector>>(payload)),
+        18 => drop(decode::<GuaranteeSigned<GetSimilarDocuments>>(payload)),
+        19 => drop(decode::<GuaranteeSigned<QueryWords>>(payload)),
+        20 => drop(decode::<GuaranteeSigned<GetWordCountDelta>>(payload)),
+        21 => drop(decode::<GuaranteeSigned<GetMembers>>(payload)),
+        22 => {
+            if let Some(query) = decode::<GuaranteeSigned<GetWordFrequencyHistogram>>(payload) {
+                let _ = query.data.data.validate();
+            }
+        }
+        23 => {
+            if let Some(query) = decode::<GuaranteeSigned<LinkAccountSuccessor>>(payload) {
+                let _ = query.data.data.validate(&query.guarantee.account);
+            }
+        }
+        24 => drop(decode::<GuaranteeSigned<GetAccountChain>>(payload)),
+        25 => drop(decode::<GuaranteeSigned<GetDynPathsMany>>(payload)),
+        26 => drop(decode::<GuaranteeSigned<AcquireWriterLease>>(payload)),
+        27 => drop(decode::<GuaranteeSigned<GetOplog>>(payload)),
+        28 => drop(decode::<GuaranteeSigned<GetInclusionProof>>(payload)),
+        // the unsigned inputs
+        29 => drop(decode::<String>(payload)),
+        30 => drop(decode::<Hash>(payload)),
+        31 => drop(decode::<Option<Vec<u8>>>(payload)),
+        32 => drop(decode::<Option<AccountRef>>(payload)),
+        33 => drop(decode::<Vec<WordKeyHash>>(payload)),
+        34 => drop(decode::<Vec<GetWordsCounts>>(payload)),
+        35 => drop(decode::<Vec<DynPath<()>>>(payload)),
+        36 => drop(decode::<Option<u64>>(payload)),
+        37 => drop(decode::<Option<Normalization>>(payload)),
+        _ => {
+            if let Some(query) = decode::<WordQuery>(payload) {
+                let _ = query.validate();
+            }
+        }
+    }
+});
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+CREATE TABLE settings (
+  name VARCHAR PRIMARY KEY,
+  value VARCHAR NOT NULL
+);
\ No newline at end of file
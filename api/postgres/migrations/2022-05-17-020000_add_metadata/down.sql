@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE words DROP COLUMN metadata;
+ALTER TABLE dyn_paths DROP COLUMN metadata;
\ No newline at end of file
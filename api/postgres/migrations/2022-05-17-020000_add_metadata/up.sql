@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE dyn_paths ADD COLUMN metadata BYTEA;
+ALTER TABLE words ADD COLUMN metadata BYTEA;
\ No newline at end of file
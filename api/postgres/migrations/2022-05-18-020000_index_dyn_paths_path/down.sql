@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX dyn_paths_path_idx;
\ No newline at end of file
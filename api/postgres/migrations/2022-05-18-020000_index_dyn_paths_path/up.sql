@@ -0,0 +1,2 @@
+-- Your SQL goes here
+CREATE INDEX dyn_paths_path_idx ON dyn_paths (path);
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE outbox (
+  id SERIAL PRIMARY KEY,
+  topic VARCHAR NOT NULL,
+  payload VARCHAR NOT NULL,
+  created_date TIMESTAMP NOT NULL,
+  delivered_date TIMESTAMP
+);
+
+CREATE INDEX outbox_undelivered_idx ON outbox (id) WHERE delivered_date IS NULL;
\ No newline at end of file
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE schema_meta (
+  id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+  version INT4 NOT NULL
+);
+
+-- the number of the migrations, which should be bumped by each migration
+INSERT INTO schema_meta (version) VALUES (9);
\ No newline at end of file
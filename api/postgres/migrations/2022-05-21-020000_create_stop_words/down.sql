@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE stop_words;
+
+UPDATE schema_meta SET version = 9;
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE stop_words (
+  id SERIAL PRIMARY KEY,
+  kind VARCHAR NOT NULL,
+  lang VARCHAR NOT NULL,
+  word VARCHAR NOT NULL,
+  UNIQUE (kind, lang, word)
+);
+
+UPDATE schema_meta SET version = 10;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE dyn_paths DROP COLUMN on_behalf_of;
+ALTER TABLE words DROP COLUMN on_behalf_of;
+
+UPDATE schema_meta SET version = 10;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE words ADD COLUMN on_behalf_of VARCHAR;
+ALTER TABLE dyn_paths ADD COLUMN on_behalf_of VARCHAR;
+
+UPDATE schema_meta SET version = 11;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE kinds;
+
+UPDATE schema_meta SET version = 11;
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE kinds (
+  id SERIAL PRIMARY KEY,
+  kind VARCHAR NOT NULL UNIQUE,
+  name VARCHAR NOT NULL,
+  description VARCHAR NOT NULL,
+  schema_version INTEGER NOT NULL
+);
+
+UPDATE schema_meta SET version = 12;
\ No newline at end of file
@@ -0,0 +1,18 @@
+-- This file should undo anything in `up.sql`
+UPDATE dyn_paths SET guarantee_signature = a.guarantee_signature, guarantor_signature = a.guarantor_signature
+FROM signatures_archive a WHERE a.table_name = 'dyn_paths' AND a.record_id = dyn_paths.id;
+UPDATE words SET guarantee_signature = a.guarantee_signature, guarantor_signature = a.guarantor_signature
+FROM signatures_archive a WHERE a.table_name = 'words' AND a.record_id = words.id;
+
+-- the dropped signatures cannot be restored
+DELETE FROM dyn_paths WHERE guarantee_signature IS NULL OR guarantor_signature IS NULL;
+DELETE FROM words WHERE guarantee_signature IS NULL OR guarantor_signature IS NULL;
+
+ALTER TABLE dyn_paths ALTER COLUMN guarantee_signature SET NOT NULL;
+ALTER TABLE dyn_paths ALTER COLUMN guarantor_signature SET NOT NULL;
+ALTER TABLE words ALTER COLUMN guarantee_signature SET NOT NULL;
+ALTER TABLE words ALTER COLUMN guarantor_signature SET NOT NULL;
+
+DROP TABLE signatures_archive;
+
+UPDATE schema_meta SET version = 12;
\ No newline at end of file
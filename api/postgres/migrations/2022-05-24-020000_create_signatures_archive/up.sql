@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE signatures_archive (
+  id SERIAL PRIMARY KEY,
+  table_name VARCHAR NOT NULL,
+  record_id INTEGER NOT NULL,
+  guarantee_signature VARCHAR NOT NULL,
+  guarantor_signature VARCHAR NOT NULL,
+  UNIQUE (table_name, record_id)
+);
+
+ALTER TABLE dyn_paths ALTER COLUMN guarantee_signature DROP NOT NULL;
+ALTER TABLE dyn_paths ALTER COLUMN guarantor_signature DROP NOT NULL;
+ALTER TABLE words ALTER COLUMN guarantee_signature DROP NOT NULL;
+ALTER TABLE words ALTER COLUMN guarantor_signature DROP NOT NULL;
+
+UPDATE schema_meta SET version = 13;
\ No newline at end of file
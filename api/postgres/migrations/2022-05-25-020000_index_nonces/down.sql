@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX words_nonce_idx;
+DROP INDEX dyn_paths_nonce_idx;
+
+UPDATE schema_meta SET version = 13;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+CREATE INDEX dyn_paths_nonce_idx ON dyn_paths (nonce);
+CREATE INDEX words_nonce_idx ON words (nonce);
+
+UPDATE schema_meta SET version = 14;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE api_tokens;
+
+UPDATE schema_meta SET version = 14;
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE api_tokens (
+  id SERIAL PRIMARY KEY,
+  token_hash VARCHAR NOT NULL UNIQUE,
+  name VARCHAR NOT NULL,
+  scopes VARCHAR NOT NULL,
+  rate_limit INTEGER NOT NULL,
+  created_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP
+);
+
+UPDATE schema_meta SET version = 15;
\ No newline at end of file
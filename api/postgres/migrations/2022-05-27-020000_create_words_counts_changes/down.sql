@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TRIGGER words_counts_changes ON words_counts;
+DROP FUNCTION log_words_counts_change;
+DROP TABLE words_counts_changes;
+
+UPDATE schema_meta SET version = 15;
\ No newline at end of file
@@ -0,0 +1,35 @@
+-- Your SQL goes here
+CREATE TABLE words_counts_changes (
+  id BIGSERIAL PRIMARY KEY,
+  namespace VARCHAR NOT NULL,
+  kind VARCHAR NOT NULL,
+  lang VARCHAR NOT NULL,
+  word VARCHAR NOT NULL,
+  delta INT8 NOT NULL
+);
+
+CREATE INDEX words_counts_changes_kind_id ON words_counts_changes (kind, id);
+
+-- every change of the counts is logged, including the bulk deletions and the migrations
+CREATE FUNCTION log_words_counts_change() RETURNS TRIGGER AS $$
+BEGIN
+  IF TG_OP IN ('UPDATE', 'DELETE') AND OLD.count <> 0 THEN
+    INSERT INTO words_counts_changes (namespace, kind, lang, word, delta)
+    VALUES (OLD.namespace, OLD.kind, OLD.lang, OLD.word, -OLD.count);
+  END IF;
+  IF TG_OP IN ('INSERT', 'UPDATE') AND NEW.count <> 0 THEN
+    INSERT INTO words_counts_changes (namespace, kind, lang, word, delta)
+    VALUES (NEW.namespace, NEW.kind, NEW.lang, NEW.word, NEW.count);
+  END IF;
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER words_counts_changes AFTER INSERT OR UPDATE OR DELETE ON words_counts
+FOR EACH ROW EXECUTE FUNCTION log_words_counts_change();
+
+-- the existing counts are the first changes
+INSERT INTO words_counts_changes (namespace, kind, lang, word, delta)
+SELECT namespace, kind, lang, word, count FROM words_counts WHERE count <> 0 ORDER BY id;
+
+UPDATE schema_meta SET version = 16;
\ No newline at end of file
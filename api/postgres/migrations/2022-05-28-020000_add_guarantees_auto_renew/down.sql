@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX accounts_guarantees_expiration_date_idx;
+
+ALTER TABLE accounts_guarantees DROP COLUMN notified_date;
+ALTER TABLE accounts_guarantees DROP COLUMN auto_renew;
+
+UPDATE schema_meta SET version = 16;
\ No newline at end of file
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE accounts_guarantees ADD COLUMN auto_renew BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE accounts_guarantees ADD COLUMN notified_date TIMESTAMP;
+
+CREATE INDEX accounts_guarantees_expiration_date_idx ON accounts_guarantees (expiration_date);
+
+UPDATE schema_meta SET version = 17;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE accounts_successors;
+
+UPDATE schema_meta SET version = 17;
\ No newline at end of file
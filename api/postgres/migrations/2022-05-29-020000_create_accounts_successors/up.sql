@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE accounts_successors (
+  id SERIAL PRIMARY KEY,
+  nonce UUID NOT NULL,
+  account VARCHAR NOT NULL UNIQUE,
+  successor VARCHAR NOT NULL UNIQUE,
+  guarantor VARCHAR NOT NULL,
+  account_signature VARCHAR NOT NULL,
+  successor_signature VARCHAR NOT NULL,
+  created_date TIMESTAMP NOT NULL
+);
+
+UPDATE schema_meta SET version = 18;
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX words_kind_hash_version_idx;
+
+ALTER TABLE words DROP COLUMN hash_version;
+
+UPDATE schema_meta SET version = 18;
\ No newline at end of file
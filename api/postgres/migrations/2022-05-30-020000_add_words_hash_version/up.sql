@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE words ADD COLUMN hash_version INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX words_kind_hash_version_idx ON words (kind, hash_version);
+
+UPDATE schema_meta SET version = 19;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE kinds_leases;
+
+UPDATE schema_meta SET version = 19;
\ No newline at end of file
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE kinds_leases (
+  id SERIAL PRIMARY KEY,
+  kind VARCHAR NOT NULL UNIQUE,
+  holder VARCHAR NOT NULL,
+  token BIGINT NOT NULL,
+  created_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP NOT NULL
+);
+
+UPDATE schema_meta SET version = 20;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE oplog;
+
+UPDATE schema_meta SET version = 20;
\ No newline at end of file
@@ -0,0 +1,10 @@
+-- Your SQL goes here
+CREATE TABLE oplog (
+  id SERIAL PRIMARY KEY,
+  nonce UUID NOT NULL,
+  hash VARCHAR NOT NULL,
+  signature VARCHAR NOT NULL,
+  created_date TIMESTAMP NOT NULL
+);
+
+UPDATE schema_meta SET version = 21;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX oplog_nonce_idx;
+DROP TABLE oplog_roots;
+
+UPDATE schema_meta SET version = 21;
\ No newline at end of file
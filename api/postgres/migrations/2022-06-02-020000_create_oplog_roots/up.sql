@@ -0,0 +1,16 @@
+-- Your SQL goes here
+CREATE TABLE oplog_roots (
+  id SERIAL PRIMARY KEY,
+  nonce UUID NOT NULL,
+  guarantee VARCHAR NOT NULL,
+  guarantor VARCHAR NOT NULL,
+  guarantee_signature VARCHAR NOT NULL,
+  created_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP,
+  tree_size BIGINT NOT NULL UNIQUE,
+  root VARCHAR NOT NULL
+);
+
+CREATE INDEX oplog_nonce_idx ON oplog (nonce);
+
+UPDATE schema_meta SET version = 22;
\ No newline at end of file
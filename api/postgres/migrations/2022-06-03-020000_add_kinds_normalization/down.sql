@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE kinds DROP COLUMN normalization;
+
+UPDATE schema_meta SET version = 22;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE kinds ADD COLUMN normalization VARCHAR;
+
+UPDATE schema_meta SET version = 23;
\ No newline at end of file
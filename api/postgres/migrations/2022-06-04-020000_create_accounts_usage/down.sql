@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE accounts_usage;
+
+UPDATE schema_meta SET version = 23;
\ No newline at end of file
@@ -0,0 +1,11 @@
+-- Your SQL goes here
+CREATE TABLE accounts_usage (
+  id SERIAL PRIMARY KEY,
+  account VARCHAR NOT NULL,
+  day TIMESTAMP NOT NULL,
+  bytes_written BIGINT NOT NULL,
+  rows_written BIGINT NOT NULL,
+  UNIQUE (account, day)
+);
+
+UPDATE schema_meta SET version = 24;
\ No newline at end of file
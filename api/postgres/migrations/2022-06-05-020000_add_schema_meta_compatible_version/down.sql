@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE schema_meta DROP COLUMN compatible_version;
+
+UPDATE schema_meta SET version = 24;
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- the oldest version of the schema whose binaries can work on this one, which the additive
+-- migrations keep, e.g. the binaries since 23 enforce the normalization policies of the kinds
+ALTER TABLE schema_meta ADD COLUMN compatible_version INTEGER NOT NULL DEFAULT 23;
+
+UPDATE schema_meta SET version = 25;
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE words_segments;
+
+UPDATE schema_meta SET version = 25;
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE words_segments (
+  id SERIAL PRIMARY KEY,
+  hash VARCHAR NOT NULL UNIQUE,
+  since_date TIMESTAMP NOT NULL,
+  until_date TIMESTAMP NOT NULL,
+  len BIGINT NOT NULL,
+  created_date TIMESTAMP NOT NULL
+);
+CREATE INDEX words_segments_date_idx ON words_segments (since_date, until_date);
+
+UPDATE schema_meta SET version = 26;
\ No newline at end of file
@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX words_nonce_idx;
+DROP INDEX dyn_paths_nonce_idx;
+CREATE INDEX dyn_paths_nonce_idx ON dyn_paths (nonce);
+CREATE INDEX words_nonce_idx ON words (nonce);
+
+UPDATE schema_meta SET version = 26;
\ No newline at end of file
@@ -0,0 +1,33 @@
+-- Your SQL goes here
+-- discount the replayed words, whose records are deleted below
+UPDATE words_counts c SET count = c.count - r.replayed
+FROM (
+    SELECT w.namespace, w.kind, w.parent, w.lang, w.word, COUNT(*) AS replayed
+    FROM words w
+    WHERE EXISTS (SELECT 1 FROM words o WHERE o.nonce = w.nonce AND o.id < w.id)
+    GROUP BY w.namespace, w.kind, w.parent, w.lang, w.word
+) r
+WHERE c.namespace = r.namespace AND c.kind = r.kind AND c.parent = r.parent
+    AND c.lang = r.lang AND c.word = r.word;
+UPDATE words_counts_guarantees c SET count = c.count - r.replayed
+FROM (
+    SELECT w.guarantee, w.namespace, w.kind, w.parent, w.lang, w.word, COUNT(*) AS replayed
+    FROM words w
+    WHERE EXISTS (SELECT 1 FROM words o WHERE o.nonce = w.nonce AND o.id < w.id)
+    GROUP BY w.guarantee, w.namespace, w.kind, w.parent, w.lang, w.word
+) r
+WHERE c.guarantee = r.guarantee AND c.namespace = r.namespace AND c.kind = r.kind
+    AND c.parent = r.parent AND c.lang = r.lang AND c.word = r.word;
+DELETE FROM words_counts WHERE count <= 0;
+DELETE FROM words_counts_guarantees WHERE count <= 0;
+
+-- keep the first accepted record of each nonce
+DELETE FROM dyn_paths r USING dyn_paths o WHERE r.nonce = o.nonce AND r.id > o.id;
+DELETE FROM words r USING words o WHERE r.nonce = o.nonce AND r.id > o.id;
+
+DROP INDEX dyn_paths_nonce_idx;
+DROP INDEX words_nonce_idx;
+CREATE UNIQUE INDEX dyn_paths_nonce_idx ON dyn_paths (nonce);
+CREATE UNIQUE INDEX words_nonce_idx ON words (nonce);
+
+UPDATE schema_meta SET version = 27;
\ No newline at end of file
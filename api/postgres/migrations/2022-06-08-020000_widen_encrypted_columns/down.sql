@@ -0,0 +1,8 @@
+-- This file should undo anything in `up.sql`
+-- it fails while the encrypted rows remain, which should be decrypted first
+ALTER TABLE words_counts_guarantees ALTER COLUMN parent TYPE SHA256HASH, ALTER COLUMN word TYPE SHA256HASH;
+ALTER TABLE words_counts ALTER COLUMN parent TYPE SHA256HASH, ALTER COLUMN word TYPE SHA256HASH;
+ALTER TABLE words ALTER COLUMN parent TYPE SHA256HASH, ALTER COLUMN word TYPE SHA256HASH;
+ALTER TABLE dyn_paths ALTER COLUMN word TYPE SHA256HASH;
+
+UPDATE schema_meta SET version = 27;
\ No newline at end of file
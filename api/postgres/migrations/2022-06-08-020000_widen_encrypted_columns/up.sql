@@ -0,0 +1,9 @@
+-- Your SQL goes here
+-- the encrypted columns (see `ColumnCipher`) do not fit the hashes, e.g. "enc:" and the hex of the nonce,
+-- the ciphertext and the tag of a hash take 148 characters
+ALTER TABLE dyn_paths ALTER COLUMN word TYPE VARCHAR;
+ALTER TABLE words ALTER COLUMN parent TYPE VARCHAR, ALTER COLUMN word TYPE VARCHAR;
+ALTER TABLE words_counts ALTER COLUMN parent TYPE VARCHAR, ALTER COLUMN word TYPE VARCHAR;
+ALTER TABLE words_counts_guarantees ALTER COLUMN parent TYPE VARCHAR, ALTER COLUMN word TYPE VARCHAR;
+
+UPDATE schema_meta SET version = 28;
\ No newline at end of file
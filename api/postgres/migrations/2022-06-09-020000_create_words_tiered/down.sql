@@ -0,0 +1,7 @@
+-- This file should undo anything in `up.sql`
+UPDATE schema_meta SET compatible_version = 23;
+
+ALTER TABLE words_segments DROP COLUMN indexed;
+DROP TABLE words_tiered;
+
+UPDATE schema_meta SET version = 28;
\ No newline at end of file
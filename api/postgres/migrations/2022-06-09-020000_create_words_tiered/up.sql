@@ -0,0 +1,36 @@
+-- Your SQL goes here
+-- the index of the words tiered out to the segments, which are served only while indexed,
+-- so that the deletions, the expiry and the migrations of the words reach the tiered ones
+CREATE TABLE words_tiered (
+  id SERIAL PRIMARY KEY,
+  segment_id INTEGER NOT NULL REFERENCES words_segments (id) ON DELETE CASCADE,
+  -- the id of the word in the hot table, which the incremental backups follow
+  record_id INTEGER NOT NULL,
+  nonce NONCE NOT NULL UNIQUE,
+  guarantee ACCOUNT NOT NULL,
+  guarantor ACCOUNT NOT NULL,
+  created_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP,
+  namespace VARCHAR NOT NULL,
+  kind VARCHAR NOT NULL,
+  parent VARCHAR NOT NULL,
+  lang VARCHAR NOT NULL,
+  word VARCHAR NOT NULL,
+  path VARCHAR NOT NULL,
+  hash_version INTEGER NOT NULL
+);
+CREATE INDEX words_tiered_segment_id_idx ON words_tiered (segment_id);
+CREATE INDEX words_tiered_created_date_idx ON words_tiered (created_date, nonce);
+CREATE INDEX words_tiered_expiration_date_idx ON words_tiered (expiration_date);
+CREATE INDEX words_tiered_kind_idx ON words_tiered (kind);
+CREATE INDEX words_tiered_namespace_idx ON words_tiered (namespace);
+CREATE INDEX words_tiered_path_idx ON words_tiered (path);
+
+-- the segments tiered out before are indexed by the next tiering
+ALTER TABLE words_segments ADD COLUMN indexed BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE words_segments ALTER COLUMN indexed SET DEFAULT TRUE;
+
+-- the older binaries neither index the tiered words nor delete them
+UPDATE schema_meta SET compatible_version = 29;
+
+UPDATE schema_meta SET version = 29;
\ No newline at end of file
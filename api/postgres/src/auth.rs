@@ -0,0 +1,122 @@
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl};
+use ipis::{
+    async_trait::async_trait,
+    core::{account::AccountRef, anyhow::Result, chrono::NaiveDateTime},
+};
+
+use crate::{diagnostics::Diagnostics, pool::ConnectionPool};
+
+/// Decides whether the guarantee is permitted to send the requests guaranteed by the server.
+///
+/// The allowlist, the guarantor and the self-authentication are checked by the client
+/// before calling the provider.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    async fn authenticate(
+        &self,
+        registry: Registry<'_>,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool>;
+}
+
+/// The guarantees registered in the database.
+pub struct Registry<'a> {
+    pub(crate) diagnostics: &'a Diagnostics,
+    pub(crate) pool: &'a ConnectionPool,
+    /// the current time of the client's clock
+    pub(crate) now: NaiveDateTime,
+}
+
+impl<'a> Registry<'a> {
+    /// Returns whether the guarantee, or any of its predecessors, is registered.
+    ///
+    /// A retired account is not registered anymore, as it has been succeeded by another one.
+    pub async fn is_registered(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool> {
+        let mut conn = self
+            .diagnostics
+            .lock(self.pool, "ensure_registered", guarantee)
+            .await;
+
+        let guarantee = guarantee.to_string();
+        if crate::succession::is_retired(&mut conn, &guarantee)? {
+            return Ok(false);
+        }
+        let principals = crate::succession::principals(&mut conn, &guarantee)?;
+
+        crate::schema::accounts_guarantees::table
+            .limit(1)
+            .filter(crate::schema::accounts_guarantees::guarantee.eq_any(principals))
+            .filter(crate::schema::accounts_guarantees::guarantor.eq(guarantor.to_string()))
+            .filter(
+                crate::schema::accounts_guarantees::expiration_date
+                    .ge(self.now)
+                    .or(crate::schema::accounts_guarantees::expiration_date.is_null()),
+            )
+            .execute(&mut *conn)
+            .map(|count| count > 0)
+            .map_err(Into::into)
+    }
+}
+
+/// The default provider, which permits the guarantees registered in the database.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RegistryAuthProvider;
+
+#[async_trait]
+impl AuthProvider for RegistryAuthProvider {
+    async fn authenticate(
+        &self,
+        registry: Registry<'_>,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool> {
+        registry.is_registered(guarantee, guarantor).await
+    }
+}
+
+/// A provider which permits the given accounts, whether they are registered or not.
+#[derive(Clone, Debug)]
+pub struct StaticAuthProvider {
+    accounts: Vec<AccountRef>,
+    /// whether to permit the registered guarantees as well
+    registered: bool,
+}
+
+impl StaticAuthProvider {
+    pub fn new(accounts: Vec<AccountRef>) -> Self {
+        Self {
+            accounts,
+            registered: false,
+        }
+    }
+
+    pub fn or_registered(self) -> Self {
+        Self {
+            registered: true,
+            ..self
+        }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for StaticAuthProvider {
+    async fn authenticate(
+        &self,
+        registry: Registry<'_>,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool> {
+        if self.accounts.contains(guarantee) {
+            Ok(true)
+        } else if self.registered {
+            registry.is_registered(guarantee, guarantor).await
+        } else {
+            Ok(false)
+        }
+    }
+}
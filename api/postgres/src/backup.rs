@@ -0,0 +1,329 @@
+use bytecheck::CheckBytes;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::GuaranteeSigned,
+        anyhow::{anyhow, bail, Result},
+        chrono::{NaiveDateTime, Timelike},
+        uuid::Uuid,
+        value::hash::Hash,
+    },
+};
+use rkyv::{Archive, Deserialize, Infallible, Serialize};
+
+/// the scratch space of the serializer, in bytes
+const SCRATCH_SPACE: usize = 4096;
+
+/// Stores the backup objects addressed by their hashes, e.g. in ipsis.
+#[async_trait]
+pub trait BackupStore {
+    async fn put(&self, object: &[u8]) -> Result<Hash>;
+
+    async fn get(&self, hash: &Hash) -> Result<Vec<u8>>;
+}
+
+/// The last record ids of the tables, which are increased on every insertion.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Archive, Serialize, Deserialize,
+)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct BackupSequence {
+    pub dyn_paths: i32,
+    pub words: i32,
+}
+
+impl BackupSequence {
+    pub(crate) fn parse(s: &str) -> Result<Self> {
+        let (dyn_paths, words) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("malformed backup sequence: {s}"))?;
+        Ok(Self {
+            dyn_paths: dyn_paths.parse()?,
+            words: words.parse()?,
+        })
+    }
+}
+
+impl ::core::fmt::Display for BackupSequence {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "{}:{}", self.dyn_paths, self.words)
+    }
+}
+
+/// Describes a backup, which is signed by the server.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct BackupHeader {
+    /// the backup which this delta follows, or `None` for a full backup
+    pub base: Option<Hash>,
+    /// the records after this sequence are included
+    pub since: BackupSequence,
+    /// the records until this sequence are included
+    pub until: BackupSequence,
+    /// the hash of the serialized records
+    pub records: Hash,
+}
+
+impl BackupHeader {
+    pub fn is_full(&self) -> bool {
+        self.base.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct BackupObject {
+    pub header: GuaranteeSigned<BackupHeader>,
+    pub records: BackupRecords,
+}
+
+impl BackupObject {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        ::rkyv::to_bytes::<_, SCRATCH_SPACE>(self)
+            .map(|bytes| bytes.into_vec())
+            .map_err(|error| anyhow!("failed to archive the backup: {error}"))
+    }
+
+    /// Parses the backup, and checks whether the records are the ones described by the header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut aligned = ::rkyv::AlignedVec::with_capacity(bytes.len());
+        aligned.extend_from_slice(bytes);
+
+        let this: Self = ::rkyv::check_archived_root::<Self>(&aligned)
+            .map_err(|error| anyhow!("malformed backup: {error}"))?
+            .deserialize(&mut Infallible)
+            .expect("infallible");
+
+        if this.header.records != this.records.hash()? {
+            bail!("the backup records have been modified")
+        }
+        this.records.validate()?;
+        Ok(this)
+    }
+}
+
+/// The raw records, whose encrypted columns are kept encrypted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct BackupRecords {
+    pub dyn_paths: Vec<BackupDynPath>,
+    pub words: Vec<BackupWord>,
+}
+
+impl BackupRecords {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        ::rkyv::to_bytes::<_, SCRATCH_SPACE>(self)
+            .map(|bytes| bytes.into_vec())
+            .map_err(|error| anyhow!("failed to archive the backup records: {error}"))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut aligned = ::rkyv::AlignedVec::with_capacity(bytes.len());
+        aligned.extend_from_slice(bytes);
+
+        let this: Self = ::rkyv::check_archived_root::<Self>(&aligned)
+            .map_err(|error| anyhow!("malformed backup records: {error}"))?
+            .deserialize(&mut Infallible)
+            .expect("infallible");
+
+        this.validate()?;
+        Ok(this)
+    }
+
+    /// Checks whether the records can be restored, e.g. whether the dates are in range.
+    pub fn validate(&self) -> Result<()> {
+        self.dyn_paths
+            .iter()
+            .map(|record| &record.metadata)
+            .chain(self.words.iter().map(|record| &record.metadata))
+            .try_for_each(BackupMetadata::validate)
+    }
+
+    pub fn hash(&self) -> Result<Hash> {
+        ::rkyv::to_bytes::<_, SCRATCH_SPACE>(self)
+            .map(|bytes| Hash::with_bytes(&bytes))
+            .map_err(|error| anyhow!("failed to archive the backup records: {error}"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct BackupMetadata {
+    pub nonce: u128,
+    pub guarantee: String,
+    pub guarantor: String,
+    /// the signatures which have been dropped by the retention policy are `None`
+    pub guarantee_signature: Option<String>,
+    pub guarantor_signature: Option<String>,
+    pub created_date: BackupDate,
+    pub expiration_date: Option<BackupDate>,
+}
+
+impl BackupMetadata {
+    fn validate(&self) -> Result<()> {
+        self.created_date.validate()?;
+        if let Some(expiration_date) = &self.expiration_date {
+            expiration_date.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct BackupDynPath {
+    pub metadata: BackupMetadata,
+    pub namespace: String,
+    pub kind: String,
+    pub word: String,
+    pub path: String,
+    pub len: i64,
+    pub data: Option<Vec<u8>>,
+    pub on_behalf_of: Option<String>,
+}
+
+impl From<crate::models::dyn_paths::DynPath> for BackupDynPath {
+    fn from(record: crate::models::dyn_paths::DynPath) -> Self {
+        Self {
+            metadata: BackupMetadata {
+                nonce: record.nonce.as_u128(),
+                guarantee: record.guarantee,
+                guarantor: record.guarantor,
+                guarantee_signature: record.guarantee_signature,
+                guarantor_signature: record.guarantor_signature,
+                created_date: record.created_date.into(),
+                expiration_date: record.expiration_date.map(Into::into),
+            },
+            namespace: record.namespace,
+            kind: record.kind,
+            word: record.word,
+            path: record.path,
+            len: record.len,
+            data: record.metadata,
+            on_behalf_of: record.on_behalf_of,
+        }
+    }
+}
+
+impl From<BackupDynPath> for crate::models::dyn_paths::NewDynPath {
+    fn from(record: BackupDynPath) -> Self {
+        Self {
+            nonce: Uuid::from_u128(record.metadata.nonce),
+            guarantee: record.metadata.guarantee,
+            guarantor: record.metadata.guarantor,
+            guarantee_signature: record.metadata.guarantee_signature,
+            guarantor_signature: record.metadata.guarantor_signature,
+            created_date: record.metadata.created_date.into(),
+            expiration_date: record.metadata.expiration_date.map(Into::into),
+            namespace: record.namespace,
+            kind: record.kind,
+            word: record.word,
+            path: record.path,
+            len: record.len,
+            metadata: record.data,
+            on_behalf_of: record.on_behalf_of,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct BackupWord {
+    pub metadata: BackupMetadata,
+    pub namespace: String,
+    pub kind: String,
+    pub parent: String,
+    pub lang: String,
+    pub word: String,
+    pub relpath: bool,
+    pub path: String,
+    pub len: i64,
+    pub data: Option<Vec<u8>>,
+    pub on_behalf_of: Option<String>,
+    pub hash_version: i32,
+}
+
+impl From<crate::models::words::Word> for BackupWord {
+    fn from(record: crate::models::words::Word) -> Self {
+        Self {
+            metadata: BackupMetadata {
+                nonce: record.nonce.as_u128(),
+                guarantee: record.guarantee,
+                guarantor: record.guarantor,
+                guarantee_signature: record.guarantee_signature,
+                guarantor_signature: record.guarantor_signature,
+                created_date: record.created_date.into(),
+                expiration_date: record.expiration_date.map(Into::into),
+            },
+            namespace: record.namespace,
+            kind: record.kind,
+            parent: record.parent,
+            lang: record.lang,
+            word: record.word,
+            relpath: record.relpath,
+            path: record.path,
+            len: record.len,
+            data: record.metadata,
+            on_behalf_of: record.on_behalf_of,
+            hash_version: record.hash_version,
+        }
+    }
+}
+
+impl From<BackupWord> for crate::models::words::NewWord {
+    fn from(record: BackupWord) -> Self {
+        Self {
+            nonce: Uuid::from_u128(record.metadata.nonce),
+            guarantee: record.metadata.guarantee,
+            guarantor: record.metadata.guarantor,
+            guarantee_signature: record.metadata.guarantee_signature,
+            guarantor_signature: record.metadata.guarantor_signature,
+            created_date: record.metadata.created_date.into(),
+            expiration_date: record.metadata.expiration_date.map(Into::into),
+            namespace: record.namespace,
+            kind: record.kind,
+            parent: record.parent,
+            lang: record.lang,
+            word: record.word,
+            relpath: record.relpath,
+            path: record.path,
+            len: record.len,
+            metadata: record.data,
+            on_behalf_of: record.on_behalf_of,
+            hash_version: record.hash_version,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct BackupDate {
+    pub secs: i64,
+    pub nsecs: u32,
+}
+
+impl BackupDate {
+    fn validate(&self) -> Result<()> {
+        match NaiveDateTime::from_timestamp_opt(self.secs, self.nsecs) {
+            Some(_) => Ok(()),
+            None => bail!("malformed backup date: {}.{:09}", self.secs, self.nsecs),
+        }
+    }
+}
+
+impl From<NaiveDateTime> for BackupDate {
+    fn from(date: NaiveDateTime) -> Self {
+        Self {
+            secs: date.timestamp(),
+            nsecs: date.nanosecond(),
+        }
+    }
+}
+
+impl From<BackupDate> for NaiveDateTime {
+    fn from(date: BackupDate) -> Self {
+        NaiveDateTime::from_timestamp(date.secs, date.nsecs)
+    }
+}
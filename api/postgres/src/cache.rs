@@ -0,0 +1,176 @@
+use std::{collections::HashMap, fmt, sync::Mutex};
+
+use diesel::{sql_types::Text, PgConnection, RunQueryDsl};
+use ipdis_common::{Fresh, GetWordsCountsOutput, Page, WithMetadata};
+use ipis::{
+    core::{account::GuarantorSigned, value::hash::Hash},
+    path::{DynPath, Path},
+};
+
+/// the channel of the invalidation notifications, shared by all the nodes
+pub const CHANNEL: &str = "ipdis_invalidate";
+
+/// the maximum number of the entries of each cache, which is cleared on overflow
+const MAX_ENTRIES: usize = 4096;
+
+pub type DynPathRecord = Option<WithMetadata<GuarantorSigned<DynPath<Path>>>>;
+
+/// In-process caches of the hot lookups, which are invalidated by the namespace.
+#[derive(Default)]
+pub struct Cache {
+    enabled: bool,
+    dyn_paths: Entries<DynPathRecord>,
+    word_counts: Entries<Fresh<Page<GetWordsCountsOutput>>>,
+}
+
+impl Cache {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_dyn_path(&self, key: &CacheKey) -> Option<DynPathRecord> {
+        self.enabled.then(|| self.dyn_paths.get(key)).flatten()
+    }
+
+    pub fn put_dyn_path(&self, key: CacheKey, value: DynPathRecord) {
+        if self.enabled {
+            self.dyn_paths.put(key, value)
+        }
+    }
+
+    pub fn get_word_counts(&self, key: &CacheKey) -> Option<Fresh<Page<GetWordsCountsOutput>>> {
+        self.enabled.then(|| self.word_counts.get(key)).flatten()
+    }
+
+    pub fn put_word_counts(&self, key: CacheKey, value: Fresh<Page<GetWordsCountsOutput>>) {
+        if self.enabled {
+            self.word_counts.put(key, value)
+        }
+    }
+
+    pub fn invalidate(&self, topic: Topic) {
+        match topic {
+            Topic::DynPath(namespace) => self.dyn_paths.invalidate(&namespace),
+            Topic::Word(namespace) => self.word_counts.invalidate(&namespace),
+            Topic::All => self.clear(),
+            Topic::Settings => {}
+        }
+    }
+
+    pub fn clear(&self) {
+        self.dyn_paths.clear();
+        self.word_counts.clear();
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    namespace: String,
+    query: String,
+}
+
+impl CacheKey {
+    pub fn new<Q>(namespace: &Hash, query: &Q) -> Self
+    where
+        Q: fmt::Debug + ?Sized,
+    {
+        Self {
+            namespace: namespace.to_string(),
+            query: format!("{query:?}"),
+        }
+    }
+}
+
+struct Entries<V>(Mutex<HashMap<CacheKey, V>>);
+
+impl<V> Default for Entries<V> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<V> Entries<V>
+where
+    V: Clone,
+{
+    fn get(&self, key: &CacheKey) -> Option<V> {
+        self.0.lock().ok()?.get(key).cloned()
+    }
+
+    fn put(&self, key: CacheKey, value: V) {
+        if let Ok(mut entries) = self.0.lock() {
+            if entries.len() >= MAX_ENTRIES {
+                entries.clear();
+            }
+            entries.insert(key, value);
+        }
+    }
+
+    fn invalidate(&self, namespace: &str) {
+        if let Ok(mut entries) = self.0.lock() {
+            entries.retain(|key, _| key.namespace != namespace);
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut entries) = self.0.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// The records which have been modified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Topic {
+    DynPath(String),
+    Word(String),
+    All,
+    /// the settings shared by all the nodes, e.g. the read-only mode
+    Settings,
+}
+
+impl Topic {
+    pub fn dyn_path(namespace: impl ToString) -> Self {
+        Self::DynPath(namespace.to_string())
+    }
+
+    pub fn word(namespace: impl ToString) -> Self {
+        Self::Word(namespace.to_string())
+    }
+
+    pub fn parse(payload: &str) -> Option<Self> {
+        match payload.split_once('/') {
+            Some(("dyn_path", namespace)) => Some(Self::dyn_path(namespace)),
+            Some(("word", namespace)) => Some(Self::word(namespace)),
+            _ if payload == "*" => Some(Self::All),
+            _ if payload == "settings" => Some(Self::Settings),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DynPath(namespace) => write!(f, "dyn_path/{namespace}"),
+            Self::Word(namespace) => write!(f, "word/{namespace}"),
+            Self::All => write!(f, "*"),
+            Self::Settings => write!(f, "settings"),
+        }
+    }
+}
+
+/// Notifies all the nodes of the modification, which is sent when the transaction is committed.
+pub(crate) fn notify(
+    conn: &mut PgConnection,
+    topic: &Topic,
+) -> Result<(), ::diesel::result::Error> {
+    ::diesel::sql_query("SELECT pg_notify($1, $2)")
+        .bind::<Text, _>(CHANNEL)
+        .bind::<Text, _>(topic.to_string())
+        .execute(conn)
+        .map(|_| ())
+}
@@ -1,30 +1,85 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
 use diesel::{
-    dsl::now, BoolExpressionMethods, Connection, ExpressionMethods, PgConnection, QueryDsl,
-    RunQueryDsl,
+    BoolExpressionMethods, Connection, ExpressionMethods, OptionalExtension, PgConnection,
+    QueryDsl, RunQueryDsl,
 };
 use ipdis_common::{
-    GetWordKeyHash, GetWords, GetWordsCounts, GetWordsCountsOutput, GetWordsParent, Ipdis,
+    ensure_same_namespace, membership, merkle, AccountStats, AcquireWriterLease, ApiTokenInfo,
+    Delegation, Feature, FeatureSet, Fresh, GetAccountChain, GetAccountStats, GetApiTokens,
+    GetDynPathsByTarget, GetIdfLogs, GetKind, GetKinds, GetMembers, GetOplog, GetServerDiagnostics,
+    GetWordCountAllLangs, GetWordCountDelta, GetWordFrequencyHistogram, GetWordKeyHash, GetWords,
+    GetWordsCounts, GetWordsCountsOutput, GetWordsParent, IdfVector, InclusionProof, Ipdis,
+    IpdisAdmin, IpdisError, IssuedApiToken, KindInfo, LinkAccountSuccessor, Member, Normalization,
+    Oplog, OplogRoot, Page, PutReceipt, RegisterKind, ServerDiagnostics, SignedRecord,
+    SimilarDocument, WithMetadata, WordCountDelta, WordCountDeltaItem, WordFrequencyBucket,
+    WordQuery, WordQueryRow, WriterLease,
 };
 use ipiis_api::common::Ipiis;
 use ipis::{
     async_trait::async_trait,
     core::{
         account::{AccountRef, GuaranteeSigned, GuarantorSigned, Identity},
-        anyhow::{bail, Result},
+        anyhow::{anyhow, bail, Error, Result},
+        chrono::{DateTime, Utc},
         metadata::Metadata,
+        signature::Verifier,
         value::{chrono::NaiveDateTime, hash::Hash, text::TextHash, uuid::Uuid},
     },
     env::{self, Infer},
     path::{DynPath, Path},
-    tokio::sync::Mutex,
     word::{WordHash, WordKeyHash},
 };
 
+use crate::{
+    auth::{AuthProvider, Registry, RegistryAuthProvider},
+    backup::{BackupHeader, BackupObject, BackupRecords, BackupSequence, BackupStore, BackupWord},
+    cache::{Cache, CacheKey, Topic},
+    clock::{Clock, SystemClock},
+    config::{DynPathConflictPolicy, IpdisConfig, StopWordsPolicy},
+    diagnostics::{ConnectionGuard, Diagnostics},
+    expiry::GuaranteeExpirySweep,
+    export::Checkpoint,
+    integrity::IntegrityReport,
+    models::{cipher::ColumnCipher, compat::SchemaVersion},
+    ndjson::{BatchSize, ImportRejection, ImportSummary, NdjsonWord},
+    outbox::{OutboxEvent, OutboxPublisher},
+    pool::ConnectionPool,
+    queue::{QueuePermit, RequestClass, RequestQueue},
+    retention::{signature_of, HasSignatures},
+    snapshot::{ConsistentView, Snapshot},
+    tiering::WordsSegmentObject,
+    token::{ApiToken, RateLimiter},
+    usage::{UsagePeriod, UsageRecord},
+};
+
 pub type IpdisClient = IpdisClientInner<::ipiis_api::client::IpiisClient>;
 
 pub struct IpdisClientInner<IpiisClient> {
     pub ipiis: IpiisClient,
-    connection: Mutex<PgConnection>,
+    auth: Box<dyn AuthProvider>,
+    cache: Cache,
+    cipher: ColumnCipher,
+    clock: Box<dyn Clock>,
+    config: IpdisConfig,
+    database_url: String,
+    pool: ConnectionPool,
+    queue: RequestQueue,
+    tokens: RateLimiter,
+    diagnostics: Diagnostics,
+    read_only: AtomicBool,
+    /// the version of the schema of the database, which may differ during a rolling upgrade
+    schema: SchemaVersion,
+    /// the store of the segments of the tiered words, see `crate::tiering`
+    segments: Option<Box<dyn BackupStore + Send + Sync>>,
 }
 
 impl<IpiisClient> AsRef<::ipiis_api::client::IpiisClient> for IpdisClientInner<IpiisClient>
@@ -76,14 +131,339 @@ where
 impl<IpiisClient> IpdisClientInner<IpiisClient> {
     pub fn with_ipiis_client(ipiis: IpiisClient) -> Result<Self> {
         let database_url: String = env::infer("DATABASE_URL")?;
+        Self::with_database_url(ipiis, database_url)
+    }
+
+    /// Connects to the given database, rather than the one of `DATABASE_URL`.
+    pub fn with_database_url(ipiis: IpiisClient, database_url: impl Into<String>) -> Result<Self> {
+        let database_url = database_url.into();
+        let column_key: Option<String> = env::infer("ipdis_column_key").ok();
+        let cipher = ColumnCipher::new(column_key.as_deref())?;
+        let config = IpdisConfig::try_infer()?;
+        let pool = ConnectionPool::establish(&database_url, config.pool_size)?;
+        let queue = RequestQueue::new(
+            config.pool_size,
+            config.queue_reserved,
+            config.max_concurrent_requests,
+            config.queue_timeout,
+        );
+
+        let (schema, read_only) = match pool.try_get() {
+            Some(mut connection) => {
+                let schema = ensure_schema_version(&mut connection)?;
+                ensure_columns_encrypted(&mut connection, &cipher, schema)?;
+
+                // restore the maintenance mode
+                let read_only: bool = get_setting(&mut connection, SETTING_READ_ONLY)?
+                    .map(|value| value.parse())
+                    .transpose()?
+                    .unwrap_or_default();
+                (schema, read_only)
+            }
+            None => bail!("failed to get an idle connection"),
+        };
 
         Ok(Self {
             ipiis,
-            connection: PgConnection::establish(&database_url)
-                .or_else(|_| bail!("Error connecting to {database_url}"))?
-                .into(),
+            auth: Box::new(RegistryAuthProvider),
+            cache: Cache::new(config.cache_enabled),
+            cipher,
+            clock: Box::new(SystemClock),
+            config,
+            database_url,
+            pool,
+            queue,
+            tokens: Default::default(),
+            diagnostics: Default::default(),
+            read_only: read_only.into(),
+            schema,
+            segments: None,
+        })
+    }
+
+    /// Replaces the policy of authenticating the guarantees.
+    pub fn with_auth_provider<A>(self, auth: A) -> Self
+    where
+        A: AuthProvider + 'static,
+    {
+        Self {
+            auth: Box::new(auth),
+            ..self
+        }
+    }
+
+    /// Replaces the hex-encoded key of the column encryption, rather than `ipdis_column_key`.
+    ///
+    /// Fails if the key is given while the plaintext rows remain.
+    pub fn with_column_key(self, key: Option<&str>) -> Result<Self> {
+        let cipher = ColumnCipher::new(key)?;
+        match self.pool.try_get() {
+            Some(mut connection) => {
+                ensure_columns_encrypted(&mut connection, &cipher, self.schema)?
+            }
+            None => bail!("failed to get an idle connection"),
+        }
+
+        Ok(Self { cipher, ..self })
+    }
+
+    /// Replaces the clock deciding whether the records have expired, e.g. to simulate the time.
+    pub fn with_clock<C>(self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        Self {
+            clock: Box::new(clock),
+            ..self
+        }
+    }
+
+    /// Replaces the store of the segments, to which the old words are tiered out.
+    ///
+    /// The tiered words are read back from it, so it should be kept as long as they are indexed.
+    pub fn with_segment_store<S>(self, store: S) -> Self
+    where
+        S: BackupStore + Send + Sync + 'static,
+    {
+        Self {
+            segments: Some(Box::new(store)),
+            ..self
+        }
+    }
+
+    /// Replaces the configuration, e.g. of the policies.
+    ///
+    /// The connections are kept as established, so the size of the pool is not changed.
+    pub fn with_config(self, config: IpdisConfig) -> Self {
+        Self {
+            cache: Cache::new(config.cache_enabled),
+            queue: RequestQueue::new(
+                config.pool_size,
+                config.queue_reserved,
+                config.max_concurrent_requests,
+                config.queue_timeout,
+            ),
+            config,
+            ..self
+        }
+    }
+
+    pub fn config(&self) -> &IpdisConfig {
+        &self.config
+    }
+
+    /// Returns the current time of the clock, which is bound to the queries.
+    fn now(&self) -> NaiveDateTime {
+        self.clock.now().naive_utc()
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::SeqCst)
+    }
+
+    async fn lock_connection<P>(&self, name: &'static str, params: &P) -> ConnectionGuard<'_>
+    where
+        P: ::core::fmt::Debug + ?Sized,
+    {
+        self.diagnostics.lock(&self.pool, name, params).await
+    }
+
+    /// Listens to the modifications made by all the nodes, and invalidates the cache.
+    /// The shared settings, e.g. the read-only mode, are reloaded as well.
+    ///
+    /// It returns only when the connection is lost,
+    /// after which the cache and the settings may be stale until it is called again.
+    pub async fn listen_invalidations(&self) -> Result<()> {
+        use futures::StreamExt;
+        use ipis::tokio::{self, sync::mpsc};
+
+        let (client, mut connection) =
+            ::tokio_postgres::connect(&self.database_url, ::tokio_postgres::NoTls).await?;
+
+        // forward the notifications, driving the connection
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut messages = ::futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+            while let Some(Ok(message)) = messages.next().await {
+                if let ::tokio_postgres::AsyncMessage::Notification(notification) = message {
+                    if sender.send(notification.payload().to_string()).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        client
+            .batch_execute(&format!("LISTEN {}", crate::cache::CHANNEL))
+            .await?;
+
+        // the modifications may have been missed while disconnected
+        self.cache.clear();
+        self.reload_settings().await?;
+
+        while let Some(payload) = receiver.recv().await {
+            match Topic::parse(&payload) {
+                Some(Topic::Settings) => self.reload_settings().await?,
+                Some(topic) => self.cache.invalidate(topic),
+                None => self.cache.clear(),
+            }
+        }
+        bail!("the invalidation listener has been disconnected")
+    }
+
+    /// Reloads the settings shared by all the nodes, which may have been changed by the others.
+    async fn reload_settings(&self) -> Result<()> {
+        let read_only: bool = get_setting(
+            &mut *self.lock_connection("reload_settings", &()).await,
+            SETTING_READ_ONLY,
+        )?
+        .map(|value| value.parse())
+        .transpose()?
+        .unwrap_or_default();
+
+        self.read_only.store(read_only, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Returns whether the aggregated counts are noised for the account,
+    /// which is neither the server nor an admin.
+    fn is_count_noised(&self, guarantee: Option<&AccountRef>) -> bool {
+        let guarantor = self.ipiis.account_me().account_ref();
+        let guarantee = guarantee.unwrap_or(&guarantor);
+
+        guarantee != &guarantor
+            && !self.config.is_admin(guarantee)
+            && self.config.count_noise.is_enabled()
+    }
+
+    fn with_count_noise(
+        &self,
+        is_noised: bool,
+        page: Page<GetWordsCountsOutput>,
+    ) -> Page<GetWordsCountsOutput> {
+        if !is_noised {
+            return page;
+        }
+
+        page.map(|mut record| {
+            record.count = self.config.count_noise.apply(
+                &record.word.kind,
+                &record.word.key.text.msg,
+                record.count,
+            );
+            record
         })
     }
+
+    /// Waits for the turn of the request of the given class, which is held until the permit is dropped.
+    pub async fn enter_queue(&self, class: RequestClass) -> Result<QueuePermit<'_>> {
+        self.queue.enter(class).await
+    }
+
+    /// Invalidates the cache of this node, as the other nodes are notified on commit.
+    fn invalidate_cache(&self, topic: Topic) {
+        self.cache.invalidate(topic)
+    }
+
+    async fn is_stop_word(&self, kind: &str, lang: &str, word: &str) -> Result<bool> {
+        let count: i64 = crate::schema::stop_words::table
+            .filter(crate::schema::stop_words::kind.eq(kind))
+            .filter(crate::schema::stop_words::lang.eq(lang))
+            .filter(crate::schema::stop_words::word.eq(word))
+            .count()
+            .get_result(
+                &mut *self
+                    .lock_connection("is_stop_word", &(kind, lang, word))
+                    .await,
+            )?;
+        Ok(count > 0)
+    }
+
+    fn ensure_feature_enabled(&self, feature: Feature) -> Result<()> {
+        self.config.ensure_feature_enabled(feature)?;
+
+        if feature.is_write() && self.is_read_only() {
+            bail!(IpdisError::ReadOnly)
+        }
+        Ok(())
+    }
+    /// Ensures that the gateway putting the record has signed the delegation for it, as permitted.
+    fn ensure_delegation<T>(
+        &self,
+        delegation: &GuaranteeSigned<Delegation>,
+        record: &GuaranteeSigned<T>,
+    ) -> Result<()> {
+        let gateway = &record.guarantee.account;
+        if &delegation.guarantee.account != gateway {
+            bail!("the delegation is not signed by the gateway: {gateway}")
+        }
+        delegation.verify(None)?;
+        delegation.data.data.validate(&record.nonce.0)?;
+
+        self.config
+            .ensure_delegate(gateway, &delegation.data.data.principal)
+    }
+
+    /// Ensures that the node announces itself to itself, see `Self::get_members_unchecked`.
+    fn ensure_announcement(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+    ) -> Result<()> {
+        let guarantor = self.ipiis.account_me().account_ref();
+        if path.guarantee.account != guarantor || on_behalf_of.is_some() {
+            bail!(
+                "the membership may only be announced by the node itself: {}",
+                path.guarantee.account,
+            )
+        }
+        Ok(())
+    }
+
+    /// Returns the store of the segments, which is required once the words have been tiered out.
+    fn segment_store(&self) -> Result<&(dyn BackupStore + Send + Sync)> {
+        self.segments
+            .as_deref()
+            .ok_or_else(|| anyhow!("the store of the tiered words is not configured"))
+    }
+
+    /// Reads back the indexed words from their segments, with the columns overridden by the index.
+    async fn read_tiered(
+        &self,
+        segments: &[crate::models::words_segments::WordsSegment],
+        index: &[crate::models::words_segments::WordTiered],
+    ) -> Result<Vec<BackupWord>> {
+        if index.is_empty() {
+            return Ok(vec![]);
+        }
+        let store = self.segment_store()?;
+
+        let mut words = Vec::with_capacity(index.len());
+        for segment in segments {
+            let object = WordsSegmentObject::from_bytes(&store.get(&segment.hash.parse()?).await?)?;
+            object.ensure_indexed(segment)?;
+
+            let mut objects: ::std::collections::BTreeMap<_, _> = object
+                .records
+                .words
+                .into_iter()
+                .map(|word| (word.metadata.nonce, word))
+                .collect();
+            for tiered in index
+                .iter()
+                .filter(|tiered| tiered.segment_id == segment.id)
+            {
+                match objects.remove(&tiered.nonce.as_u128()) {
+                    Some(word) => words.push(crate::tiering::overlay(word, tiered)),
+                    None => bail!(
+                        "the tiered word is missing in its segment: {}",
+                        tiered.nonce
+                    ),
+                }
+            }
+        }
+        Ok(words)
+    }
 }
 
 #[async_trait]
@@ -96,6 +476,8 @@ where
         guarantee: &AccountRef,
         guarantor: &AccountRef,
     ) -> Result<()> {
+        self.config.ensure_allowed(guarantee)?;
+
         let guarantor_now = self.ipiis.account_me().account_ref();
         if guarantor != &guarantor_now {
             bail!("failed to authenticate the guarantor")
@@ -106,27 +488,162 @@ where
             return Ok(());
         }
 
-        crate::schema::accounts_guarantees::table
-            .limit(1)
-            .filter(crate::schema::accounts_guarantees::guarantee.eq(guarantee.to_string()))
-            .filter(crate::schema::accounts_guarantees::guarantor.eq(guarantor.to_string()))
-            .filter(
-                crate::schema::accounts_guarantees::expiration_date
-                    .ge(now)
-                    .or(crate::schema::accounts_guarantees::expiration_date.is_null()),
-            )
-            .execute(&mut *self.connection.lock().await)
-            .map_err(Into::into)
-            .and_then(|count| {
-                if count > 0 {
-                    Ok(())
+        let registry = Registry {
+            diagnostics: &self.diagnostics,
+            pool: &self.pool,
+            now: self.now(),
+        };
+        if self
+            .auth
+            .authenticate(registry, guarantee, guarantor)
+            .await?
+        {
+            Ok(())
+        } else {
+            bail!("failed to authenticate the guarantee")
+        }
+    }
+
+    async fn ensure_admin(&self, guarantee: &AccountRef, guarantor: &AccountRef) -> Result<()> {
+        self.config.ensure_allowed(guarantee)?;
+
+        let guarantor_now = self.ipiis.account_me().account_ref();
+        if guarantor != &guarantor_now {
+            bail!("failed to authenticate the guarantor")
+        }
+
+        // the server itself is always an admin
+        if guarantee == guarantor || self.config.is_admin(guarantee) {
+            Ok(())
+        } else {
+            bail!("failed to authenticate the admin")
+        }
+    }
+
+    async fn get_kind_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetKind,
+    ) -> Result<Option<KindInfo>> {
+        let mut conn = self.lock_connection("get_kind", &query.kind).await;
+        let filter = crate::schema::kinds::table
+            .filter(crate::schema::kinds::kind.eq(query.kind.to_string()));
+
+        let record = if self.schema.supports(SchemaVersion::KINDS_NORMALIZATION) {
+            filter
+                .get_results::<crate::models::kinds::Kind>(&mut *conn)?
+                .pop()
+        } else {
+            filter
+                .select(KINDS_V22_COLUMNS)
+                .get_results::<crate::models::kinds::KindV22>(&mut *conn)?
+                .pop()
+                .map(Into::into)
+        };
+        record.map(kind_from_record).transpose()
+    }
+
+    async fn get_kind_page_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetKinds,
+    ) -> Result<Page<KindInfo>> {
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+        self.config
+            .ensure_query_rows(query.end_index - query.start_index)?;
+
+        let (total, records) = {
+            let mut conn = self.lock_connection("get_kind_page", query).await;
+
+            let total: i64 = crate::schema::kinds::table.count().get_result(&mut *conn)?;
+            let page = crate::schema::kinds::table
+                .order((
+                    crate::schema::kinds::name.asc(),
+                    crate::schema::kinds::id.asc(),
+                ))
+                .offset(query.start_index.into())
+                .limit((query.end_index - query.start_index).into());
+            let records: Vec<crate::models::kinds::Kind> =
+                if self.schema.supports(SchemaVersion::KINDS_NORMALIZATION) {
+                    page.get_results(&mut *conn)?
                 } else {
-                    bail!("failed to authenticate the guarantee")
-                }
-            })
+                    page.select(KINDS_V22_COLUMNS)
+                        .get_results::<crate::models::kinds::KindV22>(&mut *conn)?
+                        .into_iter()
+                        .map(Into::into)
+                        .collect()
+                };
+            (total, records)
+        };
+
+        let items = records
+            .into_iter()
+            .map(kind_from_record)
+            .collect::<Result<_>>()?;
+
+        Ok(Page::new(
+            items,
+            query.start_index,
+            query.end_index,
+            Some(total.try_into()?),
+        ))
+    }
+
+    async fn acquire_writer_lease_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &AcquireWriterLease,
+    ) -> Result<WriterLease> {
+        if self.is_read_only() {
+            bail!(IpdisError::ReadOnly)
+        }
+        if query.ttl_ms == 0 {
+            bail!("the ttl of the writer lease should be positive")
+        }
+        self.config.ensure_writer_lease_ttl(query.ttl_ms)?;
+
+        // the trusted callers hold the lease on behalf of the server
+        let holder = match guarantee {
+            Some(guarantee) => *guarantee,
+            None => self.ipiis.account_me().account_ref(),
+        };
+        let kind = query.kind.to_string();
+        let now = self.now();
+        let expiration_date = now
+            .checked_add_signed(::ipis::core::chrono::Duration::milliseconds(
+                query.ttl_ms.try_into()?,
+            ))
+            .ok_or(IpdisError::LeaseTooLong {
+                limit_ms: self.config.max_writer_lease_ttl.as_millis().try_into()?,
+            })?;
+
+        let record = self
+            .lock_connection("acquire_writer_lease", &kind)
+            .await
+            .transaction::<_, Error, _>(|conn| {
+                crate::lease::acquire(
+                    conn,
+                    &kind,
+                    &holder.to_string(),
+                    query.token,
+                    now,
+                    expiration_date,
+                )
+            })?;
+
+        Ok(WriterLease {
+            kind: query.kind,
+            holder,
+            token: record.token.try_into()?,
+            expiration_time: record.expiration_date.timestamp_millis(),
+        })
     }
 
     async fn add_guarantee_unchecked(&self, guarantee: &GuaranteeSigned<AccountRef>) -> Result<()> {
+        self.ensure_feature_enabled(Feature::Guarantee)?;
+
         let guarantee = self.ipiis.sign_as_guarantor(*guarantee)?;
 
         let record = crate::models::accounts_guarantees::NewAccountsGuarantee {
@@ -141,22 +658,108 @@ where
 
         ::diesel::insert_into(crate::schema::accounts_guarantees::table)
             .values(&record)
-            .execute(&mut *self.connection.lock().await)
+            .execute(
+                &mut *self
+                    .lock_connection("add_guarantee", &record.guarantee)
+                    .await,
+            )
             .map(|_| ())
             .map_err(Into::into)
     }
 
-    async fn get_dyn_path_unchecked<Path>(
+    async fn link_account_successor_unchecked(
+        &self,
+        proof: &GuaranteeSigned<LinkAccountSuccessor>,
+    ) -> Result<()> {
+        self.ensure_feature_enabled(Feature::Guarantee)?;
+
+        let successor = &proof.guarantee.account;
+        let predecessor = &proof.data.data.predecessor;
+        proof.data.data.validate(successor)?;
+
+        // both keys should agree on the succession
+        proof.verify(None)?;
+        predecessor.verify(None)?;
+
+        let record = crate::models::accounts_successors::NewAccountsSuccessor {
+            nonce: proof.nonce.0 .0,
+            account: predecessor.guarantee.account.to_string(),
+            successor: successor.to_string(),
+            guarantor: proof.data.guarantor.to_string(),
+            account_signature: predecessor.guarantee.signature.to_string(),
+            successor_signature: proof.guarantee.signature.to_string(),
+            created_date: self.now(),
+        };
+
+        self.lock_connection("link_account_successor", &record.account)
+            .await
+            .transaction::<_, Error, _>(|conn| {
+                // the chains are linked one at a time, not to close a cycle concurrently
+                crate::lock::serialize(conn, "accounts_successors")?;
+
+                if crate::succession::is_retired(conn, &record.account)? {
+                    bail!(IpdisError::Duplicate {
+                        resource: format!("account_successor/{}", &record.account),
+                    })
+                }
+                if crate::succession::principals(conn, &record.account)?.contains(&record.successor)
+                {
+                    bail!("the successor should not be a predecessor of the account")
+                }
+
+                ::diesel::insert_into(crate::schema::accounts_successors::table)
+                    .values(&record)
+                    .execute(conn)?;
+                Ok(())
+            })
+    }
+
+    async fn get_account_chain_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetAccountChain,
+    ) -> Result<Vec<AccountRef>> {
+        self.ensure_feature_enabled(Feature::Guarantee)?;
+
+        let account = query.account.to_string();
+        crate::succession::chain(
+            &mut *self.lock_connection("get_account_chain", &account).await,
+            &account,
+        )?
+        .into_iter()
+        .map(|account| account.parse())
+        .collect()
+    }
+
+    async fn get_dyn_path_record_unchecked<Path>(
         &self,
         guarantee: Option<&AccountRef>,
         path: &DynPath<Path>,
-    ) -> Result<Option<GuarantorSigned<DynPath<::ipis::path::Path>>>>
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
     where
         Path: Copy + Send + Sync,
     {
+        self.ensure_feature_enabled(Feature::DynPathGet)?;
+
         let guarantor = self.ipiis.account_me().account_ref();
         let guarantee = guarantee.unwrap_or(&guarantor);
 
+        let key = CacheKey::new(&path.namespace, &(guarantee, &path.kind, &path.word));
+        if let Some(record) = self.cache.get_dyn_path(&key) {
+            // the cached record may have been expired since, revealing an older one
+            let now = self.clock.now();
+            let is_expired = record
+                .as_ref()
+                .and_then(|record| record.data.data.data.expiration_date)
+                .map_or(false, |expiration_date| expiration_date < now);
+            if !is_expired {
+                return Ok(record);
+            }
+        }
+
+        let mut conn = self
+            .lock_connection("get_dyn_path", &(&path.namespace, &path.kind, &path.word))
+            .await;
         let mut records: Vec<crate::models::dyn_paths::DynPath> = crate::schema::dyn_paths::table
             .order(crate::schema::dyn_paths::created_date.desc())
             .limit(1)
@@ -164,205 +767,619 @@ where
             .filter(crate::schema::dyn_paths::guarantor.eq(guarantor.to_string()))
             .filter(
                 crate::schema::dyn_paths::expiration_date
-                    .ge(now)
+                    .ge(self.now())
                     .or(crate::schema::dyn_paths::expiration_date.is_null()),
             )
             .filter(crate::schema::dyn_paths::namespace.eq(path.namespace.to_string()))
             .filter(crate::schema::dyn_paths::kind.eq(path.kind.to_string()))
-            .filter(crate::schema::dyn_paths::word.eq(path.word.to_string()))
-            .get_results(&mut *self.connection.lock().await)?;
-
-        match records.pop() {
-            Some(record) => Ok(Some(GuarantorSigned {
-                guarantor: Identity {
-                    account: AccountRef {
-                        public_key: record.guarantor.parse()?,
-                    },
-                    signature: record.guarantor_signature.parse()?,
-                },
-                data: GuaranteeSigned {
-                    guarantee: Identity {
-                        account: AccountRef {
-                            public_key: record.guarantee.parse()?,
-                        },
-                        signature: record.guarantee_signature.parse()?,
-                    },
-                    data: Metadata {
-                        nonce: Uuid(record.nonce).into(),
-                        created_date: NaiveDateTime(record.created_date).to_utc(),
-                        expiration_date: record.expiration_date.map(|e| NaiveDateTime(e).to_utc()),
-                        guarantor: record.guarantor.parse()?,
-                        data: DynPath {
-                            namespace: record.namespace.parse()?,
-                            kind: record.kind.parse()?,
-                            word: record.word.parse()?,
-                            path: ::ipis::path::Path {
-                                value: record.path.parse()?,
-                                len: record.len.try_into()?,
-                            },
-                        },
-                    },
-                },
-            })),
-            None => Ok(None),
-        }
-    }
-
-    async fn put_dyn_path_unchecked(&self, path: &GuaranteeSigned<DynPath<Path>>) -> Result<()> {
-        let path = self.ipiis.sign_as_guarantor(*path)?;
+            .filter(crate::schema::dyn_paths::word.eq(self.cipher.encrypt(path.word.to_string())))
+            .get_results(&mut *conn)?;
+        crate::retention::restore(&mut conn, crate::export::TABLE_DYN_PATHS, &mut records)?;
+        crate::retention::retain_signed(&mut records);
+        drop(conn);
 
-        let record = crate::models::dyn_paths::NewDynPath {
-            nonce: path.nonce.0 .0,
-            guarantee: path.guarantee.account.to_string(),
-            guarantor: path.guarantor.account.to_string(),
-            guarantee_signature: path.guarantee.signature.to_string(),
-            guarantor_signature: path.guarantor.signature.to_string(),
-            created_date: path.created_date.naive_utc(),
-            expiration_date: path.expiration_date.map(|e| e.naive_utc()),
-            namespace: path.data.namespace.to_string(),
-            kind: path.data.kind.to_string(),
-            word: path.data.word.to_string(),
-            path: path.data.path.value.to_string(),
-            len: path.data.path.len.try_into()?,
+        let record = match records.pop() {
+            Some(record) => Some(WithMetadata {
+                data: dyn_path_from_record(&self.cipher, &record)?,
+                metadata: record.metadata,
+            }),
+            None => None,
         };
 
-        ::diesel::insert_into(crate::schema::dyn_paths::table)
-            .values(&record)
-            .execute(&mut *self.connection.lock().await)
-            .map(|_| ())
-            .map_err(Into::into)
+        self.cache.put_dyn_path(key, record.clone());
+        Ok(record)
     }
 
-    async fn get_word_many_unchecked(
+    async fn get_dyn_path_many_unchecked(
         &self,
         guarantee: Option<&AccountRef>,
-        query: &GetWords,
-    ) -> Result<Vec<GuarantorSigned<WordHash>>> {
-        if query.end_index <= query.start_index {
-            bail!("malformed index: end_index should be bigger than start_index")
+        paths: &[DynPath<()>],
+    ) -> Result<Vec<Option<GuarantorSigned<DynPath<::ipis::path::Path>>>>> {
+        self.ensure_feature_enabled(Feature::DynPathGet)?;
+        self.config.ensure_batch_size(paths.len())?;
+
+        if paths.is_empty() {
+            return Ok(vec![]);
         }
 
         let guarantor = self.ipiis.account_me().account_ref();
         let guarantee = guarantee.unwrap_or(&guarantor);
 
-        let sql = crate::schema::words::table
-            .order(crate::schema::words::id.desc())
-            // TODO: improve performance (pagination: rather than offset & limit ?)
-            .offset(query.start_index.into())
-            .limit((query.end_index - query.start_index).into())
-            .filter(crate::schema::words::guarantee.eq(guarantee.to_string()))
-            .filter(crate::schema::words::guarantor.eq(guarantor.to_string()))
-            .filter(
-                crate::schema::words::expiration_date
-                    .ge(now)
-                    .or(crate::schema::words::expiration_date.is_null()),
-            )
-            .filter(crate::schema::words::namespace.eq(query.word.namespace.to_string()))
-            .filter(crate::schema::words::lang.eq(query.word.text.lang.to_string()));
+        // the requested paths are joined as a table, so that they are resolved in a single query
+        let keys: Vec<_> = paths
+            .iter()
+            .enumerate()
+            .map(|(idx, path)| {
+                let offset = 4 * idx + 4;
+                format!(
+                    "(${}::INT4, ${}, ${}, ${})",
+                    offset,
+                    offset + 1,
+                    offset + 2,
+                    offset + 3,
+                )
+            })
+            .collect();
+        let sql = format!(
+            "SELECT DISTINCT ON (keys.idx) keys.idx, dyn_paths.*
+            FROM (VALUES {keys}) AS keys (idx, namespace, kind, word)
+            INNER JOIN dyn_paths ON dyn_paths.namespace = keys.namespace
+                AND dyn_paths.kind = keys.kind AND dyn_paths.word = keys.word
+            WHERE dyn_paths.guarantee = $1 AND dyn_paths.guarantor = $2
+                AND (dyn_paths.expiration_date IS NULL OR dyn_paths.expiration_date >= $3)
+            ORDER BY keys.idx, dyn_paths.created_date DESC",
+            keys = keys.join(", "),
+        );
 
-        let records: Vec<crate::models::words::Word> = match query.parent {
-            GetWordsParent::None => sql
-                .filter(crate::schema::words::word.eq(query.word.text.msg.to_string()))
-                .get_results(&mut *self.connection.lock().await)?,
-            GetWordsParent::Duplicated => sql
-                .filter(crate::schema::words::parent.eq(query.word.text.msg.to_string()))
-                .get_results(&mut *self.connection.lock().await)?,
-        };
+        let query = ::diesel::sql_query(sql)
+            .into_boxed()
+            .bind::<::diesel::sql_types::Text, _>(guarantee.to_string())
+            .bind::<::diesel::sql_types::Text, _>(guarantor.to_string())
+            .bind::<::diesel::sql_types::Timestamp, _>(self.now());
+        let query =
+            paths
+                .iter()
+                .enumerate()
+                .try_fold(query, |query, (idx, path)| -> Result<_> {
+                    Ok(query
+                        .bind::<::diesel::sql_types::Integer, _>(i32::try_from(idx)?)
+                        .bind::<::diesel::sql_types::Text, _>(path.namespace.to_string())
+                        .bind::<::diesel::sql_types::Text, _>(path.kind.to_string())
+                        .bind::<::diesel::sql_types::Text, _>(
+                            self.cipher.encrypt(path.word.to_string()),
+                        ))
+                })?;
 
-        records
+        let mut conn = self.lock_connection("get_dyn_path_many", paths).await;
+        let matches: Vec<crate::models::dyn_paths::DynPathMatch> = query.load(&mut *conn)?;
+        let (indices, mut records): (Vec<_>, Vec<_>) = matches
             .into_iter()
-            .map(|record| {
-                Ok(GuarantorSigned {
-                    guarantor: Identity {
-                        account: AccountRef {
-                            public_key: record.guarantor.parse()?,
-                        },
-                        signature: record.guarantor_signature.parse()?,
-                    },
-                    data: GuaranteeSigned {
-                        guarantee: Identity {
-                            account: AccountRef {
-                                public_key: record.guarantee.parse()?,
-                            },
-                            signature: record.guarantee_signature.parse()?,
-                        },
-                        data: Metadata {
-                            nonce: Uuid(record.nonce).into(),
-                            created_date: NaiveDateTime(record.created_date).to_utc(),
-                            expiration_date: record
-                                .expiration_date
-                                .map(|e| NaiveDateTime(e).to_utc()),
-                            guarantor: record.guarantor.parse()?,
-                            data: WordHash {
-                                key: WordKeyHash {
-                                    namespace: record.namespace.parse()?,
-                                    text: TextHash {
-                                        lang: record.lang.parse()?,
-                                        msg: record.word.parse()?,
-                                    },
-                                },
-                                kind: record.kind.parse()?,
-                                relpath: record.relpath,
-                                path: Path {
-                                    value: record.path.parse()?,
-                                    len: record.len.try_into()?,
-                                },
-                            },
-                        },
-                    },
-                })
-            })
-            .collect()
+            .map(|matched| (matched.idx, matched.record))
+            .unzip();
+        crate::retention::restore(&mut conn, crate::export::TABLE_DYN_PATHS, &mut records)?;
+        drop(conn);
+
+        let mut resolved = Vec::with_capacity(paths.len());
+        resolved.resize_with(paths.len(), || None);
+        for (idx, record) in indices.into_iter().zip(&records) {
+            // the latest records keep their signatures, see `crate::retention::sweep`
+            if let (Some(_), Some(_)) = record.signatures() {
+                resolved[usize::try_from(idx)?] = Some(dyn_path_from_record(&self.cipher, record)?);
+            }
+        }
+        Ok(resolved)
+    }
+
+    async fn get_members_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetMembers,
+    ) -> Result<Vec<Member>> {
+        self.ensure_feature_enabled(Feature::DynPathGet)?;
+
+        let since = self.now() - ::ipis::core::chrono::Duration::seconds(query.ttl_secs.into());
+
+        // the nodes may announce themselves to several nodes sharing the database
+        let records: Vec<(String, String, NaiveDateTime)> = crate::schema::dyn_paths::table
+            .order(crate::schema::dyn_paths::created_date.desc())
+            .filter(crate::schema::dyn_paths::namespace.eq(query.namespace.to_string()))
+            .filter(crate::schema::dyn_paths::kind.eq(membership::KIND.to_string()))
+            .filter(crate::schema::dyn_paths::created_date.ge(since))
+            .filter(
+                crate::schema::dyn_paths::expiration_date
+                    .ge(self.now())
+                    .or(crate::schema::dyn_paths::expiration_date.is_null()),
+            )
+            .select((
+                crate::schema::dyn_paths::guarantee,
+                crate::schema::dyn_paths::guarantor,
+                crate::schema::dyn_paths::created_date,
+            ))
+            .limit(self.config.max_query_rows.into())
+            .get_results(&mut *self.lock_connection("get_members", query).await)?;
+
+        // only the nodes of the cluster may announce themselves, each to itself,
+        // as the announcements may have been written before enforced or restored from the backups
+        let me = self.ipiis.account_me().account_ref();
+        let mut members: Vec<Member> = Vec::with_capacity(records.len());
+        for (guarantee, guarantor, created_date) in records {
+            if guarantee != guarantor {
+                continue;
+            }
+            let account = guarantee.parse()?;
+            if account != me && !self.config.is_cluster_member(&account) {
+                continue;
+            }
+            if members.iter().all(|member| member.account != account) {
+                members.push(Member {
+                    account,
+                    last_seen_ms: created_date.timestamp_millis(),
+                });
+            }
+        }
+        Ok(members)
     }
 
-    async fn get_word_count_many_unchecked(
+    async fn get_record_by_nonce_unchecked(
         &self,
         guarantee: Option<&AccountRef>,
-        query: &GetWordsCounts,
-    ) -> Result<Vec<GetWordsCountsOutput>> {
+        nonce: &Uuid,
+    ) -> Result<Option<SignedRecord>> {
+        self.ensure_feature_enabled(Feature::DynPathGet)?;
+        self.ensure_feature_enabled(Feature::WordGet)?;
+
         let guarantor = self.ipiis.account_me().account_ref();
         let guarantee = guarantee.unwrap_or(&guarantor);
 
-        if query.owned {
-            let sql = crate::schema::words_counts_guarantees::table
-                .order(crate::schema::words_counts_guarantees::id.desc())
-                // TODO: improve performance (pagination: rather than offset & limit ?)
-                .offset(query.start_index.into())
-                .limit((query.end_index - query.start_index).into())
-                .filter(crate::schema::words_counts_guarantees::guarantee.eq(guarantee.to_string()))
+        let mut conn = self.lock_connection("get_record_by_nonce", nonce).await;
+
+        let mut records: Vec<crate::models::dyn_paths::DynPath> = crate::schema::dyn_paths::table
+            .limit(1)
+            .filter(crate::schema::dyn_paths::nonce.eq(nonce.0))
+            .filter(crate::schema::dyn_paths::guarantee.eq(guarantee.to_string()))
+            .filter(crate::schema::dyn_paths::guarantor.eq(guarantor.to_string()))
+            .get_results(&mut *conn)?;
+        crate::retention::restore(&mut conn, crate::export::TABLE_DYN_PATHS, &mut records)?;
+        if let Some(record) = records.pop() {
+            return dyn_path_from_record(&self.cipher, &record)
+                .map(SignedRecord::DynPath)
+                .map(Some);
+        }
+
+        let mut records: Vec<crate::models::words::Word> = crate::schema::words::table
+            .limit(1)
+            .filter(crate::schema::words::nonce.eq(nonce.0))
+            .filter(crate::schema::words::guarantee.eq(guarantee.to_string()))
+            .filter(crate::schema::words::guarantor.eq(guarantor.to_string()))
+            .get_results(&mut *conn)?;
+        crate::retention::restore(&mut conn, crate::export::TABLE_WORDS, &mut records)?;
+        if let Some(record) = records.pop() {
+            return word_from_record(&self.cipher, &record)
+                .map(SignedRecord::Word)
+                .map(Some);
+        }
+
+        // the word may have been tiered out
+        if !self.schema.supports(SchemaVersion::WORDS_TIERED) {
+            return Ok(None);
+        }
+        let index: Vec<crate::models::words_segments::WordTiered> =
+            crate::schema::words_tiered::table
+                .limit(1)
+                .filter(crate::schema::words_tiered::nonce.eq(nonce.0))
+                .filter(crate::schema::words_tiered::guarantee.eq(guarantee.to_string()))
+                .filter(crate::schema::words_tiered::guarantor.eq(guarantor.to_string()))
+                .get_results(&mut *conn)?;
+        let segments = crate::tiering::find_segments(&mut conn, &index)?;
+        drop(conn);
+
+        match self.read_tiered(&segments, &index).await?.pop() {
+            Some(word) => word_from_record(&self.cipher, &crate::tiering::into_record(word, 0))
+                .map(SignedRecord::Word)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_oplog_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetOplog,
+    ) -> Result<Oplog> {
+        self.config.ensure_query_rows(query.limit)?;
+
+        let records: Vec<crate::models::oplog::OplogEntry> = crate::schema::oplog::table
+            // the watermark beyond the sequence numbers has nothing after it
+            .filter(crate::schema::oplog::id.gt(i32::try_from(query.since_seq).unwrap_or(i32::MAX)))
+            .order(crate::schema::oplog::id)
+            .limit(query.limit.into())
+            .get_results(&mut *self.lock_connection("get_oplog", query).await)?;
+
+        let until_seq = match records.last() {
+            Some(record) => record.id.try_into()?,
+            None => query.since_seq,
+        };
+        let has_more = records.len() >= query.limit as usize;
+
+        let entries = records
+            .into_iter()
+            .map(crate::oplog::entry_from_record)
+            .collect::<Result<_>>()?;
+
+        Ok(Oplog {
+            entries,
+            until_seq,
+            has_more,
+        })
+    }
+
+    async fn get_inclusion_proof_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<InclusionProof>> {
+        let mut conn = self.lock_connection("get_inclusion_proof", nonce).await;
+
+        let record: crate::models::oplog::OplogEntry = match crate::schema::oplog::table
+            .filter(crate::schema::oplog::nonce.eq(nonce.0))
+            .order(crate::schema::oplog::id)
+            .limit(1)
+            .get_results(&mut *conn)?
+            .pop()
+        {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+        let root: crate::models::oplog::OplogRoot = match crate::schema::oplog_roots::table
+            .order(crate::schema::oplog_roots::tree_size.desc())
+            .limit(1)
+            .get_results(&mut *conn)?
+            .pop()
+        {
+            Some(root) => root,
+            None => return Ok(None),
+        };
+
+        // the position of the entry, as the sequence numbers may have gaps
+        let index: i64 = crate::schema::oplog::table
+            .filter(crate::schema::oplog::id.lt(record.id))
+            .count()
+            .get_result(&mut *conn)?;
+        if index >= root.tree_size {
+            return Ok(None);
+        }
+
+        let leaves = crate::oplog::leaves(&mut conn, Some(root.tree_size))?;
+        let index: usize = index.try_into()?;
+        Ok(Some(InclusionProof {
+            entry: crate::oplog::entry_from_record(record)?,
+            index: index.try_into()?,
+            path: merkle::path(&leaves, index),
+            root: oplog_root_from_record(&root)?,
+        }))
+    }
+
+    async fn put_dyn_path_fenced_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+    ) -> Result<PutReceipt> {
+        self.ensure_feature_enabled(Feature::DynPathPut)?;
+        self.config.ensure_metadata_len(metadata)?;
+        if let Some(delegation) = on_behalf_of {
+            self.ensure_delegation(delegation, path)?;
+        }
+        if path.data.kind == *membership::KIND {
+            self.ensure_announcement(path, on_behalf_of)?;
+        }
+
+        let path = self.ipiis.sign_as_guarantor(*path)?;
+
+        let record = crate::models::dyn_paths::NewDynPath {
+            nonce: path.nonce.0 .0,
+            guarantee: path.guarantee.account.to_string(),
+            guarantor: path.guarantor.account.to_string(),
+            guarantee_signature: Some(path.guarantee.signature.to_string()),
+            guarantor_signature: Some(path.guarantor.signature.to_string()),
+            created_date: path.created_date.naive_utc(),
+            expiration_date: path.expiration_date.map(|e| e.naive_utc()),
+            namespace: path.data.namespace.to_string(),
+            kind: path.data.kind.to_string(),
+            word: self.cipher.encrypt(path.data.word.to_string()),
+            path: path.data.path.value.to_string(),
+            len: path.data.path.len.try_into()?,
+            metadata: metadata.map(ToOwned::to_owned),
+            on_behalf_of: on_behalf_of.map(|delegation| delegation.data.data.principal.to_string()),
+        };
+
+        let conflict = self.config.dyn_path_conflict.get(&path.data.kind);
+        let now = self.now();
+        let outbox_enabled = self.config.outbox_enabled;
+        let usage_enabled =
+            self.config.usage_enabled && self.schema.supports(SchemaVersion::ACCOUNTS_USAGE);
+        let oplog = if self.config.oplog_enabled {
+            Some(crate::oplog::entry(&SignedRecord::DynPath(path), now)?)
+        } else {
+            None
+        };
+        let topic = Topic::dyn_path(&record.namespace);
+        let server_time = now.timestamp_millis();
+
+        let id = self
+            .lock_connection(
+                "put_dyn_path",
+                &(&record.namespace, &record.kind, &record.word),
+            )
+            .await
+            .transaction::<i32, Error, _>(|conn| {
+                crate::lease::ensure_fenced(
+                    conn,
+                    &record.kind,
+                    &record.guarantee,
+                    fencing_token,
+                    now,
+                )?;
+
+                // the replayed record is accepted once, so that the retries are idempotent
+                if let Some(id) = find_dyn_path_by_nonce(conn, &record.nonce)? {
+                    return Ok(id);
+                }
+
+                if conflict != DynPathConflictPolicy::Append {
+                    // the existing ones are checked one at a time, not to miss the concurrent puts
+                    let resource = format!(
+                        "dyn_path/{}/{}/{}/{}",
+                        &record.guarantee, &record.namespace, &record.kind, &path.data.word,
+                    );
+                    crate::lock::serialize(conn, &resource)?;
+
+                    let existing = crate::schema::dyn_paths::table
+                        .filter(crate::schema::dyn_paths::guarantee.eq(&record.guarantee))
+                        .filter(crate::schema::dyn_paths::namespace.eq(&record.namespace))
+                        .filter(crate::schema::dyn_paths::kind.eq(&record.kind))
+                        .filter(crate::schema::dyn_paths::word.eq(&record.word))
+                        .filter(
+                            crate::schema::dyn_paths::expiration_date
+                                .ge(now)
+                                .or(crate::schema::dyn_paths::expiration_date.is_null()),
+                        );
+
+                    match conflict {
+                        DynPathConflictPolicy::Append => {}
+                        DynPathConflictPolicy::ReplaceLatest => {
+                            ::diesel::delete(existing).execute(conn)?;
+                        }
+                        DynPathConflictPolicy::RejectDuplicate => {
+                            let count: i64 = existing.count().get_result(conn)?;
+                            if count > 0 {
+                                bail!(IpdisError::Duplicate { resource })
+                            }
+                        }
+                    }
+                }
+
+                let id = match ::diesel::insert_into(crate::schema::dyn_paths::table)
+                    .values(&record)
+                    .on_conflict_do_nothing()
+                    .returning(crate::schema::dyn_paths::id)
+                    .get_result(conn)
+                    .optional()?
+                {
+                    Some(id) => id,
+                    // raced with a replay of the same record
+                    None => {
+                        return find_dyn_path_by_nonce(conn, &record.nonce)?
+                            .ok_or_else(|| anyhow!("the replayed dyn_path has been deleted"))
+                    }
+                };
+
+                if usage_enabled {
+                    crate::usage::record(conn, crate::export::TABLE_DYN_PATHS, id, now)?;
+                }
+
+                if let Some(entry) = &oplog {
+                    crate::oplog::append(conn, entry)?;
+                }
+                if outbox_enabled {
+                    crate::outbox::push(
+                        conn,
+                        crate::outbox::TOPIC_DYN_PATH_PUT,
+                        format!("{}/{}/{}", &record.namespace, &record.kind, &record.word),
+                        now,
+                    )?;
+                }
+                crate::cache::notify(conn, &topic)?;
+                Ok(id)
+            })?;
+
+        self.invalidate_cache(topic);
+        Ok(PutReceipt {
+            nonce: path.nonce.0,
+            seq: id.try_into()?,
+            guarantor_signature: path.guarantor.signature,
+            server_time,
+        })
+    }
+
+    async fn get_word_record_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+        self.config
+            .ensure_query_rows(query.end_index - query.start_index)?;
+
+        let guarantor = self.ipiis.account_me().account_ref();
+        let guarantee = guarantee.unwrap_or(&guarantor);
+        let msg = self.cipher.encrypt(query.word.text.msg.to_string());
+
+        let sql = || {
+            let sql = crate::schema::words::table
+                .filter(crate::schema::words::guarantee.eq(guarantee.to_string()))
+                .filter(crate::schema::words::guarantor.eq(guarantor.to_string()))
                 .filter(
-                    crate::schema::words_counts_guarantees::namespace
-                        .eq(query.word.namespace.to_string()),
+                    crate::schema::words::expiration_date
+                        .ge(self.now())
+                        .or(crate::schema::words::expiration_date.is_null()),
                 )
+                .filter(crate::schema::words::namespace.eq(query.word.namespace.to_string()))
+                .filter(crate::schema::words::lang.eq(query.word.text.lang.to_string()))
+                // the records whose signatures have been dropped are not served,
+                // so they are skipped before paginated, not to end the pages early
                 .filter(
-                    crate::schema::words_counts_guarantees::lang.eq(query
-                        .word
-                        .text
-                        .lang
-                        .to_string()),
-                );
-
-            let records: Vec<crate::models::words::WordCountGuarantee> = if query.parent {
-                sql.filter(
-                    crate::schema::words_counts_guarantees::parent.eq(query
-                        .word
-                        .text
-                        .msg
-                        .to_string()),
+                    crate::schema::words::guarantee_signature
+                        .is_not_null()
+                        .and(crate::schema::words::guarantor_signature.is_not_null())
+                        .or(crate::schema::words::id.eq_any(
+                            crate::schema::signatures_archive::table
+                                .select(crate::schema::signatures_archive::record_id)
+                                .filter(
+                                    crate::schema::signatures_archive::table_name
+                                        .eq(crate::export::TABLE_WORDS),
+                                ),
+                        )),
                 )
-                .get_results(&mut *self.connection.lock().await)?
+                .into_boxed();
+
+            match query.parent {
+                GetWordsParent::None => sql.filter(crate::schema::words::word.eq(&msg)),
+                GetWordsParent::Duplicated => sql.filter(crate::schema::words::parent.eq(&msg)),
+            }
+        };
+
+        let (total, records) = {
+            let mut conn = self.lock_connection("get_word_page", query).await;
+
+            let total: Option<i64> = if query.with_total {
+                Some(sql().count().get_result(&mut *conn)?)
             } else {
-                sql.filter(
-                    crate::schema::words_counts_guarantees::word.eq(query
-                        .word
-                        .text
-                        .msg
-                        .to_string()),
-                )
-                .get_results(&mut *self.connection.lock().await)?
+                None
+            };
+            let mut records: Vec<crate::models::words::Word> = sql()
+                .order(crate::schema::words::id.desc())
+                // TODO: improve performance (pagination: rather than offset & limit ?)
+                .offset(query.start_index.into())
+                .limit((query.end_index - query.start_index).into())
+                .get_results(&mut *conn)?;
+            crate::retention::restore(&mut conn, crate::export::TABLE_WORDS, &mut records)?;
+            (total, records)
+        };
+
+        let items = records
+            .into_iter()
+            .map(|record| {
+                Ok(WithMetadata {
+                    data: word_from_record(&self.cipher, &record)?,
+                    metadata: record.metadata,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Page::new(
+            items,
+            query.start_index,
+            query.end_index,
+            total.map(TryInto::try_into).transpose()?,
+        ))
+    }
+
+    async fn get_word_count_page_fresh_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+        max_staleness: Option<Duration>,
+    ) -> Result<Fresh<Page<GetWordsCountsOutput>>> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+        self.config
+            .ensure_query_rows(query.end_index - query.start_index)?;
+
+        let guarantor = self.ipiis.account_me().account_ref();
+        let guarantee = guarantee.unwrap_or(&guarantor);
+
+        // the aggregated counts are noised for the other accounts, except the admins
+        let is_noised = !query.owned
+            && guarantee != &guarantor
+            && !self.config.is_admin(guarantee)
+            && self.config.count_noise.is_enabled();
+
+        let computed_at = self.now().timestamp_millis();
+
+        let key = CacheKey::new(&query.word.namespace, &(guarantee, query));
+        if let Some(cached) = self.cache.get_word_counts(&key) {
+            let is_fresh = match max_staleness {
+                Some(max_staleness) => cached.staleness_at(computed_at) <= max_staleness,
+                None => true,
+            };
+            if is_fresh {
+                return Ok(Fresh {
+                    data: self.with_count_noise(is_noised, cached.data),
+                    computed_at: cached.computed_at,
+                    from_cache: true,
+                });
+            }
+        }
+
+        let msg = self.cipher.encrypt(query.word.text.msg.to_string());
+
+        let (total, items) = if query.owned {
+            // the records of the predecessors are owned by the successor as well
+            let principals = crate::succession::principals(
+                &mut *self.lock_connection("get_account_chain", guarantee).await,
+                &guarantee.to_string(),
+            )?;
+
+            let sql = || {
+                let sql = crate::schema::words_counts_guarantees::table
+                    .filter(crate::schema::words_counts_guarantees::guarantee.eq_any(&principals))
+                    .filter(
+                        crate::schema::words_counts_guarantees::namespace
+                            .eq(query.word.namespace.to_string()),
+                    )
+                    .filter(
+                        crate::schema::words_counts_guarantees::lang.eq(query
+                            .word
+                            .text
+                            .lang
+                            .to_string()),
+                    )
+                    .into_boxed();
+
+                if query.parent {
+                    sql.filter(crate::schema::words_counts_guarantees::parent.eq(&msg))
+                } else {
+                    sql.filter(crate::schema::words_counts_guarantees::word.eq(&msg))
+                }
+            };
+
+            let (total, records) = {
+                let mut conn = self.lock_connection("get_word_count_page", query).await;
+
+                let total: Option<i64> = if query.with_total {
+                    Some(sql().count().get_result(&mut *conn)?)
+                } else {
+                    None
+                };
+                let records: Vec<crate::models::words::WordCountGuarantee> = sql()
+                    .order(crate::schema::words_counts_guarantees::id.desc())
+                    // TODO: improve performance (pagination: rather than offset & limit ?)
+                    .offset(query.start_index.into())
+                    .limit((query.end_index - query.start_index).into())
+                    .get_results(&mut *conn)?;
+                (total, records)
             };
 
-            records
+            let items = records
                 .into_iter()
                 .map(|record| {
                     Ok(GetWordsCountsOutput {
@@ -371,7 +1388,7 @@ where
                                 namespace: record.namespace.parse()?,
                                 text: TextHash {
                                     lang: record.lang.parse()?,
-                                    msg: record.word.parse()?,
+                                    msg: self.cipher.decrypt(&record.word)?.parse()?,
                                 },
                             },
                             kind: record.kind.parse()?,
@@ -379,25 +1396,42 @@ where
                         count: record.count.try_into()?,
                     })
                 })
-                .collect()
+                .collect::<Result<_>>()?;
+            (total, items)
         } else {
-            let sql = crate::schema::words_counts::table
-                .order(crate::schema::words_counts::id.desc())
-                // TODO: improve performance (pagination: rather than offset & limit ?)
-                .offset(query.start_index.into())
-                .limit((query.end_index - query.start_index).into())
-                .filter(crate::schema::words_counts::namespace.eq(query.word.namespace.to_string()))
-                .filter(crate::schema::words_counts::lang.eq(query.word.text.lang.to_string()));
+            let sql = || {
+                let sql = crate::schema::words_counts::table
+                    .filter(
+                        crate::schema::words_counts::namespace.eq(query.word.namespace.to_string()),
+                    )
+                    .filter(crate::schema::words_counts::lang.eq(query.word.text.lang.to_string()))
+                    .into_boxed();
 
-            let records: Vec<crate::models::words::WordCount> = if query.parent {
-                sql.filter(crate::schema::words_counts::parent.eq(query.word.text.msg.to_string()))
-                    .get_results(&mut *self.connection.lock().await)?
-            } else {
-                sql.filter(crate::schema::words_counts::word.eq(query.word.text.msg.to_string()))
-                    .get_results(&mut *self.connection.lock().await)?
+                if query.parent {
+                    sql.filter(crate::schema::words_counts::parent.eq(&msg))
+                } else {
+                    sql.filter(crate::schema::words_counts::word.eq(&msg))
+                }
+            };
+
+            let (total, records) = {
+                let mut conn = self.lock_connection("get_word_count_page", query).await;
+
+                let total: Option<i64> = if query.with_total {
+                    Some(sql().count().get_result(&mut *conn)?)
+                } else {
+                    None
+                };
+                let records: Vec<crate::models::words::WordCount> = sql()
+                    .order(crate::schema::words_counts::id.desc())
+                    // TODO: improve performance (pagination: rather than offset & limit ?)
+                    .offset(query.start_index.into())
+                    .limit((query.end_index - query.start_index).into())
+                    .get_results(&mut *conn)?;
+                (total, records)
             };
 
-            records
+            let items = records
                 .into_iter()
                 .map(|record| {
                     Ok(GetWordsCountsOutput {
@@ -406,7 +1440,7 @@ where
                                 namespace: record.namespace.parse()?,
                                 text: TextHash {
                                     lang: record.lang.parse()?,
-                                    msg: record.word.parse()?,
+                                    msg: self.cipher.decrypt(&record.word)?.parse()?,
                                 },
                             },
                             kind: record.kind.parse()?,
@@ -414,167 +1448,3174 @@ where
                         count: record.count.try_into()?,
                     })
                 })
-                .collect()
+                .collect::<Result<_>>()?;
+            (total, items)
+        };
+
+        let page = Page::new(
+            items,
+            query.start_index,
+            query.end_index,
+            total.map(TryInto::try_into).transpose()?,
+        );
+
+        self.cache.put_word_counts(
+            key,
+            Fresh {
+                data: page.clone(),
+                computed_at,
+                from_cache: true,
+            },
+        );
+        Ok(Fresh {
+            data: self.with_count_noise(is_noised, page),
+            computed_at,
+            from_cache: false,
+        })
+    }
+
+    async fn get_word_count_page_batch_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        queries: &[GetWordsCounts],
+    ) -> Result<Vec<Page<GetWordsCountsOutput>>> {
+        self.config.ensure_batch_size(queries.len())?;
+
+        let mut pages = Vec::with_capacity(queries.len());
+        for query in queries {
+            pages.push(self.get_word_count_page_unchecked(guarantee, query).await?);
         }
+        Ok(pages)
     }
 
-    async fn put_word_unchecked(
+    async fn get_word_count_all_langs_unchecked(
         &self,
-        parent: &Hash,
-        word: &GuaranteeSigned<WordHash>,
-    ) -> Result<()> {
-        let word = self.ipiis.sign_as_guarantor(*word)?;
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountAllLangs,
+    ) -> Result<u32> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
 
-        let record = crate::models::words::NewWord {
-            nonce: word.nonce.0 .0,
-            guarantee: word.guarantee.account.to_string(),
-            guarantor: word.guarantor.account.to_string(),
-            guarantee_signature: word.guarantee.signature.to_string(),
-            guarantor_signature: word.guarantor.signature.to_string(),
-            created_date: word.created_date.naive_utc(),
-            expiration_date: word.expiration_date.map(|e| e.naive_utc()),
-            namespace: word.data.key.namespace.to_string(),
-            parent: parent.to_string(),
-            lang: word.data.key.text.lang.to_string(),
-            word: word.data.key.text.msg.to_string(),
-            kind: word.data.kind.to_string(),
-            relpath: word.data.relpath,
-            path: word.data.path.value.to_string(),
-            len: word.data.path.len.try_into()?,
+        let guarantor = self.ipiis.account_me().account_ref();
+        let guarantee = guarantee.unwrap_or(&guarantor);
+
+        let namespace = query.namespace.to_string();
+        let kind = query.kind.to_string();
+        let msg = self.cipher.encrypt(query.msg.to_string());
+
+        // the counts are summed up here, as there are a few languages for each message
+        let mut conn = self
+            .lock_connection("get_word_count_all_langs", query)
+            .await;
+        let counts: Vec<i64> = if query.owned {
+            // the records of the predecessors are owned by the successor as well
+            let principals = crate::succession::principals(&mut conn, &guarantee.to_string())?;
+
+            crate::schema::words_counts_guarantees::table
+                .filter(crate::schema::words_counts_guarantees::guarantee.eq_any(principals))
+                .filter(crate::schema::words_counts_guarantees::namespace.eq(&namespace))
+                .filter(crate::schema::words_counts_guarantees::kind.eq(&kind))
+                .filter(crate::schema::words_counts_guarantees::word.eq(&msg))
+                .select(crate::schema::words_counts_guarantees::count)
+                .get_results(&mut *conn)?
+        } else {
+            crate::schema::words_counts::table
+                .filter(crate::schema::words_counts::namespace.eq(&namespace))
+                .filter(crate::schema::words_counts::kind.eq(&kind))
+                .filter(crate::schema::words_counts::word.eq(&msg))
+                .select(crate::schema::words_counts::count)
+                .get_results(&mut *conn)?
         };
 
-        self.connection
-            .lock()
-            .await
-            .transaction::<(), ::diesel::result::Error, _>(|conn| {
-                // insert the word record
-                ::diesel::insert_into(crate::schema::words::table)
-                    .values(&record)
-                    .execute(conn)?;
+        let count = counts.into_iter().sum::<i64>().try_into()?;
 
-                // check whether word exists
-                match crate::schema::words_counts::table
-                    .filter(crate::schema::words_counts::namespace.eq(&record.namespace))
-                    .filter(crate::schema::words_counts::kind.eq(&record.kind))
-                    .filter(crate::schema::words_counts::parent.eq(&record.parent))
-                    .filter(crate::schema::words_counts::lang.eq(&record.lang))
-                    .filter(crate::schema::words_counts::word.eq(&record.word))
-                    .get_results::<crate::models::words::WordCount>(conn)?
-                    .pop()
-                {
-                    // old word => append the count
-                    Some(word_count) => ::diesel::update(crate::schema::words_counts::table)
-                        .filter(crate::schema::words_counts::id.eq(word_count.id))
-                        .set(crate::schema::words_counts::count.eq(word_count.count + 1))
-                        .execute(conn)?,
-                    // new word => insert the word record
-                    None => {
-                        let word_record = crate::models::words::NewWordCount {
-                            namespace: record.namespace.clone(),
-                            kind: record.kind.clone(),
-                            parent: record.parent.clone(),
-                            lang: record.lang.clone(),
-                            word: record.word.clone(),
-                            count: 1,
-                        };
+        // the aggregated counts are noised for the other accounts, except the admins
+        let is_noised = !query.owned
+            && guarantee != &guarantor
+            && !self.config.is_admin(guarantee)
+            && self.config.count_noise.is_enabled();
+        if is_noised {
+            Ok(self
+                .config
+                .count_noise
+                .apply(&query.kind, &query.msg, count))
+        } else {
+            Ok(count)
+        }
+    }
 
-                        ::diesel::insert_into(crate::schema::words_counts::table)
-                            .values(&word_record)
-                            .execute(conn)?
-                    }
-                };
+    async fn get_word_count_delta_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountDelta,
+    ) -> Result<WordCountDelta> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+        self.config.ensure_query_rows(query.limit)?;
 
-                // check whether word of guarantee exists
-                match crate::schema::words_counts_guarantees::table
-                    .filter(crate::schema::words_counts_guarantees::guarantee.eq(&record.guarantee))
-                    .filter(crate::schema::words_counts_guarantees::kind.eq(&record.kind))
-                    .filter(crate::schema::words_counts_guarantees::parent.eq(&record.parent))
-                    .filter(crate::schema::words_counts_guarantees::lang.eq(&record.lang))
-                    .filter(crate::schema::words_counts_guarantees::word.eq(&record.word))
-                    .get_results::<crate::models::words::WordCountGuarantee>(conn)?
-                    .pop()
-                {
-                    // old word => append the count
-                    Some(word_count_guarantee) => {
-                        ::diesel::update(crate::schema::words_counts_guarantees::table)
-                            .filter(
-                                crate::schema::words_counts_guarantees::id
-                                    .eq(word_count_guarantee.id),
-                            )
-                            .set(
-                                crate::schema::words_counts_guarantees::count
-                                    .eq(word_count_guarantee.count + 1),
-                            )
-                            .execute(conn)?
-                    }
-                    // new word => insert the word record
-                    None => {
-                        let word_record = crate::models::words::NewWordCountGuarantee {
-                            guarantee: record.guarantee.clone(),
-                            namespace: record.namespace.clone(),
-                            kind: record.kind.clone(),
-                            parent: record.parent.clone(),
-                            lang: record.lang.clone(),
-                            word: record.word.clone(),
-                            count: 1,
-                        };
+        // each change is a single put, which cannot be noised without breaking the replicas
+        if self.is_count_noised(guarantee) {
+            bail!(
+                "the changes of the word counts are served to the admins only, as they are noised"
+            )
+        }
 
-                        ::diesel::insert_into(crate::schema::words_counts_guarantees::table)
-                            .values(&word_record)
-                            .execute(conn)?
-                    }
-                };
+        // the changes are logged by a trigger, so the page is cut by the changes, not the words
+        let records: Vec<crate::models::words::WordCountChange> = ::diesel::sql_query(
+            "WITH changes AS (
+                SELECT id, namespace, lang, word, delta FROM words_counts_changes
+                WHERE kind = $1 AND id > $2
+                ORDER BY id
+                LIMIT $3
+            )
+            SELECT namespace, lang, word, SUM(delta)::INT8 AS delta,
+                MAX(id) AS seq, COUNT(*) AS changes
+            FROM changes
+            GROUP BY namespace, lang, word",
+        )
+        .bind::<::diesel::sql_types::Text, _>(query.kind.to_string())
+        .bind::<::diesel::sql_types::BigInt, _>(i64::try_from(query.since_seq)?)
+        .bind::<::diesel::sql_types::BigInt, _>(i64::from(query.limit))
+        .load(&mut *self.lock_connection("get_word_count_delta", query).await)?;
 
-                Ok(())
+        let until_seq = records
+            .iter()
+            .map(|record| record.seq)
+            .max()
+            .map(u64::try_from)
+            .transpose()?
+            .unwrap_or(query.since_seq);
+        let has_more =
+            records.iter().map(|record| record.changes).sum::<i64>() >= i64::from(query.limit);
+
+        let items = records
+            .into_iter()
+            // the changes cancelling each other are not reported
+            .filter(|record| record.delta != 0)
+            .map(|record| {
+                Ok(WordCountDeltaItem {
+                    word: WordKeyHash {
+                        namespace: record.namespace.parse()?,
+                        text: TextHash {
+                            lang: record.lang.parse()?,
+                            msg: self.cipher.decrypt(&record.word)?.parse()?,
+                        },
+                    },
+                    delta: record.delta,
+                })
             })
-            .map_err(Into::into)
+            .collect::<Result<_>>()?;
+
+        Ok(WordCountDelta {
+            items,
+            until_seq,
+            has_more,
+        })
     }
-}
 
-impl<IpiisClient> IpdisClientInner<IpiisClient>
-where
-    IpiisClient: Ipiis + Send + Sync,
-{
-    pub async fn delete_guarantee_unchecked(&self, guarantee: &AccountRef) -> Result<()> {
-        ::diesel::delete(crate::schema::accounts_guarantees::table)
-            .filter(crate::schema::accounts_guarantees::guarantee.eq(guarantee.to_string()))
-            .execute(&mut *self.connection.lock().await)
-            .map(|_| ())
-            .map_err(Into::into)
+    async fn get_word_frequency_histogram_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordFrequencyHistogram,
+    ) -> Result<Vec<WordFrequencyBucket>> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+        query.validate()?;
+
+        // the bucket of a count is the ceiling of its decimal logarithm, i.e. 1, 2..=10, ...
+        let records: Vec<crate::models::words::WordFrequencyBucket> = ::diesel::sql_query(
+            "SELECT LEAST(CEIL(LOG(totals.total))::INT4, $2) AS bucket, COUNT(*) AS words
+            FROM (
+                SELECT SUM(count) AS total FROM words_counts
+                WHERE kind = $1
+                GROUP BY namespace, lang, word
+                HAVING SUM(count) > 0
+            ) AS totals
+            GROUP BY 1",
+        )
+        .bind::<::diesel::sql_types::Text, _>(query.kind.to_string())
+        .bind::<::diesel::sql_types::Integer, _>(i32::try_from(query.buckets)? - 1)
+        .load(
+            &mut *self
+                .lock_connection("get_word_frequency_histogram", query)
+                .await,
+        )?;
+
+        let mut buckets = WordFrequencyBucket::empty(query.buckets);
+        for record in records {
+            let bucket = buckets
+                .get_mut(usize::try_from(record.bucket)?)
+                .ok_or_else(|| anyhow!("malformed histogram bucket: {}", record.bucket))?;
+            bucket.words = record.words.try_into()?;
+        }
+
+        if self.is_count_noised(guarantee) {
+            for bucket in &mut buckets {
+                let key = Hash::with_str(&bucket.min_count.to_string());
+                let words = bucket.words.try_into().unwrap_or(u32::MAX);
+                bucket.words = self
+                    .config
+                    .count_noise
+                    .apply(&query.kind, &key, words)
+                    .into();
+            }
+        }
+        Ok(buckets)
     }
 
-    pub async fn delete_dyn_path_all_unchecked(&self, namespace: &Hash) -> Result<()> {
-        ::diesel::delete(crate::schema::dyn_paths::table)
-            .filter(crate::schema::dyn_paths::namespace.eq(namespace.to_string()))
-            .execute(&mut *self.connection.lock().await)
-            .map(|_| ())
-            .map_err(Into::into)
+    async fn get_idf_vector_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+    ) -> Result<IdfVector> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+        self.config.ensure_words_len(words.len())?;
+
+        let vector = crate::snapshot::idf_vector(
+            &mut *self.lock_connection("get_idf_vector", words).await,
+            &self.cipher,
+            words,
+            self.now(),
+        )?;
+        if !self.is_count_noised(guarantee) {
+            return Ok(vector);
+        }
+
+        // the documents are counted over all the kinds, so the smallest budget is spent
+        let noise = &self.config.count_noise;
+        Ok(IdfVector {
+            corpus_size: match words.first() {
+                Some(word) => noise.apply_all(&word.namespace, vector.corpus_size),
+                None => vector.corpus_size,
+            },
+            document_counts: words
+                .iter()
+                .zip(vector.document_counts)
+                .map(|(word, count)| noise.apply_all(&word.text.msg, count))
+                .collect(),
+        })
     }
 
-    pub async fn delete_word_all_unchecked(&self, namespace: &Hash) -> Result<()> {
-        self.connection
-            .lock()
+    async fn get_similar_documents_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+        top_k: u32,
+        owned: bool,
+    ) -> Result<Vec<SimilarDocument>> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+        self.config.ensure_words_len(words.len())?;
+        self.config.ensure_query_rows(top_k)?;
+
+        let namespace = match ensure_same_namespace(words)? {
+            Some(namespace) => namespace,
+            None => return Ok(vec![]),
+        };
+
+        let guarantor = self.ipiis.account_me().account_ref();
+        let guarantee = guarantee.unwrap_or(&guarantor);
+
+        // the frequencies of the own words are exact, as for `GetWordsCounts::owned`
+        let is_noised = !owned && self.is_count_noised(Some(guarantee));
+
+        // match the pairs of the language and the word
+        let keys: Vec<_> = words
+            .iter()
+            .map(|word| {
+                format!(
+                    "{}/{}",
+                    word.text.lang,
+                    self.cipher.encrypt(word.text.msg.to_string()),
+                )
+            })
+            .collect();
+
+        // the tiered words are matched by their index, as their segments are out of the database
+        let words_tiered = if self.schema.supports(SchemaVersion::WORDS_TIERED) {
+            "UNION ALL
+                SELECT parent, lang, word FROM words_tiered
+                WHERE namespace = $1 AND (expiration_date IS NULL OR expiration_date >= $2)
+                    AND ($3::VARCHAR IS NULL OR guarantee = $3)"
+        } else {
+            ""
+        };
+        let live = format!(
+            "live AS (
+                SELECT parent, lang, word FROM words
+                WHERE namespace = $1 AND (expiration_date IS NULL OR expiration_date >= $2)
+                    AND ($3::VARCHAR IS NULL OR guarantee = $3)
+                {words_tiered}
+            )"
+        );
+        let owner = if owned {
+            Some(guarantee.to_string())
+        } else {
+            None
+        };
+
+        let mut conn = self
+            .lock_connection("get_similar_documents", &(words, top_k, owned))
+            .await;
+
+        // count the documents containing each word, as `get_idf_vector` does
+        let frequencies: Vec<crate::models::words::DocumentCount> = ::diesel::sql_query(format!(
+            "WITH {live}
+            SELECT corpus.corpus_size, documents.lang, documents.word, documents.count
+            FROM (
+                SELECT COUNT(DISTINCT parent) AS corpus_size FROM live
+            ) AS corpus
+            LEFT JOIN (
+                SELECT lang, word, COUNT(DISTINCT parent) AS count FROM live
+                WHERE (lang || '/' || word) = ANY($4)
+                GROUP BY lang, word
+            ) AS documents ON TRUE",
+        ))
+        .bind::<::diesel::sql_types::Text, _>(namespace.to_string())
+        .bind::<::diesel::sql_types::Timestamp, _>(self.now())
+        .bind::<::diesel::sql_types::Nullable<::diesel::sql_types::Text>, _>(&owner)
+        .bind::<::diesel::sql_types::Array<::diesel::sql_types::Text>, _>(&keys)
+        .load(&mut *conn)?;
+
+        // weigh the words by their inverse document frequencies,
+        // which are noised as the IDF vectors are, not to leak the exact frequencies
+        let noise = &self.config.count_noise;
+        let corpus_size: u32 = frequencies
+            .first()
+            .map(|record| record.corpus_size)
+            .unwrap_or_default()
+            .try_into()?;
+        let corpus_size = if is_noised {
+            noise.apply_all(&namespace, corpus_size)
+        } else {
+            corpus_size
+        };
+        let counts: HashMap<_, _> = frequencies
+            .into_iter()
+            .filter_map(|record| {
+                Some((format!("{}/{}", record.lang?, record.word?), record.count?))
+            })
+            .collect();
+        let weights: BTreeMap<_, _> = words
+            .iter()
+            .zip(keys)
+            .map(|(word, key)| {
+                let count: u32 = counts.get(&key).copied().unwrap_or_default().try_into()?;
+                let count = if is_noised {
+                    noise.apply_all(&word.text.msg, count)
+                } else {
+                    count
+                };
+                let weight = ((1.0 + f64::from(corpus_size)) / (1.0 + f64::from(count))).ln() + 1.0;
+                Ok((key, weight))
+            })
+            .collect::<Result<_>>()?;
+        let (keys, weights): (Vec<_>, Vec<_>) = weights.into_iter().unzip();
+
+        let records: Vec<crate::models::words::SimilarDocument> = ::diesel::sql_query(format!(
+            "WITH {live}, weights AS (
+                SELECT * FROM UNNEST($4::VARCHAR[], $5::FLOAT8[]) AS weights(key, weight)
+            ), matched AS (
+                SELECT DISTINCT parent, (lang || '/' || word) AS key FROM live
+                WHERE (lang || '/' || word) = ANY($4)
+            )
+            SELECT matched.parent, COUNT(*) AS overlap, SUM(weights.weight) AS score
+            FROM matched
+            JOIN weights USING (key)
+            GROUP BY matched.parent
+            ORDER BY score DESC, matched.parent
+            LIMIT $6",
+        ))
+        .bind::<::diesel::sql_types::Text, _>(namespace.to_string())
+        .bind::<::diesel::sql_types::Timestamp, _>(self.now())
+        .bind::<::diesel::sql_types::Nullable<::diesel::sql_types::Text>, _>(&owner)
+        .bind::<::diesel::sql_types::Array<::diesel::sql_types::Text>, _>(&keys)
+        .bind::<::diesel::sql_types::Array<::diesel::sql_types::Double>, _>(&weights)
+        .bind::<::diesel::sql_types::BigInt, _>(i64::from(top_k))
+        .load(&mut *conn)?;
+        drop(conn);
+
+        records
+            .into_iter()
+            .map(|record| {
+                Ok(SimilarDocument {
+                    document: self.cipher.decrypt(&record.parent)?.parse()?,
+                    overlap: record.overlap.try_into()?,
+                    score: record.score,
+                })
+            })
+            .collect()
+    }
+
+    async fn query_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<Vec<WordQueryRow>> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+        self.config.ensure_query_rows(query.limit)?;
+
+        let guarantor = self.ipiis.account_me().account_ref();
+
+        // the counts of the own words are exact, as for `GetWordsCounts::owned`
+        let is_noised = self.is_count_noised(guarantee) && query.owned_by.as_ref() != guarantee;
+
+        let records: Vec<crate::models::words::WordQueryRecord> =
+            crate::query::compile(guarantor.to_string(), self.now(), query)?
+                .load(&mut *self.lock_connection("query_words", query).await)?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                let day = record.day.map(|day| day.timestamp_millis());
+                let count = record.count.try_into()?;
+                Ok(WordQueryRow {
+                    day,
+                    count: if is_noised {
+                        let row = Hash::with_str(&format!("{day:?}"));
+                        self.config.count_noise.apply(&query.kind, &row, count)
+                    } else {
+                        count
+                    },
+                })
+            })
+            .collect()
+    }
+
+    async fn put_word_normalized_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<PutReceipt> {
+        self.ensure_feature_enabled(Feature::WordPut)?;
+        self.config.ensure_metadata_len(metadata)?;
+        if let Some(delegation) = on_behalf_of {
+            self.ensure_delegation(delegation, word)?;
+        }
+
+        let now = self.now();
+        let prepared = self
+            .prepare_word(parent, word, metadata, on_behalf_of, now)
+            .await?;
+        let record = &prepared.record;
+        let supports_normalization = self.schema.supports(SchemaVersion::KINDS_NORMALIZATION);
+        let topic = Topic::word(&record.namespace);
+        let server_time = now.timestamp_millis();
+
+        let id = self
+            .lock_connection("put_word", &(&record.namespace, &record.word))
+            .await
+            .transaction::<i32, Error, _>(|conn| {
+                crate::lease::ensure_fenced(
+                    conn,
+                    &record.kind,
+                    &record.guarantee,
+                    fencing_token,
+                    now,
+                )?;
+                if supports_normalization {
+                    ensure_normalization(conn, &record.kind, normalization)?;
+                }
+
+                let id = self.insert_word(conn, &prepared, now)?;
+                crate::cache::notify(conn, &topic)?;
+                Ok(id)
+            })?;
+
+        self.invalidate_cache(topic);
+        prepared.receipt(id, server_time)
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Vec<PutReceipt>> {
+        self.ensure_feature_enabled(Feature::WordPut)?;
+        self.config.ensure_words_len(words.len())?;
+
+        let now = self.now();
+        let mut prepared = Vec::with_capacity(words.len());
+        for word in words {
+            prepared.push(self.prepare_word(parent, word, None, None, now).await?);
+        }
+        let supports_normalization = self.schema.supports(SchemaVersion::KINDS_NORMALIZATION);
+        let topics: BTreeSet<_> = prepared
+            .iter()
+            .map(|prepared| prepared.record.namespace.clone())
+            .collect();
+        let server_time = now.timestamp_millis();
+
+        let ids = self
+            .lock_connection("put_word_many", &(parent, words.len()))
+            .await
+            .transaction::<Vec<i32>, Error, _>(|conn| {
+                let mut ids = Vec::with_capacity(prepared.len());
+                for prepared in &prepared {
+                    let record = &prepared.record;
+                    crate::lease::ensure_fenced(
+                        conn,
+                        &record.kind,
+                        &record.guarantee,
+                        fencing_token,
+                        now,
+                    )?;
+                    if supports_normalization {
+                        ensure_normalization(conn, &record.kind, normalization)?;
+                    }
+
+                    ids.push(self.insert_word(conn, prepared, now)?);
+                }
+                for namespace in &topics {
+                    crate::cache::notify(conn, &Topic::word(namespace))?;
+                }
+                Ok(ids)
+            })?;
+
+        for namespace in &topics {
+            self.invalidate_cache(Topic::word(namespace));
+        }
+        prepared
+            .iter()
+            .zip(ids)
+            .map(|(prepared, id)| prepared.receipt(id, server_time))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<IpiisClient> IpdisAdmin for IpdisClientInner<IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn set_read_only_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        enabled: bool,
+    ) -> Result<()> {
+        self.lock_connection("set_read_only", &enabled)
+            .await
+            .transaction::<_, Error, _>(|conn| {
+                put_setting(conn, SETTING_READ_ONLY, enabled.to_string())?;
+
+                // the other nodes follow the mode
+                crate::cache::notify(conn, &Topic::Settings)?;
+                Ok(())
+            })?;
+
+        self.read_only.store(enabled, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn get_server_diagnostics_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetServerDiagnostics,
+    ) -> Result<ServerDiagnostics> {
+        let connections_total = self.pool.size();
+
+        Ok(self.diagnostics.report(
+            connections_total,
+            self.queue.queued(),
+            self.queue.queue_time_us(),
+            query.slow_queries,
+        ))
+    }
+
+    async fn get_account_stats_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetAccountStats,
+    ) -> Result<AccountStats> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+
+        let account = query.account.to_string();
+
+        // the tiered words are counted by their index, as their segments are out of the database
+        let words_tiered = if self.schema.supports(SchemaVersion::WORDS_TIERED) {
+            "UNION ALL
+                SELECT created_date, pg_column_size(words_tiered.*), 1, 0
+                FROM words_tiered WHERE guarantee = $1"
+        } else {
+            ""
+        };
+
+        let record: crate::models::stats::AccountStats = ::diesel::sql_query(format!(
+            "WITH activity AS (
+                SELECT created_date, pg_column_size(words.*) AS size, 1 AS words, 0 AS dyn_paths
+                FROM words WHERE guarantee = $1
+                UNION ALL
+                SELECT created_date, pg_column_size(dyn_paths.*), 0, 1
+                FROM dyn_paths WHERE guarantee = $1
+                {words_tiered}
+            )
+            SELECT COALESCE(SUM(words), 0)::INT8 AS words,
+                COALESCE(SUM(dyn_paths), 0)::INT8 AS dyn_paths,
+                EXTRACT(EPOCH FROM MIN(created_date))::INT8 AS first_activity,
+                EXTRACT(EPOCH FROM MAX(created_date))::INT8 AS last_activity,
+                COALESCE(SUM(size), 0)::INT8 AS storage_bytes
+            FROM activity",
+        ))
+        .bind::<::diesel::sql_types::Text, _>(&account)
+        .get_result(&mut *self.lock_connection("get_account_stats", &account).await)?;
+
+        Ok(AccountStats {
+            words: record.words.try_into()?,
+            dyn_paths: record.dyn_paths.try_into()?,
+            first_activity: record.first_activity,
+            last_activity: record.last_activity,
+            storage_bytes: record.storage_bytes.try_into()?,
+        })
+    }
+
+    async fn register_kind_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &RegisterKind,
+        name: &str,
+        description: &str,
+    ) -> Result<()> {
+        if self.is_read_only() {
+            bail!(IpdisError::ReadOnly)
+        }
+
+        let record = crate::models::kinds::NewKind {
+            kind: query.kind.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            schema_version: query.schema_version.try_into()?,
+            normalization: query
+                .normalization
+                .map(|normalization| normalization.to_string()),
+        };
+
+        if !self.schema.supports(SchemaVersion::KINDS_NORMALIZATION) {
+            if record.normalization.is_some() {
+                bail!(
+                    "the database schema does not support the normalization policies yet: run `diesel migration run` to migrate it"
+                )
+            }
+            let record = crate::models::kinds::NewKindV22::from(record);
+
+            return ::diesel::insert_into(crate::schema::kinds::table)
+                .values(&record)
+                .on_conflict(crate::schema::kinds::kind)
+                .do_update()
+                .set((
+                    crate::schema::kinds::name.eq(&record.name),
+                    crate::schema::kinds::description.eq(&record.description),
+                    crate::schema::kinds::schema_version.eq(record.schema_version),
+                ))
+                .execute(&mut *self.lock_connection("register_kind", &record.kind).await)
+                .map(|_| ())
+                .map_err(Into::into);
+        }
+
+        ::diesel::insert_into(crate::schema::kinds::table)
+            .values(&record)
+            .on_conflict(crate::schema::kinds::kind)
+            .do_update()
+            .set((
+                crate::schema::kinds::name.eq(&record.name),
+                crate::schema::kinds::description.eq(&record.description),
+                crate::schema::kinds::schema_version.eq(record.schema_version),
+                crate::schema::kinds::normalization.eq(&record.normalization),
+            ))
+            .execute(&mut *self.lock_connection("register_kind", &record.kind).await)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    async fn get_path_reference_count_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        path: &Hash,
+    ) -> Result<u32> {
+        // not gated by the features, as the garbage collectors should not delete the referred contents
+        let guarantor = self.ipiis.account_me().account_ref();
+        let mut conn = self.lock_connection("get_path_reference_count", path).await;
+
+        let dyn_paths: i64 = crate::schema::dyn_paths::table
+            .filter(crate::schema::dyn_paths::guarantor.eq(guarantor.to_string()))
+            .filter(
+                crate::schema::dyn_paths::expiration_date
+                    .ge(self.now())
+                    .or(crate::schema::dyn_paths::expiration_date.is_null()),
+            )
+            .filter(crate::schema::dyn_paths::path.eq(path.to_string()))
+            .count()
+            .get_result(&mut *conn)?;
+
+        let words: i64 = crate::schema::words::table
+            .filter(crate::schema::words::guarantor.eq(guarantor.to_string()))
+            .filter(
+                crate::schema::words::expiration_date
+                    .ge(self.now())
+                    .or(crate::schema::words::expiration_date.is_null()),
+            )
+            .filter(crate::schema::words::path.eq(path.to_string()))
+            .count()
+            .get_result(&mut *conn)?;
+
+        // the tiered words still refer to the contents, as long as they are served
+        let words_tiered: i64 = if self.schema.supports(SchemaVersion::WORDS_TIERED) {
+            crate::schema::words_tiered::table
+                .filter(crate::schema::words_tiered::guarantor.eq(guarantor.to_string()))
+                .filter(
+                    crate::schema::words_tiered::expiration_date
+                        .ge(self.now())
+                        .or(crate::schema::words_tiered::expiration_date.is_null()),
+                )
+                .filter(crate::schema::words_tiered::path.eq(path.to_string()))
+                .count()
+                .get_result(&mut *conn)?
+        } else {
+            0
+        };
+
+        (dyn_paths + words + words_tiered)
+            .try_into()
+            .map_err(Into::into)
+    }
+
+    async fn get_dyn_path_by_target_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetDynPathsByTarget,
+    ) -> Result<Page<GuarantorSigned<DynPath<::ipis::path::Path>>>> {
+        self.ensure_feature_enabled(Feature::DynPathGet)?;
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+        self.config
+            .ensure_query_rows(query.end_index - query.start_index)?;
+
+        let guarantor = self.ipiis.account_me().account_ref();
+
+        let mut conn = self.lock_connection("get_dyn_path_by_target", query).await;
+        let mut records: Vec<crate::models::dyn_paths::DynPath> = crate::schema::dyn_paths::table
+            .order((
+                crate::schema::dyn_paths::created_date.desc(),
+                crate::schema::dyn_paths::id.desc(),
+            ))
+            .filter(crate::schema::dyn_paths::guarantor.eq(guarantor.to_string()))
+            .filter(
+                crate::schema::dyn_paths::expiration_date
+                    .ge(self.now())
+                    .or(crate::schema::dyn_paths::expiration_date.is_null()),
+            )
+            .filter(crate::schema::dyn_paths::path.eq(query.path.to_string()))
+            // the records whose signatures have been dropped are not served,
+            // so they are skipped before paginated, not to end the pages early
+            .filter(
+                crate::schema::dyn_paths::guarantee_signature
+                    .is_not_null()
+                    .and(crate::schema::dyn_paths::guarantor_signature.is_not_null())
+                    .or(crate::schema::dyn_paths::id.eq_any(
+                        crate::schema::signatures_archive::table
+                            .select(crate::schema::signatures_archive::record_id)
+                            .filter(
+                                crate::schema::signatures_archive::table_name
+                                    .eq(crate::export::TABLE_DYN_PATHS),
+                            ),
+                    )),
+            )
+            .offset(query.start_index.into())
+            .limit((query.end_index - query.start_index).into())
+            .get_results(&mut *conn)?;
+        crate::retention::restore(&mut conn, crate::export::TABLE_DYN_PATHS, &mut records)?;
+        drop(conn);
+
+        let items = records
+            .iter()
+            .map(|record| dyn_path_from_record(&self.cipher, record))
+            .collect::<Result<_>>()?;
+
+        Ok(Page::new(items, query.start_index, query.end_index, None))
+    }
+
+    async fn get_idf_log_page_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetIdfLogs,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+        if query.until <= query.since {
+            bail!("malformed range: until should be after since")
+        }
+        self.config
+            .ensure_query_rows(query.end_index - query.start_index)?;
+
+        let since = crate::query::timestamp(query.since)?;
+        let until = crate::query::timestamp(query.until)?;
+        self.find_idf_log_page(since, until, query.start_index, query.end_index)
+            .await
+    }
+
+    async fn explain_query_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<String> {
+        self.config.ensure_query_rows(query.limit)?;
+
+        let guarantor = self.ipiis.account_me().account_ref();
+
+        let lines: Vec<crate::models::words::QueryPlanLine> =
+            crate::query::compile_explain(guarantor.to_string(), self.now(), query)?
+                .load(&mut *self.lock_connection("explain_query", query).await)?;
+
+        Ok(lines
+            .into_iter()
+            .map(|line| line.line)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    async fn issue_api_token_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        name: &str,
+        scopes: &FeatureSet,
+        rate_limit: u32,
+        expiration_time: Option<i64>,
+    ) -> Result<IssuedApiToken> {
+        crate::token::ensure_read_only(scopes)?;
+
+        let token = crate::token::generate();
+        let record = crate::models::api_tokens::NewApiToken {
+            token_hash: crate::token::hash(&token),
+            name: name.to_string(),
+            scopes: scopes.to_string(),
+            rate_limit: rate_limit.try_into()?,
+            created_date: self.now(),
+            expiration_date: expiration_time.map(crate::query::timestamp).transpose()?,
+        };
+
+        let record: crate::models::api_tokens::ApiToken =
+            ::diesel::insert_into(crate::schema::api_tokens::table)
+                .values(&record)
+                .get_result(&mut *self.lock_connection("issue_api_token", name).await)?;
+
+        Ok(IssuedApiToken {
+            token,
+            info: ApiToken::try_from(record)?.into(),
+        })
+    }
+
+    async fn get_api_token_page_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetApiTokens,
+    ) -> Result<Page<ApiTokenInfo>> {
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+        self.config
+            .ensure_query_rows(query.end_index - query.start_index)?;
+
+        let (total, records) = {
+            let mut conn = self.lock_connection("get_api_token_page", query).await;
+
+            let total: i64 = crate::schema::api_tokens::table
+                .count()
+                .get_result(&mut *conn)?;
+            let records: Vec<crate::models::api_tokens::ApiToken> =
+                crate::schema::api_tokens::table
+                    .order(crate::schema::api_tokens::id.asc())
+                    .offset(query.start_index.into())
+                    .limit((query.end_index - query.start_index).into())
+                    .get_results(&mut *conn)?;
+            (total, records)
+        };
+
+        let items = records
+            .into_iter()
+            .map(|record| ApiToken::try_from(record).map(Into::into))
+            .collect::<Result<_>>()?;
+
+        Ok(Page::new(
+            items,
+            query.start_index,
+            query.end_index,
+            Some(total.try_into()?),
+        ))
+    }
+
+    async fn revoke_api_token_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        id: i32,
+    ) -> Result<()> {
+        ::diesel::delete(crate::schema::api_tokens::table)
+            .filter(crate::schema::api_tokens::id.eq(id))
+            .execute(&mut *self.lock_connection("revoke_api_token", &id).await)?;
+
+        self.tokens.forget(id);
+        Ok(())
+    }
+
+    async fn add_stop_word_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        kind: &Hash,
+        word: &TextHash,
+    ) -> Result<()> {
+        if self.is_read_only() {
+            bail!(IpdisError::ReadOnly)
+        }
+
+        let record = crate::models::stop_words::NewStopWord {
+            kind: kind.to_string(),
+            lang: word.lang.to_string(),
+            word: self.cipher.encrypt(word.msg.to_string()),
+        };
+        let is_counted = self.config.stop_words == StopWordsPolicy::Counted;
+        let topic = Topic::All;
+
+        self.lock_connection("add_stop_word", &(kind, word))
+            .await
+            .transaction::<(), ::diesel::result::Error, _>(|conn| {
+                ::diesel::insert_into(crate::schema::stop_words::table)
+                    .values(&record)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+
+                if !is_counted {
+                    ::diesel::delete(crate::schema::words_counts::table)
+                        .filter(crate::schema::words_counts::kind.eq(&record.kind))
+                        .filter(crate::schema::words_counts::lang.eq(&record.lang))
+                        .filter(crate::schema::words_counts::word.eq(&record.word))
+                        .execute(conn)?;
+                    ::diesel::delete(crate::schema::words_counts_guarantees::table)
+                        .filter(crate::schema::words_counts_guarantees::kind.eq(&record.kind))
+                        .filter(crate::schema::words_counts_guarantees::lang.eq(&record.lang))
+                        .filter(crate::schema::words_counts_guarantees::word.eq(&record.word))
+                        .execute(conn)?;
+                }
+
+                crate::cache::notify(conn, &topic)
+            })?;
+
+        self.invalidate_cache(topic);
+        Ok(())
+    }
+
+    async fn list_stop_words_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        kind: &Hash,
+    ) -> Result<Vec<TextHash>> {
+        let records: Vec<crate::models::stop_words::StopWord> = crate::schema::stop_words::table
+            .filter(crate::schema::stop_words::kind.eq(kind.to_string()))
+            .order(crate::schema::stop_words::id.asc())
+            .get_results(&mut *self.lock_connection("list_stop_words", kind).await)?;
+
+        records
+            .into_iter()
+            .map(|record| {
+                Ok(TextHash {
+                    lang: record.lang.parse()?,
+                    msg: self.cipher.decrypt(&record.word)?.parse()?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl<IpiisClient> IpdisClientInner<IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    /// Signs the word as the guarantor and builds its record, which is not inserted yet.
+    async fn prepare_word(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        now: NaiveDateTime,
+    ) -> Result<PreparedWord> {
+        let word = self.ipiis.sign_as_guarantor(*word)?;
+
+        let record = crate::models::words::NewWord {
+            nonce: word.nonce.0 .0,
+            guarantee: word.guarantee.account.to_string(),
+            guarantor: word.guarantor.account.to_string(),
+            guarantee_signature: Some(word.guarantee.signature.to_string()),
+            guarantor_signature: Some(word.guarantor.signature.to_string()),
+            created_date: word.created_date.naive_utc(),
+            expiration_date: word.expiration_date.map(|e| e.naive_utc()),
+            namespace: word.data.key.namespace.to_string(),
+            parent: self.cipher.encrypt(parent.to_string()),
+            lang: word.data.key.text.lang.to_string(),
+            word: self.cipher.encrypt(word.data.key.text.msg.to_string()),
+            kind: word.data.kind.to_string(),
+            relpath: word.data.relpath,
+            path: word.data.path.value.to_string(),
+            len: word.data.path.len.try_into()?,
+            metadata: metadata.map(ToOwned::to_owned),
+            on_behalf_of: on_behalf_of.map(|delegation| delegation.data.data.principal.to_string()),
+            hash_version: self.config.hash_version.try_into()?,
+        };
+
+        let counted = match self.config.stop_words {
+            StopWordsPolicy::Counted => true,
+            policy => {
+                let is_stop_word = self
+                    .is_stop_word(&record.kind, &record.lang, &record.word)
+                    .await?;
+                if is_stop_word && policy == StopWordsPolicy::Rejected {
+                    bail!(IpdisError::StopWord {
+                        word: word.data.key.text.msg.to_string(),
+                    })
+                }
+                !is_stop_word
+            }
+        };
+
+        let oplog = if self.config.oplog_enabled {
+            Some(crate::oplog::entry(&SignedRecord::Word(word), now)?)
+        } else {
+            None
+        };
+
+        Ok(PreparedWord {
+            word,
+            record,
+            counted,
+            oplog,
+        })
+    }
+
+    /// Inserts the prepared word in the transaction, and counts it.
+    ///
+    /// Returns the id of the word, which is of the stored one if the word is replayed.
+    fn insert_word(
+        &self,
+        conn: &mut PgConnection,
+        prepared: &PreparedWord,
+        now: NaiveDateTime,
+    ) -> Result<i32> {
+        let record = &prepared.record;
+        // the tiered words are indexed by their nonces, so a replay of them is accepted once as well
+        if self.schema.supports(SchemaVersion::WORDS_TIERED) {
+            if let Some(tiered) = crate::tiering::find_by_nonce(conn, &record.nonce)? {
+                return Ok(tiered.record_id);
+            }
+        }
+
+        // the replayed record is accepted once, which the older schemas do not enforce
+        if !self.schema.supports(SchemaVersion::UNIQUE_NONCES) {
+            if let Some(id) = find_word_by_nonce(conn, &record.nonce)? {
+                return Ok(id);
+            }
+        }
+
+        // insert the word record
+        let id = match ::diesel::insert_into(crate::schema::words::table)
+            .values(record)
+            .on_conflict_do_nothing()
+            .returning(crate::schema::words::id)
+            .get_result(conn)
+            .optional()?
+        {
+            Some(id) => id,
+            // raced with a replay of the same record
+            None => {
+                return find_word_by_nonce(conn, &record.nonce)?
+                    .ok_or_else(|| anyhow!("the replayed word has been deleted"))
+            }
+        };
+
+        // the stop words may not be counted
+        if prepared.counted {
+            count_word(conn, record)?;
+        }
+        if self.config.usage_enabled && self.schema.supports(SchemaVersion::ACCOUNTS_USAGE) {
+            crate::usage::record(conn, crate::export::TABLE_WORDS, id, now)?;
+        }
+
+        if let Some(entry) = &prepared.oplog {
+            crate::oplog::append(conn, entry)?;
+        }
+        if self.config.outbox_enabled {
+            crate::outbox::push(
+                conn,
+                crate::outbox::TOPIC_WORD_PUT,
+                format!(
+                    "{}/{}/{}/{}",
+                    &record.namespace, &record.kind, &record.lang, &record.word,
+                ),
+                now,
+            )?;
+        }
+        Ok(id)
+    }
+
+    pub async fn delete_guarantee_unchecked(&self, guarantee: &AccountRef) -> Result<()> {
+        self.ensure_feature_enabled(Feature::Delete)?;
+
+        ::diesel::delete(crate::schema::accounts_guarantees::table)
+            .filter(crate::schema::accounts_guarantees::guarantee.eq(guarantee.to_string()))
+            .execute(&mut *self.lock_connection("delete_guarantee", guarantee).await)
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    /// Flags the guarantee to be renewed by the server before it expires,
+    /// rather than to be notified.
+    pub async fn set_guarantee_auto_renew_unchecked(
+        &self,
+        guarantee: &AccountRef,
+        auto_renew: bool,
+    ) -> Result<()> {
+        self.ensure_feature_enabled(Feature::Guarantee)?;
+
+        ::diesel::update(crate::schema::accounts_guarantees::table)
+            .filter(crate::schema::accounts_guarantees::guarantee.eq(guarantee.to_string()))
+            .set(crate::schema::accounts_guarantees::auto_renew.eq(auto_renew))
+            .execute(
+                &mut *self
+                    .lock_connection("set_guarantee_auto_renew", guarantee)
+                    .await,
+            )
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    /// Notifies the guarantees expiring soon through the outbox,
+    /// and renews the auto-renewable ones.
+    pub async fn sweep_guarantee_expiry_unchecked(&self) -> Result<GuaranteeExpirySweep> {
+        let expiry = match self.config.guarantee_expiry {
+            Some(expiry) => expiry,
+            None => return Ok(Default::default()),
+        };
+        let now = self.now();
+        let until = now + ::ipis::core::chrono::Duration::from_std(expiry.notice)?;
+        let renewal = ::ipis::core::chrono::Duration::from_std(expiry.renewal)?;
+        let outbox_enabled = self.config.outbox_enabled;
+
+        self.lock_connection("sweep_guarantee_expiry", &now)
+            .await
+            .transaction(|conn| crate::expiry::sweep(conn, now, until, renewal, outbox_enabled))
+            .map_err(Into::into)
+    }
+
+    /// Authenticates a request of the public query tier for the feature,
+    /// and counts it against the rate limit of the token.
+    ///
+    /// The permitted requests are meant to be served with no guarantee,
+    /// i.e. as if they were guaranteed by the node itself.
+    pub async fn authenticate_api_token(&self, token: &str, feature: Feature) -> Result<ApiToken> {
+        self.ensure_feature_enabled(feature)?;
+
+        let token: ApiToken = crate::schema::api_tokens::table
+            .filter(crate::schema::api_tokens::token_hash.eq(crate::token::hash(token)))
+            .filter(
+                crate::schema::api_tokens::expiration_date
+                    .ge(self.now())
+                    .or(crate::schema::api_tokens::expiration_date.is_null()),
+            )
+            .get_results::<crate::models::api_tokens::ApiToken>(
+                &mut *self.lock_connection("authenticate_api_token", &()).await,
+            )?
+            .pop()
+            .ok_or_else(|| anyhow!("failed to authenticate the API token"))?
+            .try_into()?;
+
+        token.ensure_scope(feature)?;
+        self.tokens.acquire(&token)?;
+        Ok(token)
+    }
+
+    pub async fn delete_dyn_path_all_unchecked(&self, namespace: &Hash) -> Result<()> {
+        self.ensure_feature_enabled(Feature::Delete)?;
+
+        let topic = Topic::dyn_path(namespace);
+
+        self.lock_connection("delete_dyn_path_all", namespace)
+            .await
+            .transaction::<(), Error, _>(|conn| {
+                crate::lock::lock(conn, &[crate::lock::namespace(namespace)])?;
+
+                ::diesel::delete(crate::schema::dyn_paths::table)
+                    .filter(crate::schema::dyn_paths::namespace.eq(namespace.to_string()))
+                    .execute(conn)?;
+
+                crate::cache::notify(conn, &topic).map_err(Into::into)
+            })?;
+
+        self.invalidate_cache(topic);
+        Ok(())
+    }
+
+    pub async fn delete_word_all_unchecked(&self, namespace: &Hash) -> Result<()> {
+        self.ensure_feature_enabled(Feature::Delete)?;
+
+        let topic = Topic::word(namespace);
+        let words_tiered = self.schema.supports(SchemaVersion::WORDS_TIERED);
+
+        self.lock_connection("delete_word_all", namespace)
+            .await
+            .transaction::<(), Error, _>(|conn| {
+                crate::lock::lock(conn, &[crate::lock::namespace(namespace)])?;
+
+                ::diesel::delete(crate::schema::words::table)
+                    .filter(crate::schema::words::namespace.eq(namespace.to_string()))
+                    .execute(conn)
+                    .map(|_| ())?;
+
+                if words_tiered {
+                    ::diesel::delete(crate::schema::words_tiered::table)
+                        .filter(crate::schema::words_tiered::namespace.eq(namespace.to_string()))
+                        .execute(conn)
+                        .map(|_| ())?;
+                }
+
+                ::diesel::delete(crate::schema::words_counts::table)
+                    .filter(crate::schema::words_counts::namespace.eq(namespace.to_string()))
+                    .execute(conn)
+                    .map(|_| ())?;
+
+                ::diesel::delete(crate::schema::words_counts_guarantees::table)
+                    .filter(
+                        crate::schema::words_counts_guarantees::namespace.eq(namespace.to_string()),
+                    )
+                    .execute(conn)
+                    .map(|_| ())?;
+
+                crate::cache::notify(conn, &topic).map_err(Into::into)
+            })?;
+
+        self.invalidate_cache(topic);
+        Ok(())
+    }
+
+    /// Deletes the given words of the kind, and discounts them.
+    pub async fn delete_word_many_unchecked(&self, kind: &Hash, words: &[WordHash]) -> Result<()> {
+        self.ensure_feature_enabled(Feature::Delete)?;
+
+        self.config.ensure_words_len(words.len())?;
+
+        if words.iter().any(|word| &word.kind != kind) {
+            bail!("malformed words: all the words should be of the given kind")
+        }
+
+        let mut topics: Vec<_> = words
+            .iter()
+            .map(|word| Topic::word(word.key.namespace))
+            .collect();
+        topics.dedup();
+
+        let cipher = &self.cipher;
+        let words_tiered = self.schema.supports(SchemaVersion::WORDS_TIERED);
+        self.lock_connection("delete_word_many", &(kind, words))
+            .await
+            .transaction::<(), Error, _>(|conn| {
+                crate::lock::lock(conn, &[crate::lock::kind(kind)])?;
+
+                for word in words {
+                    let words: Vec<crate::models::words::Word> = crate::schema::words::table
+                        .filter(crate::schema::words::namespace.eq(word.key.namespace.to_string()))
+                        .filter(crate::schema::words::kind.eq(kind.to_string()))
+                        .filter(crate::schema::words::lang.eq(word.key.text.lang.to_string()))
+                        .filter(
+                            crate::schema::words::word
+                                .eq(cipher.encrypt(word.key.text.msg.to_string())),
+                        )
+                        .filter(crate::schema::words::path.eq(word.path.value.to_string()))
+                        .get_results(conn)?;
+
+                    delete_words(conn, &words)?;
+
+                    if words_tiered {
+                        let index: Vec<crate::models::words_segments::WordTiered> =
+                            crate::schema::words_tiered::table
+                                .filter(
+                                    crate::schema::words_tiered::namespace
+                                        .eq(word.key.namespace.to_string()),
+                                )
+                                .filter(crate::schema::words_tiered::kind.eq(kind.to_string()))
+                                .filter(
+                                    crate::schema::words_tiered::lang.eq(word
+                                        .key
+                                        .text
+                                        .lang
+                                        .to_string()),
+                                )
+                                .filter(
+                                    crate::schema::words_tiered::word
+                                        .eq(cipher.encrypt(word.key.text.msg.to_string())),
+                                )
+                                .filter(
+                                    crate::schema::words_tiered::path
+                                        .eq(word.path.value.to_string()),
+                                )
+                                .get_results(conn)?;
+
+                        delete_tiered_words(conn, &index)?;
+                    }
+                }
+
+                for topic in &topics {
+                    crate::cache::notify(conn, topic)?;
+                }
+                Ok(())
+            })?;
+
+        for topic in topics {
+            self.invalidate_cache(topic);
+        }
+        Ok(())
+    }
+
+    /// Rewrites the kind of all the records, merging the counts into the new kind.
+    ///
+    /// It is unsafe, as the rewritten records no longer match their signatures.
+    pub async fn migrate_kind_unsafe(&self, old: &Hash, new: &Hash) -> Result<()> {
+        self.ensure_feature_enabled(Feature::DynPathPut)?;
+        self.ensure_feature_enabled(Feature::WordPut)?;
+
+        if old == new {
+            return Ok(());
+        }
+        let (old, new) = (old.to_string(), new.to_string());
+        let log = self.write_log();
+        let words_tiered = self.schema.supports(SchemaVersion::WORDS_TIERED);
+
+        // the tiered words are read back, to be rewritten as the hot ones
+        let (index, tiered) = if words_tiered {
+            let (index, segments) = {
+                let mut conn = self.lock_connection("migrate_kind", &(&old, &new)).await;
+                let index: Vec<crate::models::words_segments::WordTiered> =
+                    crate::schema::words_tiered::table
+                        .filter(crate::schema::words_tiered::kind.eq(&old))
+                        .get_results(&mut *conn)?;
+                let segments = crate::tiering::find_segments(&mut conn, &index)?;
+                (index, segments)
+            };
+            let tiered = self.read_tiered(&segments, &index).await?;
+            (index, tiered)
+        } else {
+            Default::default()
+        };
+
+        self.lock_connection("migrate_kind", &(&old, &new))
+            .await
+            .transaction::<(), Error, _>(|conn| {
+                crate::lock::lock(conn, &[crate::lock::kind(&old), crate::lock::kind(&new)])?;
+
+                if words_tiered {
+                    crate::tiering::untier(conn, &index, tiered)?;
+                    ensure_untiered(
+                        crate::schema::words_tiered::table
+                            .filter(crate::schema::words_tiered::kind.eq(&old))
+                            .count()
+                            .get_result(conn)?,
+                    )?;
+                }
+
+                // the rewritten rows are logged as the puts are
+                let mut dyn_paths: Vec<crate::models::dyn_paths::DynPath> =
+                    ::diesel::update(crate::schema::dyn_paths::table)
+                        .filter(crate::schema::dyn_paths::kind.eq(&old))
+                        .set(crate::schema::dyn_paths::kind.eq(&new))
+                        .get_results(conn)?;
+                if log.oplog {
+                    crate::retention::restore(
+                        conn,
+                        crate::export::TABLE_DYN_PATHS,
+                        &mut dyn_paths,
+                    )?;
+                }
+                for dyn_path in &dyn_paths {
+                    log.dyn_path(conn, &self.cipher, dyn_path)?;
+                }
+
+                let mut words: Vec<crate::models::words::Word> =
+                    ::diesel::update(crate::schema::words::table)
+                        .filter(crate::schema::words::kind.eq(&old))
+                        .set(crate::schema::words::kind.eq(&new))
+                        .get_results(conn)?;
+                if log.oplog {
+                    crate::retention::restore(conn, crate::export::TABLE_WORDS, &mut words)?;
+                }
+                for word in &words {
+                    log.word(conn, &self.cipher, word)?;
+                }
+
+                let word_counts: Vec<crate::models::words::WordCount> =
+                    crate::schema::words_counts::table
+                        .filter(crate::schema::words_counts::kind.eq(&old))
+                        .get_results(conn)?;
+
+                for word_count in word_counts {
+                    match crate::schema::words_counts::table
+                        .filter(crate::schema::words_counts::namespace.eq(&word_count.namespace))
+                        .filter(crate::schema::words_counts::kind.eq(&new))
+                        .filter(crate::schema::words_counts::parent.eq(&word_count.parent))
+                        .filter(crate::schema::words_counts::lang.eq(&word_count.lang))
+                        .filter(crate::schema::words_counts::word.eq(&word_count.word))
+                        .get_results::<crate::models::words::WordCount>(conn)?
+                        .pop()
+                    {
+                        // conflicted word => merge the counts
+                        Some(merged) => {
+                            ::diesel::update(crate::schema::words_counts::table)
+                                .filter(crate::schema::words_counts::id.eq(merged.id))
+                                .set(
+                                    crate::schema::words_counts::count
+                                        .eq(merged.count + word_count.count),
+                                )
+                                .execute(conn)?;
+                            ::diesel::delete(crate::schema::words_counts::table)
+                                .filter(crate::schema::words_counts::id.eq(word_count.id))
+                                .execute(conn)?
+                        }
+                        // new word => rename the kind
+                        None => ::diesel::update(crate::schema::words_counts::table)
+                            .filter(crate::schema::words_counts::id.eq(word_count.id))
+                            .set(crate::schema::words_counts::kind.eq(&new))
+                            .execute(conn)?,
+                    };
+                }
+
+                let word_counts_guarantees: Vec<crate::models::words::WordCountGuarantee> =
+                    crate::schema::words_counts_guarantees::table
+                        .filter(crate::schema::words_counts_guarantees::kind.eq(&old))
+                        .get_results(conn)?;
+
+                for word_count in word_counts_guarantees {
+                    match crate::schema::words_counts_guarantees::table
+                        .filter(
+                            crate::schema::words_counts_guarantees::guarantee
+                                .eq(&word_count.guarantee),
+                        )
+                        .filter(
+                            crate::schema::words_counts_guarantees::namespace
+                                .eq(&word_count.namespace),
+                        )
+                        .filter(crate::schema::words_counts_guarantees::kind.eq(&new))
+                        .filter(
+                            crate::schema::words_counts_guarantees::parent.eq(&word_count.parent),
+                        )
+                        .filter(crate::schema::words_counts_guarantees::lang.eq(&word_count.lang))
+                        .filter(crate::schema::words_counts_guarantees::word.eq(&word_count.word))
+                        .get_results::<crate::models::words::WordCountGuarantee>(conn)?
+                        .pop()
+                    {
+                        // conflicted word => merge the counts
+                        Some(merged) => {
+                            ::diesel::update(crate::schema::words_counts_guarantees::table)
+                                .filter(crate::schema::words_counts_guarantees::id.eq(merged.id))
+                                .set(
+                                    crate::schema::words_counts_guarantees::count
+                                        .eq(merged.count + word_count.count),
+                                )
+                                .execute(conn)?;
+                            ::diesel::delete(crate::schema::words_counts_guarantees::table)
+                                .filter(
+                                    crate::schema::words_counts_guarantees::id.eq(word_count.id),
+                                )
+                                .execute(conn)?
+                        }
+                        // new word => rename the kind
+                        None => ::diesel::update(crate::schema::words_counts_guarantees::table)
+                            .filter(crate::schema::words_counts_guarantees::id.eq(word_count.id))
+                            .set(crate::schema::words_counts_guarantees::kind.eq(&new))
+                            .execute(conn)?,
+                    };
+                }
+
+                crate::cache::notify(conn, &Topic::All).map_err(Into::into)
+            })?;
+
+        self.invalidate_cache(Topic::All);
+        Ok(())
+    }
+
+    /// Replaces the words of the kind computed with an old hashing scheme by the ones rehashed and
+    /// signed by their guarantees, moving their counts.
+    ///
+    /// Each replacement is given with the nonce of the word it replaces, and should only differ
+    /// from it by the message, as the texts are known only to the guarantees.
+    /// The replacements are stored in batches, each of which waits for its turn as a bulk request,
+    /// so the kind is served with both of the schemes until the reindex is finished.
+    /// The replaced words, or the ones not of the old scheme, are skipped.
+    ///
+    /// Returns the number of the replaced words.
+    pub async fn reindex_kind_unchecked(
+        &self,
+        kind: &Hash,
+        from_version: u32,
+        to_version: u32,
+        replacements: &[(Uuid, GuaranteeSigned<WordHash>)],
+    ) -> Result<u64> {
+        self.ensure_feature_enabled(Feature::WordPut)?;
+
+        if from_version == to_version {
+            return Ok(0);
+        }
+        let kind = kind.to_string();
+        let (from_version, to_version): (i32, i32) =
+            (from_version.try_into()?, to_version.try_into()?);
+        let log = self.write_log();
+        let words_tiered = self.schema.supports(SchemaVersion::WORDS_TIERED);
+
+        let mut replaced = 0;
+        for batch in replacements.chunks(REINDEX_BATCH_SIZE as usize) {
+            // the replacements should be signed by the guarantees
+            let batch = batch
+                .iter()
+                .map(|(nonce, word)| {
+                    word.verify(None)?;
+                    Ok((nonce.0, self.ipiis.sign_as_guarantor(*word)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            let _permit = self.enter_queue(RequestClass::Bulk).await?;
+
+            // the tiered words are read back, to be replaced as the hot ones
+            let nonces: Vec<_> = batch.iter().map(|(nonce, _)| *nonce).collect();
+            let (index, tiered) = if words_tiered {
+                let (index, segments) = {
+                    let mut conn = self
+                        .lock_connection("reindex_kind", &(&kind, from_version, to_version))
+                        .await;
+                    let index: Vec<crate::models::words_segments::WordTiered> =
+                        crate::schema::words_tiered::table
+                            .filter(crate::schema::words_tiered::nonce.eq_any(&nonces))
+                            .filter(crate::schema::words_tiered::kind.eq(&kind))
+                            .filter(crate::schema::words_tiered::hash_version.eq(from_version))
+                            .get_results(&mut *conn)?;
+                    let segments = crate::tiering::find_segments(&mut conn, &index)?;
+                    (index, segments)
+                };
+                let tiered = self.read_tiered(&segments, &index).await?;
+                (index, tiered)
+            } else {
+                Default::default()
+            };
+
+            let count = self
+                .lock_connection("reindex_kind", &(&kind, from_version, to_version))
+                .await
+                .transaction::<u64, Error, _>(|conn| {
+                    crate::lock::lock(conn, &[crate::lock::kind(&kind)])?;
+
+                    if words_tiered {
+                        crate::tiering::untier(conn, &index, tiered)?;
+                        ensure_untiered(
+                            crate::schema::words_tiered::table
+                                .filter(crate::schema::words_tiered::nonce.eq_any(&nonces))
+                                .filter(crate::schema::words_tiered::kind.eq(&kind))
+                                .filter(crate::schema::words_tiered::hash_version.eq(from_version))
+                                .count()
+                                .get_result(conn)?,
+                        )?;
+                    }
+
+                    let mut count = 0;
+                    for (nonce, word) in &batch {
+                        let old: Option<crate::models::words::Word> = crate::schema::words::table
+                            .filter(crate::schema::words::nonce.eq(nonce))
+                            .filter(crate::schema::words::kind.eq(&kind))
+                            .filter(crate::schema::words::hash_version.eq(from_version))
+                            .get_result(conn)
+                            .optional()?;
+                        let old = match old {
+                            Some(old) => old,
+                            None => continue,
+                        };
+                        let record = replacement(&self.cipher, &old, word, to_version)?;
+
+                        // the uncounted words (e.g. the stop words) are left uncounted
+                        let counted = discount_word(conn, &old)?;
+                        ::diesel::delete(crate::schema::words::table.find(old.id)).execute(conn)?;
+                        let inserted: crate::models::words::Word =
+                            ::diesel::insert_into(crate::schema::words::table)
+                                .values(&record)
+                                .get_result(conn)?;
+                        if counted {
+                            count_word(conn, &record)?;
+                        }
+
+                        // the replacement is logged as the puts are
+                        log.word(conn, &self.cipher, &inserted)?;
+                        count += 1;
+                    }
+                    delete_uncounted_words(conn)?;
+
+                    crate::cache::notify(conn, &Topic::All)?;
+                    Ok(count)
+                })?;
+
+            self.invalidate_cache(Topic::All);
+            replaced += count;
+        }
+        Ok(replaced)
+    }
+
+    /// Publishes the undelivered events of the outbox in order, and marks them delivered.
+    ///
+    /// Returns the number of the delivered events.
+    pub async fn dispatch_outbox_unchecked<P>(&self, publisher: &P, limit: u32) -> Result<u32>
+    where
+        P: OutboxPublisher + ?Sized,
+    {
+        let events: Vec<OutboxEvent> = crate::schema::outbox::table
+            .filter(crate::schema::outbox::delivered_date.is_null())
+            .order(crate::schema::outbox::id.asc())
+            .limit(limit.into())
+            .get_results(&mut *self.lock_connection("dispatch_outbox", &limit).await)?;
+
+        let mut delivered = 0;
+        for event in &events {
+            publisher.publish(event).await?;
+
+            ::diesel::update(crate::schema::outbox::table)
+                .filter(crate::schema::outbox::id.eq(event.id))
+                .set(crate::schema::outbox::delivered_date.eq(self.now()))
+                .execute(&mut *self.lock_connection("dispatch_outbox", &event.id).await)?;
+            delivered += 1;
+        }
+        Ok(delivered)
+    }
+
+    /// Exports the usage of every account in the period, e.g. for the billing in the shared deployments.
+    ///
+    /// The writes are counted only if `IpdisConfig::usage_enabled` is set.
+    pub async fn export_usage_unchecked(&self, period: &UsagePeriod) -> Result<Vec<UsageRecord>> {
+        if period.since >= period.until {
+            bail!("malformed period: the start should be before the end")
+        }
+        if !self.schema.supports(SchemaVersion::ACCOUNTS_USAGE) {
+            bail!(
+                "the database schema does not count the usage yet: run `diesel migration run` to migrate it"
+            )
+        }
+
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+        crate::usage::export(
+            &mut *self.lock_connection("export_usage", period).await,
+            period,
+        )
+    }
+
+    /// Runs the reads in a snapshot of the database, so that they see the same committed rows
+    /// regardless of the concurrent writes, e.g. for computing TF-IDF over several words.
+    ///
+    /// The snapshot holds a connection until the reads are finished.
+    pub async fn read_consistent<'a, F, Fut, T>(&'a self, f: F) -> Result<T>
+    where
+        F: FnOnce(ConsistentView<'a>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.ensure_feature_enabled(Feature::WordGet)?;
+
+        let conn = self.lock_connection("read_consistent", &()).await;
+        let snapshot = Arc::new(Mutex::new(Snapshot::begin(conn)?));
+
+        let output = f(ConsistentView::new(
+            &self.cipher,
+            snapshot.clone(),
+            self.now(),
+        ))
+        .await?;
+        snapshot
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .commit()?;
+        Ok(output)
+    }
+
+    /// Signs the root of the whole oplog, unless it has been published already.
+    ///
+    /// Returns the newly published root, which may also be stored elsewhere (e.g. in ipsis)
+    /// so that the server cannot rewrite the history without being caught.
+    pub async fn publish_oplog_root_unchecked(&self) -> Result<Option<GuaranteeSigned<OplogRoot>>> {
+        let mut conn = self.lock_connection("publish_oplog_root", &()).await;
+
+        // the entries are committed in order, so a snapshot is always a prefix of the oplog
+        let leaves = crate::oplog::leaves(&mut conn, None)?;
+        let tree_size: i64 = leaves.len().try_into()?;
+        let published: Option<i64> = crate::schema::oplog_roots::table
+            .select(::diesel::dsl::max(crate::schema::oplog_roots::tree_size))
+            .get_result(&mut *conn)?;
+        if tree_size == 0 || published.map_or(false, |published| published >= tree_size) {
+            return Ok(None);
+        }
+
+        let root = self.ipiis.sign(
+            self.ipiis.account_me().account_ref(),
+            OplogRoot {
+                tree_size: tree_size.try_into()?,
+                root: merkle::root(&leaves),
+            },
+        )?;
+
+        // another node sharing the database may have published the same root meanwhile
+        let inserted = ::diesel::insert_into(crate::schema::oplog_roots::table)
+            .values(&crate::oplog::root_to_record(&root)?)
+            .on_conflict_do_nothing()
+            .execute(&mut *conn)?;
+        Ok((inserted > 0).then_some(root))
+    }
+
+    /// Writes the records created since the last backup as a signed delta object,
+    /// or all the records as a full backup if `full` is set or there is no previous backup.
+    ///
+    /// Returns the hash of the backup object.
+    pub async fn backup_unchecked<S>(&self, store: &S, full: bool) -> Result<Hash>
+    where
+        S: BackupStore + ?Sized,
+    {
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+
+        let (base, since, records) = {
+            let mut conn = self.lock_connection("backup", &full).await;
+
+            let (base, since) = match (
+                get_setting(&mut conn, SETTING_BACKUP_LAST)?,
+                get_setting(&mut conn, SETTING_BACKUP_SEQUENCE)?,
+            ) {
+                (Some(base), Some(since)) if !full => {
+                    (Some(base.parse()?), BackupSequence::parse(&since)?)
+                }
+                _ => (None, BackupSequence::default()),
+            };
+
+            let mut dyn_paths: Vec<crate::models::dyn_paths::DynPath> =
+                crate::schema::dyn_paths::table
+                    .filter(crate::schema::dyn_paths::id.gt(since.dyn_paths))
+                    .order(crate::schema::dyn_paths::id.asc())
+                    .get_results(&mut *conn)?;
+            let mut words: Vec<crate::models::words::Word> = crate::schema::words::table
+                .filter(crate::schema::words::id.gt(since.words))
+                .order(crate::schema::words::id.asc())
+                .get_results(&mut *conn)?;
+            crate::retention::restore(&mut conn, crate::export::TABLE_DYN_PATHS, &mut dyn_paths)?;
+            crate::retention::restore(&mut conn, crate::export::TABLE_WORDS, &mut words)?;
+
+            // the words tiered out since the last backup have left the hot table,
+            // and the ones tiered out before `SchemaVersion::WORDS_TIERED` are in the full backups only
+            let tiered = if self.schema.supports(SchemaVersion::WORDS_TIERED) {
+                let mut query = crate::schema::words_tiered::table.into_boxed();
+                if since.words > 0 {
+                    query = query.filter(crate::schema::words_tiered::record_id.gt(since.words));
+                }
+                let index: Vec<crate::models::words_segments::WordTiered> = query
+                    .order(crate::schema::words_tiered::id.asc())
+                    .get_results(&mut *conn)?;
+                let segments = crate::tiering::find_segments(&mut conn, &index)?;
+                (index, segments)
+            } else {
+                Default::default()
+            };
+            (base, since, (dyn_paths, words, tiered))
+        };
+
+        let (dyn_paths, words, (index, segments)) = records;
+        let until = BackupSequence {
+            dyn_paths: dyn_paths
+                .last()
+                .map(|record| record.id)
+                .unwrap_or(since.dyn_paths),
+            words: words
+                .iter()
+                .map(|record| record.id)
+                .chain(index.iter().map(|record| record.record_id))
+                .max()
+                .unwrap_or(since.words)
+                .max(since.words),
+        };
+
+        // the words tiered out while read are read twice, which are deduplicated by their nonces
+        let mut nonces = BTreeSet::new();
+        let words: Vec<BackupWord> = words
+            .into_iter()
+            .map(Into::into)
+            .chain(self.read_tiered(&segments, &index).await?)
+            .filter(|word: &BackupWord| nonces.insert(word.metadata.nonce))
+            .collect();
+        let records = BackupRecords {
+            dyn_paths: dyn_paths.into_iter().map(Into::into).collect(),
+            words,
+        };
+
+        let header = BackupHeader {
+            base,
+            since,
+            until,
+            records: records.hash()?,
+        };
+        let header = self
+            .ipiis
+            .sign(self.ipiis.account_me().account_ref(), header)?;
+
+        let hash = store
+            .put(&BackupObject { header, records }.to_bytes()?)
+            .await?;
+
+        // the next delta follows this backup
+        let mut conn = self.lock_connection("backup", &hash).await;
+        put_setting(&mut conn, SETTING_BACKUP_LAST, hash.to_string())?;
+        put_setting(&mut conn, SETTING_BACKUP_SEQUENCE, until.to_string())?;
+        Ok(hash)
+    }
+
+    /// Replays a full backup and its deltas in order, which should be restored into an empty database.
+    pub async fn restore_from_unchecked<S>(&self, store: &S, backups: &[Hash]) -> Result<()>
+    where
+        S: BackupStore + ?Sized,
+    {
+        self.ensure_feature_enabled(Feature::WordPut)?;
+        self.ensure_feature_enabled(Feature::DynPathPut)?;
+
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+        let guarantor = self.ipiis.account_me().account_ref();
+
+        let mut last: Option<(Hash, BackupSequence)> = None;
+        for hash in backups {
+            let backup = BackupObject::from_bytes(&store.get(hash).await?)?;
+            let header = &backup.header;
+
+            if header.guarantee.account != guarantor {
+                bail!("the backup is not signed by this server: {hash}")
+            }
+            match (&last, header.base) {
+                (None, None) => {}
+                (Some((base, until)), Some(header_base))
+                    if *base == header_base && *until == header.since => {}
+                (None, Some(_)) => bail!("the first backup should be a full backup: {hash}"),
+                _ => bail!("the backup does not follow the previous one: {hash}"),
+            }
+            last = Some((*hash, header.until));
+
+            self.insert_records("restore_from", hash, backup.records)
+                .await?;
+        }
+
+        self.invalidate_cache(Topic::All);
+        Ok(())
+    }
+
+    /// Exports the records into the directory, streaming each table by its own worker.
+    ///
+    /// The progress is recorded in the checkpoint file of the directory,
+    /// so that an interrupted export is resumed from the last written chunk.
+    pub async fn export_unchecked(&self, dir: &::std::path::Path, batch_size: u32) -> Result<()> {
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+
+        ::std::fs::create_dir_all(dir)?;
+        let checkpoint = Checkpoint::load(dir.join("export.checkpoint"))?;
+
+        ::futures::future::try_join_all(
+            crate::export::TABLES
+                .iter()
+                .map(|table| self.export_table(dir, table, batch_size, &checkpoint)),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn export_table(
+        &self,
+        dir: &::std::path::Path,
+        table: &str,
+        batch_size: u32,
+        checkpoint: &Checkpoint,
+    ) -> Result<()> {
+        loop {
+            let last = checkpoint.get(table);
+            let mut conn = self.lock_connection("export", &(table, last)).await;
+
+            let (records, until) = match table {
+                crate::export::TABLE_DYN_PATHS => {
+                    let mut records: Vec<crate::models::dyn_paths::DynPath> =
+                        crate::schema::dyn_paths::table
+                            .filter(crate::schema::dyn_paths::id.gt(last))
+                            .order(crate::schema::dyn_paths::id.asc())
+                            .limit(batch_size.into())
+                            .get_results(&mut *conn)?;
+                    crate::retention::restore(&mut conn, table, &mut records)?;
+                    let until = records.last().map(|record| record.id);
+                    let records = BackupRecords {
+                        dyn_paths: records.into_iter().map(Into::into).collect(),
+                        ..Default::default()
+                    };
+                    (records, until)
+                }
+                crate::export::TABLE_WORDS => {
+                    let mut records: Vec<crate::models::words::Word> = crate::schema::words::table
+                        .filter(crate::schema::words::id.gt(last))
+                        .order(crate::schema::words::id.asc())
+                        .limit(batch_size.into())
+                        .get_results(&mut *conn)?;
+                    crate::retention::restore(&mut conn, table, &mut records)?;
+                    let until = records.last().map(|record| record.id);
+                    let records = BackupRecords {
+                        words: records.into_iter().map(Into::into).collect(),
+                        ..Default::default()
+                    };
+                    (records, until)
+                }
+                crate::export::TABLE_WORDS_TIERED => {
+                    if !self.schema.supports(SchemaVersion::WORDS_TIERED) {
+                        break Ok(());
+                    }
+                    let index: Vec<crate::models::words_segments::WordTiered> =
+                        crate::schema::words_tiered::table
+                            .filter(crate::schema::words_tiered::id.gt(last))
+                            .order(crate::schema::words_tiered::id.asc())
+                            .limit(batch_size.into())
+                            .get_results(&mut *conn)?;
+                    let segments = crate::tiering::find_segments(&mut conn, &index)?;
+                    let until = index.last().map(|record| record.id);
+                    let records = BackupRecords {
+                        words: self.read_tiered(&segments, &index).await?,
+                        ..Default::default()
+                    };
+                    (records, until)
+                }
+                _ => bail!("unknown table: {table}"),
+            };
+            drop(conn);
+
+            match until {
+                Some(until) => {
+                    crate::export::write_chunk(
+                        &crate::export::chunk_path(dir, table, last + 1),
+                        &records,
+                    )?;
+                    checkpoint.set(table, until)?;
+                }
+                None => break Ok(()),
+            }
+        }
+    }
+
+    /// Imports the records exported by `export_unchecked`, importing each table by its own worker.
+    ///
+    /// The progress is recorded in the checkpoint file of the directory,
+    /// so that an interrupted import is resumed from the next chunk.
+    pub async fn import_unchecked(&self, dir: &::std::path::Path) -> Result<()> {
+        self.ensure_feature_enabled(Feature::WordPut)?;
+        self.ensure_feature_enabled(Feature::DynPathPut)?;
+
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+        let checkpoint = Checkpoint::load(dir.join("import.checkpoint"))?;
+
+        ::futures::future::try_join_all(
+            crate::export::TABLES
+                .iter()
+                .map(|table| self.import_table(dir, table, &checkpoint)),
+        )
+        .await?;
+
+        self.invalidate_cache(Topic::All);
+        Ok(())
+    }
+
+    async fn import_table(
+        &self,
+        dir: &::std::path::Path,
+        table: &str,
+        checkpoint: &Checkpoint,
+    ) -> Result<()> {
+        for (first, path) in crate::export::list_chunks(dir, table)? {
+            if first <= checkpoint.get(table) {
+                continue;
+            }
+
+            let records = crate::export::read_chunk(&path)?;
+            self.insert_records("import", &(table, first), records)
+                .await?;
+            checkpoint.set(table, first)?;
+        }
+        Ok(())
+    }
+
+    /// Imports the words of the legacy logs exported line by line in JSON (see `NdjsonWord`),
+    /// e.g. to seed a new node with the history from which the IDF vectors are computed.
+    ///
+    /// The records are verified before stored, and the ones stored already are skipped by their nonces,
+    /// so that an interrupted import can be simply run again.
+    /// The malformed or forged records are rejected one by one, rather than aborting the import.
+    pub async fn import_idf_logs_from_ndjson<R>(&self, reader: R) -> Result<ImportSummary>
+    where
+        R: ::ipis::tokio::io::AsyncBufRead + Unpin,
+    {
+        use ipis::tokio::io::AsyncBufReadExt;
+
+        self.ensure_feature_enabled(Feature::WordPut)?;
+
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+        let mut summary = ImportSummary::default();
+        let mut batch_size = BatchSize::default();
+        let mut pending = vec![];
+
+        let mut lines = reader.lines();
+        let mut index = 0u64;
+        while let Some(line) = lines.next_line().await? {
+            index += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            summary.read += 1;
+
+            let record = NdjsonWord::parse(&line, &self.cipher).and_then(|record| {
+                word_from_record(&self.cipher, &record)?.verify(None)?;
+                Ok(record)
+            });
+            match record {
+                Ok(record) => pending.push(BackupWord::from(record)),
+                Err(error) => summary.rejected.push(ImportRejection {
+                    line: index,
+                    message: error.to_string(),
+                }),
+            }
+
+            if pending.len() >= batch_size.get() {
+                self.import_ndjson_batches(&mut pending, &mut batch_size, &mut summary)
+                    .await?;
+            }
+        }
+        self.import_ndjson_batches(&mut pending, &mut batch_size, &mut summary)
+            .await?;
+
+        self.invalidate_cache(Topic::All);
+        Ok(summary)
+    }
+
+    /// Stores all the pending records in batches, retrying the ones failed transiently.
+    async fn import_ndjson_batches(
+        &self,
+        pending: &mut Vec<BackupWord>,
+        batch_size: &mut BatchSize,
+        summary: &mut ImportSummary,
+    ) -> Result<()> {
+        let mut retries = 0;
+        while !pending.is_empty() {
+            let len = batch_size.get().min(pending.len());
+            match self.import_ndjson_batch(&pending[..len]).await {
+                Ok((imported, duplicates)) => {
+                    summary.imported += imported;
+                    summary.duplicates += duplicates;
+                    pending.drain(..len);
+                    batch_size.grow();
+                    retries = 0;
+                }
+                Err(error)
+                    if retries < crate::ndjson::MAX_RETRIES
+                        && crate::ndjson::is_transient(&error) =>
+                {
+                    retries += 1;
+                    summary.retries += 1;
+                    batch_size.shrink();
+                    ::ipis::tokio::time::sleep(crate::ndjson::backoff(retries)).await;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores the records but the ones stored already, and returns the numbers of both.
+    async fn import_ndjson_batch(&self, records: &[BackupWord]) -> Result<(u64, u64)> {
+        let imported = self
+            .insert_records(
+                "import_idf_logs",
+                &records.len(),
+                BackupRecords {
+                    dyn_paths: vec![],
+                    words: records.to_vec(),
+                },
+            )
+            .await?;
+        Ok((imported, records.len() as u64 - imported))
+    }
+
+    /// Inserts the raw records in a transaction, and counts the words.
+    ///
+    /// Returns the number of the inserted records, where the ones stored already are skipped.
+    async fn insert_records<P>(
+        &self,
+        name: &'static str,
+        params: &P,
+        records: BackupRecords,
+    ) -> Result<u64>
+    where
+        P: ::core::fmt::Debug + ?Sized,
+    {
+        let mut counted = Vec::with_capacity(records.words.len());
+        for record in &records.words {
+            counted.push(match self.config.stop_words {
+                StopWordsPolicy::Counted => true,
+                _ => {
+                    !self
+                        .is_stop_word(&record.kind, &record.lang, &record.word)
+                        .await?
+                }
+            });
+        }
+
+        let unique_nonces = self.schema.supports(SchemaVersion::UNIQUE_NONCES);
+        let words_tiered = self.schema.supports(SchemaVersion::WORDS_TIERED);
+        let log = self.write_log();
+
+        self.lock_connection(name, params)
             .await
-            .transaction::<(), ::diesel::result::Error, _>(|conn| {
-                ::diesel::delete(crate::schema::words::table)
-                    .filter(crate::schema::words::namespace.eq(namespace.to_string()))
-                    .execute(conn)
-                    .map(|_| ())?;
+            .transaction::<u64, Error, _>(|conn| {
+                // the records stored already are skipped by their nonces,
+                // which the older schemas do not enforce
+                let mut total = 0;
+                for record in records.dyn_paths {
+                    let record = crate::models::dyn_paths::NewDynPath::from(record);
+                    if !unique_nonces && find_dyn_path_by_nonce(conn, &record.nonce)?.is_some() {
+                        continue;
+                    }
+                    let inserted: Option<crate::models::dyn_paths::DynPath> =
+                        ::diesel::insert_into(crate::schema::dyn_paths::table)
+                            .values(&record)
+                            .on_conflict_do_nothing()
+                            .get_result(conn)
+                            .optional()?;
+                    if let Some(inserted) = inserted {
+                        log.dyn_path(conn, &self.cipher, &inserted)?;
+                        total += 1;
+                    }
+                }
+                for (record, counted) in records.words.into_iter().zip(counted) {
+                    let record = crate::models::words::NewWord::from(record);
+                    if !unique_nonces && find_word_by_nonce(conn, &record.nonce)?.is_some() {
+                        continue;
+                    }
+                    // the tiered words are stored already as well
+                    if words_tiered && crate::tiering::find_by_nonce(conn, &record.nonce)?.is_some()
+                    {
+                        continue;
+                    }
+                    let inserted: Option<crate::models::words::Word> =
+                        ::diesel::insert_into(crate::schema::words::table)
+                            .values(&record)
+                            .on_conflict_do_nothing()
+                            .get_result(conn)
+                            .optional()?;
+                    if let Some(inserted) = inserted {
+                        if counted {
+                            count_word(conn, &record)?;
+                        }
+                        log.word(conn, &self.cipher, &inserted)?;
+                        total += 1;
+                    }
+                }
 
-                ::diesel::delete(crate::schema::words_counts::table)
-                    .filter(crate::schema::words_counts::namespace.eq(namespace.to_string()))
-                    .execute(conn)
-                    .map(|_| ())?;
+                crate::cache::notify(conn, &Topic::All)?;
+                Ok(total)
+            })
+    }
 
-                ::diesel::delete(crate::schema::words_counts_guarantees::table)
+    /// Returns what to log for the rows written in bulk, as the puts do.
+    fn write_log(&self) -> WriteLog {
+        WriteLog {
+            oplog: self.config.oplog_enabled,
+            usage: self.config.usage_enabled && self.schema.supports(SchemaVersion::ACCOUNTS_USAGE),
+            now: self.now(),
+        }
+    }
+
+    /// Re-validates the signatures of all the rows of the kind against their reconstructed payloads,
+    /// reading the rows in batches.
+    pub async fn verify_integrity_unchecked(&self, kind: &Hash) -> Result<IntegrityReport> {
+        const BATCH_SIZE: i64 = 256;
+
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+        let kind = kind.to_string();
+        let mut report = IntegrityReport::default();
+
+        let mut last = 0;
+        loop {
+            let mut conn = self
+                .lock_connection("verify_integrity", &(&kind, last))
+                .await;
+            let mut records: Vec<crate::models::dyn_paths::DynPath> =
+                crate::schema::dyn_paths::table
+                    .filter(crate::schema::dyn_paths::kind.eq(&kind))
+                    .filter(crate::schema::dyn_paths::id.gt(last))
+                    .order(crate::schema::dyn_paths::id.asc())
+                    .limit(BATCH_SIZE)
+                    .get_results(&mut *conn)?;
+            crate::retention::restore(&mut conn, crate::export::TABLE_DYN_PATHS, &mut records)?;
+            drop(conn);
+            let last_id = records.last().map(|record| record.id);
+            report.dropped += crate::retention::retain_signed(&mut records) as u64;
+
+            for record in &records {
+                report.verify("dyn_paths", record.id, || {
+                    dyn_path_from_record(&self.cipher, record)
+                });
+            }
+            match last_id {
+                Some(id) => last = id,
+                None => break,
+            }
+        }
+
+        let mut last = 0;
+        loop {
+            let mut conn = self
+                .lock_connection("verify_integrity", &(&kind, last))
+                .await;
+            let mut records: Vec<crate::models::words::Word> = crate::schema::words::table
+                .filter(crate::schema::words::kind.eq(&kind))
+                .filter(crate::schema::words::id.gt(last))
+                .order(crate::schema::words::id.asc())
+                .limit(BATCH_SIZE)
+                .get_results(&mut *conn)?;
+            crate::retention::restore(&mut conn, crate::export::TABLE_WORDS, &mut records)?;
+            drop(conn);
+            let last_id = records.last().map(|record| record.id);
+            report.dropped += crate::retention::retain_signed(&mut records) as u64;
+
+            for record in &records {
+                report.verify("words", record.id, || {
+                    word_from_record(&self.cipher, record)
+                });
+            }
+            match last_id {
+                Some(id) => last = id,
+                None => break,
+            }
+        }
+
+        // the tiered words are verified as read back, by the ids of their index
+        if !self.schema.supports(SchemaVersion::WORDS_TIERED) {
+            return Ok(report);
+        }
+        let mut last = 0;
+        loop {
+            let (index, segments) = {
+                let mut conn = self
+                    .lock_connection("verify_integrity", &(&kind, last))
+                    .await;
+                let index: Vec<crate::models::words_segments::WordTiered> =
+                    crate::schema::words_tiered::table
+                        .filter(crate::schema::words_tiered::kind.eq(&kind))
+                        .filter(crate::schema::words_tiered::id.gt(last))
+                        .order(crate::schema::words_tiered::id.asc())
+                        .limit(BATCH_SIZE)
+                        .get_results(&mut *conn)?;
+                let segments = crate::tiering::find_segments(&mut conn, &index)?;
+                (index, segments)
+            };
+            let last_id = index.last().map(|record| record.id);
+            let ids: ::std::collections::BTreeMap<_, _> = index
+                .iter()
+                .map(|record| (record.nonce.as_u128(), record.id))
+                .collect();
+
+            let mut records: Vec<_> = self
+                .read_tiered(&segments, &index)
+                .await?
+                .into_iter()
+                .map(|word| {
+                    let id = ids[&word.metadata.nonce];
+                    crate::tiering::into_record(word, id)
+                })
+                .collect();
+            report.dropped += crate::retention::retain_signed(&mut records) as u64;
+
+            for record in &records {
+                report.verify("words_tiered", record.id, || {
+                    word_from_record(&self.cipher, record)
+                });
+            }
+            match last_id {
+                Some(id) => last = id,
+                None => break,
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Moves the signatures of the records older than the retention period out of the hot tables,
+    /// either into the archive table or dropping them, as configured.
+    ///
+    /// Returns the number of the affected records.
+    pub async fn apply_signature_retention_unchecked(&self) -> Result<usize> {
+        let retention = match self.config.signature_retention {
+            Some(retention) => retention,
+            None => return Ok(0),
+        };
+        let cutoff = self.now() - ::ipis::core::chrono::Duration::from_std(retention.after)?;
+
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+        let affected = self
+            .lock_connection("apply_signature_retention", &cutoff)
+            .await
+            .transaction::<_, ::diesel::result::Error, _>(|conn| {
+                let affected = crate::retention::sweep(conn, retention.policy, cutoff)?;
+
+                // the cached records still carry the signatures, which are not served once dropped
+                crate::cache::notify(conn, &Topic::All)?;
+                Ok(affected)
+            })?;
+
+        self.invalidate_cache(Topic::All);
+        Ok(affected)
+    }
+
+    /// Tiers the superseded words older than the configured age out of the hot table
+    /// into the compressed segments in the store, leaving their index behind.
+    ///
+    /// The counts of the words are kept, and the tiered words are read back by `get_idf_logs_unchecked`.
+    /// The segments tiered out before `SchemaVersion::WORDS_TIERED` are indexed first.
+    /// Returns the number of the tiered words.
+    pub async fn tier_idf_logs_unchecked(&self) -> Result<u64> {
+        let age = match self.config.idf_logs_tiering_age {
+            Some(age) => age,
+            None => return Ok(0),
+        };
+        if !self.schema.supports(SchemaVersion::WORDS_TIERED) {
+            return Ok(0);
+        }
+        // the words are moved between the tables, which is a write as putting them
+        self.ensure_feature_enabled(Feature::WordPut)?;
+        let store = self.segment_store()?;
+        let cutoff = self.now() - ::ipis::core::chrono::Duration::from_std(age)?;
+
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+
+        let legacy =
+            crate::tiering::find_legacy(&mut *self.lock_connection("tier_idf_logs", &()).await)?;
+        for segment in &legacy {
+            let object = WordsSegmentObject::from_bytes(&store.get(&segment.hash.parse()?).await?)?;
+            object.ensure_indexed(segment)?;
+
+            self.lock_connection("tier_idf_logs", &segment.id)
+                .await
+                .transaction::<_, Error, _>(|conn| {
+                    crate::tiering::index_legacy(conn, segment, &object)
+                })?;
+        }
+
+        let mut tiered = 0;
+        loop {
+            let words = {
+                let mut conn = self.lock_connection("tier_idf_logs", &cutoff).await;
+                let mut words =
+                    crate::tiering::select(&mut conn, cutoff, crate::tiering::SEGMENT_LEN)?;
+                crate::retention::restore(&mut conn, crate::export::TABLE_WORDS, &mut words)?;
+                words
+            };
+            let len = words.len();
+            let segment = match WordsSegmentObject::new(words.clone()) {
+                Some(segment) => segment,
+                None => break,
+            };
+
+            // the segment is stored before its words are deleted, so that none of them are lost
+            let hash = store.put(&segment.to_bytes()?).await?;
+            let now = self.now();
+            self.lock_connection("tier_idf_logs", &hash)
+                .await
+                .transaction::<_, Error, _>(|conn| {
+                    crate::tiering::commit(conn, &hash, &segment, &words, now)?;
+                    crate::cache::notify(conn, &Topic::All)?;
+                    Ok(())
+                })?;
+            self.invalidate_cache(Topic::All);
+
+            tiered += len as u64;
+            if (len as i64) < crate::tiering::SEGMENT_LEN {
+                break;
+            }
+        }
+        Ok(tiered)
+    }
+
+    /// Returns the live words created in `since..until` ordered by their creation dates,
+    /// which are read from both the hot table and the segments tiered out to the store.
+    ///
+    /// It is a thin wrapper of `IpdisAdmin::get_idf_log_page_unchecked`, collecting all the pages,
+    /// which fails if more words than `IpdisConfig::max_query_rows` are matched.
+    /// The words whose signatures have been dropped by the retention policy are skipped.
+    pub async fn get_idf_logs_unchecked(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<GuarantorSigned<WordHash>>> {
+        let page_size = self.config.max_query_rows;
+        let mut query = GetIdfLogs {
+            since: since.timestamp_millis(),
+            until: until.timestamp_millis(),
+            start_index: 0,
+            end_index: page_size,
+        };
+
+        let _permit = self.enter_queue(RequestClass::Bulk).await?;
+        let mut words = vec![];
+        loop {
+            let page = self.get_idf_log_page_unchecked(None, &query).await?;
+            words.extend(page.items);
+            self.config.ensure_query_rows(words.len().try_into()?)?;
+
+            match page.next_cursor {
+                Some(start_index) => {
+                    query.start_index = start_index;
+                    query.end_index = start_index.saturating_add(page_size);
+                }
+                None => break Ok(words),
+            }
+        }
+    }
+
+    /// Returns the page of the live words created in `since..until` of both tiers.
+    ///
+    /// The cursor counts the words whose signatures have been dropped as well, which are skipped.
+    async fn find_idf_log_page(
+        &self,
+        since: NaiveDateTime,
+        until: NaiveDateTime,
+        start_index: u32,
+        end_index: u32,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
+        let (offset, limit): (i64, i64) = (start_index.into(), (end_index - start_index).into());
+        let now = self.now();
+
+        let (mut records, index, segments) = {
+            let mut conn = self.lock_connection("get_idf_logs", &(since, until)).await;
+            if self.schema.supports(SchemaVersion::WORDS_TIERED) {
+                conn.transaction::<_, Error, _>(|conn| {
+                    let entries =
+                        crate::tiering::find_logs(conn, since, until, now, offset, limit)?;
+
+                    let (hot, tiered): (Vec<_>, Vec<_>) = entries
+                        .into_iter()
+                        .partition(|entry| entry.tiered_id.is_none());
+                    let mut records: Vec<crate::models::words::Word> = crate::schema::words::table
+                        .filter(
+                            crate::schema::words::nonce.eq_any(hot.iter().map(|entry| entry.nonce)),
+                        )
+                        .get_results(conn)?;
+                    crate::retention::restore(conn, crate::export::TABLE_WORDS, &mut records)?;
+
+                    let index: Vec<crate::models::words_segments::WordTiered> =
+                        crate::schema::words_tiered::table
+                            .filter(
+                                crate::schema::words_tiered::id
+                                    .eq_any(tiered.iter().filter_map(|entry| entry.tiered_id)),
+                            )
+                            .get_results(conn)?;
+                    let segments = crate::tiering::find_segments(conn, &index)?;
+                    Ok((records, index, segments))
+                })?
+            } else {
+                let mut records: Vec<crate::models::words::Word> = crate::schema::words::table
+                    .filter(crate::schema::words::created_date.ge(since))
+                    .filter(crate::schema::words::created_date.lt(until))
                     .filter(
-                        crate::schema::words_counts_guarantees::namespace.eq(namespace.to_string()),
+                        crate::schema::words::expiration_date
+                            .is_null()
+                            .or(crate::schema::words::expiration_date.ge(now)),
                     )
-                    .execute(conn)
-                    .map(|_| ())?;
+                    .order((
+                        crate::schema::words::created_date.asc(),
+                        crate::schema::words::nonce.asc(),
+                    ))
+                    .offset(offset)
+                    .limit(limit)
+                    .get_results(&mut *conn)?;
+                crate::retention::restore(&mut conn, crate::export::TABLE_WORDS, &mut records)?;
+                (records, vec![], vec![])
+            }
+        };
 
-                Ok(())
-            })
-            .map_err(Into::into)
+        records.extend(
+            self.read_tiered(&segments, &index)
+                .await?
+                .into_iter()
+                .map(|word| crate::tiering::into_record(word, 0)),
+        );
+        records.sort_by_key(|record| (record.created_date, record.nonce));
+        let mut page = Page::new(records, start_index, end_index, None);
+        // the words whose signatures have been dropped are not served anymore
+        page.items.retain(|record| {
+            record.guarantee_signature.is_some() && record.guarantor_signature.is_some()
+        });
+
+        Ok(Page {
+            items: page
+                .items
+                .iter()
+                .map(|record| word_from_record(&self.cipher, record))
+                .collect::<Result<_>>()?,
+            next_cursor: page.next_cursor,
+            total: None,
+        })
+    }
+
+    /// Deletes all the expired records and the delivered events, and discounts the expired words.
+    pub async fn delete_expired_all_unchecked(&self) -> Result<()> {
+        self.ensure_feature_enabled(Feature::Delete)?;
+
+        let now = self.now();
+        let words_tiered = self.schema.supports(SchemaVersion::WORDS_TIERED);
+        self.lock_connection("delete_expired_all", &now)
+            .await
+            .transaction::<(), Error, _>(|conn| {
+                crate::lock::lock_all(conn)?;
+
+                ::diesel::delete(crate::schema::accounts_guarantees::table)
+                    .filter(crate::schema::accounts_guarantees::expiration_date.lt(now))
+                    .execute(conn)?;
+
+                ::diesel::delete(crate::schema::dyn_paths::table)
+                    .filter(crate::schema::dyn_paths::expiration_date.lt(now))
+                    .execute(conn)?;
+
+                ::diesel::delete(crate::schema::outbox::table)
+                    .filter(crate::schema::outbox::delivered_date.is_not_null())
+                    .execute(conn)?;
+
+                let words: Vec<crate::models::words::Word> = crate::schema::words::table
+                    .filter(crate::schema::words::expiration_date.lt(now))
+                    .get_results(conn)?;
+
+                delete_words(conn, &words)?;
+
+                if words_tiered {
+                    let index: Vec<crate::models::words_segments::WordTiered> =
+                        crate::schema::words_tiered::table
+                            .filter(crate::schema::words_tiered::expiration_date.lt(now))
+                            .get_results(conn)?;
+
+                    delete_tiered_words(conn, &index)?;
+                }
+
+                crate::cache::notify(conn, &Topic::All).map_err(Into::into)
+            })?;
+
+        self.invalidate_cache(Topic::All);
+        Ok(())
+    }
+}
+
+/// the number of the words rewritten in a transaction while reindexing
+const REINDEX_BATCH_SIZE: i64 = 256;
+
+const SETTING_BACKUP_LAST: &str = "backup_last";
+const SETTING_BACKUP_SEQUENCE: &str = "backup_sequence";
+const SETTING_READ_ONLY: &str = "read_only";
+const SETTING_COLUMNS_ENCRYPTED: &str = "columns_encrypted";
+
+/// The version of the schema which this binary expects, i.e. the number of the migrations.
+pub const SCHEMA_VERSION: i32 = 29;
+
+/// the columns of the kinds before `SchemaVersion::KINDS_NORMALIZATION`
+const KINDS_V22_COLUMNS: (
+    crate::schema::kinds::id,
+    crate::schema::kinds::kind,
+    crate::schema::kinds::name,
+    crate::schema::kinds::description,
+    crate::schema::kinds::schema_version,
+) = (
+    crate::schema::kinds::id,
+    crate::schema::kinds::kind,
+    crate::schema::kinds::name,
+    crate::schema::kinds::description,
+    crate::schema::kinds::schema_version,
+);
+
+/// Fails fast if this binary cannot work on the version of the database schema.
+///
+/// The older schemas since `SchemaVersion::MIN` are read through the compatibility layer,
+/// and the newer ones are accepted as long as they remain compatible with this binary.
+pub(crate) fn ensure_schema_version(conn: &mut PgConnection) -> Result<SchemaVersion> {
+    let version = crate::schema::schema_meta::table
+        .select(crate::schema::schema_meta::version)
+        .get_results::<i32>(conn)
+        .map(|mut versions| versions.pop());
+
+    let version = match version {
+        Ok(Some(version)) if version >= SchemaVersion::MIN.0 => SchemaVersion(version),
+        Ok(Some(version)) => bail!(
+            "the database schema is outdated (expected {SCHEMA_VERSION}, but given {version}): run `diesel migration run` to migrate it"
+        ),
+        Ok(None) => bail!(
+            "the database schema is outdated (expected {SCHEMA_VERSION}, but not versioned): run `diesel migration run` to migrate it"
+        ),
+        // the table may not exist yet
+        Err(error) => bail!(
+            "failed to check the database schema ({error}): run `diesel migration run` to migrate it"
+        ),
+    };
+
+    // the older schemas are compatible with their own versions only
+    let compatible_version = if version.supports(SchemaVersion::COMPATIBLE_VERSION) {
+        crate::schema::schema_meta::table
+            .select(crate::schema::schema_meta::compatible_version)
+            .get_result::<i32>(conn)?
+    } else {
+        version.0
+    };
+
+    if compatible_version > SCHEMA_VERSION {
+        bail!(
+            "the database schema is newer than this binary (expected {SCHEMA_VERSION}, but given {} compatible since {compatible_version}): upgrade the binary",
+            version.0,
+        )
+    }
+    Ok(version)
+}
+
+/// Counts the word record, which should be called in the same transaction of the insertion.
+fn count_word(
+    conn: &mut PgConnection,
+    record: &crate::models::words::NewWord,
+) -> Result<(), ::diesel::result::Error> {
+    // check whether word exists
+    match crate::schema::words_counts::table
+        .filter(crate::schema::words_counts::namespace.eq(&record.namespace))
+        .filter(crate::schema::words_counts::kind.eq(&record.kind))
+        .filter(crate::schema::words_counts::parent.eq(&record.parent))
+        .filter(crate::schema::words_counts::lang.eq(&record.lang))
+        .filter(crate::schema::words_counts::word.eq(&record.word))
+        .get_results::<crate::models::words::WordCount>(conn)?
+        .pop()
+    {
+        // old word => append the count
+        Some(word_count) => ::diesel::update(crate::schema::words_counts::table)
+            .filter(crate::schema::words_counts::id.eq(word_count.id))
+            .set(crate::schema::words_counts::count.eq(word_count.count + 1))
+            .execute(conn)?,
+        // new word => insert the word record
+        None => {
+            let word_record = crate::models::words::NewWordCount {
+                namespace: record.namespace.clone(),
+                kind: record.kind.clone(),
+                parent: record.parent.clone(),
+                lang: record.lang.clone(),
+                word: record.word.clone(),
+                count: 1,
+            };
+
+            ::diesel::insert_into(crate::schema::words_counts::table)
+                .values(&word_record)
+                .execute(conn)?
+        }
+    };
+
+    // check whether word of guarantee exists
+    match crate::schema::words_counts_guarantees::table
+        .filter(crate::schema::words_counts_guarantees::guarantee.eq(&record.guarantee))
+        .filter(crate::schema::words_counts_guarantees::kind.eq(&record.kind))
+        .filter(crate::schema::words_counts_guarantees::parent.eq(&record.parent))
+        .filter(crate::schema::words_counts_guarantees::lang.eq(&record.lang))
+        .filter(crate::schema::words_counts_guarantees::word.eq(&record.word))
+        .get_results::<crate::models::words::WordCountGuarantee>(conn)?
+        .pop()
+    {
+        // old word => append the count
+        Some(word_count_guarantee) => {
+            ::diesel::update(crate::schema::words_counts_guarantees::table)
+                .filter(crate::schema::words_counts_guarantees::id.eq(word_count_guarantee.id))
+                .set(
+                    crate::schema::words_counts_guarantees::count
+                        .eq(word_count_guarantee.count + 1),
+                )
+                .execute(conn)?
+        }
+        // new word => insert the word record
+        None => {
+            let word_record = crate::models::words::NewWordCountGuarantee {
+                guarantee: record.guarantee.clone(),
+                namespace: record.namespace.clone(),
+                kind: record.kind.clone(),
+                parent: record.parent.clone(),
+                lang: record.lang.clone(),
+                word: record.word.clone(),
+                count: 1,
+            };
+
+            ::diesel::insert_into(crate::schema::words_counts_guarantees::table)
+                .values(&word_record)
+                .execute(conn)?
+        }
+    };
+    Ok(())
+}
+
+/// A word signed by the server, which is ready to be inserted.
+struct PreparedWord {
+    word: GuarantorSigned<WordHash>,
+    record: crate::models::words::NewWord,
+    counted: bool,
+    oplog: Option<crate::models::oplog::NewOplogEntry>,
+}
+
+impl PreparedWord {
+    fn receipt(&self, id: i32, server_time: i64) -> Result<PutReceipt> {
+        Ok(PutReceipt {
+            nonce: self.word.nonce.0,
+            seq: id.try_into()?,
+            guarantor_signature: self.word.guarantor.signature,
+            server_time,
+        })
+    }
+}
+
+/// What to log for the rows written in bulk, e.g. by a restore or a migration, as the puts do.
+#[derive(Copy, Clone, Debug)]
+struct WriteLog {
+    /// whether to append the rows to the oplog
+    oplog: bool,
+    /// whether to count the rows to the usage of their owners
+    usage: bool,
+    now: ::ipis::core::chrono::NaiveDateTime,
+}
+
+impl WriteLog {
+    /// Logs the written row, which should be called in the same transaction of the write.
+    fn dyn_path(
+        &self,
+        conn: &mut PgConnection,
+        cipher: &ColumnCipher,
+        record: &crate::models::dyn_paths::DynPath,
+    ) -> Result<()> {
+        self.log(conn, crate::export::TABLE_DYN_PATHS, record, || {
+            dyn_path_from_record(cipher, record).map(SignedRecord::DynPath)
+        })
+    }
+
+    /// Logs the written row, which should be called in the same transaction of the write.
+    fn word(
+        &self,
+        conn: &mut PgConnection,
+        cipher: &ColumnCipher,
+        record: &crate::models::words::Word,
+    ) -> Result<()> {
+        self.log(conn, crate::export::TABLE_WORDS, record, || {
+            word_from_record(cipher, record).map(SignedRecord::Word)
+        })
+    }
+
+    fn log<R>(
+        &self,
+        conn: &mut PgConnection,
+        table: &'static str,
+        record: &R,
+        signed: impl FnOnce() -> Result<SignedRecord>,
+    ) -> Result<()>
+    where
+        R: HasSignatures,
+    {
+        if self.usage {
+            crate::usage::record(conn, table, record.id(), self.now)?;
+        }
+        // the rows whose signatures have been dropped have nothing to be logged
+        if self.oplog && matches!(record.signatures(), (Some(_), Some(_))) {
+            crate::oplog::append(conn, &crate::oplog::entry(&signed()?, self.now)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the id of the dyn_path record of the nonce, if it has been accepted already.
+fn find_dyn_path_by_nonce(
+    conn: &mut PgConnection,
+    nonce: &::ipis::core::uuid::Uuid,
+) -> Result<Option<i32>, ::diesel::result::Error> {
+    crate::schema::dyn_paths::table
+        .filter(crate::schema::dyn_paths::nonce.eq(nonce))
+        .select(crate::schema::dyn_paths::id)
+        .get_result(conn)
+        .optional()
+}
+
+/// Returns the id of the word record of the nonce, if it has been accepted already.
+fn find_word_by_nonce(
+    conn: &mut PgConnection,
+    nonce: &::ipis::core::uuid::Uuid,
+) -> Result<Option<i32>, ::diesel::result::Error> {
+    crate::schema::words::table
+        .filter(crate::schema::words::nonce.eq(nonce))
+        .select(crate::schema::words::id)
+        .get_result(conn)
+        .optional()
+}
+
+/// Returns the record of the replacement, which should only differ from the old word by the message.
+fn replacement(
+    cipher: &ColumnCipher,
+    old: &crate::models::words::Word,
+    word: &GuarantorSigned<WordHash>,
+    hash_version: i32,
+) -> Result<crate::models::words::NewWord> {
+    let record = crate::models::words::NewWord {
+        nonce: word.nonce.0 .0,
+        guarantee: word.guarantee.account.to_string(),
+        guarantor: word.guarantor.account.to_string(),
+        guarantee_signature: Some(word.guarantee.signature.to_string()),
+        guarantor_signature: Some(word.guarantor.signature.to_string()),
+        created_date: word.created_date.naive_utc(),
+        expiration_date: word.expiration_date.map(|e| e.naive_utc()),
+        namespace: word.data.key.namespace.to_string(),
+        parent: old.parent.clone(),
+        lang: word.data.key.text.lang.to_string(),
+        word: cipher.encrypt(word.data.key.text.msg.to_string()),
+        kind: word.data.kind.to_string(),
+        relpath: word.data.relpath,
+        path: word.data.path.value.to_string(),
+        len: word.data.path.len.try_into()?,
+        metadata: old.metadata.clone(),
+        on_behalf_of: old.on_behalf_of.clone(),
+        hash_version,
+    };
+
+    if record.guarantee != old.guarantee
+        || record.namespace != old.namespace
+        || record.kind != old.kind
+        || record.lang != old.lang
+        || record.relpath != old.relpath
+        || record.path != old.path
+        || record.len != old.len
+    {
+        bail!("the replacement does not match the word: {}", old.nonce)
+    }
+    Ok(record)
+}
+
+/// Deletes the word records, and discounts them.
+fn delete_words(
+    conn: &mut PgConnection,
+    words: &[crate::models::words::Word],
+) -> Result<(), ::diesel::result::Error> {
+    for word in words {
+        discount_word(conn, word)?;
+    }
+
+    ::diesel::delete(crate::schema::words::table)
+        .filter(crate::schema::words::id.eq_any(words.iter().map(|word| word.id)))
+        .execute(conn)?;
+
+    delete_uncounted_words(conn)
+}
+
+/// Fails if any of the tiered words to be read back has been tiered out meanwhile, e.g. concurrently,
+/// so that the rewrite not aware of the tiers can be retried.
+fn ensure_untiered(remaining: i64) -> Result<()> {
+    if remaining > 0 {
+        bail!("the words have been tiered out while read back: retry")
+    }
+    Ok(())
+}
+
+/// Deletes the index of the tiered words, and discounts them.
+///
+/// Their segments are kept, from which the words are not served anymore once unindexed.
+fn delete_tiered_words(
+    conn: &mut PgConnection,
+    index: &[crate::models::words_segments::WordTiered],
+) -> Result<(), ::diesel::result::Error> {
+    for word in index {
+        discount_word(conn, word)?;
+    }
+
+    ::diesel::delete(crate::schema::words_tiered::table)
+        .filter(crate::schema::words_tiered::id.eq_any(index.iter().map(|word| word.id)))
+        .execute(conn)?;
+
+    delete_uncounted_words(conn)
+}
+
+/// The columns by which the words are counted, of either the hot or the tiered ones.
+struct WordCountKey<'a> {
+    guarantee: &'a str,
+    namespace: &'a str,
+    kind: &'a str,
+    parent: &'a str,
+    lang: &'a str,
+    word: &'a str,
+}
+
+impl<'a> From<&'a crate::models::words::Word> for WordCountKey<'a> {
+    fn from(word: &'a crate::models::words::Word) -> Self {
+        Self {
+            guarantee: &word.guarantee,
+            namespace: &word.namespace,
+            kind: &word.kind,
+            parent: &word.parent,
+            lang: &word.lang,
+            word: &word.word,
+        }
+    }
+}
+
+impl<'a> From<&'a crate::models::words_segments::WordTiered> for WordCountKey<'a> {
+    fn from(word: &'a crate::models::words_segments::WordTiered) -> Self {
+        Self {
+            guarantee: &word.guarantee,
+            namespace: &word.namespace,
+            kind: &word.kind,
+            parent: &word.parent,
+            lang: &word.lang,
+            word: &word.word,
+        }
+    }
+}
+
+/// Discounts the word record, which should be called in the same transaction of the deletion.
+///
+/// Returns whether the word has been counted.
+fn discount_word<'a>(
+    conn: &mut PgConnection,
+    word: impl Into<WordCountKey<'a>>,
+) -> Result<bool, ::diesel::result::Error> {
+    let word = word.into();
+    let counted = ::diesel::update(crate::schema::words_counts::table)
+        .filter(crate::schema::words_counts::namespace.eq(word.namespace))
+        .filter(crate::schema::words_counts::kind.eq(word.kind))
+        .filter(crate::schema::words_counts::parent.eq(word.parent))
+        .filter(crate::schema::words_counts::lang.eq(word.lang))
+        .filter(crate::schema::words_counts::word.eq(word.word))
+        .set(crate::schema::words_counts::count.eq(crate::schema::words_counts::count - 1))
+        .execute(conn)?;
+
+    ::diesel::update(crate::schema::words_counts_guarantees::table)
+        .filter(crate::schema::words_counts_guarantees::guarantee.eq(word.guarantee))
+        .filter(crate::schema::words_counts_guarantees::namespace.eq(word.namespace))
+        .filter(crate::schema::words_counts_guarantees::kind.eq(word.kind))
+        .filter(crate::schema::words_counts_guarantees::parent.eq(word.parent))
+        .filter(crate::schema::words_counts_guarantees::lang.eq(word.lang))
+        .filter(crate::schema::words_counts_guarantees::word.eq(word.word))
+        .set(
+            crate::schema::words_counts_guarantees::count
+                .eq(crate::schema::words_counts_guarantees::count - 1),
+        )
+        .execute(conn)?;
+    Ok(counted > 0)
+}
+
+/// Cleans up the words which are not counted anymore.
+fn delete_uncounted_words(conn: &mut PgConnection) -> Result<(), ::diesel::result::Error> {
+    ::diesel::delete(crate::schema::words_counts::table)
+        .filter(crate::schema::words_counts::count.le(0))
+        .execute(conn)?;
+    ::diesel::delete(crate::schema::words_counts_guarantees::table)
+        .filter(crate::schema::words_counts_guarantees::count.le(0))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Fails fast if the column encryption is enabled while the plaintext rows remain,
+/// which could not be found by the encrypted lookups.
+///
+/// The tables are scanned only until they are found encrypted once,
+/// which is forgotten as soon as a node runs without the key.
+fn ensure_columns_encrypted(
+    conn: &mut PgConnection,
+    cipher: &ColumnCipher,
+    schema: SchemaVersion,
+) -> Result<()> {
+    let is_checked = get_setting(conn, SETTING_COLUMNS_ENCRYPTED)?.as_deref() == Some("true");
+
+    if !cipher.is_enabled() {
+        if is_checked {
+            put_setting(conn, SETTING_COLUMNS_ENCRYPTED, false.to_string())?;
+        }
+        return Ok(());
+    }
+    if !schema.supports(SchemaVersion::ENCRYPTED_COLUMNS) {
+        bail!(
+            "the column encryption key is given, but the columns are too narrow for the ciphertexts: run `diesel migration run` to migrate the database"
+        )
+    }
+    if is_checked {
+        return Ok(());
+    }
+
+    if crate::encryption::has_plaintext_rows(conn, schema)? {
+        bail!(
+            "the column encryption key is given, but the database has the plaintext rows: run `encryption::encrypt_plaintext_rows` to encrypt them"
+        )
+    }
+    put_setting(conn, SETTING_COLUMNS_ENCRYPTED, true.to_string())
+}
+
+fn get_setting(conn: &mut PgConnection, name: &str) -> Result<Option<String>> {
+    crate::schema::settings::table
+        .filter(crate::schema::settings::name.eq(name))
+        .select(crate::schema::settings::value)
+        .get_results::<String>(conn)
+        .map(|mut values| values.pop())
+        .map_err(Into::into)
+}
+
+fn put_setting(conn: &mut PgConnection, name: &str, value: String) -> Result<()> {
+    let record = crate::models::settings::NewSetting {
+        name: name.to_string(),
+        value,
+    };
+
+    ::diesel::insert_into(crate::schema::settings::table)
+        .values(&record)
+        .on_conflict(crate::schema::settings::name)
+        .do_update()
+        .set(crate::schema::settings::value.eq(&record.value))
+        .execute(conn)
+        .map(|_| ())
+        .map_err(Into::into)
+}
+
+fn kind_from_record(record: crate::models::kinds::Kind) -> Result<KindInfo> {
+    Ok(KindInfo {
+        kind: record.kind.parse()?,
+        name: record.name,
+        description: record.description,
+        schema_version: record.schema_version.try_into()?,
+        normalization: record
+            .normalization
+            .map(|normalization| normalization.parse())
+            .transpose()?,
+    })
+}
+
+/// Ensures that the word declares the normalization of its kind, if enforced,
+/// so that the counts within the kind remain comparable.
+fn ensure_normalization(
+    conn: &mut PgConnection,
+    kind: &str,
+    declared: Option<Normalization>,
+) -> Result<()> {
+    let policy: Option<Normalization> = crate::schema::kinds::table
+        .select(crate::schema::kinds::normalization)
+        .filter(crate::schema::kinds::kind.eq(kind))
+        .get_results::<Option<String>>(conn)?
+        .pop()
+        .flatten()
+        .map(|policy| policy.parse())
+        .transpose()?;
+
+    match (policy, declared) {
+        (None, _) => Ok(()),
+        (Some(policy), Some(declared)) if policy == declared => Ok(()),
+        (Some(policy), Some(declared)) => bail!(
+            "malformed word: the normalization {declared} does not match the policy {policy} of the kind"
+        ),
+        (Some(policy), None) => {
+            bail!("malformed word: the normalization {policy} of the kind should be declared")
+        }
     }
 }
+
+fn dyn_path_from_record(
+    cipher: &ColumnCipher,
+    record: &crate::models::dyn_paths::DynPath,
+) -> Result<GuarantorSigned<DynPath<Path>>> {
+    Ok(GuarantorSigned {
+        guarantor: Identity {
+            account: AccountRef {
+                public_key: record.guarantor.parse()?,
+            },
+            signature: signature_of(&record.guarantor_signature)?.parse()?,
+        },
+        data: GuaranteeSigned {
+            guarantee: Identity {
+                account: AccountRef {
+                    public_key: record.guarantee.parse()?,
+                },
+                signature: signature_of(&record.guarantee_signature)?.parse()?,
+            },
+            data: Metadata {
+                nonce: Uuid(record.nonce).into(),
+                created_date: NaiveDateTime(record.created_date).to_utc(),
+                expiration_date: record.expiration_date.map(|e| NaiveDateTime(e).to_utc()),
+                guarantor: record.guarantor.parse()?,
+                data: DynPath {
+                    namespace: record.namespace.parse()?,
+                    kind: record.kind.parse()?,
+                    word: cipher.decrypt(&record.word)?.parse()?,
+                    path: Path {
+                        value: record.path.parse()?,
+                        len: record.len.try_into()?,
+                    },
+                },
+            },
+        },
+    })
+}
+
+fn oplog_root_from_record(
+    record: &crate::models::oplog::OplogRoot,
+) -> Result<GuaranteeSigned<OplogRoot>> {
+    Ok(GuaranteeSigned {
+        guarantee: Identity {
+            account: AccountRef {
+                public_key: record.guarantee.parse()?,
+            },
+            signature: record.guarantee_signature.parse()?,
+        },
+        data: Metadata {
+            nonce: Uuid(record.nonce).into(),
+            created_date: NaiveDateTime(record.created_date).to_utc(),
+            expiration_date: record.expiration_date.map(|e| NaiveDateTime(e).to_utc()),
+            guarantor: record.guarantor.parse()?,
+            data: OplogRoot {
+                tree_size: record.tree_size.try_into()?,
+                root: record.root.parse()?,
+            },
+        },
+    })
+}
+
+fn word_from_record(
+    cipher: &ColumnCipher,
+    record: &crate::models::words::Word,
+) -> Result<GuarantorSigned<WordHash>> {
+    Ok(GuarantorSigned {
+        guarantor: Identity {
+            account: AccountRef {
+                public_key: record.guarantor.parse()?,
+            },
+            signature: signature_of(&record.guarantor_signature)?.parse()?,
+        },
+        data: GuaranteeSigned {
+            guarantee: Identity {
+                account: AccountRef {
+                    public_key: record.guarantee.parse()?,
+                },
+                signature: signature_of(&record.guarantee_signature)?.parse()?,
+            },
+            data: Metadata {
+                nonce: Uuid(record.nonce).into(),
+                created_date: NaiveDateTime(record.created_date).to_utc(),
+                expiration_date: record.expiration_date.map(|e| NaiveDateTime(e).to_utc()),
+                guarantor: record.guarantor.parse()?,
+                data: WordHash {
+                    key: WordKeyHash {
+                        namespace: record.namespace.parse()?,
+                        text: TextHash {
+                            lang: record.lang.parse()?,
+                            msg: cipher.decrypt(&record.word)?.parse()?,
+                        },
+                    },
+                    kind: record.kind.parse()?,
+                    relpath: record.relpath,
+                    path: Path {
+                        value: record.path.parse()?,
+                        len: record.len.try_into()?,
+                    },
+                },
+            },
+        },
+    })
+}
@@ -0,0 +1,502 @@
+use std::{path::PathBuf, time::Duration};
+
+use ipdis_common::{
+    ensure_payload_len, membership, Feature, FeatureSet, IpdisError, MAX_METADATA_LEN,
+    METADATA_PAYLOAD, QUERIES_PAYLOAD, WORDS_PAYLOAD,
+};
+use ipis::{
+    core::{
+        account::AccountRef,
+        anyhow::{bail, Error, Result},
+        value::hash::Hash,
+    },
+    env,
+};
+
+use crate::privacy::CountNoise;
+
+#[derive(Clone, Debug)]
+pub struct IpdisConfig {
+    /// the accounts which are permitted to call the admin APIs, besides the server itself
+    pub admin_accounts: Vec<AccountRef>,
+    /// the accounts which are permitted to send requests, or `None` (or empty) to permit all
+    pub allowed_accounts: Option<Vec<AccountRef>>,
+    /// whether to cache the hot lookups in process, invalidated across the nodes
+    pub cache_enabled: bool,
+    /// the other nodes of the cluster, whose announcements are discovered besides the admins',
+    /// see `ipdis_common::membership`
+    pub cluster_accounts: Vec<AccountRef>,
+    /// the privacy budgets of the word counts, which are noised for the other accounts
+    pub count_noise: CountNoise,
+    /// the accounts which are permitted to write on behalf of the other accounts (e.g. gateways),
+    /// each for the given principal only
+    pub delegate_accounts: Vec<Delegate>,
+    /// the features which are rejected with `IpdisError::FeatureDisabled`
+    pub features_disabled: FeatureSet,
+    /// how to put the dyn_paths which already exist, per the kind
+    pub dyn_path_conflict: DynPathConflict,
+    /// how to sweep the guarantees expiring soon, or `None` to let them expire silently
+    pub guarantee_expiry: Option<GuaranteeExpiry>,
+    /// the interval of deleting the expired records, or `None` to disable
+    pub gc_interval: Option<Duration>,
+    /// the version of the hashing scheme of the new words, which is bumped before reindexing them
+    pub hash_version: u32,
+    /// the age of the superseded words which are tiered out to the segments, or `None` to keep them,
+    /// which is ignored until the database is migrated to `SchemaVersion::WORDS_TIERED`
+    pub idf_logs_tiering_age: Option<Duration>,
+    /// whether to write the events of the writes to the outbox
+    pub outbox_enabled: bool,
+    /// whether to log every accepted write in the total order, see `Ipdis::get_oplog`
+    pub oplog_enabled: bool,
+    /// whether to count the usage of the accounts in the writes, see `export_usage_unchecked`,
+    /// which is ignored until the database is migrated to `SchemaVersion::ACCOUNTS_USAGE`
+    pub usage_enabled: bool,
+    /// the maximum number of the queries in a batch
+    pub max_batch_size: u32,
+    /// the maximum size of the metadata attached to a record, in bytes
+    pub max_metadata_len: u32,
+    /// the maximum number of the rows which a single query may return
+    pub max_query_rows: u32,
+    /// the maximum number of the words in a request, e.g. of the IDF vectors
+    pub max_words_len: u32,
+    /// the maximum time which a writer lease is held unless renewed
+    pub max_writer_lease_ttl: Duration,
+    /// the number of the database connections
+    pub pool_size: u32,
+    /// how to retain the signatures of the old records, or `None` to keep them in place
+    pub signature_retention: Option<SignatureRetention>,
+    /// how to put the stop words
+    pub stop_words: StopWordsPolicy,
+    /// the number of the database connections which the bulk requests may not use
+    pub queue_reserved: u32,
+    /// the number of the requests handled at once, or `None` to not limit them
+    pub max_concurrent_requests: Option<u32>,
+    /// the time which the queued requests may wait for their turn, or `None` to wait forever
+    pub queue_timeout: Option<Duration>,
+}
+
+impl IpdisConfig {
+    pub fn try_infer() -> Result<Self> {
+        let admin_accounts: Option<String> = env::infer("ipdis_admin_accounts").ok();
+        let allowed_accounts: Option<String> = env::infer("ipdis_allowed_accounts").ok();
+        let cluster_accounts: Option<String> = env::infer("ipdis_cluster_accounts").ok();
+        let count_noise: Option<String> = env::infer("ipdis_count_noise_epsilon").ok();
+        let count_noise_secret: Option<String> = env::infer("ipdis_count_noise_secret").ok();
+        let delegate_accounts: Option<String> = env::infer("ipdis_delegate_accounts").ok();
+
+        Ok(Self {
+            admin_accounts: admin_accounts
+                .as_deref()
+                .map(parse_list)
+                .transpose()?
+                .unwrap_or_default(),
+            allowed_accounts: allowed_accounts
+                .as_deref()
+                .map(parse_list)
+                .transpose()?
+                .filter(|accounts: &Vec<_>| !accounts.is_empty()),
+            cache_enabled: infer("ipdis_cache_enabled")?.unwrap_or_default(),
+            cluster_accounts: cluster_accounts
+                .as_deref()
+                .map(parse_list)
+                .transpose()?
+                .unwrap_or_default(),
+            count_noise: count_noise
+                .as_deref()
+                .map(|count_noise| match count_noise_secret.as_deref() {
+                    Some(secret) => CountNoise::parse(count_noise)?.with_secret(secret),
+                    None => CountNoise::parse(count_noise),
+                })
+                .transpose()?
+                .unwrap_or_default(),
+            delegate_accounts: delegate_accounts
+                .as_deref()
+                .map(parse_list)
+                .transpose()?
+                .unwrap_or_default(),
+            dyn_path_conflict: DynPathConflict::try_infer()?,
+            features_disabled: infer("ipdis_features_disabled")?.unwrap_or_default(),
+            guarantee_expiry: GuaranteeExpiry::try_infer()?,
+            gc_interval: infer("ipdis_gc_interval_secs")?.map(Duration::from_secs),
+            hash_version: infer("ipdis_hash_version")?.unwrap_or(1),
+            idf_logs_tiering_age: infer("ipdis_idf_logs_tiering_days")?
+                .map(|days: u64| Duration::from_secs(days * 24 * 60 * 60)),
+            outbox_enabled: infer("ipdis_outbox_enabled")?.unwrap_or_default(),
+            oplog_enabled: infer("ipdis_oplog_enabled")?.unwrap_or_default(),
+            usage_enabled: infer("ipdis_usage_enabled")?.unwrap_or_default(),
+            max_batch_size: infer("ipdis_max_batch_size")?.unwrap_or(256),
+            max_metadata_len: infer("ipdis_max_metadata_len")?.unwrap_or(MAX_METADATA_LEN as u32),
+            max_query_rows: infer("ipdis_max_query_rows")?.unwrap_or(1024),
+            max_words_len: infer("ipdis_max_words_len")?.unwrap_or(1024),
+            max_writer_lease_ttl: infer("ipdis_max_writer_lease_ttl_secs")?
+                .map(Duration::from_secs)
+                .unwrap_or(Duration::from_secs(60 * 60)),
+            pool_size: infer("ipdis_pool_size")?.unwrap_or(4),
+            queue_reserved: infer("ipdis_queue_reserved")?.unwrap_or(1),
+            max_concurrent_requests: infer("ipdis_max_concurrent_requests")?,
+            signature_retention: SignatureRetention::try_infer()?,
+            stop_words: infer("ipdis_stop_words")?.unwrap_or_default(),
+            queue_timeout: infer("ipdis_queue_timeout_ms")?.map(Duration::from_millis),
+        })
+    }
+
+    pub fn ensure_allowed(&self, account: &AccountRef) -> Result<()> {
+        match &self.allowed_accounts {
+            Some(accounts) if !accounts.contains(account) => {
+                bail!("the account is not allowed: {account}")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn ensure_delegate(&self, gateway: &AccountRef, principal: &AccountRef) -> Result<()> {
+        let delegate = Delegate {
+            gateway: *gateway,
+            principal: *principal,
+        };
+        if !self.delegate_accounts.contains(&delegate) {
+            bail!("the account is not permitted to write on behalf of {principal}: {gateway}")
+        }
+        Ok(())
+    }
+
+    pub fn ensure_feature_enabled(&self, feature: Feature) -> Result<()> {
+        if self.features_disabled.contains(&feature) {
+            bail!(IpdisError::FeatureDisabled { feature })
+        }
+        Ok(())
+    }
+
+    pub fn ensure_batch_size(&self, len: usize) -> Result<()> {
+        ensure_payload_len(QUERIES_PAYLOAD, Some(len), self.max_batch_size as usize)
+    }
+
+    pub fn ensure_metadata_len(&self, metadata: Option<&[u8]>) -> Result<()> {
+        ensure_payload_len(
+            METADATA_PAYLOAD,
+            metadata.map(<[u8]>::len),
+            self.max_metadata_len as usize,
+        )
+    }
+
+    pub fn ensure_words_len(&self, len: usize) -> Result<()> {
+        ensure_payload_len(WORDS_PAYLOAD, Some(len), self.max_words_len as usize)
+    }
+
+    pub fn ensure_query_rows(&self, rows: u32) -> Result<()> {
+        if rows > self.max_query_rows {
+            bail!(IpdisError::QueryTooLarge {
+                limit: self.max_query_rows,
+            })
+        }
+        Ok(())
+    }
+
+    pub fn ensure_writer_lease_ttl(&self, ttl_ms: u64) -> Result<()> {
+        let limit_ms = self
+            .max_writer_lease_ttl
+            .as_millis()
+            .try_into()
+            .unwrap_or(u64::MAX);
+        if ttl_ms > limit_ms {
+            bail!(IpdisError::LeaseTooLong { limit_ms })
+        }
+        Ok(())
+    }
+
+    pub fn is_admin(&self, account: &AccountRef) -> bool {
+        self.admin_accounts.contains(account)
+    }
+
+    pub fn is_cluster_member(&self, account: &AccountRef) -> bool {
+        self.is_admin(account) || self.cluster_accounts.contains(account)
+    }
+}
+
+/// How to put the stop words, which are registered per the kind and the language.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StopWordsPolicy {
+    /// Puts and counts them as the other words.
+    Counted,
+    /// Puts them without counting, so that the count queries exclude them.
+    Uncounted,
+    /// Rejects them.
+    Rejected,
+}
+
+impl Default for StopWordsPolicy {
+    fn default() -> Self {
+        Self::Counted
+    }
+}
+
+impl ::core::str::FromStr for StopWordsPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "counted" => Ok(Self::Counted),
+            "uncounted" => Ok(Self::Uncounted),
+            "rejected" => Ok(Self::Rejected),
+            _ => bail!("unknown stop words policy: {s:?}"),
+        }
+    }
+}
+
+/// How to put the dyn_paths of the same (guarantee, kind, word), which may differ per the kind.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DynPathConflict {
+    /// the policy of the kinds which are not listed
+    pub default: DynPathConflictPolicy,
+    pub kinds: Vec<(Hash, DynPathConflictPolicy)>,
+}
+
+impl DynPathConflict {
+    pub fn try_infer() -> Result<Self> {
+        let kinds: Option<String> = env::infer("ipdis_dyn_path_conflict_kinds").ok();
+
+        Ok(Self {
+            default: infer("ipdis_dyn_path_conflict")?.unwrap_or_default(),
+            kinds: kinds
+                .as_deref()
+                .map(parse_list::<KindPolicy>)
+                .transpose()?
+                .unwrap_or_default()
+                .into_iter()
+                .map(|KindPolicy(kind, policy)| (kind, policy))
+                .collect(),
+        })
+    }
+
+    pub fn get(&self, kind: &Hash) -> DynPathConflictPolicy {
+        match self.kinds.iter().find(|(k, _)| k == kind) {
+            Some((_, policy)) => *policy,
+            // only the latest announcement of each node is kept
+            None if kind == &*membership::KIND => DynPathConflictPolicy::ReplaceLatest,
+            None => self.default,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DynPathConflictPolicy {
+    /// Puts them alongside the existing ones, so the latest one is read.
+    Append,
+    /// Deletes the existing ones, so only the latest one is kept.
+    ReplaceLatest,
+    /// Rejects them with `IpdisError::Duplicate` while an existing one is alive.
+    RejectDuplicate,
+}
+
+impl Default for DynPathConflictPolicy {
+    fn default() -> Self {
+        Self::Append
+    }
+}
+
+impl ::core::str::FromStr for DynPathConflictPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "append" => Ok(Self::Append),
+            "replace-latest" => Ok(Self::ReplaceLatest),
+            "reject-duplicate" => Ok(Self::RejectDuplicate),
+            _ => bail!("unknown dyn_path conflict policy: {s:?}"),
+        }
+    }
+}
+
+/// A `kind=policy` item of the list.
+struct KindPolicy(Hash, DynPathConflictPolicy);
+
+impl ::core::str::FromStr for KindPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once('=') {
+            Some((kind, policy)) => Ok(Self(kind.trim().parse()?, policy.trim().parse()?)),
+            None => bail!("malformed dyn_path conflict policy, expected `kind=policy`: {s:?}"),
+        }
+    }
+}
+
+/// Notifies the guarantees before they expire, and renews the auto-renewable ones,
+/// so that the long-lived services don't silently lose the write access.
+///
+/// The notifications are written to the outbox, so they are published only if it is enabled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GuaranteeExpiry {
+    /// how long before the expiration the guarantees are notified or renewed
+    pub notice: Duration,
+    /// how long the expiration of an auto-renewable guarantee is extended
+    pub renewal: Duration,
+}
+
+impl GuaranteeExpiry {
+    pub fn try_infer() -> Result<Option<Self>> {
+        let notice_days: Option<u64> = infer("ipdis_guarantee_expiry_notice_days")?;
+        let renewal_days: u64 = infer("ipdis_guarantee_renewal_days")?.unwrap_or(30);
+
+        Ok(notice_days.map(|notice_days| Self {
+            notice: Duration::from_secs(notice_days * 24 * 60 * 60),
+            renewal: Duration::from_secs(renewal_days * 24 * 60 * 60),
+        }))
+    }
+}
+
+/// A gateway which is permitted to write on behalf of the principal,
+/// given as `gateway:principal` in `ipdis_delegate_accounts`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Delegate {
+    pub gateway: AccountRef,
+    pub principal: AccountRef,
+}
+
+impl ::core::str::FromStr for Delegate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            Some((gateway, principal)) => Ok(Self {
+                gateway: gateway.trim().parse()?,
+                principal: principal.trim().parse()?,
+            }),
+            None => bail!("malformed delegate: expected `gateway:principal`, but given {s:?}"),
+        }
+    }
+}
+
+/// Moves the signatures of the old records out of the hot tables,
+/// as they are rarely read again once verified on write.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SignatureRetention {
+    /// the age of the records whose signatures are moved
+    pub after: Duration,
+    pub policy: SignatureRetentionPolicy,
+}
+
+impl SignatureRetention {
+    pub fn try_infer() -> Result<Option<Self>> {
+        let days: Option<u64> = infer("ipdis_signature_retention_days")?;
+
+        days.map(|days| {
+            Ok(Self {
+                after: Duration::from_secs(days * 24 * 60 * 60),
+                policy: infer("ipdis_signature_retention_policy")?.unwrap_or_default(),
+            })
+        })
+        .transpose()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SignatureRetentionPolicy {
+    /// Moves them to the archive table, from which they are read on demand.
+    Archive,
+    /// Drops them, so that the old records can no longer be returned as signed.
+    Drop,
+}
+
+impl Default for SignatureRetentionPolicy {
+    fn default() -> Self {
+        Self::Archive
+    }
+}
+
+impl ::core::str::FromStr for SignatureRetentionPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "archive" => Ok(Self::Archive),
+            "drop" => Ok(Self::Drop),
+            _ => bail!("unknown signature retention policy: {s:?}"),
+        }
+    }
+}
+
+/// The TLS of the server transport, which is given to the underlying IPIIS server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// the PEM-encoded certificate chain of the server
+    pub cert_path: PathBuf,
+    /// the PEM-encoded private key of the server
+    pub key_path: PathBuf,
+    /// the PEM-encoded CA certificates to verify the client certificates with,
+    /// or `None` to accept the clients without certificates
+    pub client_ca_path: Option<PathBuf>,
+}
+
+impl TlsConfig {
+    pub fn try_infer() -> Result<Option<Self>> {
+        let cert_path: Option<PathBuf> = infer("ipdis_tls_cert")?;
+        let key_path: Option<PathBuf> = infer("ipdis_tls_key")?;
+        let client_ca_path: Option<PathBuf> = infer("ipdis_tls_client_ca")?;
+
+        let config = match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => Self {
+                cert_path,
+                key_path,
+                client_ca_path,
+            },
+            (None, None) if client_ca_path.is_none() => return Ok(None),
+            _ => bail!("both of the TLS certificate and the private key should be given"),
+        };
+
+        // fail fast rather than on the first connection
+        for path in [Some(&config.cert_path), Some(&config.key_path)]
+            .into_iter()
+            .chain([config.client_ca_path.as_ref()])
+            .flatten()
+        {
+            let pem = ::std::fs::read_to_string(path)
+                .or_else(|error| bail!("failed to read {}: {error}", path.display()))?;
+            if !pem.contains("-----BEGIN ") {
+                bail!("not a PEM file: {}", path.display())
+            }
+        }
+        Ok(Some(config))
+    }
+
+    /// Exports the configuration to the underlying IPIIS transport,
+    /// which should be called before the IPIIS server is inferred.
+    pub fn export(&self) {
+        ::std::env::set_var("ipiis_server_tls_cert", &self.cert_path);
+        ::std::env::set_var("ipiis_server_tls_key", &self.key_path);
+        match &self.client_ca_path {
+            Some(path) => ::std::env::set_var("ipiis_server_tls_client_ca", path),
+            None => ::std::env::remove_var("ipiis_server_tls_client_ca"),
+        }
+    }
+}
+
+/// Parses the environment variable if given, failing fast rather than falling back on malformed values.
+fn infer<T>(key: &str) -> Result<Option<T>>
+where
+    T: ::core::str::FromStr,
+    <T as ::core::str::FromStr>::Err: Into<Error>,
+{
+    let value: Option<String> = env::infer(key).ok();
+    value
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(Into::into)
+                .map_err(|error: Error| error.context(format!("malformed {key}: {value:?}")))
+        })
+        .transpose()
+}
+
+fn parse_list<T>(s: &str) -> Result<Vec<T>>
+where
+    T: ::core::str::FromStr,
+    <T as ::core::str::FromStr>::Err: Into<Error>,
+{
+    s.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse().map_err(Into::into))
+        .collect()
+}
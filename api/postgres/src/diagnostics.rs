@@ -0,0 +1,160 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use diesel::PgConnection;
+use ipdis_common::{ServerDiagnostics, SlowQuery};
+use ipis::core::value::hash::Hash;
+
+use crate::pool::{ConnectionPool, PooledConnection};
+
+/// the number of the recent queries to keep track of
+const RECENT_QUERIES: usize = 256;
+
+#[derive(Default)]
+pub struct Diagnostics {
+    connections_active: AtomicU32,
+    requests_waiting: AtomicU32,
+    recent_queries: Mutex<VecDeque<SlowQuery>>,
+    #[cfg(feature = "otlp")]
+    metrics: crate::metrics::Metrics,
+}
+
+impl Diagnostics {
+    pub async fn lock<'a, P>(
+        &'a self,
+        pool: &'a ConnectionPool,
+        name: &'static str,
+        params: &P,
+    ) -> ConnectionGuard<'a>
+    where
+        P: fmt::Debug + ?Sized,
+    {
+        let params = Hash::with_str(&format!("{params:?}"));
+
+        self.requests_waiting.fetch_add(1, Ordering::SeqCst);
+        let inner = pool.get().await;
+        self.requests_waiting.fetch_sub(1, Ordering::SeqCst);
+        self.connections_active.fetch_add(1, Ordering::SeqCst);
+
+        ConnectionGuard {
+            diagnostics: self,
+            inner,
+            name,
+            params,
+            started: Instant::now(),
+            #[cfg(feature = "otlp")]
+            _span: ::tracing::info_span!("ipdis.db", operation = name),
+        }
+    }
+
+    pub fn report(
+        &self,
+        connections_total: u32,
+        requests_queued: u32,
+        queue_time_us: u64,
+        slow_queries: u32,
+    ) -> ServerDiagnostics {
+        let mut queries: Vec<_> = self
+            .recent_queries
+            .lock()
+            .map(|queries| queries.iter().cloned().collect())
+            .unwrap_or_default();
+        queries.sort_by(|a: &SlowQuery, b| b.elapsed_us.cmp(&a.elapsed_us));
+        queries.truncate(slow_queries as usize);
+
+        ServerDiagnostics {
+            connections_total,
+            connections_active: self.connections_active.load(Ordering::SeqCst),
+            requests_waiting: self.requests_waiting.load(Ordering::SeqCst),
+            requests_queued,
+            queue_time_us,
+            slow_queries: queries,
+            memory_usage: memory_usage(),
+        }
+    }
+
+    fn record(&self, query: SlowQuery) {
+        if let Ok(mut queries) = self.recent_queries.lock() {
+            if queries.len() >= RECENT_QUERIES {
+                queries.pop_front();
+            }
+            queries.push_back(query);
+        }
+    }
+}
+
+pub struct ConnectionGuard<'a> {
+    diagnostics: &'a Diagnostics,
+    inner: PooledConnection<'a>,
+    name: &'static str,
+    params: Hash,
+    started: Instant,
+    /// the span of holding the connection, which is closed when it is dropped
+    #[cfg(feature = "otlp")]
+    _span: ::tracing::Span,
+}
+
+impl<'a> ::core::ops::Deref for ConnectionGuard<'a> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<'a> ::core::ops::DerefMut for ConnectionGuard<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<'a> Drop for ConnectionGuard<'a> {
+    fn drop(&mut self) {
+        self.diagnostics
+            .connections_active
+            .fetch_sub(1, Ordering::SeqCst);
+        let elapsed_us = self
+            .started
+            .elapsed()
+            .as_micros()
+            .try_into()
+            .unwrap_or(u64::MAX);
+
+        #[cfg(feature = "otlp")]
+        self.diagnostics.metrics.record(self.name, elapsed_us);
+        self.diagnostics.record(SlowQuery {
+            name: self.name.to_string(),
+            params: self.params,
+            elapsed_us,
+        });
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn memory_usage() -> Option<u64> {
+    // the resident set size is the 2nd field, counted in pages
+    let page_size = match unsafe { ::libc::sysconf(::libc::_SC_PAGESIZE) } {
+        page_size if page_size > 0 => page_size as u64,
+        _ => return None,
+    };
+
+    ::std::fs::read_to_string("/proc/self/statm")
+        .ok()?
+        .split_whitespace()
+        .nth(1)?
+        .parse::<u64>()
+        .ok()
+        .map(|pages| pages * page_size)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn memory_usage() -> Option<u64> {
+    None
+}
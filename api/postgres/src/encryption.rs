@@ -0,0 +1,221 @@
+//! Encrypts the columns written before the column encryption was enabled (see `ColumnCipher`),
+//! which cannot be found by the encrypted lookups otherwise.
+
+use diesel::{
+    sql_types::{BigInt, Nullable, Varchar},
+    Connection, PgConnection, RunQueryDsl,
+};
+use ipis::core::anyhow::{bail, Result};
+
+use crate::models::{cipher::ColumnCipher, compat::SchemaVersion};
+
+/// the number of the rows encrypted in a transaction
+const BATCH_SIZE: i64 = 256;
+
+/// A table having the encrypted columns.
+struct Table {
+    name: &'static str,
+    /// whether the table has the `parent` column, which is encrypted along with the `word` one
+    has_parent: bool,
+    /// the columns identifying a row, whose duplicates are merged once the columns are encrypted
+    key: &'static [&'static str],
+    /// whether the merged rows sum up their counts
+    counted: bool,
+    /// the version of the schema which introduced the table
+    since: SchemaVersion,
+}
+
+const TABLES: &[Table] = &[
+    Table {
+        name: "dyn_paths",
+        has_parent: false,
+        key: &[],
+        counted: false,
+        since: SchemaVersion::MIN,
+    },
+    Table {
+        name: "words",
+        has_parent: true,
+        key: &[],
+        counted: false,
+        since: SchemaVersion::MIN,
+    },
+    Table {
+        name: "words_counts",
+        has_parent: true,
+        key: &["namespace", "kind", "lang"],
+        counted: true,
+        since: SchemaVersion::MIN,
+    },
+    Table {
+        name: "words_counts_guarantees",
+        has_parent: true,
+        key: &["guarantee", "namespace", "kind", "lang"],
+        counted: true,
+        since: SchemaVersion::MIN,
+    },
+    Table {
+        name: "words_counts_changes",
+        has_parent: false,
+        key: &[],
+        counted: false,
+        since: SchemaVersion::MIN,
+    },
+    Table {
+        name: "stop_words",
+        has_parent: false,
+        key: &["kind", "lang"],
+        counted: false,
+        since: SchemaVersion::MIN,
+    },
+    Table {
+        name: "words_tiered",
+        has_parent: true,
+        key: &[],
+        counted: false,
+        since: SchemaVersion::WORDS_TIERED,
+    },
+];
+
+#[derive(QueryableByName)]
+struct PlainRow {
+    #[diesel(sql_type = BigInt)]
+    id: i64,
+    #[diesel(sql_type = Varchar)]
+    word: String,
+    #[diesel(sql_type = Nullable<Varchar>)]
+    parent: Option<String>,
+}
+
+/// Encrypts the plaintext columns with the hex-encoded key, in batches.
+///
+/// It should be run after the database is migrated to `SchemaVersion::ENCRYPTED_COLUMNS`,
+/// and before the nodes are started with the key, which refuse to start otherwise.
+/// The counts of the words written both before and after the key was enabled are merged.
+/// Returns the number of the encrypted rows.
+pub fn encrypt_plaintext_rows(database_url: &str, column_key: &str) -> Result<u64> {
+    let cipher = ColumnCipher::new(Some(column_key))?;
+    let mut conn = PgConnection::establish(database_url)
+        .or_else(|_| bail!("Error connecting to {database_url}"))?;
+
+    // the plaintext columns are too narrow for the ciphertexts before the migration
+    let schema = crate::client::ensure_schema_version(&mut conn)?;
+    if !schema.supports(SchemaVersion::ENCRYPTED_COLUMNS) {
+        bail!("the columns are too narrow for the ciphertexts: run `diesel migration run` to migrate the database")
+    }
+
+    let mut encrypted = 0;
+    for table in TABLES.iter().filter(|table| schema.supports(table.since)) {
+        loop {
+            let count = conn.transaction::<_, ::ipis::core::anyhow::Error, _>(|conn| {
+                let rows: Vec<PlainRow> =
+                    ::diesel::sql_query(select_plaintext(table, Some(BATCH_SIZE)))
+                        .get_results(conn)?;
+                for row in &rows {
+                    encrypt_row(conn, &cipher, table, row)?;
+                }
+                Ok(rows.len())
+            })?;
+
+            encrypted += count as u64;
+            if count < BATCH_SIZE as usize {
+                break;
+            }
+        }
+    }
+    Ok(encrypted)
+}
+
+/// Returns `true` if any of the encrypted columns has a plaintext value.
+pub(crate) fn has_plaintext_rows(conn: &mut PgConnection, schema: SchemaVersion) -> Result<bool> {
+    for table in TABLES.iter().filter(|table| schema.supports(table.since)) {
+        let rows: Vec<PlainRow> =
+            ::diesel::sql_query(select_plaintext(table, Some(1))).get_results(conn)?;
+        if !rows.is_empty() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn select_plaintext(table: &Table, limit: Option<i64>) -> String {
+    let Table {
+        name, has_parent, ..
+    } = table;
+    let (parent, filter) = if *has_parent {
+        ("parent", "word NOT LIKE 'enc:%' OR parent NOT LIKE 'enc:%'")
+    } else {
+        ("NULL::VARCHAR", "word NOT LIKE 'enc:%'")
+    };
+    let limit = limit
+        .map(|limit| format!(" LIMIT {limit}"))
+        .unwrap_or_default();
+
+    format!(
+        "SELECT id::INT8 AS id, word, {parent} AS parent FROM {name} WHERE {filter} ORDER BY id{limit}"
+    )
+}
+
+/// Encrypts the columns of the row, or merges it into the encrypted one of the same key.
+fn encrypt_row(
+    conn: &mut PgConnection,
+    cipher: &ColumnCipher,
+    table: &Table,
+    row: &PlainRow,
+) -> Result<()> {
+    let name = table.name;
+    let word = cipher.encrypt(cipher.decrypt(&row.word)?);
+    let parent = row
+        .parent
+        .as_deref()
+        .map(|parent| cipher.decrypt(parent).map(|parent| cipher.encrypt(parent)))
+        .transpose()?;
+
+    if !table.key.is_empty() {
+        // find the row of the same key, which has been encrypted already
+        let mut same_key: Vec<_> = table
+            .key
+            .iter()
+            .map(|column| format!("e.{column} = p.{column}"))
+            .collect();
+        same_key.push("e.word = $2".to_string());
+        if table.has_parent {
+            same_key.push("e.parent = $3".to_string());
+        }
+        let same_key = same_key.join(" AND ");
+
+        let merged = if table.counted {
+            ::diesel::sql_query(format!(
+                "UPDATE {name} e SET count = e.count + p.count FROM {name} p
+                WHERE p.id = $1 AND e.id <> p.id AND {same_key}"
+            ))
+        } else {
+            ::diesel::sql_query(format!(
+                "SELECT e.id FROM {name} e, {name} p WHERE p.id = $1 AND e.id <> p.id AND {same_key}"
+            ))
+        }
+        .bind::<BigInt, _>(row.id)
+        .bind::<Varchar, _>(&word)
+        .bind::<Nullable<Varchar>, _>(&parent)
+        .execute(conn)?;
+
+        if merged > 0 {
+            ::diesel::sql_query(format!("DELETE FROM {name} WHERE id = $1"))
+                .bind::<BigInt, _>(row.id)
+                .execute(conn)?;
+            return Ok(());
+        }
+    }
+
+    let columns = if table.has_parent {
+        "word = $2, parent = $3"
+    } else {
+        "word = $2"
+    };
+    ::diesel::sql_query(format!("UPDATE {name} SET {columns} WHERE id = $1"))
+        .bind::<BigInt, _>(row.id)
+        .bind::<Varchar, _>(&word)
+        .bind::<Nullable<Varchar>, _>(&parent)
+        .execute(conn)?;
+    Ok(())
+}
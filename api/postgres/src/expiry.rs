@@ -0,0 +1,102 @@
+use diesel::{
+    BoolExpressionMethods, ExpressionMethods, PgConnection, QueryDsl, QueryResult, RunQueryDsl,
+};
+use ipis::core::chrono::{Duration, NaiveDateTime};
+
+use crate::outbox::{TOPIC_GUARANTEE_EXPIRING, TOPIC_GUARANTEE_RENEWED};
+
+/// The guarantees affected by a sweep.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GuaranteeExpirySweep {
+    /// the number of the guarantees notified of the coming expiration
+    pub notified: usize,
+    /// the number of the auto-renewable guarantees whose expiration is extended
+    pub renewed: usize,
+}
+
+/// Renews the auto-renewable guarantees expiring before `until`, and notifies the others once.
+///
+/// The signatures of a renewed guarantee still cover the original expiration,
+/// so the extended term is vouched for by the server alone.
+pub(crate) fn sweep(
+    conn: &mut PgConnection,
+    now: NaiveDateTime,
+    until: NaiveDateTime,
+    renewal: Duration,
+    outbox_enabled: bool,
+) -> QueryResult<GuaranteeExpirySweep> {
+    let expiring = crate::schema::accounts_guarantees::table
+        .filter(crate::schema::accounts_guarantees::expiration_date.ge(now))
+        .filter(crate::schema::accounts_guarantees::expiration_date.lt(until));
+
+    let renewable: Vec<(i32, String, Option<NaiveDateTime>)> = expiring
+        .clone()
+        .filter(crate::schema::accounts_guarantees::auto_renew.eq(true))
+        .select((
+            crate::schema::accounts_guarantees::id,
+            crate::schema::accounts_guarantees::guarantee,
+            crate::schema::accounts_guarantees::expiration_date,
+        ))
+        .get_results(conn)?;
+
+    for (id, guarantee, expiration_date) in &renewable {
+        let expiration_date = expiration_date.map(|date| date + renewal);
+
+        ::diesel::update(crate::schema::accounts_guarantees::table.find(*id))
+            .set((
+                crate::schema::accounts_guarantees::expiration_date.eq(expiration_date),
+                crate::schema::accounts_guarantees::notified_date.eq(None::<NaiveDateTime>),
+            ))
+            .execute(conn)?;
+
+        if outbox_enabled {
+            crate::outbox::push(
+                conn,
+                TOPIC_GUARANTEE_RENEWED,
+                payload(guarantee, expiration_date),
+                now,
+            )?;
+        }
+    }
+
+    let notifiable: Vec<(i32, String, Option<NaiveDateTime>)> = expiring
+        .filter(
+            crate::schema::accounts_guarantees::auto_renew
+                .eq(false)
+                .and(crate::schema::accounts_guarantees::notified_date.is_null()),
+        )
+        .select((
+            crate::schema::accounts_guarantees::id,
+            crate::schema::accounts_guarantees::guarantee,
+            crate::schema::accounts_guarantees::expiration_date,
+        ))
+        .get_results(conn)?;
+
+    for (id, guarantee, expiration_date) in &notifiable {
+        ::diesel::update(crate::schema::accounts_guarantees::table.find(*id))
+            .set(crate::schema::accounts_guarantees::notified_date.eq(now))
+            .execute(conn)?;
+
+        if outbox_enabled {
+            crate::outbox::push(
+                conn,
+                TOPIC_GUARANTEE_EXPIRING,
+                payload(guarantee, *expiration_date),
+                now,
+            )?;
+        }
+    }
+
+    Ok(GuaranteeExpirySweep {
+        notified: notifiable.len(),
+        renewed: renewable.len(),
+    })
+}
+
+/// Formats the event as `<guarantee>/<expiration date in RFC 3339>`.
+fn payload(guarantee: &str, expiration_date: Option<NaiveDateTime>) -> String {
+    match expiration_date {
+        Some(date) => format!("{guarantee}/{}", date.format("%Y-%m-%dT%H:%M:%SZ")),
+        None => guarantee.to_string(),
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use ipis::core::anyhow::{anyhow, Result};
+
+use crate::backup::BackupRecords;
+
+/// the tables which are exported, each by its own worker
+pub const TABLES: &[&str] = &[TABLE_DYN_PATHS, TABLE_WORDS, TABLE_WORDS_TIERED];
+
+pub const TABLE_DYN_PATHS: &str = "dyn_paths";
+pub const TABLE_WORDS: &str = "words";
+/// the words tiered out to the segments, which are exported as the words and imported as the hot ones
+pub const TABLE_WORDS_TIERED: &str = "words_tiered";
+
+/// The last record ids of the tables which have been processed,
+/// so that an interrupted export or import can be resumed.
+///
+/// It is persisted as a file of `<table> <id>` lines, and replaced atomically on every update.
+#[derive(Debug)]
+pub struct Checkpoint {
+    path: PathBuf,
+    sequences: Mutex<BTreeMap<String, i32>>,
+}
+
+impl Checkpoint {
+    /// Loads the checkpoint, or starts from the beginning if the file does not exist.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+
+        let mut sequences = BTreeMap::default();
+        if path.exists() {
+            for line in fs::read_to_string(&path)?.lines() {
+                let (table, id) = line
+                    .split_once(' ')
+                    .ok_or_else(|| anyhow!("malformed checkpoint: {line:?}"))?;
+                sequences.insert(table.to_string(), id.parse()?);
+            }
+        }
+
+        Ok(Self {
+            path,
+            sequences: Mutex::new(sequences),
+        })
+    }
+
+    pub fn get(&self, table: &str) -> i32 {
+        self.sequences
+            .lock()
+            .ok()
+            .and_then(|sequences| sequences.get(table).copied())
+            .unwrap_or_default()
+    }
+
+    /// Records that the table has been processed until the id, and persists it.
+    pub fn set(&self, table: &str, id: i32) -> Result<()> {
+        let mut sequences = self
+            .sequences
+            .lock()
+            .map_err(|_| anyhow!("the checkpoint has been poisoned"))?;
+        sequences.insert(table.to_string(), id);
+
+        let contents: String = sequences
+            .iter()
+            .map(|(table, id)| format!("{table} {id}\n"))
+            .collect();
+
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &self.path).map_err(Into::into)
+    }
+}
+
+/// Returns the path of a chunk, which is named after its first record id
+/// so that the chunks are imported in order.
+pub(crate) fn chunk_path(dir: &Path, table: &str, first: i32) -> PathBuf {
+    dir.join(table).join(format!("{first:010}.bin"))
+}
+
+/// Returns the chunks of the table, ordered by their first record ids.
+pub(crate) fn list_chunks(dir: &Path, table: &str) -> Result<Vec<(i32, PathBuf)>> {
+    let dir = dir.join(table);
+    if !dir.exists() {
+        return Ok(Default::default());
+    }
+
+    let mut chunks = fs::read_dir(dir)?
+        .map(|entry| {
+            let path = entry?.path();
+            let first = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| anyhow!("malformed chunk: {}", path.display()))?
+                .parse()?;
+            Ok((first, path))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    chunks.sort();
+    Ok(chunks)
+}
+
+pub(crate) fn write_chunk(path: &Path, records: &BackupRecords) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, records.to_bytes()?).map_err(Into::into)
+}
+
+pub(crate) fn read_chunk(path: &Path) -> Result<BackupRecords> {
+    BackupRecords::from_bytes(&fs::read(path)?)
+}
@@ -0,0 +1,56 @@
+use ipis::core::{anyhow::Result, signature::Verifier};
+
+/// The rows which failed to be verified.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// the number of the verified rows
+    pub checked: u64,
+    /// the number of the superseded rows whose signatures have been dropped by the retention policy
+    pub dropped: u64,
+    pub violations: Vec<IntegrityViolation>,
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Reconstructs the payload of the row, and verifies its signatures.
+    pub(crate) fn verify<F, T>(&mut self, table: &'static str, id: i32, reconstruct: F)
+    where
+        F: FnOnce() -> Result<T>,
+        T: Verifier,
+    {
+        self.checked += 1;
+
+        let (kind, error) = match reconstruct() {
+            Ok(payload) => match payload.verify(None) {
+                Ok(()) => return,
+                Err(error) => (IntegrityViolationKind::Forged, error),
+            },
+            Err(error) => (IntegrityViolationKind::Corrupted, error),
+        };
+        self.violations.push(IntegrityViolation {
+            table,
+            id,
+            kind,
+            message: error.to_string(),
+        });
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityViolation {
+    pub table: &'static str,
+    pub id: i32,
+    pub kind: IntegrityViolationKind,
+    pub message: String,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityViolationKind {
+    /// the payload cannot be reconstructed from the row, e.g. malformed or undecryptable columns
+    Corrupted,
+    /// the signatures do not match the reconstructed payload
+    Forged,
+}
@@ -0,0 +1,100 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use diesel::{sql_types::BigInt, Connection, PgConnection, RunQueryDsl};
+use ipis::{
+    core::anyhow::{anyhow, bail, Result},
+    env, tokio,
+};
+
+sql_function!(fn pg_try_advisory_lock(key: BigInt) -> Bool);
+
+/// Elects a single leader among the nodes sharing the database,
+/// so that the singleton tasks such as GC run on exactly one node.
+///
+/// The leadership is a session-level advisory lock, which is held by a dedicated connection
+/// and released by the database as soon as the connection is lost.
+pub struct LeaderElection {
+    database_url: String,
+    conn: Mutex<Option<PgConnection>>,
+    key: i64,
+    is_leader: AtomicBool,
+}
+
+impl LeaderElection {
+    pub fn try_infer(name: &str) -> Result<Self> {
+        let database_url: String = env::infer("DATABASE_URL")?;
+        Self::establish(&database_url, name)
+    }
+
+    pub fn establish(database_url: &str, name: &str) -> Result<Self> {
+        let conn = establish(database_url)?;
+
+        Ok(Self {
+            database_url: database_url.to_string(),
+            conn: Mutex::new(Some(conn)),
+            key: advisory_lock_key(name),
+            is_leader: AtomicBool::new(false),
+        })
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.is_leader.load(Ordering::SeqCst)
+    }
+
+    /// Tries to be the leader, or ensures the leadership is still held.
+    ///
+    /// The blocking queries run on a dedicated thread, so that the runtime is not stalled.
+    pub async fn try_acquire(self: &Arc<Self>) -> Result<bool> {
+        let this = self.clone();
+        tokio::task::spawn_blocking(move || this.try_acquire_blocking()).await?
+    }
+
+    fn try_acquire_blocking(&self) -> Result<bool> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|_| anyhow!("the leader election has been poisoned"))?;
+
+        let mut session = match conn.take() {
+            Some(session) => session,
+            // the session has been lost, along with the leadership held by it
+            None => establish(&self.database_url)?,
+        };
+
+        let is_leader = if self.is_leader() {
+            // the lock is kept as long as the session is alive
+            ::diesel::sql_query("SELECT 1")
+                .execute(&mut session)
+                .map(|_| true)
+        } else {
+            ::diesel::select(pg_try_advisory_lock(self.key)).get_result(&mut session)
+        };
+
+        match is_leader {
+            Ok(is_leader) => {
+                *conn = Some(session);
+                self.is_leader.store(is_leader, Ordering::SeqCst);
+                Ok(is_leader)
+            }
+            Err(error) => {
+                // drop the broken session, which is re-established on the next try
+                self.is_leader.store(false, Ordering::SeqCst);
+                Err(error.into())
+            }
+        }
+    }
+}
+
+fn establish(database_url: &str) -> Result<PgConnection> {
+    PgConnection::establish(database_url).or_else(|_| bail!("Error connecting to {database_url}"))
+}
+
+/// Derives a stable key from the name (FNV-1a), which is shared by all the nodes.
+pub(crate) fn advisory_lock_key(name: &str) -> i64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    }) as i64
+}
@@ -0,0 +1,97 @@
+use diesel::{ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, RunQueryDsl};
+use ipdis_common::IpdisError;
+use ipis::core::{
+    anyhow::{bail, Result},
+    chrono::NaiveDateTime,
+};
+
+use crate::models::kinds_leases::{KindsLease, NewKindsLease};
+
+/// Acquires the writer lease of the kind, or renews the held one if its fencing token is given.
+///
+/// The leases are kept after they expire, so that the fencing tokens of a kind never go back.
+pub(crate) fn acquire(
+    conn: &mut PgConnection,
+    kind: &str,
+    holder: &str,
+    token: Option<u64>,
+    now: NaiveDateTime,
+    expiration_date: NaiveDateTime,
+) -> Result<KindsLease> {
+    let resource = resource(kind);
+
+    // the leases of a kind are acquired one at a time, not to hand out the same token twice
+    crate::lock::serialize(conn, &resource)?;
+
+    let lease: Option<KindsLease> = crate::schema::kinds_leases::table
+        .filter(crate::schema::kinds_leases::kind.eq(kind))
+        .get_result(conn)
+        .optional()?;
+
+    match (lease, token) {
+        // renew the held lease
+        (Some(lease), Some(token)) if is_held(&lease, holder, token, now) => {
+            ::diesel::update(crate::schema::kinds_leases::table.find(lease.id))
+                .set(crate::schema::kinds_leases::expiration_date.eq(expiration_date))
+                .get_result(conn)
+                .map_err(Into::into)
+        }
+        (_, Some(_)) => bail!(IpdisError::Fenced { resource }),
+        (Some(lease), None) if lease.expiration_date >= now => bail!(IpdisError::Busy { resource }),
+        // take over the expired lease
+        (Some(lease), None) => ::diesel::update(crate::schema::kinds_leases::table.find(lease.id))
+            .set((
+                crate::schema::kinds_leases::holder.eq(holder),
+                crate::schema::kinds_leases::token.eq(lease.token + 1),
+                crate::schema::kinds_leases::created_date.eq(now),
+                crate::schema::kinds_leases::expiration_date.eq(expiration_date),
+            ))
+            .get_result(conn)
+            .map_err(Into::into),
+        (None, None) => ::diesel::insert_into(crate::schema::kinds_leases::table)
+            .values(&NewKindsLease {
+                kind: kind.to_string(),
+                holder: holder.to_string(),
+                token: 1,
+                created_date: now,
+                expiration_date,
+            })
+            .get_result(conn)
+            .map_err(Into::into),
+    }
+}
+
+/// Ensures that the write of the kind is accompanied by the fencing token of the held lease, if any.
+///
+/// The lease is locked until the transaction ends, so it cannot be taken over during the write.
+/// A fencing token without a held lease is rejected, as the lease may have been taken over already.
+pub(crate) fn ensure_fenced(
+    conn: &mut PgConnection,
+    kind: &str,
+    writer: &str,
+    token: Option<u64>,
+    now: NaiveDateTime,
+) -> Result<()> {
+    let lease: Option<KindsLease> = crate::schema::kinds_leases::table
+        .filter(crate::schema::kinds_leases::kind.eq(kind))
+        .filter(crate::schema::kinds_leases::expiration_date.ge(now))
+        .for_share()
+        .get_result(conn)
+        .optional()?;
+
+    match (lease, token) {
+        (None, None) => Ok(()),
+        (Some(lease), Some(token)) if is_held(&lease, writer, token, now) => Ok(()),
+        _ => bail!(IpdisError::Fenced {
+            resource: resource(kind),
+        }),
+    }
+}
+
+fn is_held(lease: &KindsLease, holder: &str, token: u64, now: NaiveDateTime) -> bool {
+    lease.holder == holder && lease.token as u64 == token && lease.expiration_date >= now
+}
+
+fn resource(kind: &str) -> String {
+    format!("kinds_lease/{kind}")
+}
@@ -1,6 +1,34 @@
 #[macro_use]
 extern crate diesel;
 
+pub mod auth;
+pub mod backup;
+pub mod cache;
 pub mod client;
+pub mod clock;
+pub mod config;
+mod diagnostics;
+pub mod encryption;
+pub mod expiry;
+pub mod export;
+pub mod integrity;
+pub mod leader;
+mod lease;
+mod lock;
+#[cfg(feature = "otlp")]
+mod metrics;
 mod models;
+pub mod ndjson;
+mod oplog;
+pub mod outbox;
+mod pool;
+pub mod privacy;
+mod query;
+pub mod queue;
+mod retention;
 mod schema;
+pub mod snapshot;
+mod succession;
+pub mod tiering;
+pub mod token;
+pub mod usage;
@@ -0,0 +1,66 @@
+use diesel::{sql_types::BigInt, PgConnection, RunQueryDsl};
+use ipdis_common::IpdisError;
+use ipis::core::anyhow::{bail, Result};
+
+use crate::leader::advisory_lock_key;
+
+sql_function!(fn pg_try_advisory_xact_lock(key: BigInt) -> Bool);
+sql_function!(fn pg_try_advisory_xact_lock_shared(key: BigInt) -> Bool);
+
+/// the resource covering all the others, which is locked by the operations over all the kinds (e.g. GC)
+const RESOURCE_ALL: &str = "ipdis/admin";
+
+/// Locks the resources (e.g. the kinds) against the other destructive admin operations,
+/// until the transaction ends.
+///
+/// The locks are not waited for, so the contention is returned as `IpdisError::Busy`.
+pub(crate) fn lock(conn: &mut PgConnection, resources: &[String]) -> Result<()> {
+    if !::diesel::select(pg_try_advisory_xact_lock_shared(advisory_lock_key(
+        RESOURCE_ALL,
+    )))
+    .get_result(conn)?
+    {
+        bail!(IpdisError::Busy {
+            resource: RESOURCE_ALL.to_string(),
+        })
+    }
+
+    for resource in resources {
+        let resource = format!("{RESOURCE_ALL}/{resource}");
+        if !::diesel::select(pg_try_advisory_xact_lock(advisory_lock_key(&resource)))
+            .get_result(conn)?
+        {
+            bail!(IpdisError::Busy { resource })
+        }
+    }
+    Ok(())
+}
+
+/// Locks all the resources against the other destructive admin operations,
+/// until the transaction ends.
+pub(crate) fn lock_all(conn: &mut PgConnection) -> Result<()> {
+    if !::diesel::select(pg_try_advisory_xact_lock(advisory_lock_key(RESOURCE_ALL)))
+        .get_result(conn)?
+    {
+        bail!(IpdisError::Busy {
+            resource: RESOURCE_ALL.to_string(),
+        })
+    }
+    Ok(())
+}
+
+pub(crate) fn kind(kind: impl ToString) -> String {
+    format!("kind/{}", kind.to_string())
+}
+
+pub(crate) fn namespace(namespace: impl ToString) -> String {
+    format!("namespace/{}", namespace.to_string())
+}
+
+/// Waits for the other writes of the resource (e.g. a dyn_path), until the transaction ends.
+pub(crate) fn serialize(conn: &mut PgConnection, resource: &str) -> Result<()> {
+    ::diesel::sql_query("SELECT pg_advisory_xact_lock($1)")
+        .bind::<BigInt, _>(advisory_lock_key(resource))
+        .execute(conn)?;
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use opentelemetry::{
+    global,
+    metrics::{Counter, Histogram, Unit},
+    KeyValue,
+};
+
+/// The metrics of the database operations, exported through the global meter provider,
+/// which should be installed before the client is created (e.g. `ipdis_api::telemetry::init`).
+pub(crate) struct Metrics {
+    operations: Counter<u64>,
+    duration: Histogram<u64>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        let meter = global::meter("ipdis-api-postgres");
+        Self {
+            operations: meter
+                .u64_counter("ipdis.db.operations")
+                .with_description("the number of the database operations")
+                .init(),
+            duration: meter
+                .u64_histogram("ipdis.db.duration")
+                .with_description("the time of holding a database connection")
+                .with_unit(Unit::new("us"))
+                .init(),
+        }
+    }
+}
+
+impl Metrics {
+    pub(crate) fn record(&self, name: &'static str, elapsed_us: u64) {
+        let attributes = [KeyValue::new("operation", name)];
+        self.operations.add(1, &attributes);
+        self.duration.record(elapsed_us, &attributes);
+    }
+}
@@ -12,6 +12,10 @@ pub struct AccountsGuarantee {
     pub created_date: NaiveDateTime,
     pub expiration_date: Option<NaiveDateTime>,
     // -- METADATA END --
+    /// whether the server extends the expiration when it comes near
+    pub auto_renew: bool,
+    /// when the guarantee has been notified of the coming expiration
+    pub notified_date: Option<NaiveDateTime>,
 }
 
 #[derive(Insertable)]
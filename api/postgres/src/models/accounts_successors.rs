@@ -0,0 +1,23 @@
+use ipis::core::{chrono::NaiveDateTime, uuid::Uuid};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::accounts_successors)]
+pub struct NewAccountsSuccessor {
+    pub nonce: Uuid,
+    /// the retired account
+    pub account: String,
+    pub successor: String,
+    pub guarantor: String,
+    /// the signature of the retired account over the successor
+    pub account_signature: String,
+    /// the signature of the successor over the succession
+    pub successor_signature: String,
+    pub created_date: NaiveDateTime,
+}
+
+/// An account in a chain of the successions.
+#[derive(Debug, QueryableByName)]
+pub struct AccountsChainLink {
+    #[diesel(sql_type = ::diesel::sql_types::Varchar)]
+    pub account: String,
+}
@@ -0,0 +1,12 @@
+/// The usage of an account over a period, aggregated from the daily counters.
+#[derive(Debug, QueryableByName)]
+pub struct AccountUsage {
+    #[diesel(sql_type = ::diesel::sql_types::Text)]
+    pub account: String,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub bytes_written: i64,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub rows_written: i64,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub rows_stored: i64,
+}
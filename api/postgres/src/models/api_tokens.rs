@@ -0,0 +1,23 @@
+use ipis::core::chrono::NaiveDateTime;
+
+#[derive(Debug, Queryable)]
+pub struct ApiToken {
+    pub id: i32,
+    pub token_hash: String,
+    pub name: String,
+    pub scopes: String,
+    pub rate_limit: i32,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::api_tokens)]
+pub struct NewApiToken {
+    pub token_hash: String,
+    pub name: String,
+    pub scopes: String,
+    pub rate_limit: i32,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+}
@@ -0,0 +1,102 @@
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, NewAead},
+    Aes256Gcm,
+};
+use hmac::{Hmac, Mac};
+use ipis::core::anyhow::{anyhow, bail, Result};
+use sha2::Sha256;
+
+/// the prefix of the encrypted values, which distinguishes them from the plaintext ones
+const PREFIX: &str = "enc:";
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Encrypts the columns of the indexed vocabulary, e.g. the words and the aliases.
+///
+/// The nonce is derived from the plaintext, so that the equal plaintexts are
+/// encrypted to the equal ciphertexts and the columns can still be filtered by equality.
+/// As the words are stored as their hashes already, it only keeps the known words
+/// from being confirmed by hashing them, and not whether two records share the same word.
+///
+/// The ciphertexts do not fit the hashes, so the key requires the columns
+/// widened in `SchemaVersion::ENCRYPTED_COLUMNS`.
+/// The plaintext rows written before the key is enabled should be encrypted
+/// with `encryption::encrypt_plaintext_rows`.
+#[derive(Clone, Default)]
+pub struct ColumnCipher {
+    key: Option<[u8; KEY_LEN]>,
+}
+
+impl ::core::fmt::Debug for ColumnCipher {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.debug_struct("ColumnCipher")
+            .field("enabled", &self.key.is_some())
+            .finish()
+    }
+}
+
+impl ColumnCipher {
+    /// Creates a cipher with the hex-encoded 256-bit key, or a no-op one without a key.
+    pub fn new(key: Option<&str>) -> Result<Self> {
+        let key = match key {
+            Some(key) => {
+                let key = ::hex::decode(key.trim())?;
+                match key.try_into() {
+                    Ok(key) => Some(key),
+                    Err(_) => bail!("the column encryption key should be {KEY_LEN} bytes"),
+                }
+            }
+            None => None,
+        };
+        Ok(Self { key })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.key.is_some()
+    }
+
+    pub fn encrypt(&self, plaintext: String) -> String {
+        let key = match &self.key {
+            Some(key) => key,
+            None => return plaintext,
+        };
+
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("valid key length");
+        mac.update(plaintext.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let nonce = GenericArray::from_slice(&digest[..NONCE_LEN]);
+
+        let ciphertext = Aes256Gcm::new(GenericArray::from_slice(key))
+            .encrypt(nonce, plaintext.as_bytes())
+            .expect("plaintext not too long");
+
+        let mut buf = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        buf.extend_from_slice(nonce);
+        buf.extend_from_slice(&ciphertext);
+        format!("{PREFIX}{}", ::hex::encode(buf))
+    }
+
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let encoded = match value.strip_prefix(PREFIX) {
+            Some(encoded) => encoded,
+            // not encrypted yet
+            None => return Ok(value.to_string()),
+        };
+        let key = match &self.key {
+            Some(key) => key,
+            None => bail!("the column encryption key is required to read the encrypted records"),
+        };
+
+        let buf = ::hex::decode(encoded)?;
+        if buf.len() < NONCE_LEN {
+            bail!("malformed encrypted column")
+        }
+        let (nonce, ciphertext) = buf.split_at(NONCE_LEN);
+
+        let plaintext = Aes256Gcm::new(GenericArray::from_slice(key))
+            .decrypt(GenericArray::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("failed to decrypt the column: wrong key or corrupted record"))?;
+        String::from_utf8(plaintext).map_err(Into::into)
+    }
+}
@@ -0,0 +1,34 @@
+//! The compatibility layer of the models, so that the nodes of the neighbouring versions
+//! can share a database during a rolling upgrade.
+//!
+//! The migrations are additive: the readers of an older schema tolerate the missing columns,
+//! and the writers skip the features which the schema does not have yet.
+
+/// The version of the schema of a database, i.e. the number of its migrations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SchemaVersion(pub i32);
+
+impl SchemaVersion {
+    /// the oldest version which this binary can work on
+    pub const MIN: Self = Self(22);
+
+    /// the kinds have their normalization policies
+    pub const KINDS_NORMALIZATION: Self = Self(23);
+    /// the usage of the accounts is counted
+    pub const ACCOUNTS_USAGE: Self = Self(24);
+    /// the schema declares the oldest version of the binaries which can work on it
+    pub const COMPATIBLE_VERSION: Self = Self(25);
+    /// the old words may be tiered out into the segments
+    pub const WORDS_SEGMENTS: Self = Self(26);
+    /// the records are unique by their nonces
+    pub const UNIQUE_NONCES: Self = Self(27);
+    /// the encrypted columns are wide enough for their ciphertexts
+    pub const ENCRYPTED_COLUMNS: Self = Self(28);
+    /// the tiered words are indexed, so that they are deleted and expired as the hot ones
+    pub const WORDS_TIERED: Self = Self(29);
+
+    /// Returns `true` if the schema has the feature introduced in the given version.
+    pub fn supports(&self, since: Self) -> bool {
+        *self >= since
+    }
+}
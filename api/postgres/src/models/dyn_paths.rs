@@ -1,14 +1,15 @@
 use ipis::core::{chrono::NaiveDateTime, uuid::Uuid};
 
-#[derive(Debug, Queryable)]
+#[derive(Debug, Queryable, QueryableByName)]
+#[diesel(table_name = crate::schema::dyn_paths)]
 pub struct DynPath {
     pub id: i32,
     // -- METADATA BEGIN --
     pub nonce: Uuid,
     pub guarantee: String,
     pub guarantor: String,
-    pub guarantee_signature: String,
-    pub guarantor_signature: String,
+    pub guarantee_signature: Option<String>,
+    pub guarantor_signature: Option<String>,
     pub created_date: NaiveDateTime,
     pub expiration_date: Option<NaiveDateTime>,
     // -- METADATA END --
@@ -17,6 +18,8 @@ pub struct DynPath {
     pub word: String,
     pub path: String,
     pub len: i64,
+    pub metadata: Option<Vec<u8>>,
+    pub on_behalf_of: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -26,8 +29,8 @@ pub struct NewDynPath {
     pub nonce: Uuid,
     pub guarantee: String,
     pub guarantor: String,
-    pub guarantee_signature: String,
-    pub guarantor_signature: String,
+    pub guarantee_signature: Option<String>,
+    pub guarantor_signature: Option<String>,
     pub created_date: NaiveDateTime,
     pub expiration_date: Option<NaiveDateTime>,
     // -- METADATA END --
@@ -36,4 +39,16 @@ pub struct NewDynPath {
     pub word: String,
     pub path: String,
     pub len: i64,
+    pub metadata: Option<Vec<u8>>,
+    pub on_behalf_of: Option<String>,
+}
+
+/// The latest dynamic path matched with one of the requested ones.
+#[derive(Debug, QueryableByName)]
+pub struct DynPathMatch {
+    /// the index of the requested dynamic path
+    #[diesel(sql_type = ::diesel::sql_types::Integer)]
+    pub idx: i32,
+    #[diesel(embed)]
+    pub record: DynPath,
 }
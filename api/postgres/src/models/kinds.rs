@@ -0,0 +1,62 @@
+#[derive(Debug, Queryable)]
+pub struct Kind {
+    pub id: i32,
+    pub kind: String,
+    pub name: String,
+    pub description: String,
+    pub schema_version: i32,
+    pub normalization: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::kinds)]
+pub struct NewKind {
+    pub kind: String,
+    pub name: String,
+    pub description: String,
+    pub schema_version: i32,
+    pub normalization: Option<String>,
+}
+
+/// A kind of the schemas before `SchemaVersion::KINDS_NORMALIZATION`, without its normalization.
+#[derive(Debug, Queryable)]
+pub struct KindV22 {
+    pub id: i32,
+    pub kind: String,
+    pub name: String,
+    pub description: String,
+    pub schema_version: i32,
+}
+
+impl From<KindV22> for Kind {
+    fn from(record: KindV22) -> Self {
+        Self {
+            id: record.id,
+            kind: record.kind,
+            name: record.name,
+            description: record.description,
+            schema_version: record.schema_version,
+            normalization: None,
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::kinds)]
+pub struct NewKindV22 {
+    pub kind: String,
+    pub name: String,
+    pub description: String,
+    pub schema_version: i32,
+}
+
+impl From<NewKind> for NewKindV22 {
+    fn from(record: NewKind) -> Self {
+        Self {
+            kind: record.kind,
+            name: record.name,
+            description: record.description,
+            schema_version: record.schema_version,
+        }
+    }
+}
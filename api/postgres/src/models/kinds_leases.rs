@@ -0,0 +1,22 @@
+use ipis::core::chrono::NaiveDateTime;
+
+#[derive(Debug, Queryable)]
+pub struct KindsLease {
+    pub id: i32,
+    pub kind: String,
+    pub holder: String,
+    /// the fencing token, which grows with every new lease of the kind
+    pub token: i64,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::kinds_leases)]
+pub struct NewKindsLease {
+    pub kind: String,
+    pub holder: String,
+    pub token: i64,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: NaiveDateTime,
+}
@@ -1,3 +1,18 @@
 pub mod accounts_guarantees;
+pub mod accounts_successors;
+pub mod accounts_usage;
+pub mod api_tokens;
+pub mod cipher;
+pub mod compat;
 pub mod dyn_paths;
+pub mod kinds;
+pub mod kinds_leases;
+pub mod oplog;
+pub mod outbox;
+pub mod schema_meta;
+pub mod settings;
+pub mod signatures_archive;
+pub mod stats;
+pub mod stop_words;
 pub mod words;
+pub mod words_segments;
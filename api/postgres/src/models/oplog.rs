@@ -0,0 +1,49 @@
+use ipis::core::{chrono::NaiveDateTime, uuid::Uuid};
+
+#[derive(Debug, Queryable)]
+pub struct OplogEntry {
+    pub id: i32,
+    pub nonce: Uuid,
+    pub hash: String,
+    pub signature: String,
+    pub created_date: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::oplog)]
+pub struct NewOplogEntry {
+    pub nonce: Uuid,
+    pub hash: String,
+    pub signature: String,
+    pub created_date: NaiveDateTime,
+}
+
+#[derive(Debug, Queryable)]
+pub struct OplogRoot {
+    pub id: i32,
+    // -- METADATA BEGIN --
+    pub nonce: Uuid,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    // -- METADATA END --
+    pub tree_size: i64,
+    pub root: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::oplog_roots)]
+pub struct NewOplogRoot {
+    // -- METADATA BEGIN --
+    pub nonce: Uuid,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    // -- METADATA END --
+    pub tree_size: i64,
+    pub root: String,
+}
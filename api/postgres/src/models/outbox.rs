@@ -0,0 +1,18 @@
+use ipis::core::chrono::NaiveDateTime;
+
+#[derive(Clone, Debug, Queryable)]
+pub struct OutboxEvent {
+    pub id: i32,
+    pub topic: String,
+    pub payload: String,
+    pub created_date: NaiveDateTime,
+    pub delivered_date: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::outbox)]
+pub struct NewOutboxEvent {
+    pub topic: String,
+    pub payload: String,
+    pub created_date: NaiveDateTime,
+}
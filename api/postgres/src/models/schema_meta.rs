@@ -0,0 +1,6 @@
+#[derive(Debug, Queryable)]
+pub struct SchemaMeta {
+    pub id: bool,
+    pub version: i32,
+    pub compatible_version: i32,
+}
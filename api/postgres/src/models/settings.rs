@@ -0,0 +1,12 @@
+#[derive(Debug, Queryable)]
+pub struct Setting {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::settings)]
+pub struct NewSetting {
+    pub name: String,
+    pub value: String,
+}
@@ -0,0 +1,8 @@
+#[derive(Debug, Queryable)]
+pub struct SignaturesArchive {
+    pub id: i32,
+    pub table_name: String,
+    pub record_id: i32,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+}
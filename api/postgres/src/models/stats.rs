@@ -0,0 +1,13 @@
+#[derive(Debug, QueryableByName)]
+pub struct AccountStats {
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub words: i64,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub dyn_paths: i64,
+    #[diesel(sql_type = ::diesel::sql_types::Nullable<::diesel::sql_types::BigInt>)]
+    pub first_activity: Option<i64>,
+    #[diesel(sql_type = ::diesel::sql_types::Nullable<::diesel::sql_types::BigInt>)]
+    pub last_activity: Option<i64>,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub storage_bytes: i64,
+}
@@ -0,0 +1,15 @@
+#[derive(Debug, Queryable)]
+pub struct StopWord {
+    pub id: i32,
+    pub kind: String,
+    pub lang: String,
+    pub word: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::stop_words)]
+pub struct NewStopWord {
+    pub kind: String,
+    pub lang: String,
+    pub word: String,
+}
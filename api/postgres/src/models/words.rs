@@ -1,14 +1,14 @@
 use ipis::core::{chrono::NaiveDateTime, uuid::Uuid};
 
-#[derive(Debug, Queryable)]
+#[derive(Clone, Debug, Queryable)]
 pub struct Word {
     pub id: i32,
     // -- METADATA BEGIN --
     pub nonce: Uuid,
     pub guarantee: String,
     pub guarantor: String,
-    pub guarantee_signature: String,
-    pub guarantor_signature: String,
+    pub guarantee_signature: Option<String>,
+    pub guarantor_signature: Option<String>,
     pub created_date: NaiveDateTime,
     pub expiration_date: Option<NaiveDateTime>,
     // -- METADATA END --
@@ -20,6 +20,10 @@ pub struct Word {
     pub relpath: bool,
     pub path: String,
     pub len: i64,
+    pub metadata: Option<Vec<u8>>,
+    pub on_behalf_of: Option<String>,
+    /// the version of the hashing scheme, with which the word has been computed
+    pub hash_version: i32,
 }
 
 #[derive(Insertable)]
@@ -29,8 +33,8 @@ pub struct NewWord {
     pub nonce: Uuid,
     pub guarantee: String,
     pub guarantor: String,
-    pub guarantee_signature: String,
-    pub guarantor_signature: String,
+    pub guarantee_signature: Option<String>,
+    pub guarantor_signature: Option<String>,
     pub created_date: NaiveDateTime,
     pub expiration_date: Option<NaiveDateTime>,
     // -- METADATA END --
@@ -42,6 +46,10 @@ pub struct NewWord {
     pub relpath: bool,
     pub path: String,
     pub len: i64,
+    pub metadata: Option<Vec<u8>>,
+    pub on_behalf_of: Option<String>,
+    /// the version of the hashing scheme, with which the word has been computed
+    pub hash_version: i32,
 }
 
 #[derive(Debug, Queryable)]
@@ -89,3 +97,72 @@ pub struct NewWordCountGuarantee {
     pub word: String,
     pub count: i64,
 }
+
+/// The number of the documents containing a word, joined with the size of the corpus.
+#[derive(Debug, QueryableByName)]
+pub struct DocumentCount {
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub corpus_size: i64,
+    #[diesel(sql_type = ::diesel::sql_types::Nullable<::diesel::sql_types::Varchar>)]
+    pub lang: Option<String>,
+    #[diesel(sql_type = ::diesel::sql_types::Nullable<::diesel::sql_types::Varchar>)]
+    pub word: Option<String>,
+    #[diesel(sql_type = ::diesel::sql_types::Nullable<::diesel::sql_types::BigInt>)]
+    pub count: Option<i64>,
+}
+
+/// A document sharing the words, along with its score.
+#[derive(Debug, QueryableByName)]
+pub struct SimilarDocument {
+    #[diesel(sql_type = ::diesel::sql_types::Varchar)]
+    pub parent: String,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub overlap: i64,
+    #[diesel(sql_type = ::diesel::sql_types::Double)]
+    pub score: f64,
+}
+
+/// The changes of the count of a word, merged since a sequence number.
+#[derive(Debug, QueryableByName)]
+pub struct WordCountChange {
+    #[diesel(sql_type = ::diesel::sql_types::Varchar)]
+    pub namespace: String,
+    #[diesel(sql_type = ::diesel::sql_types::Varchar)]
+    pub lang: String,
+    #[diesel(sql_type = ::diesel::sql_types::Varchar)]
+    pub word: String,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub delta: i64,
+    /// the last sequence number of the changes
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub seq: i64,
+    /// the number of the merged changes
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub changes: i64,
+}
+
+/// The number of the distinct words in a bucket of the histogram.
+#[derive(Debug, QueryableByName)]
+pub struct WordFrequencyBucket {
+    #[diesel(sql_type = ::diesel::sql_types::Integer)]
+    pub bucket: i32,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub words: i64,
+}
+
+/// A row of the analytics compiled from `WordQuery`.
+#[derive(Debug, QueryableByName)]
+pub struct WordQueryRecord {
+    #[diesel(sql_type = ::diesel::sql_types::Nullable<::diesel::sql_types::Timestamp>)]
+    pub day: Option<NaiveDateTime>,
+    #[diesel(sql_type = ::diesel::sql_types::BigInt)]
+    pub count: i64,
+}
+
+/// A line of the plan explained by postgres.
+#[derive(Debug, QueryableByName)]
+pub struct QueryPlanLine {
+    #[diesel(sql_type = ::diesel::sql_types::Text)]
+    #[diesel(column_name = "QUERY PLAN")]
+    pub line: String,
+}
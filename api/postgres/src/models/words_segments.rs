@@ -0,0 +1,92 @@
+use ipis::core::{chrono::NaiveDateTime, uuid::Uuid};
+
+/// A segment of the words tiered out of the hot table, see `crate::tiering`.
+#[derive(Debug, Queryable)]
+pub struct WordsSegment {
+    pub id: i32,
+    /// the hash of the segment object in the store
+    pub hash: String,
+    /// the creation date of the oldest word in the segment
+    pub since_date: NaiveDateTime,
+    /// the creation date of the newest word in the segment
+    pub until_date: NaiveDateTime,
+    /// the number of the words in the segment
+    pub len: i64,
+    pub created_date: NaiveDateTime,
+    /// whether the words of the segment are indexed in `words_tiered`,
+    /// which the segments tiered out before `SchemaVersion::WORDS_TIERED` are not
+    pub indexed: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::words_segments)]
+pub struct NewWordsSegment {
+    pub hash: String,
+    pub since_date: NaiveDateTime,
+    pub until_date: NaiveDateTime,
+    pub len: i64,
+    pub created_date: NaiveDateTime,
+}
+
+/// The index of a word tiered out to a segment, which is served only while indexed.
+///
+/// The columns identifying the word override the ones in the segment, e.g. once migrated.
+#[derive(Debug, Queryable)]
+pub struct WordTiered {
+    pub id: i32,
+    pub segment_id: i32,
+    /// the id of the word in the hot table before tiered out
+    pub record_id: i32,
+    pub nonce: Uuid,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub namespace: String,
+    pub kind: String,
+    pub parent: String,
+    pub lang: String,
+    pub word: String,
+    pub path: String,
+    pub hash_version: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::words_tiered)]
+pub struct NewWordTiered {
+    pub segment_id: i32,
+    pub record_id: i32,
+    pub nonce: Uuid,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub namespace: String,
+    pub kind: String,
+    pub parent: String,
+    pub lang: String,
+    pub word: String,
+    pub path: String,
+    pub hash_version: i32,
+}
+
+impl NewWordTiered {
+    pub fn new(segment_id: i32, record_id: i32, word: &super::words::Word) -> Self {
+        Self {
+            segment_id,
+            record_id,
+            nonce: word.nonce,
+            guarantee: word.guarantee.clone(),
+            guarantor: word.guarantor.clone(),
+            created_date: word.created_date,
+            expiration_date: word.expiration_date,
+            namespace: word.namespace.clone(),
+            kind: word.kind.clone(),
+            parent: word.parent.clone(),
+            lang: word.lang.clone(),
+            word: word.word.clone(),
+            path: word.path.clone(),
+            hash_version: word.hash_version,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use ipdis_common::IpdisError;
+use ipis::core::{
+    anyhow::{anyhow, Result},
+    chrono::{DateTime, NaiveDateTime},
+    uuid::Uuid,
+};
+use serde::Deserialize;
+
+use crate::models::{cipher::ColumnCipher, words::Word};
+
+/// the maximum number of the retries of a batch, before the import is aborted
+pub(crate) const MAX_RETRIES: u32 = 5;
+
+/// A word of the legacy logs, as exported line by line in JSON (ndjson).
+///
+/// The hashes and the signatures are in their string forms, and the dates are in RFC 3339.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct NdjsonWord {
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: String,
+    #[serde(default)]
+    pub expiration_date: Option<String>,
+    // -- METADATA END --
+    pub namespace: String,
+    pub kind: String,
+    pub parent: String,
+    pub lang: String,
+    pub word: String,
+    pub relpath: bool,
+    pub path: String,
+    pub len: i64,
+    /// the version of the hashing scheme, where the legacy words are of the first one
+    #[serde(default = "NdjsonWord::default_hash_version")]
+    pub hash_version: i32,
+}
+
+impl NdjsonWord {
+    const fn default_hash_version() -> i32 {
+        1
+    }
+
+    /// Parses the line into the raw record, whose signatures are not verified yet.
+    pub(crate) fn parse(line: &str, cipher: &ColumnCipher) -> Result<Word> {
+        let record: Self = ::serde_json::from_str(line)?;
+
+        Ok(Word {
+            id: 0,
+            nonce: Uuid::parse_str(&record.nonce)?,
+            guarantee: record.guarantee,
+            guarantor: record.guarantor,
+            guarantee_signature: Some(record.guarantee_signature),
+            guarantor_signature: Some(record.guarantor_signature),
+            created_date: parse_date(&record.created_date)?,
+            expiration_date: record
+                .expiration_date
+                .as_deref()
+                .map(parse_date)
+                .transpose()?,
+            namespace: record.namespace,
+            kind: record.kind,
+            parent: cipher.encrypt(record.parent),
+            lang: record.lang,
+            word: cipher.encrypt(record.word),
+            relpath: record.relpath,
+            path: record.path,
+            len: record.len,
+            metadata: None,
+            on_behalf_of: None,
+            hash_version: record.hash_version,
+        })
+    }
+}
+
+fn parse_date(date: &str) -> Result<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|date| date.naive_utc())
+        .map_err(|error| anyhow!("malformed date: {date:?}: {error}"))
+}
+
+/// The result of an import.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// the number of the read records, except the blank lines
+    pub read: u64,
+    pub imported: u64,
+    /// the number of the records which have been stored already, by their nonces
+    pub duplicates: u64,
+    /// the number of the batches sent again after the transient failures
+    pub retries: u64,
+    pub rejected: Vec<ImportRejection>,
+}
+
+/// A record which is malformed or fails the signature check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportRejection {
+    /// the line of the record, from `1`
+    pub line: u64,
+    pub message: String,
+}
+
+/// The size of the batches, which shrinks on the transient failures and grows back on success.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BatchSize {
+    current: usize,
+}
+
+impl BatchSize {
+    const MIN: usize = 16;
+    const MAX: usize = 1024;
+
+    pub(crate) const fn get(&self) -> usize {
+        self.current
+    }
+
+    pub(crate) fn grow(&mut self) {
+        self.current = (self.current * 2).min(Self::MAX);
+    }
+
+    pub(crate) fn shrink(&mut self) {
+        self.current = (self.current / 2).max(Self::MIN);
+    }
+}
+
+impl Default for BatchSize {
+    fn default() -> Self {
+        Self { current: 256 }
+    }
+}
+
+/// Returns the backoff before the retry, which doubles from 100 ms.
+pub(crate) fn backoff(retries: u32) -> Duration {
+    Duration::from_millis(100 << retries.min(MAX_RETRIES))
+}
+
+/// Returns `true` if the failed batch may succeed when it is sent again.
+pub(crate) fn is_transient(error: &::ipis::core::anyhow::Error) -> bool {
+    use diesel::result::{DatabaseErrorKind, Error};
+
+    if let Some(error) = error.downcast_ref::<IpdisError>() {
+        return error.is_retryable();
+    }
+    matches!(
+        error.downcast_ref::<Error>(),
+        Some(Error::DatabaseError(
+            DatabaseErrorKind::SerializationFailure | DatabaseErrorKind::UnableToSendCommand,
+            _,
+        ))
+    )
+}
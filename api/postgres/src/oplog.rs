@@ -0,0 +1,80 @@
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+use ipdis_common::{merkle, OplogEntry, OplogRoot, SignedRecord};
+use ipis::core::{
+    account::GuaranteeSigned,
+    anyhow::Result,
+    chrono::NaiveDateTime,
+    value::{hash::Hash, uuid::Uuid},
+};
+
+use crate::models::oplog::{NewOplogEntry, NewOplogRoot};
+
+/// Returns the entry of the accepted write, which is appended along with the record.
+pub(crate) fn entry(record: &SignedRecord, created_date: NaiveDateTime) -> Result<NewOplogEntry> {
+    let (nonce, signature) = match record {
+        SignedRecord::DynPath(record) => (record.nonce, record.guarantor.signature),
+        SignedRecord::Word(record) => (record.nonce, record.guarantor.signature),
+    };
+
+    Ok(NewOplogEntry {
+        nonce: nonce.0 .0,
+        hash: record.hash()?.to_string(),
+        signature: signature.to_string(),
+        created_date,
+    })
+}
+
+/// Appends the entry, which should be called in the same transaction of the write.
+///
+/// The appends are serialized until the transaction ends,
+/// so that the entries are committed in the order of their sequence numbers
+/// and a consumer following the watermark never skips one.
+pub(crate) fn append(conn: &mut PgConnection, entry: &NewOplogEntry) -> Result<()> {
+    crate::lock::serialize(conn, "oplog")?;
+
+    ::diesel::insert_into(crate::schema::oplog::table)
+        .values(entry)
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Returns the leaves of the first entries, or of all if no limit is given, in order.
+///
+/// The leaves are indexed by their positions rather than the sequence numbers,
+/// as the sequence numbers may have gaps.
+pub(crate) fn leaves(conn: &mut PgConnection, limit: Option<i64>) -> Result<Vec<Hash>> {
+    let query = crate::schema::oplog::table
+        .select(crate::schema::oplog::hash)
+        .order(crate::schema::oplog::id);
+    let hashes: Vec<String> = match limit {
+        Some(limit) => query.limit(limit).get_results(conn)?,
+        None => query.get_results(conn)?,
+    };
+
+    hashes
+        .into_iter()
+        .map(|hash| Ok(merkle::leaf(&hash.parse()?)))
+        .collect()
+}
+
+pub(crate) fn entry_from_record(record: crate::models::oplog::OplogEntry) -> Result<OplogEntry> {
+    Ok(OplogEntry {
+        seq: record.id.try_into()?,
+        nonce: Uuid(record.nonce),
+        hash: record.hash.parse()?,
+        signature: record.signature.parse()?,
+    })
+}
+
+pub(crate) fn root_to_record(root: &GuaranteeSigned<OplogRoot>) -> Result<NewOplogRoot> {
+    Ok(NewOplogRoot {
+        nonce: root.nonce.0 .0,
+        guarantee: root.guarantee.account.to_string(),
+        guarantor: root.guarantor.to_string(),
+        guarantee_signature: root.guarantee.signature.to_string(),
+        created_date: root.created_date.naive_utc(),
+        expiration_date: root.expiration_date.map(|e| e.naive_utc()),
+        tree_size: root.data.data.tree_size.try_into()?,
+        root: root.data.data.root.to_string(),
+    })
+}
@@ -0,0 +1,40 @@
+use diesel::{PgConnection, RunQueryDsl};
+use ipis::{
+    async_trait::async_trait,
+    core::{anyhow::Result, chrono::NaiveDateTime},
+};
+
+pub use crate::models::outbox::OutboxEvent;
+
+pub const TOPIC_DYN_PATH_PUT: &str = "dyn_path.put";
+pub const TOPIC_GUARANTEE_EXPIRING: &str = "guarantee.expiring";
+pub const TOPIC_GUARANTEE_RENEWED: &str = "guarantee.renewed";
+pub const TOPIC_WORD_PUT: &str = "word.put";
+
+/// Publishes the events of the outbox, e.g. to the subscribers or the webhooks.
+///
+/// An event is marked delivered only after it has been published,
+/// so it may be published more than once if the process crashes in between.
+#[async_trait]
+pub trait OutboxPublisher {
+    async fn publish(&self, event: &OutboxEvent) -> Result<()>;
+}
+
+/// Writes an event at `now`, which should be called in the same transaction of the write.
+pub(crate) fn push(
+    conn: &mut PgConnection,
+    topic: &str,
+    payload: String,
+    now: NaiveDateTime,
+) -> Result<(), ::diesel::result::Error> {
+    let record = crate::models::outbox::NewOutboxEvent {
+        topic: topic.to_string(),
+        payload,
+        created_date: now,
+    };
+
+    ::diesel::insert_into(crate::schema::outbox::table)
+        .values(&record)
+        .execute(conn)
+        .map(|_| ())
+}
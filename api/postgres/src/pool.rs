@@ -0,0 +1,101 @@
+use std::sync::Mutex;
+
+use diesel::{Connection, PgConnection};
+use ipis::{
+    core::anyhow::{bail, Result},
+    tokio::sync::{Semaphore, SemaphorePermit},
+};
+
+pub struct ConnectionPool {
+    connections: Mutex<Vec<PgConnection>>,
+    semaphore: Semaphore,
+    size: u32,
+}
+
+impl ConnectionPool {
+    pub fn establish(database_url: &str, size: u32) -> Result<Self> {
+        if size == 0 {
+            bail!("the connection pool should have at least one connection")
+        }
+
+        let connections = (0..size)
+            .map(|_| {
+                PgConnection::establish(database_url)
+                    .or_else(|_| bail!("Error connecting to {database_url}"))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            connections: Mutex::new(connections),
+            semaphore: Semaphore::new(size as usize),
+            size,
+        })
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns an idle connection without waiting, if any.
+    pub fn try_get(&self) -> Option<PooledConnection<'_>> {
+        let permit = self.semaphore.try_acquire().ok()?;
+        let inner = self.connections.lock().ok()?.pop()?;
+
+        Some(PooledConnection {
+            pool: self,
+            inner: Some(inner),
+            _permit: permit,
+        })
+    }
+
+    pub async fn get(&self) -> PooledConnection<'_> {
+        let permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("the connection pool should not be closed");
+        let inner = self
+            .connections
+            .lock()
+            .ok()
+            .and_then(|mut connections| connections.pop())
+            .expect("the permit should guarantee an idle connection");
+
+        PooledConnection {
+            pool: self,
+            inner: Some(inner),
+            _permit: permit,
+        }
+    }
+}
+
+pub struct PooledConnection<'a> {
+    pool: &'a ConnectionPool,
+    inner: Option<PgConnection>,
+    // released after the connection is returned
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<'a> ::core::ops::Deref for PooledConnection<'a> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        self.inner.as_ref().unwrap()
+    }
+}
+
+impl<'a> ::core::ops::DerefMut for PooledConnection<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.inner.as_mut().unwrap()
+    }
+}
+
+impl<'a> Drop for PooledConnection<'a> {
+    fn drop(&mut self) {
+        if let (Some(inner), Ok(mut connections)) =
+            (self.inner.take(), self.pool.connections.lock())
+        {
+            connections.push(inner);
+        }
+    }
+}
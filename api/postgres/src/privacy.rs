@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use hmac::{Hmac, Mac};
+use ipis::core::{
+    anyhow::{bail, Result},
+    value::hash::Hash,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sha2::Sha256;
+
+const SECRET_LEN: usize = 32;
+
+/// The privacy budgets of the public word counts, by the kind.
+///
+/// Each put changes a count by one, so the noise is calibrated to the sensitivity of one.
+/// The noise is derived from the secret, the word and its count, so that the repeated queries
+/// return the same noisy count rather than the fresh samples to be averaged away.
+#[derive(Clone, Default, PartialEq)]
+pub struct CountNoise {
+    default: Option<f64>,
+    kinds: HashMap<Hash, f64>,
+    secret: [u8; SECRET_LEN],
+}
+
+impl ::core::fmt::Debug for CountNoise {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.debug_struct("CountNoise")
+            .field("default", &self.default)
+            .field("kinds", &self.kinds)
+            .finish()
+    }
+}
+
+impl CountNoise {
+    /// Parses the comma-separated `kind=epsilon` pairs, where the kind `*` matches all the others.
+    ///
+    /// The secret is chosen randomly; see `with_secret` to share it across the nodes.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut noise = Self {
+            secret: ::rand::thread_rng().gen(),
+            ..Default::default()
+        };
+        for item in s.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (kind, epsilon) = match item.split_once('=') {
+                Some((kind, epsilon)) => (kind.trim(), epsilon.trim().parse::<f64>()?),
+                None => bail!("malformed count noise: expected `kind=epsilon`, but given {item:?}"),
+            };
+            if !(epsilon.is_finite() && epsilon > 0.0) {
+                bail!("malformed count noise: epsilon should be positive, but given {epsilon}")
+            }
+
+            match kind {
+                "*" => noise.default = Some(epsilon),
+                kind => {
+                    noise.kinds.insert(Hash::with_str(kind), epsilon);
+                }
+            }
+        }
+        Ok(noise)
+    }
+
+    /// Replaces the secret with the hex-encoded 256-bit one,
+    /// so that all the nodes sharing the database return the same noisy counts.
+    pub fn with_secret(mut self, secret: &str) -> Result<Self> {
+        let secret = ::hex::decode(secret.trim())?;
+        self.secret = match secret.try_into() {
+            Ok(secret) => secret,
+            Err(_) => bail!("the count noise secret should be {SECRET_LEN} bytes"),
+        };
+        Ok(self)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.default.is_some() || !self.kinds.is_empty()
+    }
+
+    pub fn epsilon(&self, kind: &Hash) -> Option<f64> {
+        self.kinds.get(kind).copied().or(self.default)
+    }
+
+    /// Returns the smallest budget of all the kinds, for the counts over several kinds.
+    pub fn epsilon_min(&self) -> Option<f64> {
+        self.kinds
+            .values()
+            .copied()
+            .chain(self.default)
+            .reduce(f64::min)
+    }
+
+    /// Adds the Laplace noise to the count of the word, if the kind has a budget.
+    pub fn apply(&self, kind: &Hash, word: &Hash, count: u32) -> u32 {
+        self.apply_epsilon(self.epsilon(kind), kind, word, count)
+    }
+
+    /// Adds the Laplace noise of the smallest budget to the count of the word over all the kinds.
+    pub fn apply_all(&self, word: &Hash, count: u32) -> u32 {
+        self.apply_epsilon(self.epsilon_min(), &Hash::with_str("*"), word, count)
+    }
+
+    fn apply_epsilon(&self, epsilon: Option<f64>, kind: &Hash, word: &Hash, count: u32) -> u32 {
+        match epsilon {
+            Some(epsilon) => {
+                let mut rng = StdRng::from_seed(self.seed(kind, word, epsilon, count));
+                let noisy = f64::from(count) + laplace(&mut rng, 1.0 / epsilon);
+                noisy.round().clamp(0.0, f64::from(u32::MAX)) as u32
+            }
+            None => count,
+        }
+    }
+
+    /// Derives the seed of the noise, which cannot be computed without the secret.
+    fn seed(&self, kind: &Hash, word: &Hash, epsilon: f64, count: u32) -> [u8; 32] {
+        let mut mac =
+            <Hmac<Sha256> as Mac>::new_from_slice(&self.secret).expect("valid key length");
+        mac.update(kind.to_string().as_bytes());
+        mac.update(word.to_string().as_bytes());
+        mac.update(&epsilon.to_le_bytes());
+        mac.update(&count.to_le_bytes());
+        mac.finalize().into_bytes().into()
+    }
+}
+
+/// Samples from the Laplace distribution centered at zero, by the inverse CDF.
+fn laplace(rng: &mut impl Rng, scale: f64) -> f64 {
+    let u: f64 = rng.gen_range(-0.5..0.5);
+    -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+}
@@ -0,0 +1,108 @@
+use diesel::{
+    pg::Pg,
+    query_builder::{BoxedSqlQuery, SqlQuery},
+    sql_types::{BigInt, Text, Timestamp},
+};
+use ipdis_common::{WordQuery, WordQueryGroupBy};
+use ipis::core::{
+    anyhow::{anyhow, Result},
+    chrono::NaiveDateTime,
+};
+
+/// A parameter of the compiled query, which is bound in order.
+enum Bind {
+    BigInt(i64),
+    Text(String),
+    Timestamp(NaiveDateTime),
+}
+
+/// Compiles the query over the words of the guarantor into SQL,
+/// where all the values given by the clients are bound as parameters.
+pub(crate) fn compile(
+    guarantor: String,
+    now: NaiveDateTime,
+    query: &WordQuery,
+) -> Result<BoxedSqlQuery<'static, Pg, SqlQuery>> {
+    compile_with_prefix("", guarantor, now, query)
+}
+
+/// Compiles the query as `compile`, which is run with `EXPLAIN (ANALYZE, BUFFERS)`.
+///
+/// Note that the query is actually executed to be analyzed.
+pub(crate) fn compile_explain(
+    guarantor: String,
+    now: NaiveDateTime,
+    query: &WordQuery,
+) -> Result<BoxedSqlQuery<'static, Pg, SqlQuery>> {
+    compile_with_prefix("EXPLAIN (ANALYZE, BUFFERS) ", guarantor, now, query)
+}
+
+fn compile_with_prefix(
+    prefix: &str,
+    guarantor: String,
+    now: NaiveDateTime,
+    query: &WordQuery,
+) -> Result<BoxedSqlQuery<'static, Pg, SqlQuery>> {
+    query.validate()?;
+
+    let mut clauses = vec!["(expiration_date IS NULL OR expiration_date >= $1)".to_string()];
+    let mut binds = vec![Bind::Timestamp(now)];
+    let mut filter = |clause: &str, bind: Bind| {
+        binds.push(bind);
+        clauses.push(format!("{clause} = ${}", binds.len()));
+    };
+
+    filter("guarantor", Bind::Text(guarantor));
+    filter("kind", Bind::Text(query.kind.to_string()));
+    if let Some(namespace) = &query.namespace {
+        filter("namespace", Bind::Text(namespace.to_string()));
+    }
+    if let Some(lang) = &query.lang {
+        filter("lang", Bind::Text(lang.clone()));
+    }
+    if let Some(account) = &query.owned_by {
+        filter("guarantee", Bind::Text(account.to_string()));
+    }
+    if let Some(since) = query.since {
+        binds.push(Bind::Timestamp(timestamp(since)?));
+        clauses.push(format!("created_date >= ${}", binds.len()));
+    }
+    if let Some(until) = query.until {
+        binds.push(Bind::Timestamp(timestamp(until)?));
+        clauses.push(format!("created_date < ${}", binds.len()));
+    }
+
+    let (day, group_by) = match query.group_by {
+        WordQueryGroupBy::None => ("NULL::TIMESTAMP", ""),
+        WordQueryGroupBy::Day => ("DATE_TRUNC('day', created_date)", "GROUP BY 1"),
+    };
+    binds.push(Bind::BigInt(query.limit.into()));
+
+    let sql = format!(
+        "{prefix}SELECT {day} AS day, COUNT(*)::INT8 AS count FROM words
+        WHERE {clauses}
+        {group_by}
+        ORDER BY 1
+        LIMIT ${limit}",
+        clauses = clauses.join(" AND "),
+        limit = binds.len(),
+    );
+
+    Ok(binds.into_iter().fold(
+        ::diesel::sql_query(sql).into_boxed(),
+        |sql, bind| match bind {
+            Bind::BigInt(value) => sql.bind::<BigInt, _>(value),
+            Bind::Text(value) => sql.bind::<Text, _>(value),
+            Bind::Timestamp(value) => sql.bind::<Timestamp, _>(value),
+        },
+    ))
+}
+
+/// Converts the unix timestamp in milliseconds, as given by the clients.
+pub(crate) fn timestamp(millis: i64) -> Result<NaiveDateTime> {
+    NaiveDateTime::from_timestamp_opt(
+        millis.div_euclid(1000),
+        (millis.rem_euclid(1000) * 1_000_000) as u32,
+    )
+    .ok_or_else(|| anyhow!("malformed timestamp: {millis}"))
+}
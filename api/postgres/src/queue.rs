@@ -0,0 +1,153 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use ipdis_common::IpdisError;
+use ipis::{
+    core::anyhow::{bail, Result},
+    tokio::{
+        self,
+        sync::{Semaphore, SemaphorePermit},
+    },
+};
+
+/// the number of the recent waits to keep track of
+const RECENT_WAITS: usize = 256;
+
+/// The classes of the requests, by their expected cost.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RequestClass {
+    /// Cheap requests, e.g. the health checks and the single lookups, which are never queued.
+    ///
+    /// They are still bounded by the limit of the concurrent requests, if any.
+    Priority,
+    /// Expensive requests, e.g. the pages and the batches, which are queued under load.
+    Bulk,
+}
+
+/// A two-tier queue in front of the connection pool.
+///
+/// The bulk requests may use all but the reserved connections,
+/// so that the priority requests are not starved by them.
+/// If the concurrent requests are limited, a burst of requests of either class
+/// is queued rather than exhausting the connections and the memory.
+pub struct RequestQueue {
+    bulk: Semaphore,
+    requests: Option<Semaphore>,
+    timeout: Option<Duration>,
+    queued: AtomicU32,
+    recent_waits: Mutex<VecDeque<u64>>,
+}
+
+/// The turn of a request, which is held until dropped.
+pub struct QueuePermit<'a> {
+    _bulk: Option<SemaphorePermit<'a>>,
+    _request: Option<SemaphorePermit<'a>>,
+}
+
+impl RequestQueue {
+    pub fn new(
+        pool_size: u32,
+        reserved: u32,
+        max_concurrent_requests: Option<u32>,
+        timeout: Option<Duration>,
+    ) -> Self {
+        // leave at least one connection to the bulk requests
+        let bulk = pool_size.saturating_sub(reserved).max(1);
+
+        Self {
+            bulk: Semaphore::new(bulk as usize),
+            requests: max_concurrent_requests.map(|limit| Semaphore::new(limit.max(1) as usize)),
+            timeout,
+            queued: Default::default(),
+            recent_waits: Default::default(),
+        }
+    }
+
+    /// Waits for the turn of the request, which is held until the permit is dropped.
+    ///
+    /// The requests which are not started in time are rejected,
+    /// as their clients may have given up already.
+    pub async fn enter(&self, class: RequestClass) -> Result<QueuePermit<'_>> {
+        if class == RequestClass::Priority && self.requests.is_none() {
+            return Ok(QueuePermit {
+                _bulk: None,
+                _request: None,
+            });
+        }
+
+        let started = Instant::now();
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        let permit = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.acquire(class))
+                .await
+                .ok(),
+            None => Some(self.acquire(class).await),
+        };
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+        self.record(started.elapsed());
+
+        match permit {
+            Some(permit) => Ok(permit),
+            // the request has been queued too long
+            None => bail!(IpdisError::Busy {
+                resource: "the request queue".into(),
+            }),
+        }
+    }
+
+    async fn acquire(&self, class: RequestClass) -> QueuePermit<'_> {
+        // the bulk requests wait for their tier first, so that they hold no turn of the others
+        let bulk = match class {
+            RequestClass::Priority => None,
+            RequestClass::Bulk => Some(
+                self.bulk
+                    .acquire()
+                    .await
+                    .expect("the request queue should not be closed"),
+            ),
+        };
+        let request = match &self.requests {
+            Some(requests) => Some(
+                requests
+                    .acquire()
+                    .await
+                    .expect("the request queue should not be closed"),
+            ),
+            None => None,
+        };
+
+        QueuePermit {
+            _bulk: bulk,
+            _request: request,
+        }
+    }
+
+    /// Returns the number of the queued requests.
+    pub fn queued(&self) -> u32 {
+        self.queued.load(Ordering::SeqCst)
+    }
+
+    /// Returns the longest time which the recent requests have been queued, in microseconds.
+    pub fn queue_time_us(&self) -> u64 {
+        self.recent_waits
+            .lock()
+            .ok()
+            .and_then(|waits| waits.iter().max().copied())
+            .unwrap_or_default()
+    }
+
+    fn record(&self, elapsed: Duration) {
+        if let Ok(mut waits) = self.recent_waits.lock() {
+            if waits.len() >= RECENT_WAITS {
+                waits.pop_front();
+            }
+            waits.push_back(elapsed.as_micros().try_into().unwrap_or(u64::MAX));
+        }
+    }
+}
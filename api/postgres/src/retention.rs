@@ -0,0 +1,182 @@
+use diesel::{
+    sql_types::Timestamp, ExpressionMethods, PgConnection, QueryDsl, QueryResult, RunQueryDsl,
+};
+use ipis::core::{
+    anyhow::{anyhow, Result},
+    chrono::NaiveDateTime,
+};
+
+use crate::{
+    config::SignatureRetentionPolicy,
+    export::{TABLE_DYN_PATHS, TABLE_WORDS},
+};
+
+/// The tables whose signatures are retained, with the columns identifying a record,
+/// of which only the newest row is read back as the latest one.
+const TABLES: &[(&str, &[&str])] = &[
+    (
+        TABLE_DYN_PATHS,
+        &["guarantee", "guarantor", "namespace", "kind", "word"],
+    ),
+    (
+        TABLE_WORDS,
+        &[
+            "guarantee",
+            "guarantor",
+            "namespace",
+            "kind",
+            "parent",
+            "lang",
+            "word",
+        ],
+    ),
+];
+
+/// A record whose signatures may have been moved out by the retention policy.
+pub(crate) trait HasSignatures {
+    fn id(&self) -> i32;
+
+    fn signatures(&self) -> (&Option<String>, &Option<String>);
+
+    fn signatures_mut(&mut self) -> (&mut Option<String>, &mut Option<String>);
+}
+
+impl HasSignatures for crate::models::dyn_paths::DynPath {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn signatures(&self) -> (&Option<String>, &Option<String>) {
+        (&self.guarantee_signature, &self.guarantor_signature)
+    }
+
+    fn signatures_mut(&mut self) -> (&mut Option<String>, &mut Option<String>) {
+        (&mut self.guarantee_signature, &mut self.guarantor_signature)
+    }
+}
+
+impl HasSignatures for crate::models::words::Word {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn signatures(&self) -> (&Option<String>, &Option<String>) {
+        (&self.guarantee_signature, &self.guarantor_signature)
+    }
+
+    fn signatures_mut(&mut self) -> (&mut Option<String>, &mut Option<String>) {
+        (&mut self.guarantee_signature, &mut self.guarantor_signature)
+    }
+}
+
+/// Moves the signatures of the records created before the cutoff out of the hot tables,
+/// and forgets the archived signatures of the deleted records.
+///
+/// The records which are still the latest ones keep their signatures,
+/// so that they can be served even if the signatures are dropped.
+///
+/// Returns the number of the affected records.
+pub(crate) fn sweep(
+    conn: &mut PgConnection,
+    policy: SignatureRetentionPolicy,
+    cutoff: NaiveDateTime,
+) -> QueryResult<usize> {
+    let mut affected = 0;
+    for (table, keys) in TABLES {
+        let superseded = superseded(table, keys);
+
+        if policy == SignatureRetentionPolicy::Archive {
+            ::diesel::sql_query(format!(
+                "INSERT INTO signatures_archive (table_name, record_id, guarantee_signature, guarantor_signature)
+                SELECT '{table}', id, guarantee_signature, guarantor_signature FROM {table} r
+                WHERE r.created_date < $1 AND r.guarantee_signature IS NOT NULL AND r.guarantor_signature IS NOT NULL
+                    AND {superseded}
+                ON CONFLICT (table_name, record_id) DO NOTHING"
+            ))
+            .bind::<Timestamp, _>(cutoff)
+            .execute(conn)?;
+        }
+
+        affected += ::diesel::sql_query(format!(
+            "UPDATE {table} r SET guarantee_signature = NULL, guarantor_signature = NULL
+            WHERE r.created_date < $1 AND (r.guarantee_signature IS NOT NULL OR r.guarantor_signature IS NOT NULL)
+                AND {superseded}"
+        ))
+        .bind::<Timestamp, _>(cutoff)
+        .execute(conn)?;
+
+        ::diesel::sql_query(format!(
+            "DELETE FROM signatures_archive a WHERE a.table_name = '{table}'
+            AND NOT EXISTS (SELECT 1 FROM {table} r WHERE r.id = a.record_id)"
+        ))
+        .execute(conn)?;
+    }
+    Ok(affected)
+}
+
+/// Returns the condition of the records `r` which have been replaced by a newer one.
+fn superseded(table: &str, keys: &[&str]) -> String {
+    let keys: Vec<_> = keys
+        .iter()
+        .map(|key| format!("n.{key} = r.{key}"))
+        .collect();
+    format!(
+        "EXISTS (SELECT 1 FROM {table} n WHERE {keys} AND n.created_date > r.created_date)",
+        keys = keys.join(" AND "),
+    )
+}
+
+/// Fills the signatures of the records which have been archived, in place.
+///
+/// The dropped signatures are left as `None`.
+pub(crate) fn restore<R>(conn: &mut PgConnection, table: &str, records: &mut [R]) -> QueryResult<()>
+where
+    R: HasSignatures,
+{
+    let ids: Vec<_> = records
+        .iter_mut()
+        .filter(|record| matches!(record.signatures_mut(), (None, _) | (_, None)))
+        .map(|record| record.id())
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let archived: Vec<crate::models::signatures_archive::SignaturesArchive> =
+        crate::schema::signatures_archive::table
+            .filter(crate::schema::signatures_archive::table_name.eq(table))
+            .filter(crate::schema::signatures_archive::record_id.eq_any(&ids))
+            .get_results(conn)?;
+
+    for archived in archived {
+        if let Some(record) = records
+            .iter_mut()
+            .find(|record| record.id() == archived.record_id)
+        {
+            let (guarantee_signature, guarantor_signature) = record.signatures_mut();
+            *guarantee_signature = Some(archived.guarantee_signature);
+            *guarantor_signature = Some(archived.guarantor_signature);
+        }
+    }
+    Ok(())
+}
+
+/// Removes the records whose signatures have been dropped,
+/// which are the superseded ones and so not served anymore.
+///
+/// Returns the number of the removed records.
+pub(crate) fn retain_signed<R>(records: &mut Vec<R>) -> usize
+where
+    R: HasSignatures,
+{
+    let len = records.len();
+    records.retain(|record| matches!(record.signatures(), (Some(_), Some(_))));
+    len - records.len()
+}
+
+/// Returns the signature of the record, unless it has been dropped by the retention policy.
+pub(crate) fn signature_of(signature: &Option<String>) -> Result<&str> {
+    signature
+        .as_deref()
+        .ok_or_else(|| anyhow!("the signature has been dropped by the retention policy"))
+}
@@ -1,3 +1,15 @@
+table! {
+    api_tokens (id) {
+        id -> Int4,
+        token_hash -> Varchar,
+        name -> Varchar,
+        scopes -> Varchar,
+        rate_limit -> Int4,
+        created_date -> Timestamp,
+        expiration_date -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     accounts_guarantees (id) {
         id -> Int4,
@@ -8,6 +20,31 @@ table! {
         guarantor_signature -> Varchar,
         created_date -> Timestamp,
         expiration_date -> Nullable<Timestamp>,
+        auto_renew -> Bool,
+        notified_date -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    accounts_successors (id) {
+        id -> Int4,
+        nonce -> Uuid,
+        account -> Varchar,
+        successor -> Varchar,
+        guarantor -> Varchar,
+        account_signature -> Varchar,
+        successor_signature -> Varchar,
+        created_date -> Timestamp,
+    }
+}
+
+table! {
+    accounts_usage (id) {
+        id -> Int4,
+        account -> Varchar,
+        day -> Timestamp,
+        bytes_written -> Int8,
+        rows_written -> Int8,
     }
 }
 
@@ -17,8 +54,8 @@ table! {
         nonce -> Uuid,
         guarantee -> Varchar,
         guarantor -> Varchar,
-        guarantee_signature -> Varchar,
-        guarantor_signature -> Varchar,
+        guarantee_signature -> Nullable<Varchar>,
+        guarantor_signature -> Nullable<Varchar>,
         created_date -> Timestamp,
         expiration_date -> Nullable<Timestamp>,
         namespace -> Varchar,
@@ -26,17 +63,109 @@ table! {
         word -> Varchar,
         path -> Varchar,
         len -> Int8,
+        metadata -> Nullable<Bytea>,
+        on_behalf_of -> Nullable<Varchar>,
     }
 }
 
 table! {
-    words (id) {
+    kinds (id) {
+        id -> Int4,
+        kind -> Varchar,
+        name -> Varchar,
+        description -> Varchar,
+        schema_version -> Int4,
+        normalization -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    kinds_leases (id) {
+        id -> Int4,
+        kind -> Varchar,
+        holder -> Varchar,
+        token -> Int8,
+        created_date -> Timestamp,
+        expiration_date -> Timestamp,
+    }
+}
+
+table! {
+    oplog (id) {
+        id -> Int4,
+        nonce -> Uuid,
+        hash -> Varchar,
+        signature -> Varchar,
+        created_date -> Timestamp,
+    }
+}
+
+table! {
+    oplog_roots (id) {
         id -> Int4,
         nonce -> Uuid,
         guarantee -> Varchar,
         guarantor -> Varchar,
         guarantee_signature -> Varchar,
+        created_date -> Timestamp,
+        expiration_date -> Nullable<Timestamp>,
+        tree_size -> Int8,
+        root -> Varchar,
+    }
+}
+
+table! {
+    outbox (id) {
+        id -> Int4,
+        topic -> Varchar,
+        payload -> Varchar,
+        created_date -> Timestamp,
+        delivered_date -> Nullable<Timestamp>,
+    }
+}
+
+table! {
+    schema_meta (id) {
+        id -> Bool,
+        version -> Int4,
+        compatible_version -> Int4,
+    }
+}
+
+table! {
+    settings (name) {
+        name -> Varchar,
+        value -> Varchar,
+    }
+}
+
+table! {
+    signatures_archive (id) {
+        id -> Int4,
+        table_name -> Varchar,
+        record_id -> Int4,
+        guarantee_signature -> Varchar,
         guarantor_signature -> Varchar,
+    }
+}
+
+table! {
+    stop_words (id) {
+        id -> Int4,
+        kind -> Varchar,
+        lang -> Varchar,
+        word -> Varchar,
+    }
+}
+
+table! {
+    words (id) {
+        id -> Int4,
+        nonce -> Uuid,
+        guarantee -> Varchar,
+        guarantor -> Varchar,
+        guarantee_signature -> Nullable<Varchar>,
+        guarantor_signature -> Nullable<Varchar>,
         created_date -> Timestamp,
         expiration_date -> Nullable<Timestamp>,
         namespace -> Varchar,
@@ -47,6 +176,9 @@ table! {
         relpath -> Bool,
         path -> Varchar,
         len -> Int8,
+        metadata -> Nullable<Bytea>,
+        on_behalf_of -> Nullable<Varchar>,
+        hash_version -> Int4,
     }
 }
 
@@ -62,6 +194,17 @@ table! {
     }
 }
 
+table! {
+    words_counts_changes (id) {
+        id -> Int8,
+        namespace -> Varchar,
+        kind -> Varchar,
+        lang -> Varchar,
+        word -> Varchar,
+        delta -> Int8,
+    }
+}
+
 table! {
     words_counts_guarantees (id) {
         id -> Int4,
@@ -75,10 +218,57 @@ table! {
     }
 }
 
+table! {
+    words_segments (id) {
+        id -> Int4,
+        hash -> Varchar,
+        since_date -> Timestamp,
+        until_date -> Timestamp,
+        len -> Int8,
+        created_date -> Timestamp,
+        indexed -> Bool,
+    }
+}
+
+table! {
+    words_tiered (id) {
+        id -> Int4,
+        segment_id -> Int4,
+        record_id -> Int4,
+        nonce -> Uuid,
+        guarantee -> Varchar,
+        guarantor -> Varchar,
+        created_date -> Timestamp,
+        expiration_date -> Nullable<Timestamp>,
+        namespace -> Varchar,
+        kind -> Varchar,
+        parent -> Varchar,
+        lang -> Varchar,
+        word -> Varchar,
+        path -> Varchar,
+        hash_version -> Int4,
+    }
+}
+
 allow_tables_to_appear_in_same_query!(
     accounts_guarantees,
+    accounts_successors,
+    accounts_usage,
+    api_tokens,
     dyn_paths,
+    kinds,
+    kinds_leases,
+    oplog,
+    oplog_roots,
+    outbox,
+    schema_meta,
+    settings,
+    signatures_archive,
+    stop_words,
     words,
     words_counts,
+    words_counts_changes,
     words_counts_guarantees,
+    words_segments,
+    words_tiered,
 );
@@ -0,0 +1,182 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use diesel::{
+    connection::{AnsiTransactionManager, TransactionManager},
+    ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl,
+};
+use ipdis_common::{ensure_same_namespace, IdfVector};
+use ipis::{
+    core::{anyhow::Result, chrono::NaiveDateTime},
+    word::WordKeyHash,
+};
+
+use crate::{diagnostics::ConnectionGuard, models::cipher::ColumnCipher};
+
+/// the isolation of the snapshots, which sees only the rows committed before its first query
+const BEGIN_SNAPSHOT: &str = "BEGIN TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY";
+
+/// A snapshot of the database, on which several reads see the same committed rows,
+/// e.g. for computing TF-IDF without the concurrent writes interleaved.
+///
+/// It is given by `IpdisClient::read_consistent`, and the reads are not noised nor checked.
+#[derive(Clone)]
+pub struct ConsistentView<'a> {
+    cipher: &'a ColumnCipher,
+    snapshot: Arc<Mutex<Snapshot<'a>>>,
+    now: NaiveDateTime,
+}
+
+impl<'a> ConsistentView<'a> {
+    pub(crate) fn new(
+        cipher: &'a ColumnCipher,
+        snapshot: Arc<Mutex<Snapshot<'a>>>,
+        now: NaiveDateTime,
+    ) -> Self {
+        Self {
+            cipher,
+            snapshot,
+            now,
+        }
+    }
+
+    /// Returns the number of the word in the namespace, summed over the kinds and the parents.
+    pub async fn get_word_count(&self, word: &WordKeyHash) -> Result<u32> {
+        word_count(&mut self.lock().conn, self.cipher, word)
+    }
+
+    /// Returns the numbers of the words, in the order of the query.
+    pub async fn get_word_count_many(&self, words: &[WordKeyHash]) -> Result<Vec<u32>> {
+        let mut snapshot = self.lock();
+        words
+            .iter()
+            .map(|word| word_count(&mut snapshot.conn, self.cipher, word))
+            .collect()
+    }
+
+    /// Returns the document frequencies of the words, see `Ipdis::get_idf_vector`.
+    pub async fn get_idf_vector(&self, words: &[WordKeyHash]) -> Result<IdfVector> {
+        idf_vector(&mut self.lock().conn, self.cipher, words, self.now)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Snapshot<'a>> {
+        self.snapshot
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// A connection in a read-only transaction, which is rolled back unless committed.
+pub(crate) struct Snapshot<'a> {
+    conn: ConnectionGuard<'a>,
+    is_open: bool,
+}
+
+impl<'a> Snapshot<'a> {
+    pub(crate) fn begin(mut conn: ConnectionGuard<'a>) -> Result<Self> {
+        AnsiTransactionManager::begin_transaction_sql(&mut *conn, BEGIN_SNAPSHOT)?;
+        Ok(Self {
+            conn,
+            is_open: true,
+        })
+    }
+
+    pub(crate) fn commit(&mut self) -> Result<()> {
+        self.is_open = false;
+        AnsiTransactionManager::commit_transaction(&mut *self.conn).map_err(Into::into)
+    }
+}
+
+impl<'a> Drop for Snapshot<'a> {
+    fn drop(&mut self) {
+        // the connection should not be returned to the pool in the transaction
+        if self.is_open {
+            let _ = AnsiTransactionManager::rollback_transaction(&mut *self.conn);
+        }
+    }
+}
+
+pub(crate) fn word_count(
+    conn: &mut PgConnection,
+    cipher: &ColumnCipher,
+    word: &WordKeyHash,
+) -> Result<u32> {
+    let counts: Vec<i64> = crate::schema::words_counts::table
+        .filter(crate::schema::words_counts::namespace.eq(word.namespace.to_string()))
+        .filter(crate::schema::words_counts::lang.eq(word.text.lang.to_string()))
+        .filter(crate::schema::words_counts::word.eq(cipher.encrypt(word.text.msg.to_string())))
+        .select(crate::schema::words_counts::count)
+        .get_results(conn)?;
+
+    counts
+        .into_iter()
+        .sum::<i64>()
+        .try_into()
+        .map_err(Into::into)
+}
+
+pub(crate) fn idf_vector(
+    conn: &mut PgConnection,
+    cipher: &ColumnCipher,
+    words: &[WordKeyHash],
+    now: NaiveDateTime,
+) -> Result<IdfVector> {
+    let namespace = match ensure_same_namespace(words)? {
+        Some(namespace) => namespace,
+        None => {
+            return Ok(IdfVector {
+                corpus_size: 0,
+                document_counts: vec![],
+            })
+        }
+    };
+
+    let msgs: Vec<_> = words
+        .iter()
+        .map(|word| cipher.encrypt(word.text.msg.to_string()))
+        .collect();
+
+    let records: Vec<crate::models::words::DocumentCount> = ::diesel::sql_query(
+        "SELECT corpus.corpus_size, documents.lang, documents.word, documents.count
+        FROM (
+            SELECT COUNT(DISTINCT parent) AS corpus_size FROM words
+            WHERE namespace = $1 AND (expiration_date IS NULL OR expiration_date >= $3)
+        ) AS corpus
+        LEFT JOIN (
+            SELECT lang, word, COUNT(DISTINCT parent) AS count FROM words
+            WHERE namespace = $1 AND (expiration_date IS NULL OR expiration_date >= $3)
+                AND word = ANY($2)
+            GROUP BY lang, word
+        ) AS documents ON TRUE",
+    )
+    .bind::<::diesel::sql_types::Text, _>(namespace.to_string())
+    .bind::<::diesel::sql_types::Array<::diesel::sql_types::Text>, _>(&msgs)
+    .bind::<::diesel::sql_types::Timestamp, _>(now)
+    .load(conn)?;
+
+    let corpus_size = records
+        .first()
+        .map(|record| record.corpus_size)
+        .unwrap_or_default();
+    let counts: HashMap<_, _> = records
+        .into_iter()
+        .filter_map(|record| Some(((record.lang?, record.word?), record.count?)))
+        .collect();
+
+    Ok(IdfVector {
+        corpus_size: corpus_size.try_into()?,
+        document_counts: words
+            .iter()
+            .zip(msgs)
+            .map(|(word, msg)| {
+                counts
+                    .get(&(word.text.lang.to_string(), msg))
+                    .copied()
+                    .unwrap_or_default()
+                    .try_into()
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
@@ -0,0 +1,56 @@
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, QueryResult, RunQueryDsl};
+
+use crate::models::accounts_successors::AccountsChainLink;
+
+/// Returns the account along with all of its predecessors, latest first.
+///
+/// The records of the predecessors belong to the same principal as the account.
+pub(crate) fn principals(conn: &mut PgConnection, account: &str) -> QueryResult<Vec<String>> {
+    ::diesel::sql_query(
+        "WITH RECURSIVE chain (account, depth) AS (
+            SELECT $1::VARCHAR, 0
+            UNION ALL
+            SELECT accounts_successors.account, chain.depth + 1
+            FROM accounts_successors
+            INNER JOIN chain ON accounts_successors.successor = chain.account
+        )
+        SELECT account FROM chain ORDER BY depth",
+    )
+    .bind::<::diesel::sql_types::Text, _>(account)
+    .load::<AccountsChainLink>(conn)
+    .map(|records| records.into_iter().map(|record| record.account).collect())
+}
+
+/// Returns the latest successor of the account, or the account itself if it has never been rotated.
+pub(crate) fn latest(conn: &mut PgConnection, account: &str) -> QueryResult<String> {
+    ::diesel::sql_query(
+        "WITH RECURSIVE chain (account, depth) AS (
+            SELECT $1::VARCHAR, 0
+            UNION ALL
+            SELECT accounts_successors.successor, chain.depth + 1
+            FROM accounts_successors
+            INNER JOIN chain ON accounts_successors.account = chain.account
+        )
+        SELECT account FROM chain ORDER BY depth DESC LIMIT 1",
+    )
+    .bind::<::diesel::sql_types::Text, _>(account)
+    .get_result::<AccountsChainLink>(conn)
+    .map(|record| record.account)
+}
+
+/// Returns the whole chain of the successions containing the account, oldest first.
+pub(crate) fn chain(conn: &mut PgConnection, account: &str) -> QueryResult<Vec<String>> {
+    let latest = latest(conn, account)?;
+    let mut chain = principals(conn, &latest)?;
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Returns whether the account has been succeeded by another one.
+pub(crate) fn is_retired(conn: &mut PgConnection, account: &str) -> QueryResult<bool> {
+    crate::schema::accounts_successors::table
+        .filter(crate::schema::accounts_successors::account.eq(account))
+        .count()
+        .get_result::<i64>(conn)
+        .map(|count| count > 0)
+}
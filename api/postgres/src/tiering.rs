@@ -0,0 +1,381 @@
+//! Tiers the old words, i.e. the logs from which the IDF vectors are computed,
+//! out of the hot table into the compressed segments addressed by their contents, e.g. in ipsis.
+//!
+//! Only the superseded words are tiered, so that the latest ones are still served as before.
+//! The segments are indexed by the dates of their words in `words_segments`,
+//! and each tiered word is indexed in `words_tiered`, from which the time-range queries read the cold tier.
+//! The tiered words are served only while indexed, so that the deletions and the expiry
+//! reach them as the hot ones, without rewriting the segments.
+
+use std::{
+    collections::BTreeSet,
+    io::{Read, Write},
+};
+
+use bytecheck::CheckBytes;
+use diesel::{
+    sql_types::{BigInt, Integer, Nullable, Timestamp, Uuid as SqlUuid},
+    ExpressionMethods, OptionalExtension, PgConnection, QueryDsl, QueryResult, RunQueryDsl,
+};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use ipis::core::{
+    anyhow::{anyhow, bail, Result},
+    chrono::NaiveDateTime,
+    uuid::Uuid,
+    value::hash::Hash,
+};
+use rkyv::{Archive, Deserialize, Infallible, Serialize};
+
+use crate::{
+    backup::{BackupDate, BackupRecords, BackupWord},
+    export::TABLE_WORDS,
+    models::{
+        words::{NewWord, Word},
+        words_segments::{NewWordTiered, NewWordsSegment, WordTiered, WordsSegment},
+    },
+};
+
+/// the scratch space of the serializer, in bytes
+const SCRATCH_SPACE: usize = 4096;
+
+/// the maximum number of the words in a segment
+pub(crate) const SEGMENT_LEN: i64 = 4096;
+
+/// the maximum size of a decompressed segment, in bytes, which bounds the forged ones
+const MAX_SEGMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The words tiered out at once, whose encrypted columns are kept encrypted.
+#[derive(Clone, Debug, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug))]
+pub struct WordsSegmentObject {
+    /// the creation date of the oldest word
+    pub since: BackupDate,
+    /// the creation date of the newest word
+    pub until: BackupDate,
+    pub records: BackupRecords,
+}
+
+impl WordsSegmentObject {
+    /// Collects the words, which should be ordered by their creation dates.
+    pub(crate) fn new(words: Vec<Word>) -> Option<Self> {
+        let since = words.first()?.created_date.into();
+        let until = words.last()?.created_date.into();
+
+        Some(Self {
+            since,
+            until,
+            records: BackupRecords {
+                dyn_paths: vec![],
+                words: words.into_iter().map(Into::into).collect(),
+            },
+        })
+    }
+
+    /// Archives and compresses the segment.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let bytes = ::rkyv::to_bytes::<_, SCRATCH_SPACE>(self)
+            .map_err(|error| anyhow!("failed to archive the segment: {error}"))?;
+
+        let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+        encoder.write_all(&bytes)?;
+        encoder.finish().map_err(Into::into)
+    }
+
+    /// Decompresses and parses the segment, and checks whether the words can be read back.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut decoded = vec![];
+        DeflateDecoder::new(bytes)
+            .take(MAX_SEGMENT_SIZE + 1)
+            .read_to_end(&mut decoded)
+            .map_err(|error| anyhow!("malformed segment: {error}"))?;
+        if decoded.len() as u64 > MAX_SEGMENT_SIZE {
+            bail!("the segment is too large: over {MAX_SEGMENT_SIZE} bytes")
+        }
+
+        let mut aligned = ::rkyv::AlignedVec::with_capacity(decoded.len());
+        aligned.extend_from_slice(&decoded);
+
+        let this: Self = ::rkyv::check_archived_root::<Self>(&aligned)
+            .map_err(|error| anyhow!("malformed segment: {error}"))?
+            .deserialize(&mut Infallible)
+            .expect("infallible");
+
+        this.records.validate()?;
+        Ok(this)
+    }
+
+    /// Returns the number of the words.
+    pub fn len(&self) -> usize {
+        self.records.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.words.is_empty()
+    }
+
+    /// Ensures that the segment is the one described by the index.
+    pub(crate) fn ensure_indexed(&self, segment: &WordsSegment) -> Result<()> {
+        if NaiveDateTime::from(self.since) != segment.since_date
+            || NaiveDateTime::from(self.until) != segment.until_date
+            || self.len() as i64 != segment.len
+        {
+            bail!("the segment does not match its index: {}", &segment.hash)
+        }
+        Ok(())
+    }
+}
+
+/// The condition of the words `r` which have been replaced by a newer one.
+const SUPERSEDED: &str = "EXISTS (SELECT 1 FROM words n WHERE n.guarantee = r.guarantee
+    AND n.guarantor = r.guarantor AND n.namespace = r.namespace AND n.kind = r.kind
+    AND n.parent = r.parent AND n.lang = r.lang AND n.word = r.word
+    AND n.created_date > r.created_date)";
+
+#[derive(QueryableByName)]
+struct RecordId {
+    #[diesel(sql_type = Integer)]
+    id: i32,
+}
+
+/// Returns the oldest superseded words created before the cutoff, ordered by their creation dates.
+///
+/// Their signatures may have been moved out by the retention policy, which are not restored yet.
+pub(crate) fn select(
+    conn: &mut PgConnection,
+    cutoff: NaiveDateTime,
+    limit: i64,
+) -> QueryResult<Vec<Word>> {
+    let ids: Vec<RecordId> = ::diesel::sql_query(format!(
+        "SELECT r.id FROM {TABLE_WORDS} r WHERE r.created_date < $1 AND {superseded}
+        ORDER BY r.created_date ASC, r.id ASC LIMIT $2",
+        superseded = SUPERSEDED,
+    ))
+    .bind::<Timestamp, _>(cutoff)
+    .bind::<BigInt, _>(limit)
+    .get_results(conn)?;
+
+    crate::schema::words::table
+        .filter(crate::schema::words::id.eq_any(ids.into_iter().map(|record| record.id)))
+        .order((
+            crate::schema::words::created_date.asc(),
+            crate::schema::words::id.asc(),
+        ))
+        .get_results(conn)
+}
+
+/// Indexes the segment stored as the hash along with its words, and deletes them from the hot table.
+///
+/// It fails if any of the words has been deleted since selected, e.g. as expired,
+/// so that the deleted words are not indexed again.
+pub(crate) fn commit(
+    conn: &mut PgConnection,
+    hash: &Hash,
+    segment: &WordsSegmentObject,
+    words: &[Word],
+    now: NaiveDateTime,
+) -> Result<()> {
+    let segment_id: i32 = ::diesel::insert_into(crate::schema::words_segments::table)
+        .values(&NewWordsSegment {
+            hash: hash.to_string(),
+            since_date: segment.since.into(),
+            until_date: segment.until.into(),
+            len: segment.len().try_into()?,
+            created_date: now,
+        })
+        .returning(crate::schema::words_segments::id)
+        .get_result(conn)?;
+
+    let index: Vec<_> = words
+        .iter()
+        .map(|word| NewWordTiered::new(segment_id, word.id, word))
+        .collect();
+    ::diesel::insert_into(crate::schema::words_tiered::table)
+        .values(&index)
+        .execute(conn)?;
+
+    let ids: Vec<_> = words.iter().map(|word| word.id).collect();
+    let deleted = ::diesel::delete(crate::schema::words::table)
+        .filter(crate::schema::words::id.eq_any(&ids))
+        .execute(conn)?;
+    if deleted != ids.len() {
+        bail!("the words have been deleted while tiered out")
+    }
+    ::diesel::delete(crate::schema::signatures_archive::table)
+        .filter(crate::schema::signatures_archive::table_name.eq(TABLE_WORDS))
+        .filter(crate::schema::signatures_archive::record_id.eq_any(&ids))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Returns the segments tiered out before `SchemaVersion::WORDS_TIERED`, whose words are not indexed yet.
+pub(crate) fn find_legacy(conn: &mut PgConnection) -> QueryResult<Vec<WordsSegment>> {
+    crate::schema::words_segments::table
+        .filter(crate::schema::words_segments::indexed.eq(false))
+        .order(crate::schema::words_segments::id.asc())
+        .get_results(conn)
+}
+
+/// Indexes the words of the segment tiered out before `SchemaVersion::WORDS_TIERED`,
+/// except the ones put again into the hot table since.
+///
+/// The words deleted meanwhile cannot be told apart, which are indexed as well.
+pub(crate) fn index_legacy(
+    conn: &mut PgConnection,
+    segment: &WordsSegment,
+    object: &WordsSegmentObject,
+) -> Result<()> {
+    for word in &object.records.words {
+        let record = into_record(word.clone(), 0);
+        let is_hot = crate::schema::words::table
+            .filter(crate::schema::words::nonce.eq(&record.nonce))
+            .select(crate::schema::words::id)
+            .get_result::<i32>(conn)
+            .optional()?
+            .is_some();
+        if is_hot {
+            continue;
+        }
+
+        ::diesel::insert_into(crate::schema::words_tiered::table)
+            .values(&NewWordTiered::new(segment.id, 0, &record))
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+    }
+
+    ::diesel::update(crate::schema::words_segments::table.find(segment.id))
+        .set(crate::schema::words_segments::indexed.eq(true))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Returns the index of the tiered word of the nonce, if it has been tiered out.
+pub(crate) fn find_by_nonce(
+    conn: &mut PgConnection,
+    nonce: &Uuid,
+) -> QueryResult<Option<WordTiered>> {
+    crate::schema::words_tiered::table
+        .filter(crate::schema::words_tiered::nonce.eq(nonce))
+        .get_result(conn)
+        .optional()
+}
+
+/// Returns the segments having the tiered words.
+pub(crate) fn find_segments(
+    conn: &mut PgConnection,
+    index: &[WordTiered],
+) -> QueryResult<Vec<WordsSegment>> {
+    let ids: BTreeSet<_> = index.iter().map(|word| word.segment_id).collect();
+
+    crate::schema::words_segments::table
+        .filter(crate::schema::words_segments::id.eq_any(ids))
+        .order(crate::schema::words_segments::id.asc())
+        .get_results(conn)
+}
+
+/// A word of the IDF logs, which is either in the hot table or tiered out.
+#[derive(QueryableByName)]
+pub(crate) struct LogEntry {
+    #[diesel(sql_type = SqlUuid)]
+    pub nonce: Uuid,
+    /// the id of the index of the tiered word, or `None` for the hot one
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub tiered_id: Option<i32>,
+}
+
+/// Returns the live words created in `since..until` of both tiers, ordered by their creation dates
+/// and then by their nonces, skipping the first `offset` ones.
+///
+/// The words are moved between the tiers in a transaction, so a statement sees each of them once.
+pub(crate) fn find_logs(
+    conn: &mut PgConnection,
+    since: NaiveDateTime,
+    until: NaiveDateTime,
+    now: NaiveDateTime,
+    offset: i64,
+    limit: i64,
+) -> QueryResult<Vec<LogEntry>> {
+    const LIVE: &str = "created_date >= $1 AND created_date < $2
+        AND (expiration_date IS NULL OR expiration_date >= $3)";
+
+    ::diesel::sql_query(format!(
+        "SELECT nonce, tiered_id FROM (
+            SELECT nonce, NULL::INTEGER AS tiered_id, created_date FROM {TABLE_WORDS} WHERE {LIVE}
+            UNION ALL
+            SELECT nonce, id, created_date FROM words_tiered WHERE {LIVE}
+        ) logs
+        ORDER BY created_date ASC, nonce ASC OFFSET $4 LIMIT $5"
+    ))
+    .bind::<Timestamp, _>(since)
+    .bind::<Timestamp, _>(until)
+    .bind::<Timestamp, _>(now)
+    .bind::<BigInt, _>(offset)
+    .bind::<BigInt, _>(limit)
+    .get_results(conn)
+}
+
+/// Moves the tiered words read back from their segments into the hot table, keeping their counts,
+/// which should be called in the same transaction of the rewrite not aware of the tiers, e.g. a migration.
+///
+/// It fails if any of them has been deleted since read, so that the rewrite can be retried.
+pub(crate) fn untier(
+    conn: &mut PgConnection,
+    index: &[WordTiered],
+    words: Vec<BackupWord>,
+) -> Result<()> {
+    /// the number of the words inserted at once, bounded by the parameters of a statement
+    const BATCH_SIZE: usize = 1024;
+
+    let deleted = ::diesel::delete(crate::schema::words_tiered::table)
+        .filter(crate::schema::words_tiered::id.eq_any(index.iter().map(|word| word.id)))
+        .execute(conn)?;
+    if deleted != index.len() {
+        bail!("the tiered words have been deleted while read back: retry")
+    }
+
+    let records: Vec<_> = words.into_iter().map(NewWord::from).collect();
+    for batch in records.chunks(BATCH_SIZE) {
+        ::diesel::insert_into(crate::schema::words::table)
+            .values(batch)
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+/// Overrides the columns identifying the tiered word by its index, e.g. as migrated since tiered out.
+pub(crate) fn overlay(word: BackupWord, index: &WordTiered) -> BackupWord {
+    BackupWord {
+        namespace: index.namespace.clone(),
+        kind: index.kind.clone(),
+        parent: index.parent.clone(),
+        lang: index.lang.clone(),
+        word: index.word.clone(),
+        path: index.path.clone(),
+        hash_version: index.hash_version,
+        ..word
+    }
+}
+
+/// Reads back the tiered word as a raw record of the given id.
+pub(crate) fn into_record(word: BackupWord, id: i32) -> Word {
+    Word {
+        id,
+        nonce: Uuid::from_u128(word.metadata.nonce),
+        guarantee: word.metadata.guarantee,
+        guarantor: word.metadata.guarantor,
+        guarantee_signature: word.metadata.guarantee_signature,
+        guarantor_signature: word.metadata.guarantor_signature,
+        created_date: word.metadata.created_date.into(),
+        expiration_date: word.metadata.expiration_date.map(Into::into),
+        namespace: word.namespace,
+        kind: word.kind,
+        parent: word.parent,
+        lang: word.lang,
+        word: word.word,
+        relpath: word.relpath,
+        path: word.path,
+        len: word.len,
+        metadata: word.data,
+        on_behalf_of: word.on_behalf_of,
+        hash_version: word.hash_version,
+    }
+}
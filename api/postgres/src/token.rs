@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use ipdis_common::{ApiTokenInfo, Feature, FeatureSet, IpdisError};
+use ipis::core::{
+    anyhow::{anyhow, Result},
+    chrono::NaiveDateTime,
+};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// the prefix of the tokens, which makes the leaked ones easy to find
+const PREFIX: &str = "ipdis_";
+
+const TOKEN_LEN: usize = 32;
+
+/// the window in which the requests of a token are counted
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// A token permitting a lightweight integration to read the records of the node,
+/// as if it were guaranteed by the node itself.
+///
+/// Only the hash of the token is stored, so the token is shown once when it is issued.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiToken {
+    pub id: i32,
+    pub name: String,
+    /// the read features permitted to the token
+    pub scopes: FeatureSet,
+    /// the maximum number of the requests per minute, on each node
+    pub rate_limit: u32,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+}
+
+impl ApiToken {
+    /// Ensures that the feature is in the scopes of the token.
+    pub fn ensure_scope(&self, feature: Feature) -> Result<()> {
+        if self.scopes.contains(&feature) {
+            Ok(())
+        } else {
+            Err(IpdisError::FeatureDisabled { feature }.into())
+        }
+    }
+}
+
+impl TryFrom<crate::models::api_tokens::ApiToken> for ApiToken {
+    type Error = ::ipis::core::anyhow::Error;
+
+    fn try_from(record: crate::models::api_tokens::ApiToken) -> Result<Self> {
+        Ok(Self {
+            id: record.id,
+            name: record.name,
+            scopes: record.scopes.parse()?,
+            rate_limit: record.rate_limit.try_into()?,
+            created_date: record.created_date,
+            expiration_date: record.expiration_date,
+        })
+    }
+}
+
+impl From<ApiToken> for ApiTokenInfo {
+    fn from(token: ApiToken) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            scopes: token.scopes.to_string(),
+            rate_limit: token.rate_limit,
+            created_time: token.created_date.timestamp_millis(),
+            expiration_time: token.expiration_date.map(|date| date.timestamp_millis()),
+        }
+    }
+}
+
+pub(crate) fn generate() -> String {
+    let buf: [u8; TOKEN_LEN] = ::rand::thread_rng().gen();
+    format!("{PREFIX}{}", ::hex::encode(buf))
+}
+
+pub(crate) fn hash(token: &str) -> String {
+    ::hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Rejects the scopes which modify the records, as the tokens are for the public query tier.
+pub(crate) fn ensure_read_only(scopes: &FeatureSet) -> Result<()> {
+    match scopes.iter().find(|feature| feature.is_write()) {
+        Some(feature) => Err(anyhow!(
+            "the API tokens cannot be granted a write feature: {feature}"
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Counts the requests of the tokens in fixed windows.
+///
+/// The counters are kept in memory, so the limits apply to each node separately.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    windows: Mutex<HashMap<i32, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn acquire(&self, token: &ApiToken) -> Result<()> {
+        let mut windows = self
+            .windows
+            .lock()
+            .map_err(|_| anyhow!("the rate limiter has been poisoned"))?;
+
+        let now = Instant::now();
+        let (started, count) = windows.entry(token.id).or_insert((now, 0));
+        if now.duration_since(*started) >= RATE_LIMIT_WINDOW {
+            *started = now;
+            *count = 0;
+        }
+
+        if *count >= token.rate_limit {
+            let retry_after = RATE_LIMIT_WINDOW.saturating_sub(now.duration_since(*started));
+            return Err(IpdisError::RateLimited {
+                retry_after_ms: retry_after.as_millis().try_into().unwrap_or(u64::MAX),
+            }
+            .into());
+        }
+        *count += 1;
+        Ok(())
+    }
+
+    pub(crate) fn forget(&self, id: i32) {
+        if let Ok(mut windows) = self.windows.lock() {
+            windows.remove(&id);
+        }
+    }
+}
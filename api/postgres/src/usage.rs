@@ -0,0 +1,116 @@
+use diesel::{
+    sql_types::{Integer, Timestamp},
+    PgConnection, RunQueryDsl,
+};
+use ipis::core::{
+    account::AccountRef,
+    anyhow::{bail, Result},
+    chrono::{DateTime, NaiveDate, NaiveDateTime, Utc},
+};
+
+/// A period of the usage, from `since` (inclusive) until `until` (exclusive).
+///
+/// The usage is counted by the days, so the bounds are truncated to the start of their days.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UsagePeriod {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+}
+
+impl UsagePeriod {
+    /// Returns the period of the calendar month in UTC, e.g. for the monthly billing.
+    pub fn month(year: i32, month: u32) -> Result<Self> {
+        let (next_year, next_month) = match month {
+            12 => (year + 1, 1),
+            month => (year, month + 1),
+        };
+        match (
+            NaiveDate::from_ymd_opt(year, month, 1),
+            NaiveDate::from_ymd_opt(next_year, next_month, 1),
+        ) {
+            (Some(since), Some(until)) => Ok(Self {
+                since: DateTime::from_utc(since.and_hms(0, 0, 0), Utc),
+                until: DateTime::from_utc(until.and_hms(0, 0, 0), Utc),
+            }),
+            _ => bail!("malformed month: {year}-{month:02}"),
+        }
+    }
+}
+
+/// The usage of an account over a period, e.g. for the billing or the chargeback.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsageRecord {
+    /// the owner of the rows, i.e. the account on whose behalf they have been written
+    pub account: AccountRef,
+    pub period: UsagePeriod,
+    /// the total size of the rows written in the period, as stored by postgres
+    pub bytes_written: u64,
+    pub rows_written: u64,
+    /// the number of the rows stored at the end of the period, which have not been deleted yet
+    pub rows_stored: u64,
+}
+
+/// Counts the written row to its owner, which should be called in the same transaction of the write.
+pub(crate) fn record(
+    conn: &mut PgConnection,
+    table: &'static str,
+    id: i32,
+    now: NaiveDateTime,
+) -> Result<()> {
+    ::diesel::sql_query(format!(
+        "INSERT INTO accounts_usage (account, day, bytes_written, rows_written)
+        SELECT COALESCE(on_behalf_of, guarantee), DATE_TRUNC('day', $2), pg_column_size({table}.*), 1
+        FROM {table} WHERE id = $1
+        ON CONFLICT (account, day) DO UPDATE SET
+            bytes_written = accounts_usage.bytes_written + EXCLUDED.bytes_written,
+            rows_written = accounts_usage.rows_written + EXCLUDED.rows_written",
+    ))
+    .bind::<Integer, _>(id)
+    .bind::<Timestamp, _>(now)
+    .execute(conn)?;
+    Ok(())
+}
+
+/// Aggregates the usage of every account in the period, ordered by the accounts.
+pub(crate) fn export(conn: &mut PgConnection, period: &UsagePeriod) -> Result<Vec<UsageRecord>> {
+    let records: Vec<crate::models::accounts_usage::AccountUsage> = ::diesel::sql_query(
+        "WITH written AS (
+            SELECT account, SUM(bytes_written)::INT8 AS bytes_written,
+                SUM(rows_written)::INT8 AS rows_written
+            FROM accounts_usage
+            WHERE day >= DATE_TRUNC('day', $1) AND day < DATE_TRUNC('day', $2)
+            GROUP BY account
+        ), stored AS (
+            SELECT account, COUNT(*)::INT8 AS rows_stored FROM (
+                SELECT COALESCE(on_behalf_of, guarantee) AS account
+                FROM words WHERE created_date < $2
+                UNION ALL
+                SELECT COALESCE(on_behalf_of, guarantee)
+                FROM dyn_paths WHERE created_date < $2
+            ) AS stored_rows
+            GROUP BY account
+        )
+        SELECT COALESCE(written.account, stored.account) AS account,
+            COALESCE(written.bytes_written, 0)::INT8 AS bytes_written,
+            COALESCE(written.rows_written, 0)::INT8 AS rows_written,
+            COALESCE(stored.rows_stored, 0)::INT8 AS rows_stored
+        FROM written FULL OUTER JOIN stored ON written.account = stored.account
+        ORDER BY 1",
+    )
+    .bind::<Timestamp, _>(period.since.naive_utc())
+    .bind::<Timestamp, _>(period.until.naive_utc())
+    .load(conn)?;
+
+    records
+        .into_iter()
+        .map(|record| {
+            Ok(UsageRecord {
+                account: record.account.parse()?,
+                period: *period,
+                bytes_written: record.bytes_written.try_into()?,
+                rows_written: record.rows_written.try_into()?,
+                rows_stored: record.rows_stored.try_into()?,
+            })
+        })
+        .collect()
+}
@@ -1,6 +1,11 @@
 pub extern crate ipdis_common as common;
 
+#[cfg(feature = "server")]
 pub mod server;
+#[cfg(feature = "otlp")]
+pub mod telemetry;
 
+#[cfg(feature = "client")]
+pub use ipdis_common::{failover::IpdisFailover, IpdisRemote};
 #[cfg(feature = "postgres")]
 pub use ipdis_api_postgres::*;
@@ -1,17 +1,31 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use ipdis_common::Ipdis;
+use ipdis_common::{
+    kv::{self, ValueStore},
+    membership, Feature, Ipdis, IpdisAdmin, IpdisError,
+};
 use ipiis_api::{
     client::IpiisClient,
     common::{handle_external_call, Ipiis, ServerResult},
     server::IpiisServer,
 };
-use ipis::{async_trait::async_trait, core::anyhow::Result, env::Infer};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        anyhow::{bail, Result},
+        value::hash::Hash,
+    },
+    env::Infer,
+};
 
-use crate::client::IpdisClientInner;
+use crate::{
+    backup::BackupStore, client::IpdisClientInner, config::TlsConfig, leader::LeaderElection,
+    outbox::OutboxPublisher, queue::RequestClass,
+};
 
 pub struct IpdisServer {
     client: Arc<IpdisClientInner<IpiisServer>>,
+    leader: Arc<LeaderElection>,
 }
 
 impl ::core::ops::Deref for IpdisServer {
@@ -28,80 +42,410 @@ impl<'a> Infer<'a> for IpdisServer {
     type GenesisResult = Self;
 
     async fn try_infer() -> Result<Self> {
+        if let Some(tls) = TlsConfig::try_infer()? {
+            tls.export();
+        }
+
         Ok(Self {
             client: IpdisClientInner::try_infer().await?.into(),
+            leader: LeaderElection::try_infer(LEADER_BACKGROUND_TASKS)?.into(),
         })
     }
 
     async fn genesis(
         args: <Self as Infer<'a>>::GenesisArgs,
     ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        if let Some(tls) = TlsConfig::try_infer()? {
+            tls.export();
+        }
+
         Ok(Self {
             client: IpdisClientInner::genesis(args).await?.into(),
+            leader: LeaderElection::try_infer(LEADER_BACKGROUND_TASKS)?.into(),
         })
     }
 }
 
-handle_external_call!(
-    server: IpdisServer => IpdisClientInner<IpiisServer>,
-    name: run,
-    request: ::ipdis_common::io => {
-        GuaranteePut => handle_guarantee_put,
-        DynPathGet => handle_dyn_path_get,
-        DynPathPut => handle_dyn_path_put,
-        WordGetMany => handle_word_get_many,
-        WordCountGetMany => handle_word_count_get_many,
-        WordPut => handle_word_put,
-    },
-);
+/// the name of the leadership, which is shared by all the nodes
+const LEADER_BACKGROUND_TASKS: &str = "ipdis-background-tasks";
 
 impl IpdisServer {
-    async fn handle_guarantee_put(
+    /// Serves the given database, rather than the one of `DATABASE_URL`.
+    ///
+    /// Note that the TLS of the given IPIIS server should be configured beforehand, see `TlsConfig`.
+    pub fn with_database_url(ipiis: IpiisServer, database_url: &str) -> Result<Self> {
+        Ok(Self {
+            client: IpdisClientInner::with_database_url(ipiis, database_url)?.into(),
+            leader: LeaderElection::establish(database_url, LEADER_BACKGROUND_TASKS)?.into(),
+        })
+    }
+
+    /// Replaces the store of the segments, to which the old words are tiered out,
+    /// which should be given before spawning any task.
+    pub fn with_segment_store<S>(self, store: S) -> Result<Self>
+    where
+        S: BackupStore + Send + Sync + 'static,
+    {
+        let client = match Arc::try_unwrap(self.client) {
+            Ok(client) => client.with_segment_store(store),
+            Err(_) => bail!("the store of the segments should be given before spawning any task"),
+        };
+
+        Ok(Self {
+            client: client.into(),
+            leader: self.leader,
+        })
+    }
+
+    /// Spawns the background tasks, such as deleting the expired records.
+    ///
+    /// Only the leader among the nodes sharing the database deletes the expired records
+    /// and sweeps the expiring guarantees.
+    pub fn spawn_background_tasks(&self) {
+        if let Some(interval) = self.config().gc_interval {
+            let client = self.client.clone();
+            let leader = self.leader.clone();
+            ::ipis::tokio::spawn(async move {
+                let mut timer = ::ipis::tokio::time::interval(interval);
+                loop {
+                    timer.tick().await;
+                    if !is_leader(&leader).await {
+                        continue;
+                    }
+                    if let Err(error) = client.delete_expired_all_unchecked().await {
+                        ::tracing::warn!("failed to delete the expired records: {error}");
+                    }
+                    if let Err(error) = client.apply_signature_retention_unchecked().await {
+                        ::tracing::warn!("failed to apply the signature retention: {error}");
+                    }
+                    match client.sweep_guarantee_expiry_unchecked().await {
+                        Ok(sweep) if sweep.notified + sweep.renewed > 0 => ::tracing::info!(
+                            "swept the expiring guarantees: {} notified, {} renewed",
+                            sweep.notified,
+                            sweep.renewed,
+                        ),
+                        Ok(_) => {}
+                        Err(error) => {
+                            ::tracing::warn!("failed to sweep the expiring guarantees: {error}")
+                        }
+                    }
+                }
+            });
+        }
+
+        // every node keeps its own cache, so they all listen to the invalidations
+        if self.config().cache_enabled {
+            let client = self.client.clone();
+            ::ipis::tokio::spawn(async move {
+                loop {
+                    if let Err(error) = client.listen_invalidations().await {
+                        ::tracing::warn!("failed to listen to the cache invalidations: {error}");
+                    }
+                    ::ipis::tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            });
+        }
+    }
+
+    /// Spawns the task publishing the events of the outbox periodically.
+    ///
+    /// Only the leader among the nodes sharing the database runs it.
+    pub fn spawn_outbox_dispatcher<P>(&self, publisher: P, interval: Duration)
+    where
+        P: OutboxPublisher + Send + Sync + 'static,
+    {
+        const BATCH_SIZE: u32 = 256;
+
+        let client = self.client.clone();
+        let leader = self.leader.clone();
+        ::ipis::tokio::spawn(async move {
+            let mut timer = ::ipis::tokio::time::interval(interval);
+            loop {
+                timer.tick().await;
+                if !is_leader(&leader).await {
+                    continue;
+                }
+                // drain the outbox, as many events may have been written during the interval
+                loop {
+                    match client
+                        .dispatch_outbox_unchecked(&publisher, BATCH_SIZE)
+                        .await
+                    {
+                        Ok(delivered) if delivered == BATCH_SIZE => continue,
+                        Ok(_) => break,
+                        Err(error) => {
+                            ::tracing::warn!("failed to dispatch the outbox: {error}");
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Spawns the task announcing the node to the cluster periodically,
+    /// so that the clients can discover it with `Ipdis::get_members_unchecked`.
+    ///
+    /// Every node runs it, as the membership is per the node.
+    pub fn spawn_membership(&self, namespace: Hash, interval: Duration) {
+        let client = self.client.clone();
+        ::ipis::tokio::spawn(async move {
+            let mut timer = ::ipis::tokio::time::interval(interval);
+            loop {
+                timer.tick().await;
+
+                let server: &IpiisServer = client.as_ref();
+                let account = server.account_me().account_ref();
+                if let Err(error) = membership::announce(&*client, server, account, namespace).await
+                {
+                    ::tracing::warn!("failed to announce the node to the cluster: {error}");
+                }
+            }
+        });
+    }
+
+    /// Spawns the task writing the incremental backups periodically.
+    ///
+    /// Only the leader among the nodes sharing the database runs it.
+    pub fn spawn_backup<S>(&self, store: S, interval: Duration)
+    where
+        S: BackupStore + Send + Sync + 'static,
+    {
+        let client = self.client.clone();
+        let leader = self.leader.clone();
+        ::ipis::tokio::spawn(async move {
+            let mut timer = ::ipis::tokio::time::interval(interval);
+            loop {
+                timer.tick().await;
+                if !is_leader(&leader).await {
+                    continue;
+                }
+                match client.backup_unchecked(&store, false).await {
+                    Ok(hash) => ::tracing::info!("written a backup: {hash}"),
+                    Err(error) => ::tracing::warn!("failed to write a backup: {error}"),
+                }
+            }
+        });
+    }
+
+    /// Spawns the task tiering the old words out to the segments periodically,
+    /// if `IpdisConfig::idf_logs_tiering_age` is set.
+    ///
+    /// The store of the segments should be given by `with_segment_store`.
+    /// Only the leader among the nodes sharing the database runs it.
+    pub fn spawn_idf_logs_tiering(&self, interval: Duration) {
+        let client = self.client.clone();
+        let leader = self.leader.clone();
+        ::ipis::tokio::spawn(async move {
+            let mut timer = ::ipis::tokio::time::interval(interval);
+            loop {
+                timer.tick().await;
+                if !is_leader(&leader).await {
+                    continue;
+                }
+                match client.tier_idf_logs_unchecked().await {
+                    Ok(tiered) if tiered > 0 => ::tracing::info!("tiered the words out: {tiered}"),
+                    Ok(_) => {}
+                    Err(error) => ::tracing::warn!("failed to tier the words out: {error}"),
+                }
+            }
+        });
+    }
+
+    /// Spawns the task publishing the signed roots of the oplog periodically,
+    /// which are also stored in the given store (e.g. ipsis) if any.
+    ///
+    /// Only the leader among the nodes sharing the database runs it.
+    pub fn spawn_oplog_roots<S>(&self, store: Option<S>, interval: Duration)
+    where
+        S: ValueStore + Send + Sync + 'static,
+    {
+        let client = self.client.clone();
+        let leader = self.leader.clone();
+        ::ipis::tokio::spawn(async move {
+            let mut timer = ::ipis::tokio::time::interval(interval);
+            loop {
+                timer.tick().await;
+                if !is_leader(&leader).await {
+                    continue;
+                }
+                let root = match client.publish_oplog_root_unchecked().await {
+                    Ok(Some(root)) => root,
+                    Ok(None) => continue,
+                    Err(error) => {
+                        ::tracing::warn!("failed to publish the oplog root: {error}");
+                        continue;
+                    }
+                };
+                ::tracing::info!(
+                    "published the oplog root: {} ({} entries)",
+                    root.data.data.root,
+                    root.data.data.tree_size,
+                );
+
+                if let Some(store) = &store {
+                    match kv::put_value(store, &root).await {
+                        Ok(path) => ::tracing::info!("stored the oplog root: {}", path.value),
+                        Err(error) => ::tracing::warn!("failed to store the oplog root: {error}"),
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Classifies the paginated requests, where the single lookups are prioritized.
+fn classify_page(start_index: u32, end_index: u32) -> RequestClass {
+    if end_index.saturating_sub(start_index) <= 1 {
+        RequestClass::Priority
+    } else {
+        RequestClass::Bulk
+    }
+}
+
+async fn is_leader(leader: &Arc<LeaderElection>) -> bool {
+    match leader.try_acquire().await {
+        Ok(is_leader) => is_leader,
+        Err(error) => {
+            ::tracing::warn!("failed to elect the leader: {error}");
+            false
+        }
+    }
+}
+
+/// Registers the handlers, attaching the codes of their typed errors
+/// so that the remote clients can recover them, see `IpdisError::encode`.
+macro_rules! handle_external_call_encoded {
+    ( $( $request:ident => $handler:ident , )* ) => {
+        handle_external_call!(
+            server: IpdisServer => IpdisClientInner<IpiisServer>,
+            name: run,
+            request: ::ipdis_common::io => {
+                $( $request => $handler , )*
+            },
+        );
+
+        impl IpdisServer {
+            $(
+                async fn $handler(
+                    client: &IpdisClientInner<IpiisServer>,
+                    req: ::ipdis_common::io::request::$request<'static>,
+                ) -> Result<::ipdis_common::io::response::$request<'static>> {
+                    Handlers::$handler(client, req)
+                        .await
+                        .map_err(IpdisError::encode)
+                }
+            )*
+        }
+    };
+}
+
+handle_external_call_encoded! {
+    ReadOnlySet => handle_read_only_set,
+    DiagnosticsGet => handle_diagnostics_get,
+    AccountStatsGet => handle_account_stats_get,
+    KindRegister => handle_kind_register,
+    KindGet => handle_kind_get,
+    KindGetMany => handle_kind_get_many,
+    WriterLeaseAcquire => handle_writer_lease_acquire,
+    GuaranteePut => handle_guarantee_put,
+    AccountSuccessorLink => handle_account_successor_link,
+    AccountChainGet => handle_account_chain_get,
+    DynPathGet => handle_dyn_path_get,
+    DynPathGetByTarget => handle_dyn_path_get_by_target,
+    DynPathGetMany => handle_dyn_path_get_many,
+    MembersGet => handle_members_get,
+    PathReferenceCountGet => handle_path_reference_count_get,
+    RecordGetByNonce => handle_record_get_by_nonce,
+    OplogGet => handle_oplog_get,
+    InclusionProofGet => handle_inclusion_proof_get,
+    DynPathPut => handle_dyn_path_put,
+    WordGetMany => handle_word_get_many,
+    WordCountGetMany => handle_word_count_get_many,
+    WordCountGetBatch => handle_word_count_get_batch,
+    WordPut => handle_word_put,
+    WordPutMany => handle_word_put_many,
+    WordCountGetAllLangs => handle_word_count_get_all_langs,
+    WordCountDeltaGet => handle_word_count_delta_get,
+    WordFrequencyHistogramGet => handle_word_frequency_histogram_get,
+    IdfVectorGet => handle_idf_vector_get,
+    SimilarDocumentsGet => handle_similar_documents_get,
+    WordQueryGet => handle_word_query_get,
+    QueryExplain => handle_query_explain,
+    IdfLogsGet => handle_idf_logs_get,
+    DynPathGetWithToken => handle_dyn_path_get_with_token,
+    WordGetManyWithToken => handle_word_get_many_with_token,
+    ApiTokenIssue => handle_api_token_issue,
+    ApiTokenGetMany => handle_api_token_get_many,
+    ApiTokenRevoke => handle_api_token_revoke,
+    StopWordAdd => handle_stop_word_add,
+    StopWordGetMany => handle_stop_word_get_many,
+}
+
+/// The handlers of the requests, whose errors are encoded by `IpdisServer`.
+struct Handlers;
+
+impl Handlers {
+    #[::tracing::instrument(skip_all)]
+    async fn handle_read_only_set(
         client: &IpdisClientInner<IpiisServer>,
-        req: ::ipdis_common::io::request::GuaranteePut<'static>,
-    ) -> Result<::ipdis_common::io::response::GuaranteePut<'static>> {
-        // unpack sign
+        req: ::ipdis_common::io::request::ReadOnlySet<'static>,
+    ) -> Result<::ipdis_common::io::response::ReadOnlySet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
         let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
 
-        // ensure registered
+        // ensure admin
         let guarantee = &sign_as_guarantee.guarantee.account;
         client
-            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
             .await?;
 
+        // unpack data
+        let enabled = sign_as_guarantee.data.data.enabled;
+
         // handle data
-        client.add_guarantee_unchecked(&sign_as_guarantee).await?;
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        client
+            .set_read_only_unchecked(Some(guarantee), enabled)
+            .await?;
 
         // sign data
         let server: &IpiisServer = client.as_ref();
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
-        Ok(::ipdis_common::io::response::GuaranteePut {
+        Ok(::ipdis_common::io::response::ReadOnlySet {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
         })
     }
 
-    async fn handle_dyn_path_get(
+    #[::tracing::instrument(skip_all)]
+    async fn handle_diagnostics_get(
         client: &IpdisClientInner<IpiisServer>,
-        req: ::ipdis_common::io::request::DynPathGet<'static>,
-    ) -> Result<::ipdis_common::io::response::DynPathGet<'static>> {
-        // unpack sign
+        req: ::ipdis_common::io::request::DiagnosticsGet<'static>,
+    ) -> Result<::ipdis_common::io::response::DiagnosticsGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
         let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
 
-        // ensure registered
+        // ensure admin
         let guarantee = &sign_as_guarantee.guarantee.account;
         client
-            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
             .await?;
 
         // unpack data
-        let path = sign_as_guarantee.data.data;
+        let query = sign_as_guarantee.data.data;
 
         // handle data
-        let path = client
-            .get_dyn_path_unchecked(Some(guarantee), &path)
+        let diagnostics = client
+            .get_server_diagnostics_unchecked(Some(guarantee), &query)
             .await?;
 
         // sign data
@@ -109,19 +453,107 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
-        Ok(::ipdis_common::io::response::DynPathGet {
+        Ok(::ipdis_common::io::response::DiagnosticsGet {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
-            path: ::ipis::stream::DynStream::Owned(path),
+            diagnostics: ::ipis::stream::DynStream::Owned(diagnostics),
         })
     }
 
-    async fn handle_dyn_path_put(
+    #[::tracing::instrument(skip_all)]
+    async fn handle_account_stats_get(
         client: &IpdisClientInner<IpiisServer>,
-        req: ::ipdis_common::io::request::DynPathPut<'static>,
-    ) -> Result<::ipdis_common::io::response::DynPathPut<'static>> {
-        // unpack sign
+        req: ::ipdis_common::io::request::AccountStatsGet<'static>,
+    ) -> Result<::ipdis_common::io::response::AccountStatsGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // ensure registered, or admin for the other accounts
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        if guarantee == &query.account {
+            client
+                .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+                .await?;
+        } else {
+            client
+                .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+                .await?;
+        }
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let stats = client
+            .get_account_stats_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::AccountStatsGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            stats: ::ipis::stream::DynStream::Owned(stats),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_kind_register(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::KindRegister<'static>,
+    ) -> Result<::ipdis_common::io::response::KindRegister<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let name = req.name.into_owned().await?;
+        let description = req.description.into_owned().await?;
+        sign_as_guarantee.data.data.validate(&name, &description)?;
+        let query = sign_as_guarantee.data.data.query;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        client
+            .register_kind_unchecked(Some(guarantee), &query, &name, &description)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::KindRegister {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_kind_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::KindGet<'static>,
+    ) -> Result<::ipdis_common::io::response::KindGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
         let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
 
         // ensure registered
         let guarantee = &sign_as_guarantee.guarantee.account;
@@ -129,26 +561,35 @@ impl IpdisServer {
             .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
             .await?;
 
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
         // handle data
-        client.put_dyn_path_unchecked(&sign_as_guarantee).await?;
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let info = client.get_kind_unchecked(Some(guarantee), &query).await?;
 
         // sign data
         let server: &IpiisServer = client.as_ref();
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
-        Ok(::ipdis_common::io::response::DynPathPut {
+        Ok(::ipdis_common::io::response::KindGet {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
+            info: ::ipis::stream::DynStream::Owned(info),
         })
     }
 
-    async fn handle_word_get_many(
+    #[::tracing::instrument(skip_all)]
+    async fn handle_kind_get_many(
         client: &IpdisClientInner<IpiisServer>,
-        req: ::ipdis_common::io::request::WordGetMany<'static>,
-    ) -> Result<::ipdis_common::io::response::WordGetMany<'static>> {
-        // unpack sign
+        req: ::ipdis_common::io::request::KindGetMany<'static>,
+    ) -> Result<::ipdis_common::io::response::KindGetMany<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
         let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
 
         // ensure registered
         let guarantee = &sign_as_guarantee.guarantee.account;
@@ -160,8 +601,9 @@ impl IpdisServer {
         let query = sign_as_guarantee.data.data;
 
         // handle data
-        let words = client
-            .get_word_many_unchecked(Some(guarantee), &query)
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let kinds = client
+            .get_kind_page_unchecked(Some(guarantee), &query)
             .await?;
 
         // sign data
@@ -169,19 +611,119 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
-        Ok(::ipdis_common::io::response::WordGetMany {
+        Ok(::ipdis_common::io::response::KindGetMany {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
-            words: ::ipis::stream::DynStream::Owned(words),
+            kinds: ::ipis::stream::DynStream::Owned(kinds),
         })
     }
 
-    async fn handle_word_count_get_many(
+    #[::tracing::instrument(skip_all)]
+    async fn handle_writer_lease_acquire(
         client: &IpdisClientInner<IpiisServer>,
-        req: ::ipdis_common::io::request::WordCountGetMany<'static>,
-    ) -> Result<::ipdis_common::io::response::WordCountGetMany<'static>> {
-        // unpack sign
+        req: ::ipdis_common::io::request::WriterLeaseAcquire<'static>,
+    ) -> Result<::ipdis_common::io::response::WriterLeaseAcquire<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin, as the lease fences all the other writers of the kind
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let lease = client
+            .acquire_writer_lease_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WriterLeaseAcquire {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            lease: ::ipis::stream::DynStream::Owned(lease),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_guarantee_put(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::GuaranteePut<'static>,
+    ) -> Result<::ipdis_common::io::response::GuaranteePut<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        client.add_guarantee_unchecked(&sign_as_guarantee).await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::GuaranteePut {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_account_successor_link(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::AccountSuccessorLink<'static>,
+    ) -> Result<::ipdis_common::io::response::AccountSuccessorLink<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        client.link_account_successor(&sign_as_guarantee).await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::AccountSuccessorLink {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_account_chain_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::AccountChainGet<'static>,
+    ) -> Result<::ipdis_common::io::response::AccountChainGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
         let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
 
         // ensure registered
         let guarantee = &sign_as_guarantee.guarantee.account;
@@ -193,8 +735,9 @@ impl IpdisServer {
         let query = sign_as_guarantee.data.data;
 
         // handle data
-        let counts = client
-            .get_word_count_many_unchecked(Some(guarantee), &query)
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let chain = client
+            .get_account_chain_unchecked(Some(guarantee), &query)
             .await?;
 
         // sign data
@@ -202,19 +745,23 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
-        Ok(::ipdis_common::io::response::WordCountGetMany {
+        Ok(::ipdis_common::io::response::AccountChainGet {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
-            counts: ::ipis::stream::DynStream::Owned(counts),
+            chain: ::ipis::stream::DynStream::Owned(chain),
         })
     }
 
-    async fn handle_word_put(
+    #[::tracing::instrument(skip_all)]
+    async fn handle_dyn_path_get(
         client: &IpdisClientInner<IpiisServer>,
-        req: ::ipdis_common::io::request::WordPut<'static>,
-    ) -> Result<::ipdis_common::io::response::WordPut<'static>> {
-        // unpack sign
+        req: ::ipdis_common::io::request::DynPathGet<'static>,
+    ) -> Result<::ipdis_common::io::response::DynPathGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
         let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
 
         // ensure registered
         let guarantee = &sign_as_guarantee.guarantee.account;
@@ -223,11 +770,50 @@ impl IpdisServer {
             .await?;
 
         // unpack data
-        let parent = req.parent.into_owned().await?;
+        let path = sign_as_guarantee.data.data;
 
         // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let path = client
+            .get_dyn_path_record_unchecked(Some(guarantee), &path)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::DynPathGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            path: ::ipis::stream::DynStream::Owned(path),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_dyn_path_get_by_target(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::DynPathGetByTarget<'static>,
+    ) -> Result<::ipdis_common::io::response::DynPathGetByTarget<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
         client
-            .put_word_unchecked(&parent, &sign_as_guarantee)
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let paths = client
+            .get_dyn_path_by_target_unchecked(Some(guarantee), &query)
             .await?;
 
         // sign data
@@ -235,9 +821,1114 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
-        Ok(::ipdis_common::io::response::WordPut {
+        Ok(::ipdis_common::io::response::DynPathGetByTarget {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            paths: ::ipis::stream::DynStream::Owned(paths),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_dyn_path_get_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::DynPathGetMany<'static>,
+    ) -> Result<::ipdis_common::io::response::DynPathGetMany<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // ensure the payload is bounded, before reading it
+        client
+            .config()
+            .ensure_batch_size(sign_as_guarantee.data.data.len as usize)?;
+
+        // unpack data
+        let paths = req.paths.into_owned().await?;
+        sign_as_guarantee.data.data.validate(&paths)?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let paths = client
+            .get_dyn_path_many_unchecked(Some(guarantee), &paths)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::DynPathGetMany {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            paths: ::ipis::stream::DynStream::Owned(paths),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_members_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::MembersGet<'static>,
+    ) -> Result<::ipdis_common::io::response::MembersGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let members = client
+            .get_members_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::MembersGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            members: ::ipis::stream::DynStream::Owned(members),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_path_reference_count_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::PathReferenceCountGet<'static>,
+    ) -> Result<::ipdis_common::io::response::PathReferenceCountGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let count = client
+            .get_path_reference_count_unchecked(Some(guarantee), &query.path)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::PathReferenceCountGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            count: ::ipis::stream::DynStream::Owned(count),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_record_get_by_nonce(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::RecordGetByNonce<'static>,
+    ) -> Result<::ipdis_common::io::response::RecordGetByNonce<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let nonce = sign_as_guarantee.data.data.nonce;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let record = client
+            .get_record_by_nonce_unchecked(Some(guarantee), &nonce)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::RecordGetByNonce {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            record: ::ipis::stream::DynStream::Owned(record),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_oplog_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::OplogGet<'static>,
+    ) -> Result<::ipdis_common::io::response::OplogGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let oplog = client.get_oplog_unchecked(Some(guarantee), &query).await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::OplogGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            oplog: ::ipis::stream::DynStream::Owned(oplog),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_inclusion_proof_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::InclusionProofGet<'static>,
+    ) -> Result<::ipdis_common::io::response::InclusionProofGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let nonce = sign_as_guarantee.data.data.nonce;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let proof = client
+            .get_inclusion_proof_unchecked(Some(guarantee), &nonce)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::InclusionProofGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            proof: ::ipis::stream::DynStream::Owned(proof),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_dyn_path_put(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::DynPathPut<'static>,
+    ) -> Result<::ipdis_common::io::response::DynPathPut<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let metadata = req.metadata.into_owned().await?;
+        let on_behalf_of = req.on_behalf_of.into_owned().await?;
+        let fencing_token = req.fencing_token.into_owned().await?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let receipt = client
+            .put_dyn_path_fenced_unchecked(
+                &sign_as_guarantee,
+                metadata.as_deref(),
+                on_behalf_of.as_ref(),
+                fencing_token,
+            )
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::DynPathPut {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            receipt: ::ipis::stream::DynStream::Owned(receipt),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_get_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordGetMany<'static>,
+    ) -> Result<::ipdis_common::io::response::WordGetMany<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let words = client
+            .get_word_record_page_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordGetMany {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            words: ::ipis::stream::DynStream::Owned(words),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_count_get_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordCountGetMany<'static>,
+    ) -> Result<::ipdis_common::io::response::WordCountGetMany<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+        let max_staleness = req
+            .max_staleness_ms
+            .into_owned()
+            .await?
+            .map(Duration::from_millis);
+
+        // handle data
+        let _permit = client
+            .enter_queue(classify_page(query.start_index, query.end_index))
+            .await?;
+        let counts = client
+            .get_word_count_page_fresh_unchecked(Some(guarantee), &query, max_staleness)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordCountGetMany {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            counts: ::ipis::stream::DynStream::Owned(counts),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_count_get_batch(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordCountGetBatch<'static>,
+    ) -> Result<::ipdis_common::io::response::WordCountGetBatch<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // ensure the payload is bounded, before reading it
+        client
+            .config()
+            .ensure_batch_size(sign_as_guarantee.data.data.len as usize)?;
+
+        // unpack data
+        let queries = req.queries.into_owned().await?;
+        sign_as_guarantee.data.data.validate(&queries)?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let pages = client
+            .get_word_count_page_batch_unchecked(Some(guarantee), &queries)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordCountGetBatch {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            pages: ::ipis::stream::DynStream::Owned(pages),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_count_get_all_langs(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordCountGetAllLangs<'static>,
+    ) -> Result<::ipdis_common::io::response::WordCountGetAllLangs<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let count = client
+            .get_word_count_all_langs_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordCountGetAllLangs {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            count: ::ipis::stream::DynStream::Owned(count),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_count_delta_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordCountDeltaGet<'static>,
+    ) -> Result<::ipdis_common::io::response::WordCountDeltaGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let delta = client
+            .get_word_count_delta_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordCountDeltaGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            delta: ::ipis::stream::DynStream::Owned(delta),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_frequency_histogram_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordFrequencyHistogramGet<'static>,
+    ) -> Result<::ipdis_common::io::response::WordFrequencyHistogramGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let buckets = client
+            .get_word_frequency_histogram_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordFrequencyHistogramGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            buckets: ::ipis::stream::DynStream::Owned(buckets),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_idf_vector_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::IdfVectorGet<'static>,
+    ) -> Result<::ipdis_common::io::response::IdfVectorGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // ensure the payload is bounded, before reading it
+        client
+            .config()
+            .ensure_words_len(sign_as_guarantee.data.data.len as usize)?;
+
+        // unpack data
+        let words = req.words.into_owned().await?;
+        sign_as_guarantee.data.data.validate(&words)?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let vector = client
+            .get_idf_vector_unchecked(Some(guarantee), &words)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::IdfVectorGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            vector: ::ipis::stream::DynStream::Owned(vector),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_similar_documents_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::SimilarDocumentsGet<'static>,
+    ) -> Result<::ipdis_common::io::response::SimilarDocumentsGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // ensure the payload is bounded, before reading it
+        client
+            .config()
+            .ensure_words_len(sign_as_guarantee.data.data.len as usize)?;
+
+        // unpack data
+        let words = req.words.into_owned().await?;
+        let query = sign_as_guarantee.data.data;
+        query.validate(&words)?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let documents = client
+            .get_similar_documents_unchecked(Some(guarantee), &words, query.top_k, query.owned)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::SimilarDocumentsGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            documents: ::ipis::stream::DynStream::Owned(documents),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_query_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordQueryGet<'static>,
+    ) -> Result<::ipdis_common::io::response::WordQueryGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // unpack data
+        let query = req.query.into_owned().await?;
+        sign_as_guarantee.data.data.validate(&query)?;
+
+        // ensure registered, or admin for the words of the other accounts
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        match &query.owned_by {
+            Some(account) if account != guarantee => {
+                client
+                    .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+                    .await?
+            }
+            _ => {
+                client
+                    .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+                    .await?
+            }
+        }
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let rows = client
+            .query_words_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordQueryGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            rows: ::ipis::stream::DynStream::Owned(rows),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_query_explain(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::QueryExplain<'static>,
+    ) -> Result<::ipdis_common::io::response::QueryExplain<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = req.query.into_owned().await?;
+        sign_as_guarantee.data.data.validate(&query)?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let plan = client
+            .explain_query_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::QueryExplain {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            plan: ::ipis::stream::DynStream::Owned(plan),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_idf_logs_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::IdfLogsGet<'static>,
+    ) -> Result<::ipdis_common::io::response::IdfLogsGet<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let words = client
+            .get_idf_log_page_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::IdfLogsGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            words: ::ipis::stream::DynStream::Owned(words),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_dyn_path_get_with_token(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::DynPathGetWithToken<'static>,
+    ) -> Result<::ipdis_common::io::response::DynPathGetWithToken<'static>> {
+        // ensure the token before anything else, in place of the guarantee
+        let token = req.token.into_owned().await?;
+        client
+            .authenticate_api_token(&token, Feature::DynPathGet)
+            .await?;
+
+        // unpack sign, whose account is neither allowlisted nor registered,
+        // e.g. an ephemeral one generated by the token user
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // unpack data
+        let path = sign_as_guarantee.data.data;
+
+        // handle data, as guaranteed by the server itself
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let path = client.get_dyn_path_record_unchecked(None, &path).await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::DynPathGetWithToken {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            path: ::ipis::stream::DynStream::Owned(path),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_get_many_with_token(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordGetManyWithToken<'static>,
+    ) -> Result<::ipdis_common::io::response::WordGetManyWithToken<'static>> {
+        // ensure the token before anything else, in place of the guarantee
+        let token = req.token.into_owned().await?;
+        client
+            .authenticate_api_token(&token, Feature::WordGet)
+            .await?;
+
+        // unpack sign, whose account is neither allowlisted nor registered,
+        // e.g. an ephemeral one generated by the token user
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data, as guaranteed by the server itself
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let words = client.get_word_record_page_unchecked(None, &query).await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordGetManyWithToken {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            words: ::ipis::stream::DynStream::Owned(words),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_api_token_issue(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::ApiTokenIssue<'static>,
+    ) -> Result<::ipdis_common::io::response::ApiTokenIssue<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let name = req.name.into_owned().await?;
+        let scopes = req.scopes.into_owned().await?.parse()?;
+        let query = sign_as_guarantee.data.data;
+        query.validate(&name, &scopes)?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let token = client
+            .issue_api_token_unchecked(
+                Some(guarantee),
+                &name,
+                &scopes,
+                query.rate_limit,
+                query.expiration_time,
+            )
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::ApiTokenIssue {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            token: ::ipis::stream::DynStream::Owned(token),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_api_token_get_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::ApiTokenGetMany<'static>,
+    ) -> Result<::ipdis_common::io::response::ApiTokenGetMany<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let tokens = client
+            .get_api_token_page_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::ApiTokenGetMany {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            tokens: ::ipis::stream::DynStream::Owned(tokens),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_api_token_revoke(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::ApiTokenRevoke<'static>,
+    ) -> Result<::ipdis_common::io::response::ApiTokenRevoke<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let id = sign_as_guarantee.data.data.id;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        client
+            .revoke_api_token_unchecked(Some(guarantee), id)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::ApiTokenRevoke {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_stop_word_add(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::StopWordAdd<'static>,
+    ) -> Result<::ipdis_common::io::response::StopWordAdd<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        client
+            .add_stop_word_unchecked(Some(guarantee), &query.kind, &query.word)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::StopWordAdd {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_stop_word_get_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::StopWordGetMany<'static>,
+    ) -> Result<::ipdis_common::io::response::StopWordGetMany<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure admin
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_admin(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data.data;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let words = client
+            .list_stop_words_unchecked(Some(guarantee), &query.kind)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::StopWordGetMany {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            words: ::ipis::stream::DynStream::Owned(words),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_put(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordPut<'static>,
+    ) -> Result<::ipdis_common::io::response::WordPut<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // unpack data
+        let parent = req.parent.into_owned().await?;
+        let metadata = req.metadata.into_owned().await?;
+        let on_behalf_of = req.on_behalf_of.into_owned().await?;
+        let fencing_token = req.fencing_token.into_owned().await?;
+        let normalization = req.normalization.into_owned().await?;
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Priority).await?;
+        let receipt = client
+            .put_word_normalized_unchecked(
+                &parent,
+                &sign_as_guarantee,
+                metadata.as_deref(),
+                on_behalf_of.as_ref(),
+                fencing_token,
+                normalization,
+            )
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordPut {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            receipt: ::ipis::stream::DynStream::Owned(receipt),
+        })
+    }
+
+    #[::tracing::instrument(skip_all)]
+    async fn handle_word_put_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordPutMany<'static>,
+    ) -> Result<::ipdis_common::io::response::WordPutMany<'static>> {
+        // unpack sign, rejecting the accounts not allowed before anything else
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+        client
+            .config()
+            .ensure_allowed(&sign_as_guarantee.guarantee.account)?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.guarantee.account;
+        client
+            .ensure_registered(guarantee, &sign_as_guarantee.guarantor)
+            .await?;
+
+        // ensure the payload is bounded, before reading it
+        client
+            .config()
+            .ensure_words_len(sign_as_guarantee.data.data.len as usize)?;
+
+        // unpack data
+        let parent = req.parent.into_owned().await?;
+        let words = req.words.into_owned().await?;
+        let fencing_token = req.fencing_token.into_owned().await?;
+        let normalization = req.normalization.into_owned().await?;
+        sign_as_guarantee
+            .data
+            .data
+            .validate(&words, fencing_token, normalization)?;
+
+        // the words should be signed by the caller, one by one
+        for word in &words {
+            if &word.guarantee.account != guarantee {
+                bail!("malformed batch: the words should be signed by the caller")
+            }
+            word.verify(None)?;
+        }
+
+        // handle data
+        let _permit = client.enter_queue(RequestClass::Bulk).await?;
+        let receipts = client
+            .put_word_many_unchecked(&parent, &words, fencing_token, normalization)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        Ok(::ipdis_common::io::response::WordPutMany {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
+            receipts: ::ipis::stream::DynStream::Owned(receipts),
         })
     }
 }
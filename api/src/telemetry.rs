@@ -0,0 +1,85 @@
+//! Exports the traces and the metrics through OTLP, e.g. to Jaeger, Tempo or Grafana.
+//!
+//! The exporters are configured with the standard `OTEL_*` environment variables,
+//! e.g. `OTEL_EXPORTER_OTLP_ENDPOINT`, `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT` and `OTEL_SERVICE_NAME`.
+
+use ipis::core::anyhow::Result;
+use opentelemetry::{
+    global,
+    runtime::Tokio,
+    sdk::{
+        export::metrics::ExportKindSelector,
+        metrics::{controllers::PushController, selectors},
+    },
+};
+use opentelemetry_otlp::WithExportConfig;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+/// The installed exporters, which flush the remaining data when dropped.
+pub struct Telemetry {
+    /// the controller of the metrics, which is present only if the metrics are exported
+    metrics: Option<PushController>,
+    /// whether the traces are exported
+    traces: bool,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        // the metrics are flushed when the controller is dropped
+        self.metrics.take();
+        if self.traces {
+            global::shutdown_tracer_provider();
+        }
+    }
+}
+
+/// Installs the global subscriber of the logs, filtered by `RUST_LOG` (`info` by default),
+/// which also exports the traces and the metrics if their OTLP endpoints are given.
+///
+/// The exporters are disabled altogether with `OTEL_SDK_DISABLED=true`.
+/// It should be called before the clients are created, so that their metrics are exported.
+pub fn init() -> Result<Telemetry> {
+    let is_disabled = ::std::env::var("OTEL_SDK_DISABLED")
+        .map(|value| value.trim().eq_ignore_ascii_case("true"))
+        .unwrap_or_default();
+    let is_enabled =
+        |key: &str| !is_disabled && (has_env("OTEL_EXPORTER_OTLP_ENDPOINT") || has_env(key));
+
+    let tracer = if is_enabled("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT") {
+        Some(
+            ::opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(::opentelemetry_otlp::new_exporter().tonic().with_env())
+                .install_batch(Tokio)?,
+        )
+    } else {
+        None
+    };
+
+    let metrics = if is_enabled("OTEL_EXPORTER_OTLP_METRICS_ENDPOINT") {
+        Some(
+            ::opentelemetry_otlp::new_pipeline()
+                .metrics(
+                    selectors::simple::inexpensive(),
+                    ExportKindSelector::Cumulative,
+                    Tokio,
+                )
+                .with_exporter(::opentelemetry_otlp::new_exporter().tonic().with_env())
+                .build()?,
+        )
+    } else {
+        None
+    };
+
+    let traces = tracer.is_some();
+    ::tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(::tracing_subscriber::fmt::layer())
+        .with(tracer.map(|tracer| ::tracing_opentelemetry::layer().with_tracer(tracer)))
+        .try_init()?;
+    Ok(Telemetry { metrics, traces })
+}
+
+fn has_env(key: &str) -> bool {
+    ::std::env::var_os(key).map_or(false, |value| !value.is_empty())
+}
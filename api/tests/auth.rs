@@ -0,0 +1,131 @@
+use ipdis_api::{auth::StaticAuthProvider, client::IpdisClient};
+use ipdis_common::{
+    Feature, FeatureSet, GetApiTokens, Ipdis, IpdisAdmin, IpdisError, IssueApiToken,
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{env::Infer, tokio};
+
+#[tokio::test]
+async fn test_static_auth_provider() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let guarantor = {
+        let ipiis: &IpiisClient = client.as_ref();
+        ipiis.account_me().account_ref()
+    };
+
+    // create an account which is not registered
+    let guarantee = IpiisClient::genesis(None)
+        .await
+        .unwrap()
+        .account_me()
+        .account_ref();
+
+    // the unregistered account should be rejected by default
+    assert!(client
+        .ensure_registered(&guarantee, &guarantor)
+        .await
+        .is_err());
+
+    // the account should be permitted by the static allowlist
+    let client = client.with_auth_provider(StaticAuthProvider::new(vec![guarantee]));
+    client
+        .ensure_registered(&guarantee, &guarantor)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_api_token() {
+    // create a client
+    let client = IpdisClient::infer().await;
+
+    // the tokens cannot be granted the write features
+    assert!(client
+        .issue_api_token_unchecked(None, "test", &"word-put".parse().unwrap(), 2, None)
+        .await
+        .is_err());
+
+    // issue a read-only token
+    let scopes: FeatureSet = "word-get".parse().unwrap();
+    let issued = client
+        .issue_api_token_unchecked(None, "test", &scopes, 2, None)
+        .await
+        .unwrap();
+    assert_eq!(issued.info.scopes, scopes.to_string());
+
+    // the name and the scopes are signed along with the rate limit
+    let sign = IssueApiToken::new("test", &scopes, 2, None).unwrap();
+    sign.validate("test", &scopes).unwrap();
+    assert!(sign
+        .validate("test", &"word-get,dyn-path-get".parse().unwrap())
+        .is_err());
+
+    // the issued tokens are listed without their secrets
+    let tokens = client
+        .get_api_token_page_unchecked(
+            None,
+            &GetApiTokens {
+                start_index: 0,
+                end_index: 1024,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(tokens.items.contains(&issued.info));
+
+    // an unknown token should be rejected
+    assert!(client
+        .authenticate_api_token("ipdis_unknown", Feature::WordGet)
+        .await
+        .is_err());
+
+    // the token should be permitted within its scopes
+    let error = client
+        .authenticate_api_token(&issued.token, Feature::DynPathGet)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<IpdisError>(),
+        Some(&IpdisError::FeatureDisabled {
+            feature: Feature::DynPathGet,
+        }),
+    );
+
+    // the token should be permitted within its rate limit
+    for _ in 0..2 {
+        client
+            .authenticate_api_token(&issued.token, Feature::WordGet)
+            .await
+            .unwrap();
+    }
+    let error = client
+        .authenticate_api_token(&issued.token, Feature::WordGet)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<IpdisError>(),
+        Some(IpdisError::RateLimited { .. }),
+    ));
+
+    // the revoked token should be rejected
+    client
+        .revoke_api_token_unchecked(None, issued.info.id)
+        .await
+        .unwrap();
+    assert!(client
+        .authenticate_api_token(&issued.token, Feature::WordGet)
+        .await
+        .is_err());
+    let tokens = client
+        .get_api_token_page_unchecked(
+            None,
+            &GetApiTokens {
+                start_index: 0,
+                end_index: 1024,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(!tokens.items.contains(&issued.info));
+}
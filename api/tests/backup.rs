@@ -0,0 +1,122 @@
+use std::sync::Mutex;
+
+use ipdis_api::{
+    backup::{BackupObject, BackupStore},
+    client::IpdisClient,
+    common::Ipdis,
+    export::{Checkpoint, TABLE_WORDS},
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        anyhow::{anyhow, Result},
+        value::{hash::Hash, text::Text},
+    },
+    env::Infer,
+    path::Path,
+    tokio,
+    word::{Word, WordHash, WordKey},
+};
+
+#[derive(Default)]
+struct MemoryStore {
+    objects: Mutex<Vec<(Hash, Vec<u8>)>>,
+}
+
+#[async_trait]
+impl BackupStore for MemoryStore {
+    async fn put(&self, object: &[u8]) -> Result<Hash> {
+        let hash = Hash::with_bytes(object);
+        self.objects.lock().unwrap().push((hash, object.to_vec()));
+        Ok(hash)
+    }
+
+    async fn get(&self, hash: &Hash) -> Result<Vec<u8>> {
+        self.objects
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(key, _)| key == hash)
+            .map(|(_, object)| object.clone())
+            .ok_or_else(|| anyhow!("no such backup: {hash}"))
+    }
+}
+
+#[tokio::test]
+async fn test_backup_incremental() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a sample word to be stored
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-backup".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+
+    // write a full backup
+    let store = MemoryStore::default();
+    let full = client.backup_unchecked(&store, true).await.unwrap();
+
+    // put the word in IPDIS
+    let word = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &word).await.unwrap();
+
+    // write a delta, which should follow the full backup
+    let delta = client.backup_unchecked(&store, false).await.unwrap();
+    let delta = BackupObject::from_bytes(&store.get(&delta).await.unwrap()).unwrap();
+    assert_eq!(delta.header.base, Some(full));
+    assert!(delta
+        .records
+        .words
+        .iter()
+        .any(|record| record.namespace == word.key.namespace.to_string()));
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_export_resumable() {
+    // create a client
+    let client = IpdisClient::infer().await;
+
+    // export the records into an empty directory
+    let dir = ::std::env::temp_dir().join(format!("ipdis-export-{}", ::std::process::id()));
+    let _ = ::std::fs::remove_dir_all(&dir);
+    client.export_unchecked(&dir, 256).await.unwrap();
+
+    let checkpoint = Checkpoint::load(dir.join("export.checkpoint")).unwrap();
+    let words = checkpoint.get(TABLE_WORDS);
+
+    // the export is resumed from the checkpoint, rather than starting over
+    client.export_unchecked(&dir, 256).await.unwrap();
+    let checkpoint = Checkpoint::load(dir.join("export.checkpoint")).unwrap();
+    assert!(checkpoint.get(TABLE_WORDS) >= words);
+
+    // cleanup test data
+    ::std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,58 @@
+use ipdis_api::config::{IpdisConfig, TlsConfig};
+use ipdis_common::{Feature, FeatureSet};
+
+#[test]
+fn test_feature_set() {
+    let features: FeatureSet = "word-get, write".parse().unwrap();
+    assert!(features.contains(&Feature::WordGet));
+    assert!(features.contains(&Feature::WordPut));
+    assert!(features.contains(&Feature::DynPathPut));
+    assert!(!features.contains(&Feature::DynPathGet));
+
+    assert_eq!("".parse::<FeatureSet>().unwrap(), FeatureSet::default());
+    assert!("word-get,unknown".parse::<FeatureSet>().is_err());
+}
+
+#[test]
+fn test_infer() {
+    // the variables are shared by the tests, so they are checked in order here
+    fn infer_with(key: &str, value: &str) -> ::ipis::core::anyhow::Result<IpdisConfig> {
+        ::std::env::set_var(key, value);
+        let config = IpdisConfig::try_infer();
+        ::std::env::remove_var(key);
+        config
+    }
+
+    // an empty allowlist permits all, rather than rejecting all
+    let config = infer_with("ipdis_allowed_accounts", "").unwrap();
+    assert!(config.allowed_accounts.is_none());
+
+    let config = infer_with("ipdis_features_disabled", "write").unwrap();
+    assert!(config.features_disabled.contains(&Feature::WordPut));
+
+    // the malformed values are rejected rather than falling back to the defaults
+    for (key, value) in [
+        ("ipdis_features_disabled", "word-gett"),
+        ("ipdis_stop_words", "unknown"),
+        ("ipdis_dyn_path_conflict", "unknown"),
+        ("ipdis_cache_enabled", "yes please"),
+        ("ipdis_max_query_rows", "-1"),
+        ("ipdis_gc_interval_secs", "1m"),
+    ] {
+        assert!(infer_with(key, value).is_err(), "{key}={value}");
+    }
+
+    ::std::env::set_var("ipdis_signature_retention_days", "30");
+    assert!(infer_with("ipdis_signature_retention_policy", "forever").is_err());
+    ::std::env::remove_var("ipdis_signature_retention_days");
+}
+
+#[test]
+fn test_tls() {
+    // the certificate should be given with its private key
+    ::std::env::set_var("ipdis_tls_cert", "/dev/null");
+    assert!(TlsConfig::try_infer().is_err());
+    ::std::env::remove_var("ipdis_tls_cert");
+
+    assert_eq!(TlsConfig::try_infer().unwrap(), None);
+}
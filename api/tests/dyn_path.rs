@@ -1,5 +1,11 @@
-use ipdis_api::client::IpdisClient;
-use ipdis_common::Ipdis;
+use ipdis_api::{
+    client::IpdisClient,
+    config::{Delegate, IpdisConfig},
+};
+use ipdis_common::{
+    kv::{self, MemoryValueStore},
+    Delegation, GetDynPathsByTarget, GetDynPathsMany, Ipdis, IpdisAdmin, SignedRecord,
+};
 use ipiis_api::{client::IpiisClient, common::Ipiis};
 use ipis::{
     core::value::hash::Hash,
@@ -62,3 +68,429 @@ async fn test_create() {
         .await
         .unwrap()
 }
+
+#[tokio::test]
+async fn test_metadata() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a dynamic path
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-postgres-test"),
+        kind: Hash::with_str("ipdis-api-postgres-test-metadata"),
+        word: Hash::with_str("my model"),
+        path: Path {
+            value: "FjL3dTmyrudvLxFcezJ7b3oGq7Q48ZUS8HH5e4wajVL7"
+                .parse()
+                .unwrap(),
+            len: 496_300_196,
+        },
+    };
+    let metadata = br#"{"score":0.9}"#;
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap();
+
+    // sign as guarantee
+    let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+
+    // put the path in IPDIS along with the metadata
+    client
+        .put_dyn_path_with_metadata_unchecked(&dyn_path, Some(metadata))
+        .await
+        .unwrap();
+
+    // get the path and its metadata at once
+    let record = client
+        .get_dyn_path_record_unchecked(None, &dyn_path.remove_path())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&record.data.data.data.data, &dyn_path.data.data);
+    assert_eq!(record.metadata.as_deref(), Some(&metadata[..]));
+
+    // too large metadata should be rejected
+    let metadata = vec![0; ::ipdis_common::MAX_METADATA_LEN + 1];
+    assert!(client
+        .put_dyn_path_with_metadata_unchecked(&dyn_path, Some(&metadata))
+        .await
+        .is_err());
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_by_target() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a dynamic path
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-postgres-test"),
+        kind: Hash::with_str("ipdis-api-postgres-test-by-target"),
+        word: Hash::with_str("my model"),
+        path: Path {
+            value: "FjL3dTmyrudvLxFcezJ7b3oGq7Q48ZUS8HH5e4wajVL7"
+                .parse()
+                .unwrap(),
+            len: 496_300_196,
+        },
+    };
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap();
+
+    // sign as guarantee, along with the one of another account
+    let other = IpiisClient::genesis(None).await.unwrap();
+    let other_path = other
+        .sign(
+            account,
+            DynPath {
+                word: Hash::with_str("my other model"),
+                ..dyn_path
+            },
+        )
+        .unwrap();
+    let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+
+    // put the paths in IPDIS
+    client.put_dyn_path_unchecked(&dyn_path).await.unwrap();
+    client.put_dyn_path_unchecked(&other_path).await.unwrap();
+
+    // find the dynamic paths of all accounts referring to the static path
+    let query = GetDynPathsByTarget {
+        path: dyn_path.path.value,
+        start_index: 0,
+        end_index: 10,
+    };
+    let paths = client
+        .get_dyn_path_by_target_unchecked(None, &query)
+        .await
+        .unwrap();
+    for expected in [&dyn_path, &other_path] {
+        assert!(paths
+            .items
+            .iter()
+            .any(|path| path.data.data.data == expected.data.data));
+    }
+
+    // the paths are paged
+    let page = client
+        .get_dyn_path_by_target_unchecked(
+            None,
+            &GetDynPathsByTarget {
+                end_index: 1,
+                ..query
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.next_cursor, Some(1));
+
+    // the static path should be kept from the garbage collection
+    assert!(client
+        .is_path_referenced_unchecked(None, &dyn_path.path.value)
+        .await
+        .unwrap());
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_many() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create the dynamic paths, one of which is updated later
+    let kind = Hash::with_str("ipdis-api-postgres-test-many");
+    let dyn_paths: Vec<_> = ["my model", "my dataset", "my model"]
+        .iter()
+        .enumerate()
+        .map(|(len, word)| DynPath {
+            namespace: Hash::with_str("ipdis-api-postgres-test"),
+            kind,
+            word: Hash::with_str(word),
+            path: Path {
+                value: "FjL3dTmyrudvLxFcezJ7b3oGq7Q48ZUS8HH5e4wajVL7"
+                    .parse()
+                    .unwrap(),
+                len: len as u64,
+            },
+        })
+        .collect();
+
+    // cleanup test data
+    client.delete_dyn_path_all_unchecked(&kind).await.unwrap();
+
+    // put the paths in IPDIS
+    for dyn_path in &dyn_paths {
+        let dyn_path = ipiis.sign(account, *dyn_path).unwrap();
+        client.put_dyn_path_unchecked(&dyn_path).await.unwrap();
+    }
+
+    // resolve the paths at once, along with a missing one
+    let missing = DynPath {
+        word: Hash::with_str("my missing model"),
+        ..dyn_paths[0]
+    };
+    let queries = [dyn_paths[1], missing, dyn_paths[0]].map(|path| path.remove_path());
+    let paths = client
+        .get_dyn_path_many_unchecked(None, &queries)
+        .await
+        .unwrap();
+
+    // the latest paths should be returned in order
+    assert_eq!(paths.len(), 3);
+    assert_eq!(paths[0].as_ref().unwrap().data.data.data, dyn_paths[1]);
+    assert!(paths[1].is_none());
+    assert_eq!(paths[2].as_ref().unwrap().data.data.data, dyn_paths[2]);
+
+    // the signed paths cannot be replaced in transit
+    let sign = GetDynPathsMany::new(&queries).unwrap();
+    sign.validate(&queries).unwrap();
+    assert!(sign
+        .validate(&[queries[0], queries[1], queries[1]])
+        .is_err());
+    assert!(sign.validate(&queries[1..]).is_err());
+
+    // cleanup test data
+    client.delete_dyn_path_all_unchecked(&kind).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_delegated() {
+    // create a client, which is permitted to write on behalf of a principal only
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+    let principal = IpiisClient::genesis(None)
+        .await
+        .unwrap()
+        .account_me()
+        .account_ref();
+    let config = IpdisConfig {
+        delegate_accounts: vec![Delegate {
+            gateway: account,
+            principal,
+        }],
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+
+    // create a dynamic path
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-postgres-test"),
+        kind: Hash::with_str("ipdis-api-postgres-test-delegated"),
+        word: Hash::with_str("my model"),
+        path: Path {
+            value: "FjL3dTmyrudvLxFcezJ7b3oGq7Q48ZUS8HH5e4wajVL7"
+                .parse()
+                .unwrap(),
+            len: 496_300_196,
+        },
+    };
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap();
+
+    // sign as guarantee, delegating the record to the principal
+    let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+    let delegate = |principal| {
+        let delegation = Delegation {
+            principal,
+            nonce: dyn_path.nonce.0,
+        };
+        ipiis.sign(account, delegation).unwrap()
+    };
+
+    // the gateway cannot write on behalf of the other principals
+    assert!(client
+        .put_dyn_path_delegated_unchecked(&dyn_path, None, Some(&delegate(account)))
+        .await
+        .is_err());
+
+    // the delegation cannot be replayed on the other records
+    let other = ipiis.sign(account, dyn_path.data.data).unwrap();
+    assert!(client
+        .put_dyn_path_delegated_unchecked(&other, None, Some(&delegate(principal)))
+        .await
+        .is_err());
+
+    // the permitted principal is recorded
+    client
+        .put_dyn_path_delegated_unchecked(&dyn_path, None, Some(&delegate(principal)))
+        .await
+        .unwrap();
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_verify_integrity() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a dynamic path
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-postgres-test"),
+        kind: Hash::with_str("ipdis-api-postgres-test-integrity"),
+        word: Hash::with_str("my model"),
+        path: Path {
+            value: "FjL3dTmyrudvLxFcezJ7b3oGq7Q48ZUS8HH5e4wajVL7"
+                .parse()
+                .unwrap(),
+            len: 496_300_196,
+        },
+    };
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap();
+
+    // put the path in IPDIS
+    let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+    client.put_dyn_path_unchecked(&dyn_path).await.unwrap();
+
+    // the stored rows should be verified
+    let report = client
+        .verify_integrity_unchecked(&dyn_path.kind)
+        .await
+        .unwrap();
+    assert_eq!(report.checked, 1);
+    assert!(report.is_ok());
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_record_by_nonce() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a dynamic path
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-postgres-test"),
+        kind: Hash::with_str("ipdis-api-postgres-test-nonce"),
+        word: Hash::with_str("my model"),
+        path: Path {
+            value: "FjL3dTmyrudvLxFcezJ7b3oGq7Q48ZUS8HH5e4wajVL7"
+                .parse()
+                .unwrap(),
+            len: 496_300_196,
+        },
+    };
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap();
+
+    // put the path in IPDIS
+    let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+    client.put_dyn_path_unchecked(&dyn_path).await.unwrap();
+
+    // get exactly the signed record with its nonce
+    let record = client
+        .get_record_by_nonce_unchecked(None, &dyn_path.nonce.0)
+        .await
+        .unwrap();
+    match record {
+        Some(SignedRecord::DynPath(record)) => {
+            assert_eq!(&record.data.data.data, &dyn_path.data.data);
+            assert_eq!(record.data.guarantee, dyn_path.guarantee);
+        }
+        record => panic!("unexpected record: {record:?}"),
+    }
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_kv() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+    let store = MemoryValueStore::default();
+
+    // create a key of the config
+    let namespace = Hash::with_str("ipdis-api-postgres-test");
+    let kind = Hash::with_str("ipdis-api-postgres-test-kv");
+    let key = kv::key(namespace, kind, "my config");
+
+    // cleanup test data
+    client.delete_dyn_path_all_unchecked(&kind).await.unwrap();
+    assert_eq!(
+        kv::get_kv::<String, _, _>(&client, &store, None, key)
+            .await
+            .unwrap(),
+        None,
+    );
+
+    // put the values, where the latest one is resolved
+    for value in ["hello", "world"] {
+        kv::put_kv(&client, ipiis, &store, account, key, &value.to_string())
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        kv::get_kv::<String, _, _>(&client, &store, None, key)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("world"),
+    );
+
+    // the value missing in the store cannot be resolved
+    assert!(
+        kv::get_kv::<String, _, _>(&client, &MemoryValueStore::default(), None, key)
+            .await
+            .is_err()
+    );
+
+    // cleanup test data
+    client.delete_dyn_path_all_unchecked(&kind).await.unwrap();
+}
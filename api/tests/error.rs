@@ -0,0 +1,46 @@
+use ipdis_api::common::{Feature, IpdisError};
+use ipis::core::anyhow::{anyhow, Error};
+
+#[test]
+fn test_encode() {
+    let errors = [
+        IpdisError::FeatureDisabled {
+            feature: Feature::WordGet,
+        },
+        IpdisError::ReadOnly,
+        IpdisError::QueryTooLarge { limit: 256 },
+        IpdisError::PayloadTooLarge {
+            payload: ::ipdis_api::common::WORDS_PAYLOAD,
+            limit: 64,
+            given: 65,
+        },
+        // the free-form fields may contain the delimiters of the envelope
+        IpdisError::Busy {
+            resource: "the [request]: queue".into(),
+        },
+        IpdisError::RateLimited {
+            retry_after_ms: 1000,
+        },
+        IpdisError::Duplicate {
+            resource: "the kind: \"word\"".into(),
+        },
+        IpdisError::Fenced {
+            resource: "the path".into(),
+        },
+        IpdisError::LeaseTooLong { limit_ms: 60_000 },
+        IpdisError::StopWord { word: "the".into() },
+    ];
+
+    for expected in errors {
+        // the typed errors may be wrapped with the contexts before sent
+        let error = IpdisError::encode(Error::new(expected.clone()).context("failed to handle"));
+
+        // the remote clients receive only the message
+        let error = IpdisError::recover(anyhow!("{error}"));
+        assert_eq!(error.downcast_ref::<IpdisError>(), Some(&expected));
+    }
+
+    // the messages which merely look alike are not typed
+    let error = IpdisError::recover(anyhow!("{}", IpdisError::ReadOnly));
+    assert!(error.downcast_ref::<IpdisError>().is_none());
+}
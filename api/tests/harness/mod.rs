@@ -0,0 +1,100 @@
+use std::{sync::Mutex, thread, time::Duration};
+
+use diesel::{Connection, PgConnection, RunQueryDsl};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use ipdis_api::{backup::BackupStore, client::IpdisClient};
+use ipiis_api::client::IpiisClient;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        anyhow::{anyhow, Result},
+        value::hash::Hash,
+    },
+    env::Infer,
+};
+use once_cell::sync::Lazy;
+use testcontainers::{clients::Cli, images::postgres::Postgres, Container};
+
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!("postgres/migrations");
+
+/// the number of the attempts to connect to the database while it is starting up
+const CONNECT_ATTEMPTS: u32 = 50;
+
+static DOCKER: Lazy<Cli> = Lazy::new(Cli::default);
+
+/// An ephemeral PostgreSQL server, which is migrated to the latest schema.
+///
+/// The container is removed when it is dropped, so that every test starts from an empty database.
+pub struct Database {
+    node: Container<'static, Postgres>,
+}
+
+impl Database {
+    pub fn start() -> Self {
+        let this = Self {
+            node: DOCKER.run(Postgres::default()),
+        };
+
+        this.connect()
+            .run_pending_migrations(MIGRATIONS)
+            .expect("failed to migrate the database");
+        this
+    }
+
+    pub fn url(&self) -> String {
+        format!(
+            "postgres://postgres@127.0.0.1:{}/postgres",
+            self.node.get_host_port_ipv4(5432),
+        )
+    }
+
+    /// Creates a client of a new account, which is connected to the database directly.
+    pub async fn client(&self) -> IpdisClient {
+        let ipiis = IpiisClient::genesis(None).await.unwrap();
+        IpdisClient::with_database_url(ipiis, self.url()).unwrap()
+    }
+
+    /// Executes the raw SQL, e.g. to simulate the passage of time.
+    pub fn execute(&self, sql: &str) -> usize {
+        ::diesel::sql_query(sql)
+            .execute(&mut self.connect())
+            .unwrap()
+    }
+
+    fn connect(&self) -> PgConnection {
+        let url = self.url();
+        for _ in 1..CONNECT_ATTEMPTS {
+            match PgConnection::establish(&url) {
+                Ok(conn) => return conn,
+                // the server may restart once after initializing the database
+                Err(_) => thread::sleep(Duration::from_millis(100)),
+            }
+        }
+        PgConnection::establish(&url).expect("failed to connect to the database")
+    }
+}
+
+/// Stores the objects in memory, addressed by their hashes as in ipsis.
+#[derive(Default)]
+pub struct MemoryStore {
+    objects: Mutex<Vec<(Hash, Vec<u8>)>>,
+}
+
+#[async_trait]
+impl BackupStore for MemoryStore {
+    async fn put(&self, object: &[u8]) -> Result<Hash> {
+        let hash = Hash::with_bytes(object);
+        self.objects.lock().unwrap().push((hash, object.to_vec()));
+        Ok(hash)
+    }
+
+    async fn get(&self, hash: &Hash) -> Result<Vec<u8>> {
+        self.objects
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(key, _)| key == hash)
+            .map(|(_, object)| object.clone())
+            .ok_or_else(|| anyhow!("no such object: {hash}"))
+    }
+}
@@ -0,0 +1,2597 @@
+mod harness;
+
+use futures::future::try_join_all;
+use std::{sync::Arc, time::Duration};
+
+use ipdis_api::{
+    client::{IpdisClient, SCHEMA_VERSION},
+    clock::ManualClock,
+    common::{
+        failover::IpdisFailover,
+        fixtures::{self, Fixtures},
+        membership,
+        normalize::Normalization,
+        pipeline::IpdisPipeline,
+        replay::{IpdisReplay, MemoryReplayStore},
+        AcquireWriterLease, AddStopWord, Feature, FeatureSet, GetAccountChain, GetIdfLogs, GetKind,
+        GetMembers, GetOplog, GetServerDiagnostics, GetWordCountDelta, GetWordFrequencyHistogram,
+        GetWords, GetWordsCounts, GetWordsCountsBatch, GetWordsParent, Ipdis, IpdisAdmin,
+        IpdisError, IpdisRemote, LinkAccountSuccessor, PutWordsBatch, RegisterKind, WordQuery,
+        KIND,
+    },
+    config::{
+        DynPathConflict, DynPathConflictPolicy, GuaranteeExpiry, IpdisConfig, SignatureRetention,
+        SignatureRetentionPolicy, StopWordsPolicy,
+    },
+    encryption,
+    leader::LeaderElection,
+    privacy::CountNoise,
+    queue::RequestClass,
+    server::IpdisServer,
+    usage::UsagePeriod,
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis, server::IpiisServer};
+use ipis::{
+    core::{
+        chrono::Datelike,
+        value::{hash::Hash, text::Text},
+    },
+    env::Infer,
+    path::{DynPath, Path},
+    tokio,
+    word::{Word, WordHash, WordKey},
+};
+
+use self::harness::{Database, MemoryStore};
+
+fn sample_word(namespace: &str) -> WordHash {
+    Word {
+        key: WordKey {
+            namespace: namespace.to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into()
+}
+
+#[tokio::test]
+async fn test_remote() {
+    // deploy a server on an ephemeral database
+    let database = Database::start();
+    let server =
+        IpdisServer::with_database_url(IpiisServer::genesis(5001).await.unwrap(), &database.url())
+            .unwrap();
+    let server_account = {
+        let server: &IpiisServer = server.as_ref();
+        server.account_me().account_ref()
+    };
+
+    // create a client
+    let client = IpiisClient::genesis(None).await.unwrap();
+    let client_account = client.account_me().account_ref();
+    client
+        .set_account_primary(KIND.as_ref(), &server_account)
+        .await
+        .unwrap();
+    client
+        .set_address(
+            KIND.as_ref(),
+            &server_account,
+            &"127.0.0.1:5001".parse().unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // register the client as guarantee
+    {
+        // sign as guarantee
+        let guarantee = client.sign(server_account, client_account).unwrap();
+
+        server.add_guarantee_unchecked(&guarantee).await.unwrap();
+    }
+
+    // put the word of the server itself, which is served to the API tokens
+    let word = sample_word("ipdis-api-remote-test");
+    let parent = Hash::with_str("");
+    {
+        let ipiis: &IpiisServer = server.as_ref();
+        let word = ipiis.sign(server_account, word).unwrap();
+        server.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+    tokio::spawn(async move { server.run().await });
+
+    // put the word in IPDIS (* 3 times)
+    let count = 3u32;
+    for _ in 0..count {
+        // sign as guarantee
+        let word = client.sign(server_account, word).unwrap();
+
+        // put the word in IPDIS
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    // get the words
+    let word_from_ipdis = client
+        .get_word_latest_unchecked(None, &word.key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&word_from_ipdis.data.data.data, &word);
+
+    // get the word counts of the account
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, true)
+            .await
+            .unwrap(),
+        count,
+    );
+
+    // the typed errors of the server are carried across the wire
+    let query = GetWords {
+        word: word.key,
+        parent: GetWordsParent::None,
+        start_index: 0,
+        end_index: u32::MAX,
+        with_total: false,
+    };
+    let error = client
+        .get_word_record_page_unchecked(None, &query)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<IpdisError>(),
+        Some(IpdisError::QueryTooLarge { .. }),
+    ));
+
+    // create a reader, which is not registered but given an API token
+    let reader = IpiisClient::genesis(None).await.unwrap();
+    reader
+        .set_address(
+            KIND.as_ref(),
+            &server_account,
+            &"127.0.0.1:5001".parse().unwrap(),
+        )
+        .await
+        .unwrap();
+    let reader = IpdisRemote::new(&reader, server_account);
+    let issued = database
+        .client()
+        .await
+        .issue_api_token_unchecked("test", &"word-get".parse().unwrap(), 4, None)
+        .await
+        .unwrap();
+
+    // the reader should be permitted by the token only, as if it were the server itself
+    let query = GetWords {
+        end_index: count,
+        ..query
+    };
+    assert!(reader
+        .get_word_record_page_unchecked(None, &query)
+        .await
+        .is_err());
+    assert_eq!(
+        reader
+            .get_word_record_page_with_token(&issued.token, &query)
+            .await
+            .unwrap()
+            .items
+            .len(),
+        1,
+    );
+
+    // and within the scopes of the token
+    let path = DynPath {
+        namespace: Hash::with_str(&word.key.namespace),
+        kind: word.kind,
+        word: Hash::with_str("latest"),
+        path: (),
+    };
+    let error = reader
+        .get_dyn_path_record_with_token(&issued.token, &path)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<IpdisError>(),
+        Some(&IpdisError::FeatureDisabled {
+            feature: Feature::DynPathGet,
+        }),
+    );
+
+    // revoke the client
+    database
+        .client()
+        .await
+        .delete_guarantee_unchecked(&client_account)
+        .await
+        .unwrap();
+
+    // the revoked client should be rejected
+    let word = client.sign(server_account, word).unwrap();
+    assert!(client.put_word_unchecked(&parent, &word).await.is_err());
+}
+
+#[tokio::test]
+async fn test_expiration() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS
+    let word = sample_word("ipdis-api-expiration-test");
+    let parent = Hash::with_str("");
+    client
+        .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        1,
+    );
+
+    // let the word expire
+    assert_eq!(
+        database.execute("UPDATE words SET expiration_date = NOW() - INTERVAL '1 second'"),
+        1,
+    );
+
+    // the expired word should be hidden even before it is deleted
+    assert!(client
+        .get_word_latest_unchecked(None, &word.key)
+        .await
+        .unwrap()
+        .is_none());
+
+    // the expired word should be discounted
+    client.delete_expired_all_unchecked().await.unwrap();
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        0,
+    );
+
+    // register an account as guarantee
+    let guarantee = IpiisClient::genesis(None).await.unwrap();
+    let guarantee_account = guarantee.account_me().account_ref();
+    client
+        .add_guarantee_unchecked(&guarantee.sign(account, guarantee_account).unwrap())
+        .await
+        .unwrap();
+    client
+        .ensure_registered(&guarantee_account, &account)
+        .await
+        .unwrap();
+
+    // the expired guarantee should be rejected
+    assert_eq!(
+        database.execute(
+            "UPDATE accounts_guarantees SET expiration_date = NOW() - INTERVAL '1 second'",
+        ),
+        1,
+    );
+    assert!(client
+        .ensure_registered(&guarantee_account, &account)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_guarantee_expiry() {
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        guarantee_expiry: Some(GuaranteeExpiry {
+            notice: 7 * DAY,
+            renewal: 30 * DAY,
+        }),
+        outbox_enabled: true,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // register the guarantees, one of which is auto-renewable
+    let notified = IpiisClient::genesis(None).await.unwrap();
+    let renewed = IpiisClient::genesis(None).await.unwrap();
+    for guarantee in [&notified, &renewed] {
+        let guarantee = guarantee.account_me().account_ref();
+        client
+            .add_guarantee_unchecked(&ipiis.sign(account, guarantee).unwrap())
+            .await
+            .unwrap();
+    }
+    client
+        .set_guarantee_auto_renew_unchecked(&renewed.account_me().account_ref(), true)
+        .await
+        .unwrap();
+
+    // let the guarantees expire soon
+    assert_eq!(
+        database
+            .execute("UPDATE accounts_guarantees SET expiration_date = NOW() + INTERVAL '1 day'"),
+        2,
+    );
+
+    let sweep = client.sweep_guarantee_expiry_unchecked().await.unwrap();
+    assert_eq!(sweep.notified, 1);
+    assert_eq!(sweep.renewed, 1);
+
+    // the guarantees should not be swept twice
+    let sweep = client.sweep_guarantee_expiry_unchecked().await.unwrap();
+    assert_eq!(sweep.notified, 0);
+    assert_eq!(sweep.renewed, 0);
+
+    // the events should be written to the outbox
+    assert_eq!(
+        database.execute("DELETE FROM outbox WHERE topic LIKE 'guarantee.%'"),
+        2,
+    );
+
+    // only the renewed one should outlive the original expiration
+    assert_eq!(
+        database.execute(
+            "DELETE FROM accounts_guarantees WHERE expiration_date > NOW() + INTERVAL '7 days'",
+        ),
+        1,
+    );
+}
+
+#[tokio::test]
+async fn test_account_successor() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // register an account, which has put a word
+    let old = IpiisClient::genesis(None).await.unwrap();
+    let old_account = old.account_me().account_ref();
+    client
+        .add_guarantee_unchecked(&ipiis.sign(account, old_account).unwrap())
+        .await
+        .unwrap();
+
+    let word = sample_word("ipdis-api-successor-test");
+    let parent = Hash::with_str("");
+    client
+        .put_word_unchecked(&parent, &old.sign(account, word).unwrap())
+        .await
+        .unwrap();
+
+    // rotate the keypair, signed by both of the keys
+    let new = IpiisClient::genesis(None).await.unwrap();
+    let new_account = new.account_me().account_ref();
+    let proof = LinkAccountSuccessor {
+        predecessor: old.sign(account, new_account).unwrap(),
+    };
+    client
+        .link_account_successor(&new.sign(account, proof).unwrap())
+        .await
+        .unwrap();
+
+    // the successor should be treated as the same principal, and the old key is retired
+    client
+        .ensure_registered(&new_account, &account)
+        .await
+        .unwrap();
+    assert!(client
+        .ensure_registered(&old_account, &account)
+        .await
+        .is_err());
+    assert_eq!(
+        client
+            .get_word_count_unchecked(Some(&new_account), &word.key, true)
+            .await
+            .unwrap(),
+        1,
+    );
+
+    // the chain should be found from any of the accounts
+    for member in [old_account, new_account] {
+        let query = GetAccountChain { account: member };
+        assert_eq!(
+            client
+                .get_account_chain_unchecked(None, &query)
+                .await
+                .unwrap(),
+            vec![old_account, new_account],
+        );
+    }
+
+    // the chain should not be closed into a cycle
+    let proof = LinkAccountSuccessor {
+        predecessor: new.sign(account, old_account).unwrap(),
+    };
+    assert!(client
+        .link_account_successor_unchecked(&old.sign(account, proof).unwrap())
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_clock() {
+    let database = Database::start();
+    let clock = ManualClock::default();
+    let client = database.client().await.with_clock(clock.clone());
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS, which expires in an hour
+    let word = sample_word("ipdis-api-clock-test");
+    let parent = Hash::with_str("");
+    client
+        .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        database.execute("UPDATE words SET expiration_date = NOW() + INTERVAL '1 hour'"),
+        1,
+    );
+
+    // the word should be alive until the clock passes the expiration
+    client.delete_expired_all_unchecked().await.unwrap();
+    assert!(client
+        .get_word_latest_unchecked(None, &word.key)
+        .await
+        .unwrap()
+        .is_some());
+
+    // fast-forward the clock
+    clock.advance(Duration::from_secs(2 * 60 * 60));
+    assert!(client
+        .get_word_latest_unchecked(None, &word.key)
+        .await
+        .unwrap()
+        .is_none());
+
+    // the expired word should be deleted by the clock as well
+    client.delete_expired_all_unchecked().await.unwrap();
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        0,
+    );
+}
+
+#[tokio::test]
+async fn test_clock_server_time() {
+    let database = Database::start();
+    let now = ::ipis::core::chrono::Utc::now() - ::ipis::core::chrono::Duration::days(1);
+    let clock = ManualClock::new(now);
+    let client = database.client().await.with_clock(clock.clone());
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // the receipts are stamped by the clock rather than the system time
+    let word = sample_word("ipdis-api-clock-server-time-test");
+    let parent = Hash::with_str("");
+    let receipt = client
+        .put_word_with_metadata_unchecked(&parent, &ipiis.sign(account, word).unwrap(), None)
+        .await
+        .unwrap();
+    assert_eq!(receipt.server_time, now.timestamp_millis());
+
+    // so are the computed counts
+    let query = GetWordsCounts {
+        word: word.key,
+        parent: false,
+        owned: false,
+        start_index: 0,
+        end_index: 1,
+        with_total: false,
+    };
+    let counts = client
+        .get_word_count_page_fresh_unchecked(None, &query, None)
+        .await
+        .unwrap();
+    assert_eq!(counts.computed_at, now.timestamp_millis());
+
+    // the cached counts are staled by the clock as well
+    clock.advance(Duration::from_secs(60));
+    let counts = client
+        .get_word_count_page_fresh_unchecked(None, &query, Some(Duration::from_secs(1)))
+        .await
+        .unwrap();
+    assert!(!counts.from_cache);
+}
+
+#[tokio::test]
+async fn test_dyn_path_cache_expiration() {
+    let database = Database::start();
+    let clock = ManualClock::default();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        cache_enabled: true,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config).with_clock(clock.clone());
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the path in IPDIS, which expires in an hour
+    let word = sample_word("ipdis-api-cache-expiration-test");
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-cache-expiration-test"),
+        kind: Hash::with_str("ipdis-api-cache-expiration-test"),
+        word: Hash::with_str("my model"),
+        path: word.path,
+    };
+    client
+        .put_dyn_path_unchecked(&ipiis.sign(account, dyn_path).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        database.execute("UPDATE dyn_paths SET expiration_date = NOW() + INTERVAL '1 hour'"),
+        1,
+    );
+
+    // the path should be cached while it is alive
+    let query = dyn_path.remove_path();
+    assert!(client
+        .get_dyn_path_unchecked(None, &query)
+        .await
+        .unwrap()
+        .is_some());
+
+    // the cached path should not outlive its expiration
+    clock.advance(Duration::from_secs(2 * 60 * 60));
+    assert!(client
+        .get_dyn_path_unchecked(None, &query)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_replayed_nonce() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the same records in IPDIS (* 2 times)
+    let word = sample_word("ipdis-api-replay-test");
+    let word = ipiis.sign(account, word).unwrap();
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-replay-test"),
+        kind: Hash::with_str("ipdis-api-replay-test"),
+        word: Hash::with_str("my model"),
+        path: word.data.data.path,
+    };
+    let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+
+    let parent = Hash::with_str("");
+    let mut receipts = vec![];
+    for _ in 0..2 {
+        receipts.push((
+            client
+                .put_word_with_metadata_unchecked(&parent, &word, None)
+                .await
+                .unwrap(),
+            client
+                .put_dyn_path_with_metadata_unchecked(&dyn_path, None)
+                .await
+                .unwrap(),
+        ));
+    }
+
+    // the replayed records should be accepted once
+    assert_eq!(receipts[0].0.seq, receipts[1].0.seq);
+    assert_eq!(receipts[0].1.seq, receipts[1].1.seq);
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.data.data.key, false)
+            .await
+            .unwrap(),
+        1,
+    );
+    assert_eq!(database.execute("SELECT id FROM dyn_paths"), 1);
+}
+
+#[tokio::test]
+async fn test_dyn_path_conflict() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    let appended = Hash::with_str("ipdis-api-conflict-append");
+    let replaced = Hash::with_str("ipdis-api-conflict-replace");
+    let rejected = Hash::with_str("ipdis-api-conflict-reject");
+    let config = IpdisConfig {
+        dyn_path_conflict: DynPathConflict {
+            default: DynPathConflictPolicy::Append,
+            kinds: vec![
+                (replaced, DynPathConflictPolicy::ReplaceLatest),
+                (rejected, DynPathConflictPolicy::RejectDuplicate),
+            ],
+        },
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+
+    let dyn_path = |kind| DynPath {
+        namespace: Hash::with_str("ipdis-api-conflict-test"),
+        kind,
+        word: Hash::with_str("my model"),
+        path: sample_word("ipdis-api-conflict-test").path,
+    };
+
+    // put the same dyn_path twice for each policy
+    for kind in [appended, replaced] {
+        for _ in 0..2 {
+            client
+                .put_dyn_path_unchecked(&ipiis.sign(account, dyn_path(kind)).unwrap())
+                .await
+                .unwrap();
+        }
+    }
+    client
+        .put_dyn_path_unchecked(&ipiis.sign(account, dyn_path(rejected)).unwrap())
+        .await
+        .unwrap();
+    let error = client
+        .put_dyn_path_unchecked(&ipiis.sign(account, dyn_path(rejected)).unwrap())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<IpdisError>(),
+        Some(IpdisError::Duplicate { .. }),
+    ));
+
+    // only the appended ones should be kept alongside
+    for (kind, count) in [(appended, 2), (replaced, 1), (rejected, 1)] {
+        assert_eq!(
+            database.execute(&format!("DELETE FROM dyn_paths WHERE kind = '{kind}'")),
+            count,
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_replay() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS
+    let word = sample_word("ipdis-api-replay-test");
+    let parent = Hash::with_str("");
+    client
+        .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+        .await
+        .unwrap();
+
+    // get the word while the server is reachable
+    let client = IpdisReplay::new(client, MemoryReplayStore::default());
+    let word_from_ipdis = client
+        .get_word_latest_unchecked(None, &word.key)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the word should be replayed after the database is gone
+    drop(database);
+    assert!(client
+        .inner()
+        .get_word_latest_unchecked(None, &word.key)
+        .await
+        .is_err());
+    assert_eq!(
+        client
+            .get_word_latest_unchecked(None, &word.key)
+            .await
+            .unwrap(),
+        Some(word_from_ipdis),
+    );
+}
+
+#[tokio::test]
+async fn test_membership() {
+    let database = Database::start();
+    let namespace = Hash::with_str("ipdis-api-membership-test");
+
+    // announce the nodes sharing the database, one of which twice,
+    // and one of which is not a member of the cluster
+    let nodes = [
+        database.client().await,
+        database.client().await,
+        database.client().await,
+    ];
+    let accounts: Vec<_> = nodes
+        .iter()
+        .map(|node| {
+            let ipiis: &IpiisClient = node.as_ref();
+            ipiis.account_me().account_ref()
+        })
+        .collect();
+    for node in nodes.iter().chain(&nodes[..1]) {
+        let ipiis: &IpiisClient = node.as_ref();
+        let account = ipiis.account_me().account_ref();
+        membership::announce(node, ipiis, account, namespace)
+            .await
+            .unwrap();
+    }
+
+    // the node should not announce itself through the others
+    {
+        let ipiis: &IpiisClient = nodes[2].as_ref();
+        assert!(
+            membership::announce(&nodes[0], ipiis, accounts[0], namespace)
+                .await
+                .is_err()
+        );
+    }
+
+    // every node of the cluster should be discovered once
+    let config = IpdisConfig {
+        cluster_accounts: vec![accounts[0]],
+        ..nodes[1].config().clone()
+    };
+    let reader = database.client().await.with_config(config);
+    let query = GetMembers {
+        namespace,
+        ttl_secs: 60,
+    };
+    let members = reader.get_members_unchecked(None, &query).await.unwrap();
+    assert_eq!(
+        members
+            .iter()
+            .map(|member| member.account)
+            .collect::<Vec<_>>(),
+        vec![accounts[0]],
+    );
+
+    // only the latest announcement should be kept
+    assert_eq!(
+        database.execute(&format!(
+            "DELETE FROM dyn_paths WHERE kind = '{}'",
+            *membership::KIND,
+        )),
+        3,
+    );
+}
+
+#[tokio::test]
+async fn test_concurrent_put() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the same word in parallel
+    let word = sample_word("ipdis-api-concurrency-test");
+    let parent = Hash::with_str("");
+    let count = 16u32;
+    let words = (0..count)
+        .map(|_| ipiis.sign(account, word))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    try_join_all(
+        words
+            .iter()
+            .map(|word| client.put_word_unchecked(&parent, word)),
+    )
+    .await
+    .unwrap();
+
+    // no put should be lost
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        count,
+    );
+}
+
+#[tokio::test]
+async fn test_word_count_delta() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS (* 3 times)
+    let word = sample_word("ipdis-api-delta-test");
+    let parent = Hash::with_str("");
+    let count = 3i64;
+    for _ in 0..count {
+        client
+            .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+            .await
+            .unwrap();
+    }
+
+    // the changes should be merged for each word
+    let query = GetWordCountDelta {
+        kind: word.kind,
+        since_seq: 0,
+        limit: 100,
+    };
+    let delta = client
+        .get_word_count_delta_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(delta.items.len(), 1);
+    assert_eq!(delta.items[0].word, word.key);
+    assert_eq!(delta.items[0].delta, count);
+    assert!(!delta.has_more);
+
+    // nothing should be changed after the watermark
+    let query = GetWordCountDelta {
+        since_seq: delta.until_seq,
+        ..query
+    };
+    assert!(client
+        .get_word_count_delta_unchecked(None, &query)
+        .await
+        .unwrap()
+        .items
+        .is_empty());
+
+    // the discounted words should be reported as the negative changes
+    assert_eq!(
+        database.execute("UPDATE words SET expiration_date = NOW() - INTERVAL '1 second'"),
+        count as usize,
+    );
+    client.delete_expired_all_unchecked().await.unwrap();
+    let delta = client
+        .get_word_count_delta_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(delta.items.len(), 1);
+    assert_eq!(delta.items[0].delta, -count);
+}
+
+#[tokio::test]
+async fn test_word_frequency_histogram() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put a word once, and another 3 times
+    let rare = sample_word("ipdis-api-histogram-rare-test");
+    let common = sample_word("ipdis-api-histogram-common-test");
+    let parent = Hash::with_str("");
+    for word in [rare, common, common, common] {
+        client
+            .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+            .await
+            .unwrap();
+    }
+
+    let query = GetWordFrequencyHistogram {
+        kind: rare.kind,
+        buckets: 3,
+    };
+    let buckets = client
+        .get_word_frequency_histogram_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(
+        buckets
+            .iter()
+            .map(|bucket| (bucket.min_count, bucket.max_count, bucket.words))
+            .collect::<Vec<_>>(),
+        [(1, Some(1), 1), (2, Some(10), 1), (11, None, 0)],
+    );
+
+    // the empty histograms should be rejected
+    let query = GetWordFrequencyHistogram {
+        buckets: 0,
+        ..query
+    };
+    assert!(client
+        .get_word_frequency_histogram_unchecked(None, &query)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_pagination() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS (* 5 times)
+    let word = sample_word("ipdis-api-pagination-test");
+    let parent = Hash::with_str("");
+    let count = 5u32;
+    for _ in 0..count {
+        client
+            .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+            .await
+            .unwrap();
+    }
+
+    let query = GetWords {
+        word: word.key,
+        parent: GetWordsParent::None,
+        start_index: 0,
+        end_index: 2,
+        with_total: false,
+    };
+
+    // get the first page
+    let page = client
+        .get_word_record_page_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.next_cursor, Some(2));
+    assert_eq!(page.total, None);
+
+    // count all the items only if requested
+    let page = client
+        .get_word_record_page_unchecked(
+            None,
+            &GetWords {
+                with_total: true,
+                ..query
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.total, Some(count));
+
+    // visit all the pages
+    let mut visited = 0u32;
+    client
+        .for_each_word_record_unchecked(None, &query, |record| {
+            assert_eq!(&record.data.data.data.data, &word);
+            visited += 1;
+            Ok(())
+        })
+        .await
+        .unwrap();
+    assert_eq!(visited, count);
+}
+
+#[tokio::test]
+async fn test_request_queue() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        max_concurrent_requests: Some(1),
+        queue_timeout: Some(Duration::from_millis(100)),
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+
+    // a burst of requests is queued beyond the limit
+    let permit = client.enter_queue(RequestClass::Priority).await.unwrap();
+    for class in [RequestClass::Priority, RequestClass::Bulk] {
+        let error = client.enter_queue(class).await.err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<IpdisError>(),
+            Some(IpdisError::Busy { .. }),
+        ));
+    }
+
+    // the queue time is exposed in the diagnostics
+    let query = GetServerDiagnostics { slow_queries: 0 };
+    let diagnostics = client
+        .get_server_diagnostics_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(diagnostics.requests_queued, 0);
+    assert!(diagnostics.queue_time_us >= 100_000);
+
+    // the queued requests take their turn once the previous ones are done
+    drop(permit);
+    assert!(client.enter_queue(RequestClass::Bulk).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_writer_lease() {
+    let database = Database::start();
+    let clock = ManualClock::default();
+    let client = database.client().await.with_clock(clock.clone());
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+    let other_ipiis = IpiisClient::genesis(None).await.unwrap();
+    let other = other_ipiis.account_me().account_ref();
+
+    let word = sample_word("ipdis-api-writer-lease-test");
+    let parent = Hash::with_str("");
+    let acquire = |token| AcquireWriterLease {
+        kind: word.kind,
+        ttl_ms: 60_000,
+        token,
+    };
+    let is_fenced = |error: ipis::core::anyhow::Error| {
+        matches!(
+            error.downcast_ref::<IpdisError>(),
+            Some(IpdisError::Fenced { .. }),
+        )
+    };
+
+    // the non-admins cannot fence the kind
+    let guarantee = other_ipiis.sign(account, other).unwrap();
+    client.add_guarantee_unchecked(&guarantee).await.unwrap();
+    assert!(client
+        .acquire_writer_lease(&other_ipiis.sign(account, acquire(None)).unwrap())
+        .await
+        .is_err());
+
+    // the leases cannot be held beyond the limit
+    let limit_ms = client.config().max_writer_lease_ttl.as_millis() as u64;
+    for ttl_ms in [limit_ms + 1, u64::MAX] {
+        let error = client
+            .acquire_writer_lease_unchecked(
+                Some(&account),
+                &AcquireWriterLease {
+                    ttl_ms,
+                    ..acquire(None)
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<IpdisError>(),
+            Some(&IpdisError::LeaseTooLong { limit_ms }),
+        );
+    }
+
+    // acquire the lease of the kind
+    let lease = client
+        .acquire_writer_lease_unchecked(Some(&account), &acquire(None))
+        .await
+        .unwrap();
+    assert_eq!(lease.holder, account);
+    assert_eq!(lease.token, 1);
+
+    // the writes of the kind should be accompanied by the fencing token
+    let signed = ipiis.sign(account, word).unwrap();
+    assert!(is_fenced(
+        client
+            .put_word_unchecked(&parent, &signed)
+            .await
+            .unwrap_err()
+    ));
+    client
+        .put_word_fenced_unchecked(&parent, &signed, None, None, Some(lease.token))
+        .await
+        .unwrap();
+
+    // the held lease cannot be acquired by the others, but can be renewed by the holder
+    let error = client
+        .acquire_writer_lease_unchecked(Some(&other), &acquire(None))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<IpdisError>(),
+        Some(IpdisError::Busy { .. }),
+    ));
+    let renewed = client
+        .acquire_writer_lease_unchecked(Some(&account), &acquire(Some(lease.token)))
+        .await
+        .unwrap();
+    assert_eq!(renewed.token, lease.token);
+    assert!(renewed.expiration_time >= lease.expiration_time);
+
+    // the expired lease is taken over with a new fencing token
+    clock.advance(Duration::from_secs(2 * 60));
+    let taken = client
+        .acquire_writer_lease_unchecked(Some(&other), &acquire(None))
+        .await
+        .unwrap();
+    assert_eq!(taken.holder, other);
+    assert_eq!(taken.token, lease.token + 1);
+
+    // the previous holder is fenced off
+    assert!(is_fenced(
+        client
+            .put_word_fenced_unchecked(&parent, &signed, None, None, Some(lease.token))
+            .await
+            .unwrap_err()
+    ));
+    assert!(is_fenced(
+        client
+            .acquire_writer_lease_unchecked(Some(&account), &acquire(Some(lease.token)))
+            .await
+            .unwrap_err()
+    ));
+}
+
+#[tokio::test]
+async fn test_oplog() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        oplog_enabled: true,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the records in IPDIS
+    let word = sample_word("ipdis-api-oplog-test");
+    let parent = Hash::with_str("");
+    let word = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &word).await.unwrap();
+
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-oplog-test"),
+        kind: Hash::with_str("ipdis-api-oplog-test"),
+        word: Hash::with_str("my model"),
+        path: word.data.data.path,
+    };
+    let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+    client.put_dyn_path_unchecked(&dyn_path).await.unwrap();
+
+    // follow the oplog, one entry at a time
+    let mut entries = vec![];
+    let mut query = GetOplog {
+        since_seq: 0,
+        limit: 1,
+    };
+    loop {
+        let oplog = client.get_oplog_unchecked(None, &query).await.unwrap();
+        entries.extend(oplog.entries);
+        query.since_seq = oplog.until_seq;
+        if !oplog.has_more {
+            break;
+        }
+    }
+
+    // the writes should be logged in the order of their acceptance
+    let nonces: Vec<_> = entries.iter().map(|entry| entry.nonce).collect();
+    assert_eq!(nonces, [word.nonce.0, dyn_path.nonce.0]);
+    assert!(entries[0].seq < entries[1].seq);
+
+    // the logged records should be verifiable
+    for entry in &entries {
+        let record = client
+            .get_record_by_nonce_unchecked(None, &entry.nonce)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.hash().unwrap(), entry.hash);
+    }
+}
+
+#[tokio::test]
+async fn test_oplog_bulk_writes() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        oplog_enabled: true,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS
+    let word = sample_word("ipdis-api-oplog-bulk-test");
+    let parent = Hash::with_str("");
+    let word = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &word).await.unwrap();
+
+    // rewrite the word in bulk
+    let kind = Hash::with_str("ipdis-api-oplog-bulk-test");
+    client
+        .migrate_kind_unsafe(&word.data.data.kind, &kind)
+        .await
+        .unwrap();
+
+    // the rewritten word should be logged as well
+    let query = GetOplog {
+        since_seq: 0,
+        limit: 16,
+    };
+    let entries = client
+        .get_oplog_unchecked(None, &query)
+        .await
+        .unwrap()
+        .entries;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].nonce, word.nonce.0);
+
+    let record = client
+        .get_record_by_nonce_unchecked(None, &entries[1].nonce)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(record.hash().unwrap(), entries[1].hash);
+    assert_ne!(entries[0].hash, entries[1].hash);
+}
+
+#[tokio::test]
+async fn test_inclusion_proof() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        oplog_enabled: true,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the records in IPDIS
+    let mut dyn_paths = vec![];
+    for name in ["my model", "my dataset", "my notebook"] {
+        let dyn_path = DynPath {
+            namespace: Hash::with_str("ipdis-api-inclusion-proof-test"),
+            kind: Hash::with_str("ipdis-api-inclusion-proof-test"),
+            word: Hash::with_str(name),
+            path: Path {
+                value: Hash::with_str(name),
+                len: 42,
+            },
+        };
+        let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+        client.put_dyn_path_unchecked(&dyn_path).await.unwrap();
+        dyn_paths.push(dyn_path);
+    }
+
+    // no root covers the records yet
+    let nonce = dyn_paths[0].nonce.0;
+    assert!(client
+        .get_inclusion_proof_unchecked(None, &nonce)
+        .await
+        .unwrap()
+        .is_none());
+
+    // publish the root, only once for the same oplog
+    let root = client
+        .publish_oplog_root_unchecked()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(root.data.data.tree_size, 3);
+    assert!(client
+        .publish_oplog_root_unchecked()
+        .await
+        .unwrap()
+        .is_none());
+
+    // the proofs should be verifiable against the signed root
+    for (index, dyn_path) in dyn_paths.iter().enumerate() {
+        let proof = client
+            .get_inclusion_proof_unchecked(None, &dyn_path.nonce.0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(proof.index, index as u64);
+        assert_eq!(proof.root, root);
+        proof.verify(&account).unwrap();
+
+        let record = client
+            .get_record_by_nonce_unchecked(None, &dyn_path.nonce.0)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.hash().unwrap(), proof.entry.hash);
+
+        // a tampered proof should be rejected
+        let mut tampered = proof.clone();
+        tampered.index = (tampered.index + 1) % 3;
+        assert!(tampered.verify(&account).is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_import_idf_logs_from_ndjson() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // export the words line by line, as the legacy logs
+    let parent = Hash::with_str("");
+    let mut words = vec![];
+    for namespace in ["ipdis-api-import-test-1", "ipdis-api-import-test-2"] {
+        let word = ipiis.sign(account, sample_word(namespace)).unwrap();
+        words.push(ipiis.sign_as_guarantor(word).unwrap());
+    }
+    let to_line = |word: &::ipis::core::account::GuarantorSigned<WordHash>| {
+        format!(
+            r#"{{"nonce":"{nonce}","guarantee":"{guarantee}","guarantor":"{guarantor}","guarantee_signature":"{guarantee_signature}","guarantor_signature":"{guarantor_signature}","created_date":"{created_date}","namespace":"{namespace}","kind":"{kind}","parent":"{parent}","lang":"{lang}","word":"{msg}","relpath":{relpath},"path":"{path}","len":{len}}}"#,
+            nonce = word.nonce.0 .0,
+            guarantee = word.guarantee.account,
+            guarantor = word.guarantor.account,
+            guarantee_signature = word.guarantee.signature,
+            guarantor_signature = word.guarantor.signature,
+            created_date = word.created_date.to_rfc3339(),
+            namespace = word.data.key.namespace,
+            kind = word.data.kind,
+            lang = word.data.key.text.lang,
+            msg = word.data.key.text.msg,
+            relpath = word.data.relpath,
+            path = word.data.path.value,
+            len = word.data.path.len,
+        )
+    };
+
+    // the forged and the malformed records should be rejected one by one
+    let mut lines: Vec<_> = words.iter().map(to_line).collect();
+    lines.push(to_line(&words[0]).replace(&words[0].data.kind.to_string(), &parent.to_string()));
+    lines.push("{}".to_string());
+    lines.push(to_line(&words[1]));
+    let ndjson = lines.join("\n\n");
+
+    let summary = client
+        .import_idf_logs_from_ndjson(ndjson.as_bytes())
+        .await
+        .unwrap();
+    assert_eq!(summary.read, 5);
+    assert_eq!(summary.imported, 2);
+    assert_eq!(summary.duplicates, 1);
+    assert_eq!(
+        summary
+            .rejected
+            .iter()
+            .map(|rejection| rejection.line)
+            .collect::<Vec<_>>(),
+        [5, 7],
+    );
+
+    // the imported records should be verifiable
+    let report = client
+        .verify_integrity_unchecked(&words[0].data.kind)
+        .await
+        .unwrap();
+    assert_eq!(report.checked, 2);
+    assert!(report.is_ok());
+
+    // importing again should skip the stored records
+    let summary = client
+        .import_idf_logs_from_ndjson(ndjson.as_bytes())
+        .await
+        .unwrap();
+    assert_eq!(summary.imported, 0);
+    assert_eq!(summary.duplicates, 3);
+}
+
+#[tokio::test]
+async fn test_export_usage() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        usage_enabled: true,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the records in IPDIS
+    let word = sample_word("ipdis-api-usage-test");
+    let parent = Hash::with_str("");
+    let word = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &word).await.unwrap();
+
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-usage-test"),
+        kind: Hash::with_str("ipdis-api-usage-test"),
+        word: Hash::with_str("my model"),
+        path: word.data.data.path,
+    };
+    let dyn_path = ipiis.sign(account, dyn_path).unwrap();
+    client.put_dyn_path_unchecked(&dyn_path).await.unwrap();
+
+    // export the usage of today
+    let now = ::ipis::core::chrono::Utc::now();
+    let period = UsagePeriod {
+        since: now - ::ipis::core::chrono::Duration::days(1),
+        until: now + ::ipis::core::chrono::Duration::days(1),
+    };
+    let records = client.export_usage_unchecked(&period).await.unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].account, account);
+    assert_eq!(records[0].rows_written, 2);
+    assert_eq!(records[0].rows_stored, 2);
+    assert!(records[0].bytes_written > 0);
+
+    // nothing has been written in the next month
+    let next = now + ::ipis::core::chrono::Duration::days(40);
+    let period = UsagePeriod::month(next.year(), next.month()).unwrap();
+    let records = client.export_usage_unchecked(&period).await.unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].rows_written, 0);
+    assert_eq!(records[0].rows_stored, 2);
+}
+
+#[tokio::test]
+async fn test_older_schema_compatibility() {
+    let database = Database::start();
+
+    // roll the schema back to the oldest supported version
+    database.execute("ALTER TABLE schema_meta DROP COLUMN compatible_version");
+    database.execute("DROP TABLE words_tiered");
+    database.execute("DROP TABLE words_segments");
+    database.execute("DROP TABLE accounts_usage");
+    database.execute("ALTER TABLE kinds DROP COLUMN normalization");
+    database.execute("UPDATE schema_meta SET version = 22");
+
+    let client = database.client().await;
+    let config = IpdisConfig {
+        usage_enabled: true,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // the kinds are read without their normalization policies
+    let kind = Hash::with_str("ipdis-api-postgres-test");
+    let query = RegisterKind {
+        kind,
+        schema_version: 1,
+        normalization: None,
+    };
+    client
+        .register_kind_unchecked(None, &query, "test", "a kind for testing")
+        .await
+        .unwrap();
+    let info = client
+        .get_kind_unchecked(None, &GetKind { kind })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.schema_version, 1);
+    assert_eq!(info.normalization, None);
+
+    // the policies cannot be stored yet
+    let query = RegisterKind {
+        normalization: Some(Normalization::Nfc),
+        ..query
+    };
+    assert!(client
+        .register_kind_unchecked(None, &query, "test", "a kind for testing")
+        .await
+        .is_err());
+
+    // the writes skip the missing features
+    let word = sample_word("ipdis-api-compat-test");
+    let parent = Hash::with_str("");
+    let word = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &word).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_newer_schema_compatibility() {
+    let database = Database::start();
+
+    // an additive migration keeps the binaries of this version working
+    database.execute(&format!(
+        "UPDATE schema_meta SET version = {}",
+        SCHEMA_VERSION + 1,
+    ));
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    let word = sample_word("ipdis-api-compat-test");
+    let parent = Hash::with_str("");
+    let word = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &word).await.unwrap();
+
+    // a breaking migration requires the binaries to be upgraded first
+    database.execute(&format!(
+        "UPDATE schema_meta SET compatible_version = {}",
+        SCHEMA_VERSION + 1,
+    ));
+    let ipiis = IpiisClient::genesis(None).await.unwrap();
+    assert!(IpdisClient::with_database_url(ipiis, database.url()).is_err());
+}
+
+#[tokio::test]
+async fn test_read_consistent() {
+    let database = Database::start();
+    let client = &database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS
+    let word = sample_word("ipdis-api-snapshot-test");
+    let parent = Hash::with_str("");
+    client
+        .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+        .await
+        .unwrap();
+
+    let (count, idf, count_after) = client
+        .read_consistent(|view| async move {
+            let count = view.get_word_count(&word.key).await?;
+
+            // put the word again, outside of the snapshot
+            client
+                .put_word_unchecked(&parent, &ipiis.sign(account, word)?)
+                .await?;
+
+            let idf = view.get_idf_vector(&[word.key]).await?;
+            let count_after = view.get_word_count(&word.key).await?;
+            Ok((count, idf, count_after))
+        })
+        .await
+        .unwrap();
+
+    // the concurrent write should not be seen in the snapshot
+    assert_eq!(count, 1);
+    assert_eq!(count_after, 1);
+    assert_eq!(idf.corpus_size, 1);
+    assert_eq!(idf.document_counts, vec![1]);
+
+    // but after it
+    let counts = client
+        .read_consistent(|view| async move { view.get_word_count_many(&[word.key]).await })
+        .await
+        .unwrap();
+    assert_eq!(counts, vec![2]);
+}
+
+#[tokio::test]
+async fn test_fixtures() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // the same seed should generate the same records
+    let mut fixtures = Fixtures::new(42);
+    let words: Vec<_> = (0..16).map(|_| fixtures.word()).collect();
+    let mut replayed = Fixtures::new(42);
+    assert!(words.iter().all(|word| word == &replayed.word()));
+
+    // put the generated records in IPDIS
+    let words = fixtures.signed_words(ipiis, account, 64).unwrap();
+    fixtures::put_words(&client, &Hash::with_str(""), &words)
+        .await
+        .unwrap();
+    let paths = fixtures.signed_dyn_paths(ipiis, account, 16).unwrap();
+    fixtures::put_dyn_paths(&client, &paths).await.unwrap();
+
+    let word = &words[0].data.data;
+    assert!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap()
+            > 0
+    );
+}
+
+#[tokio::test]
+async fn test_signature_retention_policies() {
+    for policy in [
+        SignatureRetentionPolicy::Archive,
+        SignatureRetentionPolicy::Drop,
+    ] {
+        let database = Database::start();
+        let clock = ManualClock::default();
+        let client = database.client().await.with_clock(clock.clone());
+        let config = IpdisConfig {
+            signature_retention: Some(SignatureRetention {
+                after: Duration::from_secs(24 * 60 * 60),
+                policy,
+            }),
+            ..client.config().clone()
+        };
+        let client = client.with_config(config);
+        let ipiis: &IpiisClient = client.as_ref();
+        let account = ipiis.account_me().account_ref();
+
+        // put a word twice, of which the former is superseded
+        let word = sample_word("ipdis-api-signature-retention-test");
+        let parent = Hash::with_str("");
+        for _ in 0..2 {
+            let signed = ipiis.sign(account, word).unwrap();
+            client.put_word_unchecked(&parent, &signed).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let path = DynPath {
+            namespace: Hash::with_str(&word.key.namespace),
+            kind: word.kind,
+            word: Hash::with_str("latest"),
+            path: word.path,
+        };
+        let signed = ipiis.sign(account, path).unwrap();
+        client.put_dyn_path_unchecked(&signed).await.unwrap();
+
+        // sweep the signatures of the old records
+        clock.advance(Duration::from_secs(2 * 24 * 60 * 60));
+        assert_eq!(
+            client.apply_signature_retention_unchecked().await.unwrap(),
+            1
+        );
+
+        // the latest records are still served as signed
+        let latest = client
+            .get_word_latest_unchecked(None, &word.key)
+            .await
+            .unwrap()
+            .unwrap();
+        latest.verify(None).unwrap();
+        let dyn_path = client
+            .get_dyn_path_unchecked(None, &path.remove_path())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(dyn_path.data.data.data, path);
+
+        // the superseded records are served only if their signatures are archived
+        let query = GetWords {
+            word: word.key,
+            parent: GetWordsParent::None,
+            start_index: 0,
+            end_index: 2,
+            with_total: false,
+        };
+        let words = client.get_word_many_unchecked(None, &query).await.unwrap();
+        let report = client.verify_integrity_unchecked(&word.kind).await.unwrap();
+        assert!(report.is_ok());
+        match policy {
+            SignatureRetentionPolicy::Archive => {
+                assert_eq!(words.len(), 2);
+                assert_eq!(report.dropped, 0);
+            }
+            SignatureRetentionPolicy::Drop => {
+                assert_eq!(words.len(), 1);
+                assert_eq!(report.dropped, 1);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_signature_retention_pagination() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put a word 4 times, of which the middle ones straddling the first page are unsigned
+    let word = sample_word("ipdis-api-signature-retention-pagination-test");
+    let parent = Hash::with_str("");
+    for _ in 0..4 {
+        let signed = ipiis.sign(account, word).unwrap();
+        client.put_word_unchecked(&parent, &signed).await.unwrap();
+    }
+    assert_eq!(
+        database.execute(
+            "UPDATE words SET guarantee_signature = NULL, guarantor_signature = NULL
+            WHERE id IN (SELECT id FROM words ORDER BY id DESC OFFSET 1 LIMIT 2)",
+        ),
+        2,
+    );
+
+    // the unsigned records are skipped, neither ending the pages early nor skipping the others
+    let query = |start_index, end_index| GetWords {
+        word: word.key,
+        parent: GetWordsParent::None,
+        start_index,
+        end_index,
+        with_total: true,
+    };
+    let page = client
+        .get_word_record_page_unchecked(None, &query(0, 2))
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.total, Some(2));
+    assert_eq!(page.next_cursor, None);
+
+    let page = client
+        .get_word_record_page_unchecked(None, &query(0, 1))
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.next_cursor, Some(1));
+    let page = client
+        .get_word_record_page_unchecked(None, &query(1, 2))
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[tokio::test]
+async fn test_idf_logs_tiering() {
+    let database = Database::start();
+    let clock = ManualClock::default();
+    let client = database.client().await.with_clock(clock.clone());
+    let config = IpdisConfig {
+        idf_logs_tiering_age: Some(Duration::from_secs(24 * 60 * 60)),
+        ..client.config().clone()
+    };
+    let client = client
+        .with_config(config)
+        .with_segment_store(MemoryStore::default());
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put a word twice, of which the former is superseded
+    let word = sample_word("ipdis-api-idf-logs-tiering-test");
+    let parent = Hash::with_str("");
+    let mut signed = vec![];
+    for _ in 0..2 {
+        let word = ipiis.sign(account, word).unwrap();
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+        signed.push(word);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // the recent words are kept in the hot table
+    assert_eq!(client.tier_idf_logs_unchecked().await.unwrap(), 0);
+
+    // only the superseded word is tiered out
+    clock.advance(Duration::from_secs(2 * 24 * 60 * 60));
+    assert_eq!(client.tier_idf_logs_unchecked().await.unwrap(), 1);
+    let query = GetWords {
+        word: word.key,
+        parent: GetWordsParent::None,
+        start_index: 0,
+        end_index: 2,
+        with_total: false,
+    };
+    let words = client.get_word_many_unchecked(None, &query).await.unwrap();
+    assert_eq!(words.len(), 1);
+
+    // the time-range query spans both tiers
+    let now = ::ipis::core::chrono::Utc::now();
+    let since = now - ::ipis::core::chrono::Duration::hours(1);
+    let until = now + ::ipis::core::chrono::Duration::hours(1);
+    let logs = client.get_idf_logs_unchecked(since, until).await.unwrap();
+    assert_eq!(logs.len(), 2);
+    for (log, word) in logs.iter().zip(&signed) {
+        log.verify(None).unwrap();
+        assert_eq!(log.nonce, word.nonce);
+    }
+
+    // the pages span both tiers as well, oldest first
+    let mut query = GetIdfLogs {
+        since: since.timestamp_millis(),
+        until: until.timestamp_millis(),
+        start_index: 0,
+        end_index: 1,
+    };
+    let page = client
+        .get_idf_log_page_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].nonce, signed[0].nonce);
+    assert_eq!(page.next_cursor, Some(1));
+
+    query.start_index = 1;
+    query.end_index = 3;
+    let page = client
+        .get_idf_log_page_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].nonce, signed[1].nonce);
+    assert_eq!(page.next_cursor, None);
+
+    // the tiered word is found by its nonce, and its replay is accepted once
+    assert!(client
+        .get_record_by_nonce_unchecked(None, &signed[0].nonce.0)
+        .await
+        .unwrap()
+        .is_some());
+    client
+        .put_word_unchecked(&parent, &signed[0])
+        .await
+        .unwrap();
+    let logs = client.get_idf_logs_unchecked(since, until).await.unwrap();
+    assert_eq!(logs.len(), 2);
+
+    // the deletions reach the tiered word as well
+    client
+        .delete_word_many_unchecked(&word.kind, &[word])
+        .await
+        .unwrap();
+    let logs = client.get_idf_logs_unchecked(since, until).await.unwrap();
+    assert!(logs.is_empty());
+}
+
+#[tokio::test]
+async fn test_path_reference_count() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        features_disabled: [Feature::DynPathGet, Feature::WordGet]
+            .into_iter()
+            .collect::<FeatureSet>(),
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // the content is referred by a word only
+    let word = sample_word("ipdis-api-path-reference-test");
+    let path = word.path.value;
+    assert!(!client
+        .is_path_referenced_unchecked(None, &path)
+        .await
+        .unwrap());
+
+    let signed = ipiis.sign(account, word).unwrap();
+    client
+        .put_word_unchecked(&Hash::with_str(""), &signed)
+        .await
+        .unwrap();
+
+    // the references are counted even if the reads are disabled
+    assert_eq!(
+        client
+            .get_path_reference_count_unchecked(None, &path)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(client
+        .is_path_referenced_unchecked(None, &path)
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn test_count_noise_aggregates() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        count_noise: CountNoise::parse("*=0.001").unwrap(),
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+    let other = IpiisClient::genesis(None)
+        .await
+        .unwrap()
+        .account_me()
+        .account_ref();
+
+    let word = sample_word("ipdis-api-count-noise-test");
+    let signed = ipiis.sign(account, word).unwrap();
+    client
+        .put_word_unchecked(&Hash::with_str(""), &signed)
+        .await
+        .unwrap();
+
+    // the document frequencies are exact for the server only
+    let words = [word.key];
+    let exact = client.get_idf_vector_unchecked(None, &words).await.unwrap();
+    assert_eq!(exact.corpus_size, 1);
+    assert_eq!(exact.document_counts, [1]);
+
+    let noised = client
+        .get_idf_vector_unchecked(Some(&other), &words)
+        .await
+        .unwrap();
+    assert_ne!(noised, exact);
+    assert_eq!(
+        client
+            .get_idf_vector_unchecked(Some(&other), &words)
+            .await
+            .unwrap(),
+        noised
+    );
+
+    // so are the scores of the similar documents, unless ranking the own words
+    let exact = client
+        .get_similar_documents_unchecked(None, &words, 10, false)
+        .await
+        .unwrap();
+    assert_eq!(exact.len(), 1);
+    let noised = client
+        .get_similar_documents_unchecked(Some(&other), &words, 10, false)
+        .await
+        .unwrap();
+    assert_eq!(noised.len(), 1);
+    assert_ne!(noised[0].score, exact[0].score);
+    assert!(client
+        .get_similar_documents_unchecked(Some(&other), &words, 10, true)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // so are the analytics, unless counting the own words
+    let query = WordQuery::kind(word.kind);
+    let exact = client.query_words_unchecked(None, &query).await.unwrap();
+    assert_eq!(exact[0].count, 1);
+    let noised = client
+        .query_words_unchecked(Some(&other), &query)
+        .await
+        .unwrap();
+    assert_ne!(noised, exact);
+    let owned = query.owned_by(other);
+    assert!(client
+        .query_words_unchecked(Some(&other), &owned)
+        .await
+        .unwrap()
+        .iter()
+        .all(|row| row.count == 0));
+
+    // the exact changes are not served to the others
+    let query = GetWordCountDelta {
+        kind: word.kind,
+        since_seq: 0,
+        limit: 16,
+    };
+    assert_eq!(
+        client
+            .get_word_count_delta_unchecked(None, &query)
+            .await
+            .unwrap()
+            .items
+            .len(),
+        1
+    );
+    assert!(client
+        .get_word_count_delta_unchecked(Some(&other), &query)
+        .await
+        .is_err());
+
+    // nor are the histograms
+    let query = GetWordFrequencyHistogram {
+        kind: word.kind,
+        buckets: 4,
+    };
+    let exact = client
+        .get_word_frequency_histogram_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(exact[0].words, 1);
+    let noised = client
+        .get_word_frequency_histogram_unchecked(Some(&other), &query)
+        .await
+        .unwrap();
+    assert_ne!(noised, exact);
+}
+
+#[tokio::test]
+async fn test_features_disabled() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        features_disabled: "write".parse().unwrap(),
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // the disabled features are rejected with the typed error
+    let word = sample_word("ipdis-api-features-disabled-test");
+    let signed = ipiis.sign(account, word).unwrap();
+    let error = client
+        .put_word_unchecked(&Hash::with_str(""), &signed)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<IpdisError>(),
+        Some(&IpdisError::FeatureDisabled {
+            feature: Feature::WordPut,
+        }),
+    );
+
+    // the others are served as usual
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_remote_pipeline() {
+    // deploy a server on an ephemeral database
+    let database = Database::start();
+    let server =
+        IpdisServer::with_database_url(IpiisServer::genesis(5002).await.unwrap(), &database.url())
+            .unwrap();
+    let server_account = {
+        let server: &IpiisServer = server.as_ref();
+        server.account_me().account_ref()
+    };
+
+    // create a client
+    let client = IpiisClient::genesis(None).await.unwrap();
+    let client_account = client.account_me().account_ref();
+    client
+        .set_account_primary(KIND.as_ref(), &server_account)
+        .await
+        .unwrap();
+    client
+        .set_address(
+            KIND.as_ref(),
+            &server_account,
+            &"127.0.0.1:5002".parse().unwrap(),
+        )
+        .await
+        .unwrap();
+    let guarantee = client.sign(server_account, client_account).unwrap();
+    server.add_guarantee_unchecked(&guarantee).await.unwrap();
+
+    tokio::spawn(async move { server.run().await });
+
+    // put the words in IPDIS
+    let words: Vec<_> = (0..4)
+        .map(|index| sample_word(&format!("ipdis-api-pipeline-test-{index}")))
+        .collect();
+    let parent = Hash::with_str("");
+    for word in &words {
+        // sign as guarantee
+        let word = client.sign(server_account, *word).unwrap();
+
+        // put the word in IPDIS
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    // the concurrent queries are answered in their own order
+    let (pipeline, dispatcher) = IpdisPipeline::new(Arc::new(client));
+    tokio::spawn(dispatcher.with_max_in_flight(2).run());
+    let counts = try_join_all(
+        words
+            .iter()
+            .map(|word| pipeline.get_word_count_unchecked(&word.key, false)),
+    )
+    .await
+    .unwrap();
+    assert_eq!(counts, [1; 4]);
+
+    // the typed errors of the batches are kept
+    let query = GetWordsCounts {
+        word: words[0].key,
+        parent: false,
+        owned: false,
+        start_index: 0,
+        end_index: u32::MAX,
+        with_total: false,
+    };
+    let error = pipeline
+        .get_word_count_page_unchecked(&query)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<IpdisError>(),
+        Some(IpdisError::QueryTooLarge { .. }),
+    ));
+
+    // the signed batches cannot be replaced in transit
+    let queries: Vec<_> = words
+        .iter()
+        .map(|word| GetWordsCounts {
+            word: word.key,
+            parent: false,
+            owned: false,
+            start_index: 0,
+            end_index: 1,
+            with_total: false,
+        })
+        .collect();
+    let batch = GetWordsCountsBatch::new(&queries).unwrap();
+    batch.validate(&queries).unwrap();
+    let mut replaced = queries.clone();
+    replaced.swap(0, 1);
+    assert!(batch.validate(&replaced).is_err());
+    assert!(batch.validate(&queries[1..]).is_err());
+}
+
+#[tokio::test]
+async fn test_leader_reconnect() {
+    let database = Database::start();
+    let leader =
+        Arc::new(LeaderElection::establish(&database.url(), "ipdis-api-leader-test").unwrap());
+    let follower =
+        Arc::new(LeaderElection::establish(&database.url(), "ipdis-api-leader-test").unwrap());
+
+    // elect a single leader
+    assert!(leader.try_acquire().await.unwrap());
+    assert!(!follower.try_acquire().await.unwrap());
+
+    // lose the session of the leader, along with its lock
+    assert_eq!(
+        database
+            .execute("SELECT pg_terminate_backend(pid) FROM pg_locks WHERE locktype = 'advisory'"),
+        1,
+    );
+    assert!(leader.try_acquire().await.is_err());
+    assert!(!leader.is_leader());
+
+    // the follower takes over, and the former leader rejoins as a follower
+    assert!(follower.try_acquire().await.unwrap());
+    assert!(!leader.try_acquire().await.unwrap());
+    assert!(follower.try_acquire().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_column_encryption() {
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS, before the encryption is enabled
+    let word = sample_word("ipdis-api-encryption-test");
+    let parent = Hash::with_str("");
+    client
+        .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+        .await
+        .unwrap();
+
+    // the nodes should not start with the key, leaving the plaintext rows unreachable
+    assert!(database.client().await.with_column_key(Some(KEY)).is_err());
+
+    // encrypt the plaintext rows
+    assert!(encryption::encrypt_plaintext_rows(&database.url(), KEY).unwrap() > 0);
+    assert_eq!(
+        encryption::encrypt_plaintext_rows(&database.url(), KEY).unwrap(),
+        0,
+    );
+
+    // the encrypted word should be found with the key
+    let client = database.client().await.with_column_key(Some(KEY)).unwrap();
+    assert!(client
+        .get_word_latest_unchecked(None, &word.key)
+        .await
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        1,
+    );
+
+    // the ciphertexts should fit the columns of the new records
+    let ipiis: &IpiisClient = client.as_ref();
+    client
+        .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        2,
+    );
+
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-encryption-test"),
+        kind: Hash::with_str("ipdis-api-encryption-test"),
+        word: Hash::with_str("my model"),
+        path: word.path,
+    };
+    client
+        .put_dyn_path_unchecked(&ipiis.sign(account, dyn_path).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .get_dyn_path_unchecked(None, &dyn_path.remove_path())
+            .await
+            .unwrap()
+            .map(|path| path.data.data.data.path),
+        Some(word.path),
+    );
+}
+
+#[tokio::test]
+async fn test_read_only_propagation() {
+    let database = Database::start();
+    let client = database.client().await;
+    let other = Arc::new(database.client().await);
+
+    // wait until the node follows the mode of the others
+    async fn wait_read_only(client: &IpdisClient, enabled: bool) {
+        for _ in 0..50 {
+            if client.is_read_only() == enabled {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("the read-only mode has not been propagated")
+    }
+
+    // enable the read-only mode, while the other node is not listening yet
+    client.set_read_only_unchecked(None, true).await.unwrap();
+    assert!(client.is_read_only());
+    assert!(!other.is_read_only());
+
+    // the other node reloads the mode once listening
+    tokio::spawn({
+        let other = other.clone();
+        async move { other.listen_invalidations().await }
+    });
+    wait_read_only(&other, true).await;
+
+    // and follows the changes made by the others
+    client.set_read_only_unchecked(None, false).await.unwrap();
+    wait_read_only(&other, false).await;
+}
+
+#[tokio::test]
+async fn test_remote_failover() {
+    // deploy a server on an ephemeral database
+    let database = Database::start();
+    let server =
+        IpdisServer::with_database_url(IpiisServer::genesis(5003).await.unwrap(), &database.url())
+            .unwrap();
+    let server_account = {
+        let server: &IpiisServer = server.as_ref();
+        server.account_me().account_ref()
+    };
+
+    // create a client, whose first server is down
+    let client = IpiisClient::genesis(None).await.unwrap();
+    let client_account = client.account_me().account_ref();
+    let down_account = IpiisClient::genesis(None)
+        .await
+        .unwrap()
+        .account_me()
+        .account_ref();
+    for (account, address) in [
+        (&down_account, "127.0.0.1:5004"),
+        (&server_account, "127.0.0.1:5003"),
+    ] {
+        client
+            .set_address(KIND.as_ref(), account, &address.parse().unwrap())
+            .await
+            .unwrap();
+    }
+    let guarantee = client.sign(server_account, client_account).unwrap();
+    server.add_guarantee_unchecked(&guarantee).await.unwrap();
+
+    tokio::spawn(async move { server.run().await });
+
+    let client = IpdisFailover::new(client, vec![down_account, server_account]).unwrap();
+    let ipiis = client.ipiis();
+
+    // the signed records are sent to the server they have been signed for
+    let word = sample_word("ipdis-api-failover-test");
+    let parent = Hash::with_str("");
+    let signed = ipiis.sign(server_account, word).unwrap();
+    client.put_word_unchecked(&parent, &signed).await.unwrap();
+    client.put_word_unchecked(&parent, &signed).await.unwrap();
+
+    // which are not retried on the others, as they would be rejected
+    let signed = ipiis.sign(down_account, word).unwrap();
+    assert!(client.put_word_unchecked(&parent, &signed).await.is_err());
+
+    // the reads are retried on the next server
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        1,
+    );
+
+    // the other writes are not repeated, as they may have been applied
+    let query = AcquireWriterLease {
+        kind: word.kind,
+        ttl_ms: 60_000,
+        token: None,
+    };
+    assert!(client
+        .acquire_writer_lease_unchecked(None, &query)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_delete_many() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the words in IPDIS
+    let words: Vec<_> = ["hello", "world"]
+        .into_iter()
+        .map(|text| {
+            let mut word = sample_word("ipdis-api-delete-many-test");
+            word.key.text.msg = Hash::with_str(text);
+            word
+        })
+        .collect();
+    let parent = Hash::with_str("");
+    for word in &words {
+        let word = ipiis.sign(account, *word).unwrap();
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    // delete only the first word
+    client
+        .delete_word_many_unchecked(&words[0].kind, &words[..1])
+        .await
+        .unwrap();
+
+    // ensure that only the first word has been discounted
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &words[0].key, false)
+            .await
+            .unwrap(),
+        0,
+    );
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &words[1].key, false)
+            .await
+            .unwrap(),
+        1,
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_kind() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the words of the different kinds in IPDIS
+    let words: Vec<_> = ["ipdis-api-migrate-kind-old", "ipdis-api-migrate-kind-new"]
+        .into_iter()
+        .map(|kind| {
+            let mut word = sample_word("ipdis-api-migrate-kind-test");
+            word.kind = Hash::with_str(kind);
+            word
+        })
+        .collect();
+    let parent = Hash::with_str("");
+    for word in &words {
+        let word = ipiis.sign(account, *word).unwrap();
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    // merge the old kind into the new one
+    client
+        .migrate_kind_unsafe(&words[0].kind, &words[1].kind)
+        .await
+        .unwrap();
+
+    // ensure that the counts have been merged
+    let counts = client
+        .get_word_count_many_unchecked(
+            None,
+            &GetWordsCounts {
+                word: words[0].key,
+                parent: false,
+                owned: false,
+                start_index: 0,
+                end_index: 2,
+                with_total: false,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].word.kind, words[1].kind);
+    assert_eq!(counts[0].count, 2);
+}
+
+#[tokio::test]
+async fn test_reindex_kind() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the word in IPDIS, hashed with the old scheme
+    let word = sample_word("ipdis-api-reindex-kind-test");
+    let rehashed = {
+        let mut word = word;
+        word.key.text.msg = Hash::with_str("hello world (rehashed)");
+        word
+    };
+    let parent = Hash::with_str("");
+    let signed = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &signed).await.unwrap();
+
+    // replace the words of the old scheme by the rehashed ones
+    let version = client.config().hash_version;
+    let replacement = ipiis.sign(account, rehashed).unwrap();
+    let replaced = client
+        .reindex_kind_unchecked(
+            &word.kind,
+            version,
+            version + 1,
+            &[(signed.nonce.0, replacement)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(replaced, 1);
+
+    // ensure that the count has been moved to the new hash
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        0,
+    );
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &rehashed.key, false)
+            .await
+            .unwrap(),
+        1,
+    );
+
+    // ensure that the replacements match their signatures
+    let report = client.verify_integrity_unchecked(&word.kind).await.unwrap();
+    assert!(report.is_ok());
+
+    // the reindexed words should not be replaced twice
+    let replaced = client
+        .reindex_kind_unchecked(
+            &word.kind,
+            version,
+            version + 1,
+            &[(signed.nonce.0, replacement)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(replaced, 0);
+
+    // the replacements of the other words are rejected
+    let forged = {
+        let mut word = rehashed;
+        word.key.namespace = Hash::with_str("ipdis-api-reindex-kind-forged");
+        ipiis.sign(account, word).unwrap()
+    };
+    assert!(client
+        .reindex_kind_unchecked(
+            &word.kind,
+            version + 1,
+            version + 2,
+            &[(replacement.nonce.0, forged)],
+        )
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_put_word_many_fenced_normalized() {
+    let database = Database::start();
+    let client = database.client().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // register a kind enforcing the normalization, and fence it
+    let word = sample_word("ipdis-api-put-word-many-test");
+    let query = RegisterKind {
+        kind: word.kind,
+        schema_version: 1,
+        normalization: Some(Normalization::NfcLowercase),
+    };
+    client
+        .register_kind_unchecked(None, &query, "test", "a kind for testing")
+        .await
+        .unwrap();
+    let lease = client
+        .acquire_writer_lease_unchecked(
+            Some(&account),
+            &AcquireWriterLease {
+                kind: word.kind,
+                ttl_ms: 60_000,
+                token: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    // the batches should declare both of them, as the single puts do
+    let parent = Hash::with_str("");
+    let words = vec![ipiis.sign(account, word).unwrap()];
+    let normalization = Some(Normalization::NfcLowercase);
+    assert!(client
+        .put_word_many_unchecked(&parent, &words, None, normalization)
+        .await
+        .is_err());
+    assert!(client
+        .put_word_many_unchecked(&parent, &words, Some(lease.token), None)
+        .await
+        .is_err());
+    let receipts = client
+        .put_word_many_unchecked(&parent, &words, Some(lease.token), normalization)
+        .await
+        .unwrap();
+    assert_eq!(receipts.len(), 1);
+
+    // which are signed along with the words
+    let batch = PutWordsBatch::new(&words, Some(lease.token), normalization).unwrap();
+    batch
+        .validate(&words, Some(lease.token), normalization)
+        .unwrap();
+    assert!(batch
+        .validate(&words, Some(lease.token + 1), normalization)
+        .is_err());
+    assert!(batch.validate(&words, Some(lease.token), None).is_err());
+}
+
+#[tokio::test]
+async fn test_stop_words_rejected() {
+    let database = Database::start();
+    let client = database.client().await;
+    let config = IpdisConfig {
+        stop_words: StopWordsPolicy::Rejected,
+        ..client.config().clone()
+    };
+    let client = client.with_config(config);
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+    let other_ipiis = IpiisClient::genesis(None).await.unwrap();
+    let other = other_ipiis.account_me().account_ref();
+
+    let word = sample_word("ipdis-api-stop-words-test");
+    let stop_word = AddStopWord {
+        kind: word.kind,
+        word: word.key.text,
+    };
+
+    // the non-admins cannot register the stop words
+    let guarantee = other_ipiis.sign(account, other).unwrap();
+    client.add_guarantee_unchecked(&guarantee).await.unwrap();
+    assert!(client
+        .add_stop_word(&other_ipiis.sign(account, stop_word).unwrap())
+        .await
+        .is_err());
+
+    // register the stop word as the admin
+    client
+        .add_stop_word(&ipiis.sign(account, stop_word).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .list_stop_words_unchecked(None, &word.kind)
+            .await
+            .unwrap(),
+        vec![word.key.text],
+    );
+
+    // the stop word is rejected with the typed error
+    let error = client
+        .put_word_unchecked(&Hash::with_str(""), &ipiis.sign(account, word).unwrap())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<IpdisError>(),
+        Some(&IpdisError::StopWord {
+            word: word.key.text.msg.to_string(),
+        }),
+    );
+}
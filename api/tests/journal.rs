@@ -0,0 +1,114 @@
+use std::{
+    fs::OpenOptions,
+    io::{Seek, SeekFrom, Write},
+};
+
+use ipdis_api::common::{
+    journal::{FsyncPolicy, Journal},
+    replay::{JournalReplayStore, ReplayStore},
+};
+use ipis::{core::value::hash::Hash, tokio};
+
+#[test]
+fn test_recover() {
+    let path = ::std::env::temp_dir().join(format!(
+        "ipdis-api-journal-test-{}.log",
+        ::std::process::id(),
+    ));
+    let _ = ::std::fs::remove_file(&path);
+
+    let entries: Vec<_> = ["a", "b", "c"].into_iter().map(Hash::with_str).collect();
+
+    // append the entries
+    {
+        let (mut journal, recovered) = Journal::<Hash>::open(&path, FsyncPolicy::Always).unwrap();
+        assert!(recovered.is_empty());
+        for entry in &entries {
+            journal.append(entry).unwrap();
+        }
+    }
+
+    // simulate a torn write
+    OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap()
+        .write_all(&[0xff, 0x00])
+        .unwrap();
+
+    // the entries should be recovered, without the torn tail
+    {
+        let (mut journal, recovered) = Journal::<Hash>::open(&path, FsyncPolicy::Never).unwrap();
+        assert_eq!(recovered, entries);
+
+        // compact the journal into the last entry
+        journal.compact(&entries[2..]).unwrap();
+        assert_eq!(journal.len(), 1);
+
+        journal.append(&entries[0]).unwrap();
+        journal.flush().unwrap();
+    }
+
+    let (_, recovered) = Journal::<Hash>::open(&path, FsyncPolicy::default()).unwrap();
+    assert_eq!(recovered, vec![entries[2], entries[0]]);
+
+    ::std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_corrupted() {
+    let path = ::std::env::temp_dir().join(format!(
+        "ipdis-api-journal-corrupted-test-{}.log",
+        ::std::process::id(),
+    ));
+    let _ = ::std::fs::remove_file(&path);
+
+    let entries: Vec<_> = ["a", "b"].into_iter().map(Hash::with_str).collect();
+    {
+        let (mut journal, _) = Journal::<Hash>::open(&path, FsyncPolicy::Always).unwrap();
+        for entry in &entries {
+            journal.append(entry).unwrap();
+        }
+    }
+
+    // corrupt the last byte of the last entry
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&path)
+        .unwrap();
+    let len = file.seek(SeekFrom::End(0)).unwrap();
+    file.set_len(len - 1).unwrap();
+    file.seek(SeekFrom::End(0)).unwrap();
+    file.write_all(&[0xff]).unwrap();
+    drop(file);
+
+    // the corrupted entry should be dropped by its checksum
+    let (_, recovered) = Journal::<Hash>::open(&path, FsyncPolicy::Never).unwrap();
+    assert_eq!(recovered, entries[..1]);
+
+    ::std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_replay_store() {
+    let path = ::std::env::temp_dir().join(format!(
+        "ipdis-api-journal-replay-test-{}.log",
+        ::std::process::id(),
+    ));
+    let _ = ::std::fs::remove_file(&path);
+
+    let query = Hash::with_str("query");
+    {
+        let store = JournalReplayStore::open(&path, FsyncPolicy::Always).unwrap();
+        store.put(&query, b"stale").await.unwrap();
+        store.put(&query, b"latest").await.unwrap();
+        store.compact().unwrap();
+    }
+
+    // the latest response should be recovered after a restart
+    let store = JournalReplayStore::open(&path, FsyncPolicy::Always).unwrap();
+    assert_eq!(store.get(&query).await.unwrap(), Some(b"latest".to_vec()));
+
+    ::std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,155 @@
+use ipdis_api::{
+    client::IpdisClient,
+    common::{
+        normalize::{Normalization, Normalizer},
+        DescribeKind, GetKind, GetKinds, Ipdis, IpdisAdmin, RegisterKind,
+    },
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::value::{hash::Hash, text::Text},
+    env::Infer,
+    path::Path,
+    tokio,
+    word::{Word, WordHash, WordKey},
+};
+
+#[tokio::test]
+async fn test_register_kind() {
+    // create a client
+    let client = IpdisClient::infer().await;
+
+    // register a kind
+    let kind = Hash::with_str("ipdis-api-postgres-test-kind");
+    let query = RegisterKind {
+        kind,
+        schema_version: 1,
+        normalization: None,
+    };
+    client
+        .register_kind_unchecked(None, &query, "test", "a kind for testing")
+        .await
+        .unwrap();
+
+    // overwrite the description
+    let query = RegisterKind {
+        kind,
+        schema_version: 2,
+        normalization: None,
+    };
+    client
+        .register_kind_unchecked(None, &query, "test", "a kind for testing, revised")
+        .await
+        .unwrap();
+
+    // resolve the kind
+    let info = client
+        .get_kind_unchecked(None, &GetKind { kind })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.name, "test");
+    assert_eq!(info.description, "a kind for testing, revised");
+    assert_eq!(info.schema_version, 2);
+
+    // the name and the description are signed along with the kind
+    let sign = DescribeKind::new(query, "test", "a kind for testing, revised").unwrap();
+    sign.validate("test", "a kind for testing, revised")
+        .unwrap();
+    assert!(sign.validate("test", "a kind for testing").is_err());
+    assert!(sign
+        .validate("tset", "a kind for testing, revised")
+        .is_err());
+
+    // enumerate the kinds
+    let kinds = client
+        .get_kind_page_unchecked(
+            None,
+            &GetKinds {
+                start_index: 0,
+                end_index: 1024,
+            },
+        )
+        .await
+        .unwrap();
+    assert!(kinds.items.contains(&info));
+}
+
+#[tokio::test]
+async fn test_normalization_policy() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // register a kind enforcing the normalization
+    let kind = "ipdis-api-postgres-test-kind-normalization";
+    let query = RegisterKind {
+        kind: Hash::with_str(kind),
+        schema_version: 1,
+        normalization: Some(Normalization::NfcLowercase),
+    };
+    client
+        .register_kind_unchecked(None, &query, "test", "a kind normalizing the words")
+        .await
+        .unwrap();
+
+    let info = client
+        .get_kind_unchecked(None, &GetKind { kind: query.kind })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.normalization, Some(Normalization::NfcLowercase));
+
+    // create a sample word following the policy
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-normalization".to_string(),
+            text: Normalization::NfcLowercase.normalize(Text::with_en_us("Hello World")),
+        },
+        kind: kind.to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+    let parent = Hash::with_str("");
+    let signed = ipiis.sign(account, word).unwrap();
+
+    // the words should declare the normalization of the kind
+    assert!(client.put_word_unchecked(&parent, &signed).await.is_err());
+    assert!(
+        client
+            .put_word_normalized_unchecked(
+                &parent,
+                &signed,
+                None,
+                None,
+                None,
+                Some(Normalization::Nfc),
+            )
+            .await
+            .is_err()
+    );
+    client
+        .put_word_normalized_unchecked(
+            &parent,
+            &signed,
+            None,
+            None,
+            None,
+            Some(Normalization::NfcLowercase),
+        )
+        .await
+        .unwrap();
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+}
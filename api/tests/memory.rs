@@ -0,0 +1,60 @@
+use ipdis_common::{fixtures::Fixtures, memory::IpdisMemory, GetWords, GetWordsParent, Ipdis};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{core::value::hash::Hash, tokio};
+
+#[tokio::test]
+async fn test_memory() {
+    let client = IpdisMemory::new(IpiisClient::genesis(None).await.unwrap());
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // put the same word several times
+    let mut fixtures = Fixtures::new(42);
+    let word = fixtures.word();
+    let parent = Hash::with_str("");
+    for _ in 0..3 {
+        client
+            .put_word_unchecked(&parent, &ipiis.sign(account, word).unwrap())
+            .await
+            .unwrap();
+    }
+
+    // the records are paginated, latest first
+    let query = GetWords {
+        word: word.key,
+        parent: GetWordsParent::None,
+        start_index: 0,
+        end_index: 2,
+        with_total: true,
+    };
+    let page = client.get_word_page_unchecked(None, &query).await.unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.next_cursor, Some(2));
+    assert_eq!(page.total, Some(3));
+
+    // the duplicated words are counted for each occurrence
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        3,
+    );
+
+    // the unregistered accounts are rejected
+    let guarantee = IpiisClient::genesis(None)
+        .await
+        .unwrap()
+        .account_me()
+        .account_ref();
+    assert!(client
+        .ensure_registered(&guarantee, &account)
+        .await
+        .is_err());
+
+    // the unsupported operations are rejected rather than ignored
+    assert!(client
+        .get_idf_vector_unchecked(None, &[word.key])
+        .await
+        .is_err());
+}
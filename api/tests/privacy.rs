@@ -0,0 +1,36 @@
+use ipdis_api::privacy::CountNoise;
+use ipis::core::value::hash::Hash;
+
+#[test]
+fn test_count_noise() {
+    const SECRET: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    let noise = CountNoise::parse("*=0.1").unwrap();
+    let kind = Hash::with_str("ipdis-api-privacy-test");
+    let words: Vec<_> = (0..16)
+        .map(|index| Hash::with_str(&index.to_string()))
+        .collect();
+
+    // the repeated queries cannot be averaged
+    for word in &words {
+        let count = noise.apply(&kind, word, 100);
+        assert!((0..16).all(|_| noise.apply(&kind, word, 100) == count));
+    }
+
+    // the counts are noised by the words
+    let counts: Vec<_> = words
+        .iter()
+        .map(|word| noise.apply(&kind, word, 100))
+        .collect();
+    assert!(counts.iter().any(|count| count != &counts[0]));
+
+    // the nodes sharing the secret return the same noisy counts
+    let noise = noise.with_secret(SECRET).unwrap();
+    let other = CountNoise::parse("*=0.1")
+        .unwrap()
+        .with_secret(SECRET)
+        .unwrap();
+    assert!(words
+        .iter()
+        .all(|word| noise.apply(&kind, word, 100) == other.apply(&kind, word, 100)));
+}
@@ -1,10 +1,21 @@
+use std::time::Duration;
+
 use ipdis_api::{
     client::IpdisClient,
-    common::{GetWords, GetWordsCounts, GetWordsParent, Ipdis},
+    common::{
+        lang::undetermined,
+        tokenize::{register_tokenizer, tokenize, Tokenizer},
+        GetAccountStats, GetIdfVector, GetSimilarDocuments, GetWordCountAllLangs, GetWords,
+        GetWordsCounts, GetWordsParent, Ipdis, IpdisAdmin, IpdisError, QueryWords, SignedRecord,
+        WordQuery,
+    },
 };
 use ipiis_api::{client::IpiisClient, common::Ipiis};
 use ipis::{
-    core::value::{hash::Hash, text::Text},
+    core::value::{
+        hash::Hash,
+        text::{Text, TextHash},
+    },
     env::Infer,
     path::Path,
     tokio,
@@ -79,6 +90,7 @@ async fn test_create() {
                 parent: GetWordsParent::Duplicated,
                 start_index: 0,
                 end_index: 1,
+                with_total: false,
             },
         )
         .await
@@ -110,6 +122,7 @@ async fn test_create() {
                     owned: false,
                     start_index: 0,
                     end_index: 1,
+                    with_total: false,
                 }
             )
             .await
@@ -131,6 +144,7 @@ async fn test_create() {
                     owned: true,
                     start_index: 0,
                     end_index: 1,
+                    with_total: false,
                 }
             )
             .await
@@ -156,3 +170,596 @@ async fn test_create() {
         0,
     );
 }
+
+#[tokio::test]
+async fn test_query_too_large() {
+    // create a client
+    let client = IpdisClient::infer().await;
+
+    // create a query beyond the limit
+    let limit = client.config().max_query_rows;
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-query-too-large".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+
+    // ensure that the query is rejected
+    let error = client
+        .get_word_count_many_unchecked(
+            None,
+            &GetWordsCounts {
+                word: word.key,
+                parent: false,
+                owned: false,
+                start_index: 0,
+                end_index: limit + 1,
+                with_total: false,
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<IpdisError>(),
+        Some(&IpdisError::QueryTooLarge { limit }),
+    );
+}
+
+#[tokio::test]
+async fn test_payload_too_large() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a word with the metadata beyond the limit
+    let limit = client.config().max_metadata_len;
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-payload-too-large".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+    let word = ipiis.sign(account, word).unwrap();
+    let metadata = vec![0; limit as usize + 1];
+
+    // ensure that the payload is rejected
+    let error = client
+        .put_word_with_metadata_unchecked(&Hash::with_str(""), &word, Some(&metadata))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<IpdisError>(),
+        Some(&IpdisError::PayloadTooLarge {
+            payload: "metadata bytes",
+            limit: limit.into(),
+            given: metadata.len() as u64,
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_idf_vector() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create sample words of 2 documents
+    let namespace = "ipdis-api-postgres-test-idf";
+    let kind = "ipdis-api-postgres-test";
+    let new_word = |text: &str| -> WordHash {
+        Word {
+            key: WordKey {
+                namespace: namespace.to_string(),
+                text: Text::with_en_us(text),
+            },
+            kind: kind.to_string(),
+            relpath: true,
+            path: Path {
+                value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                    .parse()
+                    .unwrap(),
+                len: 13,
+            },
+        }
+        .into()
+    };
+    let documents = [
+        (Hash::with_str("document 1"), vec!["hello", "world"]),
+        (Hash::with_str("document 2"), vec!["hello"]),
+    ];
+
+    // cleanup test data
+    let namespace_hash = new_word("hello").key.namespace;
+    client
+        .delete_word_all_unchecked(&namespace_hash)
+        .await
+        .unwrap();
+
+    // put the words in IPDIS
+    for (parent, texts) in &documents {
+        for text in texts {
+            let word = ipiis.sign(account, new_word(text)).unwrap();
+            client.put_word_unchecked(parent, &word).await.unwrap();
+        }
+    }
+
+    // get the IDF vector
+    let words = ["hello", "world", "unknown"].map(|text| new_word(text).key);
+    let vector = client.get_idf_vector_unchecked(None, &words).await.unwrap();
+    assert_eq!(vector.corpus_size, 2);
+    assert_eq!(vector.document_counts, vec![2, 1, 0]);
+
+    // the signed words cannot be replaced in transit
+    let sign = GetIdfVector::new(&words).unwrap();
+    sign.validate(&words).unwrap();
+    assert!(sign.validate(&[words[1], words[0], words[2]]).is_err());
+    assert!(sign.validate(&words[1..]).is_err());
+    let sign = GetSimilarDocuments::new(&words, 10, false).unwrap();
+    sign.validate(&words).unwrap();
+    assert!(sign.validate(&[words[1], words[0], words[2]]).is_err());
+
+    // find the documents sharing the words, where the rarer word weighs more
+    let similar = client
+        .get_similar_documents_unchecked(None, &words, 10, false)
+        .await
+        .unwrap();
+    assert_eq!(similar.len(), 2);
+    assert_eq!(similar[0].document, documents[0].0);
+    assert_eq!(similar[0].overlap, 2);
+    assert_eq!(similar[1].document, documents[1].0);
+    assert_eq!(similar[1].overlap, 1);
+    assert!(similar[0].score > similar[1].score);
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&namespace_hash)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_stop_words() {
+    // create a client
+    let client = IpdisClient::infer().await;
+
+    // create a sample stop word
+    let kind = Hash::with_str("ipdis-api-postgres-test-stop-words");
+    let word: TextHash = Text::with_en_us("the").into();
+
+    // register the stop word (* 2 times)
+    for _ in 0..2 {
+        client
+            .add_stop_word_unchecked(None, &kind, &word)
+            .await
+            .unwrap();
+    }
+
+    // ensure that the stop word is listed once
+    let stop_words = client.list_stop_words_unchecked(None, &kind).await.unwrap();
+    assert_eq!(stop_words, vec![word]);
+}
+
+#[tokio::test]
+async fn test_count_all_langs() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a sample word in 2 languages
+    let namespace = "ipdis-api-postgres-test-all-langs";
+    let kind = "ipdis-api-postgres-test";
+    let texts = [Text::with_en_us("hello"), undetermined("hello").unwrap()];
+    let words: Vec<WordHash> = texts
+        .into_iter()
+        .map(|text| {
+            Word {
+                key: WordKey {
+                    namespace: namespace.to_string(),
+                    text,
+                },
+                kind: kind.to_string(),
+                relpath: true,
+                path: Path {
+                    value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                        .parse()
+                        .unwrap(),
+                    len: 13,
+                },
+            }
+            .into()
+        })
+        .collect();
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&words[0].key.namespace)
+        .await
+        .unwrap();
+
+    // put the words in IPDIS
+    for word in &words {
+        let word = ipiis.sign(account, *word).unwrap();
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    // ensure that the counts are summed up
+    let count = client
+        .get_word_count_all_langs_unchecked(
+            None,
+            &GetWordCountAllLangs {
+                namespace: words[0].key.namespace,
+                kind: words[0].kind,
+                msg: words[0].key.text.msg,
+                owned: false,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&words[0].key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_account_stats() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a sample word to be stored
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-account-stats".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+    let parent = Hash::with_str("");
+
+    // put the word in IPDIS
+    let signed = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &signed).await.unwrap();
+
+    // ensure that the word is attributed to the account
+    let stats = client
+        .get_account_stats_unchecked(None, &GetAccountStats { account })
+        .await
+        .unwrap();
+    assert!(stats.words >= 1);
+    assert!(stats.storage_bytes > 0);
+    assert!(stats.first_activity <= stats.last_activity);
+    assert!(stats.last_activity.is_some());
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_count_freshness() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a sample word to be stored
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-freshness".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the word in IPDIS
+    let signed = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &signed).await.unwrap();
+
+    let query = GetWordsCounts {
+        word: word.key,
+        parent: false,
+        owned: false,
+        start_index: 0,
+        end_index: 1,
+        with_total: false,
+    };
+
+    // the counts may be served from the cache
+    let counts = client
+        .get_word_count_page_fresh_unchecked(None, &query, None)
+        .await
+        .unwrap();
+    assert_eq!(counts.data.items[0].count, 1);
+
+    // the stale counts should be computed again
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let fresh = client
+        .get_word_count_page_fresh_unchecked(None, &query, Some(Duration::ZERO))
+        .await
+        .unwrap();
+    assert!(!fresh.from_cache);
+    assert!(fresh.computed_at > counts.computed_at);
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_signature_retention() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a sample word to be stored
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-retention".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the word in IPDIS
+    let signed = ipiis.sign(account, word).unwrap();
+    client.put_word_unchecked(&parent, &signed).await.unwrap();
+
+    // the recent records keep their signatures
+    client.apply_signature_retention_unchecked().await.unwrap();
+    let word_from_ipdis = client
+        .get_word_latest_unchecked(None, &word.key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&word_from_ipdis.data.data.data, &word);
+    assert_eq!(word_from_ipdis.data.data.guarantee, signed.guarantee);
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_query_words() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a sample word to be stored
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-query".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test-query".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the word in IPDIS (* 3 times)
+    for _ in 0..3 {
+        let signed = ipiis.sign(account, word).unwrap();
+        client.put_word_unchecked(&parent, &signed).await.unwrap();
+    }
+
+    // count the words of today
+    let query = WordQuery::kind(word.kind)
+        .namespace(word.key.namespace)
+        .lang(word.key.text.lang)
+        .group_by_day()
+        .owned_by(account);
+    let rows = client.query_words_unchecked(None, &query).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].day.is_some());
+    assert_eq!(rows[0].count, 3);
+
+    // explain the same query, which is analyzed as executed
+    let plan = client.explain_query_unchecked(None, &query).await.unwrap();
+    assert!(plan.contains("words"));
+    assert!(plan.contains("Execution Time"));
+
+    // the whole query is signed, not only the kind
+    let sign = QueryWords::new(&query).unwrap();
+    sign.validate(&query).unwrap();
+    assert!(sign
+        .validate(&WordQuery::kind(word.kind).namespace(word.key.namespace))
+        .is_err());
+    assert!(sign.validate(&query.clone().limit(1)).is_err());
+
+    // the empty range should count nothing
+    let since = ::ipis::core::chrono::Utc::now() + ::ipis::core::chrono::Duration::days(1);
+    let query = WordQuery::kind(word.kind)
+        .namespace(word.key.namespace)
+        .between(since, since + ::ipis::core::chrono::Duration::days(1));
+    let rows = client.query_words_unchecked(None, &query).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].count, 0);
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_put_receipt() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_me().account_ref();
+
+    // create a sample word to be stored
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: "ipdis-api-postgres-test-receipt".to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: "ipdis-api-postgres-test".to_string(),
+        relpath: true,
+        path: Path {
+            value: "Gx1fwyQphUwVU5E2HRbx7H6t7QVZ8XsMzrFz6TnyxaC1"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the word in IPDIS
+    let signed = ipiis.sign(account, word).unwrap();
+    let receipt = client
+        .put_word_with_metadata_unchecked(&parent, &signed, None)
+        .await
+        .unwrap();
+    assert_eq!(receipt.nonce, signed.nonce.0);
+
+    // the receipt should refer to the stored record
+    match client
+        .get_record_by_nonce_unchecked(None, &receipt.nonce)
+        .await
+        .unwrap()
+    {
+        Some(SignedRecord::Word(record)) => {
+            assert_eq!(record.guarantor.signature, receipt.guarantor_signature);
+        }
+        record => panic!("unexpected record: {record:?}"),
+    }
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(&word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[test]
+fn test_tokenizer_plugin() {
+    /// Splits the message into the characters, as a naive segmentation of CJK.
+    struct Chars;
+
+    impl Tokenizer for Chars {
+        fn tokenize(&self, msg: &str) -> Vec<String> {
+            msg.chars()
+                .filter(|c| !c.is_whitespace())
+                .map(String::from)
+                .collect()
+        }
+    }
+
+    // the defaults split by the whitespaces
+    let text = Text::with_en_us("hello  world");
+    assert_eq!(tokenize(&text), ["hello", "world"]);
+    assert_eq!(
+        tokenize(&undetermined("東京 タワー").unwrap()),
+        ["東京", "タワー"]
+    );
+
+    // register the tokenizer of a language
+    register_tokenizer("ja", Box::new(Chars));
+    let text = Text {
+        msg: "東京タワー".to_string(),
+        lang: "ja".parse().unwrap(),
+    };
+    assert_eq!(tokenize(&text), ["東", "京", "タ", "ワ", "ー"]);
+
+    // the other languages are not affected
+    assert_eq!(
+        tokenize(&undetermined("東京 タワー").unwrap()),
+        ["東京", "タワー"]
+    );
+}
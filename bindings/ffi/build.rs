@@ -0,0 +1,13 @@
+fn main() {
+    let crate_dir = ::std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = ::std::env::var("OUT_DIR").unwrap();
+
+    let bindings = ::cbindgen::generate(&crate_dir).expect("failed to generate the C header");
+    bindings.write_to_file(format!("{out_dir}/ipdis.h"));
+
+    // the header is copied into the source tree only if requested, e.g. `IPDIS_FFI_INCLUDE_DIR=include`
+    println!("cargo:rerun-if-env-changed=IPDIS_FFI_INCLUDE_DIR");
+    if let Some(include_dir) = ::std::env::var_os("IPDIS_FFI_INCLUDE_DIR") {
+        bindings.write_to_file(::std::path::Path::new(&include_dir).join("ipdis.h"));
+    }
+}
@@ -0,0 +1,138 @@
+#ifndef IPDIS_H
+#define IPDIS_H
+
+/* This file is generated by cbindgen. Do not edit it manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum IpdisErrorCode {
+  IPDIS_ERROR_CODE_OK = 0,
+  IPDIS_ERROR_CODE_INVALID_ARGUMENT = 1,
+  IPDIS_ERROR_CODE_NOT_FOUND = 2,
+  IPDIS_ERROR_CODE_FEATURE_DISABLED = 3,
+  IPDIS_ERROR_CODE_READ_ONLY = 4,
+  IPDIS_ERROR_CODE_QUERY_TOO_LARGE = 5,
+  IPDIS_ERROR_CODE_PAYLOAD_TOO_LARGE = 6,
+  IPDIS_ERROR_CODE_BUSY = 7,
+  IPDIS_ERROR_CODE_RATE_LIMITED = 8,
+  IPDIS_ERROR_CODE_DUPLICATE = 9,
+  IPDIS_ERROR_CODE_FENCED = 10,
+  IPDIS_ERROR_CODE_LEASE_TOO_LONG = 11,
+  IPDIS_ERROR_CODE_STOP_WORD = 12,
+  IPDIS_ERROR_CODE_INTERNAL = 255,
+} IpdisErrorCode;
+
+/**
+ * An opaque handle of the IPDIS client.
+ */
+typedef struct IpdisClient IpdisClient;
+
+/**
+ * A byte buffer owned by the library, which should be released with `ipdis_buffer_free`.
+ */
+typedef struct IpdisBuffer {
+  uint8_t *data;
+  size_t len;
+} IpdisBuffer;
+
+/**
+ * Creates a client from the `ipis_*` environment variables.
+ *
+ * # Safety
+ *
+ * `out` should be a valid pointer.
+ */
+enum IpdisErrorCode ipdis_client_new(struct IpdisClient **out);
+
+/**
+ * Releases the client.
+ *
+ * # Safety
+ *
+ * `client` should be created by `ipdis_client_new` and should not be used anymore.
+ */
+void ipdis_client_free(struct IpdisClient *client);
+
+/**
+ * Registers the account as a guarantee of the primary IPDIS server.
+ *
+ * # Safety
+ *
+ * `client` should be created by `ipdis_client_new`.
+ */
+enum IpdisErrorCode ipdis_register(const struct IpdisClient *client);
+
+/**
+ * Puts a word, which refers to the given static path.
+ *
+ * The text is normalized (NFC, lowercase) before hashing.
+ *
+ * # Safety
+ *
+ * `client` should be created by `ipdis_client_new`,
+ * and the strings should be valid, nul-terminated UTF-8 strings.
+ */
+enum IpdisErrorCode ipdis_put_word(const struct IpdisClient *client,
+                                   const char *namespace_,
+                                   const char *kind,
+                                   const char *text,
+                                   const char *path,
+                                   uint64_t len,
+                                   const char *parent);
+
+/**
+ * Gets the number of the puts of the word.
+ *
+ * The text is normalized (NFC, lowercase) before hashing.
+ *
+ * # Safety
+ *
+ * `client` should be created by `ipdis_client_new`, `out` should be a valid pointer,
+ * and the strings should be valid, nul-terminated UTF-8 strings.
+ */
+enum IpdisErrorCode ipdis_get_word_count(const struct IpdisClient *client,
+                                         const char *namespace_,
+                                         const char *text,
+                                         bool owned,
+                                         uint32_t *out);
+
+/**
+ * Resolves the dynamic path into the static path as a string and its length.
+ *
+ * Returns `IPDIS_ERROR_CODE_NOT_FOUND` if no such path exists.
+ *
+ * # Safety
+ *
+ * `client` should be created by `ipdis_client_new`, `out_path` and `out_len` should be
+ * valid pointers, and the strings should be valid, nul-terminated UTF-8 strings.
+ */
+enum IpdisErrorCode ipdis_get_dyn_path(const struct IpdisClient *client,
+                                       const char *namespace_,
+                                       const char *kind,
+                                       const char *word,
+                                       struct IpdisBuffer *out_path,
+                                       uint64_t *out_len);
+
+/**
+ * Copies the message of the last error on this thread, if any.
+ *
+ * # Safety
+ *
+ * `out` should be a valid pointer.
+ */
+enum IpdisErrorCode ipdis_last_error(struct IpdisBuffer *out);
+
+/**
+ * Releases the buffer.
+ *
+ * # Safety
+ *
+ * `buffer` should be created by this library and should not be used anymore.
+ */
+void ipdis_buffer_free(struct IpdisBuffer buffer);
+
+#endif /* IPDIS_H */
@@ -0,0 +1,404 @@
+use std::{
+    cell::RefCell,
+    ffi::CStr,
+    os::raw::c_char,
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr::slice_from_raw_parts_mut,
+};
+
+use ipdis_common::{
+    normalize::{DefaultNormalizer, Normalized},
+    Ipdis, IpdisError, KIND,
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::{
+        anyhow::{anyhow, Error, Result},
+        value::{hash::Hash, text::Text},
+    },
+    env::Infer,
+    path::{DynPath, Path},
+    tokio::runtime::Runtime,
+    word::{Word, WordHash, WordKey, WordKeyHash},
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = RefCell::new(None);
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IpdisErrorCode {
+    Ok = 0,
+    InvalidArgument = 1,
+    NotFound = 2,
+    FeatureDisabled = 3,
+    ReadOnly = 4,
+    QueryTooLarge = 5,
+    PayloadTooLarge = 6,
+    Busy = 7,
+    RateLimited = 8,
+    Duplicate = 9,
+    Fenced = 10,
+    LeaseTooLong = 11,
+    StopWord = 12,
+    Internal = 255,
+}
+
+impl From<Error> for IpdisErrorCode {
+    fn from(error: Error) -> Self {
+        let code = match error.downcast_ref::<IpdisError>() {
+            Some(IpdisError::FeatureDisabled { .. }) => Self::FeatureDisabled,
+            Some(IpdisError::ReadOnly) => Self::ReadOnly,
+            Some(IpdisError::QueryTooLarge { .. }) => Self::QueryTooLarge,
+            Some(IpdisError::PayloadTooLarge { .. }) => Self::PayloadTooLarge,
+            Some(IpdisError::Busy { .. }) => Self::Busy,
+            Some(IpdisError::RateLimited { .. }) => Self::RateLimited,
+            Some(IpdisError::Duplicate { .. }) => Self::Duplicate,
+            Some(IpdisError::Fenced { .. }) => Self::Fenced,
+            Some(IpdisError::LeaseTooLong { .. }) => Self::LeaseTooLong,
+            Some(IpdisError::StopWord { .. }) => Self::StopWord,
+            None => Self::Internal,
+        };
+
+        LAST_ERROR.with(|last| last.replace(Some(error.to_string())));
+        code
+    }
+}
+
+/// An opaque handle of the IPDIS client.
+pub struct IpdisClient {
+    ipiis: IpiisClient,
+    runtime: Runtime,
+}
+
+/// A byte buffer owned by the library, which should be released with `ipdis_buffer_free`.
+#[repr(C)]
+pub struct IpdisBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl From<Vec<u8>> for IpdisBuffer {
+    fn from(data: Vec<u8>) -> Self {
+        let data = Box::into_raw(data.into_boxed_slice());
+        Self {
+            len: unsafe { (*data).len() },
+            data: data as *mut u8,
+        }
+    }
+}
+
+/// Creates a client from the `ipis_*` environment variables.
+///
+/// # Safety
+///
+/// `out` should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ipdis_client_new(out: *mut *mut IpdisClient) -> IpdisErrorCode {
+    guard(|| {
+        if out.is_null() {
+            return invalid_argument("out");
+        }
+
+        let client = Runtime::new().map_err(Into::into).and_then(|runtime| {
+            let ipiis = runtime.block_on(IpiisClient::try_infer())?;
+            Ok(IpdisClient { ipiis, runtime })
+        });
+
+        match client {
+            Ok(client) => {
+                *out = Box::into_raw(Box::new(client));
+                IpdisErrorCode::Ok
+            }
+            Err(error) => error.into(),
+        }
+    })
+}
+
+/// Releases the client.
+///
+/// # Safety
+///
+/// `client` should be created by `ipdis_client_new` and should not be used anymore.
+#[no_mangle]
+pub unsafe extern "C" fn ipdis_client_free(client: *mut IpdisClient) {
+    guard(|| {
+        if !client.is_null() {
+            drop(Box::from_raw(client));
+        }
+        IpdisErrorCode::Ok
+    });
+}
+
+/// Registers the account as a guarantee of the primary IPDIS server.
+///
+/// # Safety
+///
+/// `client` should be created by `ipdis_client_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ipdis_register(client: *const IpdisClient) -> IpdisErrorCode {
+    guard(|| {
+        let client = match client.as_ref() {
+            Some(client) => client,
+            None => return invalid_argument("client"),
+        };
+
+        client.block_on(async {
+            let guarantee = client.ipiis.account_me().account_ref();
+            let guarantor = client.ipiis.get_account_primary(KIND.as_ref()).await?;
+
+            let guarantee = client.ipiis.sign(guarantor, guarantee)?;
+            client.ipiis.add_guarantee_unchecked(&guarantee).await
+        })
+    })
+}
+
+/// Puts a word, which refers to the given static path.
+///
+/// The text is normalized (NFC, lowercase) before hashing.
+///
+/// # Safety
+///
+/// `client` should be created by `ipdis_client_new`,
+/// and the strings should be valid, nul-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ipdis_put_word(
+    client: *const IpdisClient,
+    namespace: *const c_char,
+    kind: *const c_char,
+    text: *const c_char,
+    path: *const c_char,
+    len: u64,
+    parent: *const c_char,
+) -> IpdisErrorCode {
+    guard(|| {
+        let client = match client.as_ref() {
+            Some(client) => client,
+            None => return invalid_argument("client"),
+        };
+        let (namespace, kind, text, path, parent) = match (
+            as_str(namespace),
+            as_str(kind),
+            as_str(text),
+            as_str(path).and_then(|path| path.parse().map_err(Into::into)),
+            as_str(parent),
+        ) {
+            (Ok(namespace), Ok(kind), Ok(text), Ok(path), Ok(parent)) => {
+                (namespace, kind, text, path, parent)
+            }
+            _ => return invalid_argument("word"),
+        };
+
+        client.block_on(async {
+            let word: WordHash = Word {
+                key: WordKey {
+                    namespace: namespace.to_string(),
+                    text: Text::with_en_us(text),
+                },
+                kind: kind.to_string(),
+                relpath: true,
+                path: Path { value: path, len },
+            }
+            .normalized(&DefaultNormalizer::default())
+            .into();
+            let parent = Hash::with_str(parent);
+
+            // sign as guarantee
+            let target = client.ipiis.get_account_primary(KIND.as_ref()).await?;
+            let word = client.ipiis.sign(target, word)?;
+
+            client.ipiis.put_word_unchecked(&parent, &word).await
+        })
+    })
+}
+
+/// Gets the number of the puts of the word.
+///
+/// The text is normalized (NFC, lowercase) before hashing.
+///
+/// # Safety
+///
+/// `client` should be created by `ipdis_client_new`, `out` should be a valid pointer,
+/// and the strings should be valid, nul-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ipdis_get_word_count(
+    client: *const IpdisClient,
+    namespace: *const c_char,
+    text: *const c_char,
+    owned: bool,
+    out: *mut u32,
+) -> IpdisErrorCode {
+    guard(|| {
+        let client = match client.as_ref() {
+            Some(client) => client,
+            None => return invalid_argument("client"),
+        };
+        let word = match (as_str(namespace), as_str(text)) {
+            (Ok(namespace), Ok(text)) => word_key(namespace, text),
+            _ => return invalid_argument("word"),
+        };
+        if out.is_null() {
+            return invalid_argument("out");
+        }
+
+        client.block_on(async {
+            *out = client
+                .ipiis
+                .get_word_count_unchecked(None, &word, owned)
+                .await?;
+            Ok(())
+        })
+    })
+}
+
+/// Resolves the dynamic path into the static path as a string and its length.
+///
+/// Returns `IPDIS_ERROR_CODE_NOT_FOUND` if no such path exists.
+///
+/// # Safety
+///
+/// `client` should be created by `ipdis_client_new`, `out_path` and `out_len` should be
+/// valid pointers, and the strings should be valid, nul-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ipdis_get_dyn_path(
+    client: *const IpdisClient,
+    namespace: *const c_char,
+    kind: *const c_char,
+    word: *const c_char,
+    out_path: *mut IpdisBuffer,
+    out_len: *mut u64,
+) -> IpdisErrorCode {
+    guard(|| {
+        let client = match client.as_ref() {
+            Some(client) => client,
+            None => return invalid_argument("client"),
+        };
+        let path = match (as_str(namespace), as_str(kind), as_str(word)) {
+            (Ok(namespace), Ok(kind), Ok(word)) => DynPath {
+                namespace: Hash::with_str(namespace),
+                kind: Hash::with_str(kind),
+                word: Hash::with_str(word),
+                path: (),
+            },
+            _ => return invalid_argument("path"),
+        };
+        if out_path.is_null() || out_len.is_null() {
+            return invalid_argument("out");
+        }
+
+        let path = match client
+            .runtime
+            .block_on(client.ipiis.get_dyn_path_unchecked(None, &path))
+        {
+            Ok(Some(path)) => path,
+            Ok(None) => return IpdisErrorCode::NotFound,
+            Err(error) => return error.into(),
+        };
+
+        *out_path = path
+            .data
+            .data
+            .data
+            .path
+            .value
+            .to_string()
+            .into_bytes()
+            .into();
+        *out_len = path.data.data.data.path.len;
+        IpdisErrorCode::Ok
+    })
+}
+
+/// Copies the message of the last error on this thread, if any.
+///
+/// # Safety
+///
+/// `out` should be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn ipdis_last_error(out: *mut IpdisBuffer) -> IpdisErrorCode {
+    guard(|| {
+        if out.is_null() {
+            return invalid_argument("out");
+        }
+
+        match LAST_ERROR.with(|last| last.borrow().clone()) {
+            Some(message) => {
+                *out = message.into_bytes().into();
+                IpdisErrorCode::Ok
+            }
+            None => IpdisErrorCode::NotFound,
+        }
+    })
+}
+
+/// Releases the buffer.
+///
+/// # Safety
+///
+/// `buffer` should be created by this library and should not be used anymore.
+#[no_mangle]
+pub unsafe extern "C" fn ipdis_buffer_free(buffer: IpdisBuffer) {
+    guard(|| {
+        if !buffer.data.is_null() {
+            drop(Box::from_raw(slice_from_raw_parts_mut(
+                buffer.data,
+                buffer.len,
+            )));
+        }
+        IpdisErrorCode::Ok
+    });
+}
+
+/// Runs the body of an exported function, reporting a panic as `IpdisErrorCode::Internal`
+/// rather than unwinding across the FFI boundary.
+fn guard<F>(f: F) -> IpdisErrorCode
+where
+    F: FnOnce() -> IpdisErrorCode,
+{
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(code) => code,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            LAST_ERROR.with(|last| last.replace(Some(format!("panicked: {message}"))));
+            IpdisErrorCode::Internal
+        }
+    }
+}
+
+impl IpdisClient {
+    fn block_on<F>(&self, f: F) -> IpdisErrorCode
+    where
+        F: ::core::future::Future<Output = Result<()>>,
+    {
+        match self.runtime.block_on(f) {
+            Ok(()) => IpdisErrorCode::Ok,
+            Err(error) => error.into(),
+        }
+    }
+}
+
+unsafe fn as_str<'a>(s: *const c_char) -> Result<&'a str> {
+    if s.is_null() {
+        return Err(anyhow!("null string"));
+    }
+    CStr::from_ptr(s).to_str().map_err(Into::into)
+}
+
+fn invalid_argument(name: &str) -> IpdisErrorCode {
+    LAST_ERROR.with(|last| last.replace(Some(format!("invalid argument: {name}"))));
+    IpdisErrorCode::InvalidArgument
+}
+
+fn word_key(namespace: &str, text: &str) -> WordKeyHash {
+    WordKey {
+        namespace: namespace.to_string(),
+        text: Text::with_en_us(text),
+    }
+    .normalized(&DefaultNormalizer::default())
+    .into()
+}
@@ -0,0 +1,179 @@
+use ipdis_common::{
+    normalize::{DefaultNormalizer, Normalized},
+    Ipdis,
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::{
+        anyhow::Result,
+        value::{hash::Hash, text::Text},
+    },
+    env::Infer,
+    path::{DynPath, Path},
+    tokio::runtime::Runtime,
+    word::{Word, WordHash, WordKey, WordKeyHash},
+};
+use pyo3::{exceptions::PyRuntimeError, prelude::*};
+
+/// A blocking IPDIS client, which talks to the primary IPDIS server of the account.
+#[pyclass(unsendable)]
+struct Client {
+    ipiis: IpiisClient,
+    runtime: Runtime,
+}
+
+#[pymethods]
+impl Client {
+    /// Creates a client from the `ipis_*` environment variables.
+    #[new]
+    fn new() -> PyResult<Self> {
+        let runtime = Runtime::new()?;
+        let ipiis = runtime
+            .block_on(IpiisClient::try_infer())
+            .map_err(into_py_err)?;
+
+        Ok(Self { ipiis, runtime })
+    }
+
+    /// Registers the account as a guarantee of the primary IPDIS server.
+    fn register(&self) -> PyResult<()> {
+        self.block_on(async {
+            let guarantee = self.ipiis.account_me().account_ref();
+            let guarantor = self
+                .ipiis
+                .get_account_primary(::ipdis_common::KIND.as_ref())
+                .await?;
+
+            let guarantee = self.ipiis.sign(guarantor, guarantee)?;
+            self.ipiis.add_guarantee_unchecked(&guarantee).await
+        })
+    }
+
+    #[args(parent = "\"\"")]
+    fn put_word(
+        &self,
+        namespace: &str,
+        kind: &str,
+        text: &str,
+        path: &str,
+        len: u64,
+        parent: &str,
+    ) -> PyResult<()> {
+        self.block_on(async {
+            let word: WordHash = Word {
+                key: WordKey {
+                    namespace: namespace.to_string(),
+                    text: Text::with_en_us(text),
+                },
+                kind: kind.to_string(),
+                relpath: true,
+                path: Path {
+                    value: path.parse()?,
+                    len,
+                },
+            }
+            .normalized(&DefaultNormalizer::default())
+            .into();
+            let parent = Hash::with_str(parent);
+
+            // sign as guarantee
+            let target = self
+                .ipiis
+                .get_account_primary(::ipdis_common::KIND.as_ref())
+                .await?;
+            let word = self.ipiis.sign(target, word)?;
+
+            self.ipiis.put_word_unchecked(&parent, &word).await
+        })
+    }
+
+    /// Returns the latest `(kind, path, len)` of the word, if any.
+    fn get_word_latest(
+        &self,
+        namespace: &str,
+        text: &str,
+    ) -> PyResult<Option<(String, String, u64)>> {
+        self.block_on(async {
+            let word = word_key(namespace, text);
+
+            Ok(self
+                .ipiis
+                .get_word_latest_unchecked(None, &word)
+                .await?
+                .map(|word| {
+                    (
+                        word.data.data.data.kind.to_string(),
+                        word.data.data.data.path.value.to_string(),
+                        word.data.data.data.path.len,
+                    )
+                }))
+        })
+    }
+
+    #[args(owned = "false")]
+    fn get_word_count(&self, namespace: &str, text: &str, owned: bool) -> PyResult<u32> {
+        self.block_on(async {
+            let word = word_key(namespace, text);
+
+            self.ipiis
+                .get_word_count_unchecked(None, &word, owned)
+                .await
+        })
+    }
+
+    /// Resolves the dynamic path into `(path, len)`, if any.
+    fn get_dyn_path(
+        &self,
+        namespace: &str,
+        kind: &str,
+        word: &str,
+    ) -> PyResult<Option<(String, u64)>> {
+        self.block_on(async {
+            let path = DynPath {
+                namespace: Hash::with_str(namespace),
+                kind: Hash::with_str(kind),
+                word: Hash::with_str(word),
+                path: (),
+            };
+
+            Ok(self
+                .ipiis
+                .get_dyn_path_unchecked(None, &path)
+                .await?
+                .map(|path| {
+                    (
+                        path.data.data.data.path.value.to_string(),
+                        path.data.data.data.path.len,
+                    )
+                }))
+        })
+    }
+}
+
+impl Client {
+    fn block_on<F, T>(&self, f: F) -> PyResult<T>
+    where
+        F: ::core::future::Future<Output = Result<T>>,
+    {
+        self.runtime.block_on(f).map_err(into_py_err)
+    }
+}
+
+fn word_key(namespace: &str, text: &str) -> WordKeyHash {
+    WordKey {
+        namespace: namespace.to_string(),
+        text: Text::with_en_us(text),
+    }
+    .normalized(&DefaultNormalizer::default())
+    .into()
+}
+
+fn into_py_err(error: ::ipis::core::anyhow::Error) -> PyErr {
+    PyRuntimeError::new_err(error.to_string())
+}
+
+#[pymodule]
+fn ipdis(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Client>()?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use core::marker::PhantomData;
+
+use ipis::core::anyhow::{anyhow, Result};
+use rkyv::{
+    ser::serializers::AllocSerializer, validation::validators::DefaultValidator, AlignedVec,
+    Archive, Archived, Deserialize, Infallible, Serialize,
+};
+
+use crate::Page;
+
+/// the scratch space of the serializer, in bytes
+const SCRATCH_SPACE: usize = 4096;
+
+/// A serialized page, whose items are accessed in place without deserializing them.
+///
+/// The bytes are validated once on construction,
+/// so that the memory-constrained consumers can iterate the large pages
+/// (e.g. the ones persisted or forwarded as they are) without a full materialization.
+pub struct ArchivedPageBuf<T> {
+    bytes: AlignedVec,
+    _type: PhantomData<T>,
+}
+
+impl<T> ArchivedPageBuf<T>
+where
+    T: Archive,
+    Archived<Page<T>>: for<'a> ::bytecheck::CheckBytes<DefaultValidator<'a>>,
+{
+    /// Validates the serialized page.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut aligned = AlignedVec::with_capacity(bytes.len());
+        aligned.extend_from_slice(bytes);
+
+        ::rkyv::check_archived_root::<Page<T>>(&aligned)
+            .map_err(|error| anyhow!("malformed archived page: {error}"))?;
+
+        Ok(Self {
+            bytes: aligned,
+            _type: PhantomData,
+        })
+    }
+
+    /// Serializes the page.
+    pub fn from_page(page: &Page<T>) -> Result<Self>
+    where
+        Page<T>: Serialize<AllocSerializer<SCRATCH_SPACE>>,
+    {
+        let bytes = ::rkyv::to_bytes::<_, SCRATCH_SPACE>(page)
+            .map_err(|error| anyhow!("failed to archive the page: {error}"))?;
+
+        Ok(Self {
+            bytes,
+            _type: PhantomData,
+        })
+    }
+
+    pub fn archived(&self) -> &Archived<Page<T>> {
+        // SAFETY: the bytes have been validated or serialized on construction
+        unsafe { ::rkyv::archived_root::<Page<T>>(&self.bytes) }
+    }
+
+    pub fn items(&self) -> &[Archived<T>] {
+        &self.archived().items
+    }
+
+    pub fn next_cursor(&self) -> Option<u32> {
+        self.archived()
+            .next_cursor
+            .deserialize(&mut Infallible)
+            .expect("infallible")
+    }
+
+    pub fn total(&self) -> Option<u32> {
+        self.archived()
+            .total
+            .deserialize(&mut Infallible)
+            .expect("infallible")
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Deserializes the whole page.
+    pub fn to_page(&self) -> Page<T>
+    where
+        Archived<Page<T>>: Deserialize<Page<T>, Infallible>,
+    {
+        self.archived()
+            .deserialize(&mut Infallible)
+            .expect("infallible")
+    }
+}
@@ -0,0 +1,1490 @@
+use std::time::Duration;
+
+use ipiis_common::Ipiis;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{bail, Result},
+        value::{hash::Hash, text::TextHash, uuid::Uuid},
+    },
+    path::{DynPath, Path},
+    word::{WordHash, WordKeyHash},
+};
+
+use crate::{
+    ensure_metadata_len, AccountStats, AcquireWriterLease, AddStopWord, ApiTokenInfo, Delegation,
+    DescribeKind, FeatureSet, Fresh, GetAccountChain, GetAccountStats, GetApiTokens,
+    GetDynPathsByTarget, GetDynPathsMany, GetIdfLogs, GetIdfVector, GetInclusionProof, GetKind,
+    GetKinds, GetMembers, GetOplog, GetPathReferenceCount, GetRecordByNonce, GetServerDiagnostics,
+    GetSimilarDocuments, GetStopWords, GetWordCountAllLangs, GetWordCountDelta,
+    GetWordFrequencyHistogram, GetWords, GetWordsCounts, GetWordsCountsBatch, GetWordsCountsOutput,
+    IdfVector, InclusionProof, Ipdis, IpdisAdmin, IssueApiToken, IssuedApiToken, KindInfo,
+    LinkAccountSuccessor, Member, Normalization, Oplog, Page, PutReceipt, PutWordsBatch,
+    QueryWords, RegisterKind, RevokeApiToken, ServerDiagnostics, SetReadOnly, SignedRecord,
+    SimilarDocument, WithMetadata, WordCountDelta, WordFrequencyBucket, WordQuery, WordQueryRow,
+    WriterLease, KIND,
+};
+
+/// Calls the server as `ipiis_common::external_call!`, recovering the typed errors of the server,
+/// which are sent as their messages.
+macro_rules! external_call {
+    ( $( $tt:tt )* ) => {
+        async { Ok::<_, ::ipis::core::anyhow::Error>(::ipiis_common::external_call!($( $tt )*)) }
+            .await
+            .map_err(crate::IpdisError::recover)?
+    };
+}
+
+/// The remote client, which talks to an IPDIS server over the IPIIS protocol.
+///
+/// It has no database dependency, so it can be built for `wasm32-unknown-unknown`
+/// with `default-features = false, features = ["client"]`.
+pub struct IpdisRemote<'a, IpiisClient> {
+    pub ipiis: &'a IpiisClient,
+    pub target: AccountRef,
+}
+
+impl<'a, IpiisClient> IpdisRemote<'a, IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    pub fn new(ipiis: &'a IpiisClient, target: AccountRef) -> Self {
+        Self { ipiis, target }
+    }
+
+    /// Targets the primary IPDIS server of the account.
+    pub async fn with_primary(ipiis: &'a IpiisClient) -> Result<IpdisRemote<'a, IpiisClient>> {
+        let target = ipiis.get_account_primary(KIND.as_ref()).await?;
+        Ok(Self::new(ipiis, target))
+    }
+
+    /// Returns the latest dynamic path, authenticated by an API token of the server
+    /// rather than by the guarantee of the account.
+    ///
+    /// The account of the IPIIS client is neither allowlisted nor registered,
+    /// so an ephemeral one is enough, e.g. `IpiisClient::genesis(None)`.
+    pub async fn get_dyn_path_record_with_token<Path>(
+        &self,
+        token: &str,
+        path: &DynPath<Path>,
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
+    where
+        Path: Copy,
+    {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (path,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => DynPathGetWithToken,
+            sign: self.ipiis.sign(target, (*path).remove_path())?,
+            inputs: {
+                token: token.to_string(),
+            },
+            outputs: { path, },
+        );
+
+        // unpack response
+        Ok(path)
+    }
+
+    /// Returns a page of the words, authenticated by an API token of the server
+    /// rather than by the guarantee of the account.
+    ///
+    /// The account of the IPIIS client is neither allowlisted nor registered,
+    /// so an ephemeral one is enough, e.g. `IpiisClient::genesis(None)`.
+    pub async fn get_word_record_page_with_token(
+        &self,
+        token: &str,
+        query: &GetWords,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (words,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordGetManyWithToken,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: {
+                token: token.to_string(),
+            },
+            outputs: { words, },
+        );
+
+        // unpack response
+        Ok(words)
+    }
+}
+
+#[async_trait]
+impl<'a, IpiisClient> Ipdis for IpdisRemote<'a, IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn ensure_registered(
+        &self,
+        guarantee: &AccountRef,
+        _guarantor: &AccountRef,
+    ) -> Result<()> {
+        let guarantee_now = self.ipiis.account_me().account_ref();
+        if guarantee != &guarantee_now {
+            bail!("failed to authenticate the guarantee")
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_admin(&self, guarantee: &AccountRef, guarantor: &AccountRef) -> Result<()> {
+        self.ensure_registered(guarantee, guarantor).await
+    }
+
+    async fn get_kind_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetKind,
+    ) -> Result<Option<KindInfo>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (info,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => KindGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { info, },
+        );
+
+        // unpack response
+        Ok(info)
+    }
+
+    async fn get_kind_page_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetKinds,
+    ) -> Result<Page<KindInfo>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (kinds,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => KindGetMany,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { kinds, },
+        );
+
+        // unpack response
+        Ok(kinds)
+    }
+
+    async fn acquire_writer_lease_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &AcquireWriterLease,
+    ) -> Result<WriterLease> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (lease,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WriterLeaseAcquire,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { lease, },
+        );
+
+        // unpack response
+        Ok(lease)
+    }
+
+    async fn add_guarantee_unchecked(&self, guarantee: &GuaranteeSigned<AccountRef>) -> Result<()> {
+        // next target
+        let target = self.target;
+
+        // external call
+        external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => GuaranteePut,
+            sign: *guarantee,
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn link_account_successor_unchecked(
+        &self,
+        proof: &GuaranteeSigned<LinkAccountSuccessor>,
+    ) -> Result<()> {
+        // next target
+        let target = self.target;
+
+        // external call
+        external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => AccountSuccessorLink,
+            sign: *proof,
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn get_account_chain_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetAccountChain,
+    ) -> Result<Vec<AccountRef>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (chain,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => AccountChainGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { chain, },
+        );
+
+        // unpack response
+        Ok(chain)
+    }
+
+    async fn get_dyn_path_record_unchecked<Path>(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (path,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => DynPathGet,
+            sign: self.ipiis.sign(target, (*path).remove_path())?,
+            inputs: { },
+            outputs: { path, },
+        );
+
+        // unpack response
+        Ok(path)
+    }
+
+    async fn get_dyn_path_many_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        paths: &[DynPath<()>],
+    ) -> Result<Vec<Option<GuarantorSigned<DynPath<Path>>>>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (paths,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => DynPathGetMany,
+            sign: self.ipiis.sign(target, GetDynPathsMany::new(paths)?)?,
+            inputs: {
+                paths: paths.to_vec(),
+            },
+            outputs: { paths, },
+        );
+
+        // unpack response
+        Ok(paths)
+    }
+
+    async fn get_members_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetMembers,
+    ) -> Result<Vec<Member>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (members,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => MembersGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { members, },
+        );
+
+        // unpack response
+        Ok(members)
+    }
+
+    async fn get_record_by_nonce_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<SignedRecord>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (record,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => RecordGetByNonce,
+            sign: self.ipiis.sign(target, GetRecordByNonce { nonce: *nonce })?,
+            inputs: { },
+            outputs: { record, },
+        );
+
+        // unpack response
+        Ok(record)
+    }
+
+    async fn get_oplog_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetOplog,
+    ) -> Result<Oplog> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (oplog,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => OplogGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { oplog, },
+        );
+
+        // unpack response
+        Ok(oplog)
+    }
+
+    async fn get_inclusion_proof_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<InclusionProof>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (proof,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => InclusionProofGet,
+            sign: self.ipiis.sign(target, GetInclusionProof { nonce: *nonce })?,
+            inputs: { },
+            outputs: { proof, },
+        );
+
+        // unpack response
+        Ok(proof)
+    }
+
+    async fn put_dyn_path_fenced_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+    ) -> Result<PutReceipt> {
+        ensure_metadata_len(metadata)?;
+
+        // next target
+        let target = self.target;
+
+        // external call
+        let (receipt,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => DynPathPut,
+            sign: *path,
+            inputs: {
+                metadata: metadata.map(ToOwned::to_owned),
+                on_behalf_of: on_behalf_of.copied(),
+                fencing_token,
+            },
+            outputs: { receipt, },
+        );
+
+        // unpack response
+        Ok(receipt)
+    }
+
+    async fn get_word_record_page_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (words,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordGetMany,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { words, },
+        );
+
+        // unpack response
+        Ok(words)
+    }
+
+    async fn get_word_count_page_fresh_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+        max_staleness: Option<Duration>,
+    ) -> Result<Fresh<Page<GetWordsCountsOutput>>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (counts,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordCountGetMany,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: {
+                max_staleness_ms: max_staleness.map(|d| d.as_millis().try_into().unwrap_or(u64::MAX)),
+            },
+            outputs: { counts, },
+        );
+
+        // unpack response
+        Ok(counts)
+    }
+
+    async fn get_word_count_page_batch_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        queries: &[GetWordsCounts],
+    ) -> Result<Vec<Page<GetWordsCountsOutput>>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (pages,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordCountGetBatch,
+            sign: self.ipiis.sign(target, GetWordsCountsBatch::new(queries)?)?,
+            inputs: {
+                queries: queries.to_vec(),
+            },
+            outputs: { pages, },
+        );
+
+        // unpack response
+        Ok(pages)
+    }
+
+    async fn get_word_count_all_langs_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetWordCountAllLangs,
+    ) -> Result<u32> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (count,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordCountGetAllLangs,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { count, },
+        );
+
+        // unpack response
+        Ok(count)
+    }
+
+    async fn get_word_count_delta_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetWordCountDelta,
+    ) -> Result<WordCountDelta> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (delta,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordCountDeltaGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { delta, },
+        );
+
+        // unpack response
+        Ok(delta)
+    }
+
+    async fn get_word_frequency_histogram_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetWordFrequencyHistogram,
+    ) -> Result<Vec<WordFrequencyBucket>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (buckets,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordFrequencyHistogramGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { buckets, },
+        );
+
+        // unpack response
+        Ok(buckets)
+    }
+
+    async fn get_idf_vector_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+    ) -> Result<IdfVector> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (vector,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => IdfVectorGet,
+            sign: self.ipiis.sign(target, GetIdfVector::new(words)?)?,
+            inputs: {
+                words: words.to_vec(),
+            },
+            outputs: { vector, },
+        );
+
+        // unpack response
+        Ok(vector)
+    }
+
+    async fn get_similar_documents_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+        top_k: u32,
+        owned: bool,
+    ) -> Result<Vec<SimilarDocument>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (documents,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => SimilarDocumentsGet,
+            sign: self.ipiis.sign(target, GetSimilarDocuments::new(words, top_k, owned)?)?,
+            inputs: {
+                words: words.to_vec(),
+            },
+            outputs: { documents, },
+        );
+
+        // unpack response
+        Ok(documents)
+    }
+
+    async fn query_words_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<Vec<WordQueryRow>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (rows,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordQueryGet,
+            sign: self.ipiis.sign(target, QueryWords::new(query)?)?,
+            inputs: {
+                query: query.clone(),
+            },
+            outputs: { rows, },
+        );
+
+        // unpack response
+        Ok(rows)
+    }
+
+    async fn put_word_normalized_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<PutReceipt> {
+        ensure_metadata_len(metadata)?;
+
+        // next target
+        let target = self.target;
+
+        // external call
+        let (receipt,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordPut,
+            sign: *word,
+            inputs: {
+                parent: *parent,
+                metadata: metadata.map(ToOwned::to_owned),
+                on_behalf_of: on_behalf_of.copied(),
+                fencing_token,
+                normalization,
+            },
+            outputs: { receipt, },
+        );
+
+        // unpack response
+        Ok(receipt)
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Vec<PutReceipt>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (receipts,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordPutMany,
+            sign: self.ipiis.sign(
+                target,
+                PutWordsBatch::new(words, fencing_token, normalization)?,
+            )?,
+            inputs: {
+                parent: *parent,
+                words: words.to_vec(),
+                fencing_token,
+                normalization,
+            },
+            outputs: { receipts, },
+        );
+
+        // unpack response
+        Ok(receipts)
+    }
+}
+
+#[async_trait]
+impl<'a, IpiisClient> IpdisAdmin for IpdisRemote<'a, IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn set_read_only_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        enabled: bool,
+    ) -> Result<()> {
+        // next target
+        let target = self.target;
+
+        // external call
+        external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => ReadOnlySet,
+            sign: self.ipiis.sign(target, SetReadOnly { enabled })?,
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn get_server_diagnostics_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetServerDiagnostics,
+    ) -> Result<ServerDiagnostics> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (diagnostics,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => DiagnosticsGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { diagnostics, },
+        );
+
+        // unpack response
+        Ok(diagnostics)
+    }
+
+    async fn get_account_stats_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetAccountStats,
+    ) -> Result<AccountStats> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (stats,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => AccountStatsGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { stats, },
+        );
+
+        // unpack response
+        Ok(stats)
+    }
+
+    async fn register_kind_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &RegisterKind,
+        name: &str,
+        description: &str,
+    ) -> Result<()> {
+        // next target
+        let target = self.target;
+
+        // external call
+        external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => KindRegister,
+            sign: self
+                .ipiis
+                .sign(target, DescribeKind::new(*query, name, description)?)?,
+            inputs: {
+                name: name.to_string(),
+                description: description.to_string(),
+            },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn get_path_reference_count_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        path: &Hash,
+    ) -> Result<u32> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (count,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => PathReferenceCountGet,
+            sign: self.ipiis.sign(target, GetPathReferenceCount { path: *path })?,
+            inputs: { },
+            outputs: { count, },
+        );
+
+        // unpack response
+        Ok(count)
+    }
+
+    async fn get_dyn_path_by_target_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetDynPathsByTarget,
+    ) -> Result<Page<GuarantorSigned<DynPath<Path>>>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (paths,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => DynPathGetByTarget,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { paths, },
+        );
+
+        // unpack response
+        Ok(paths)
+    }
+
+    async fn get_idf_log_page_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetIdfLogs,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (words,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => IdfLogsGet,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { words, },
+        );
+
+        // unpack response
+        Ok(words)
+    }
+
+    async fn explain_query_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<String> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (plan,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => QueryExplain,
+            sign: self.ipiis.sign(target, QueryWords::new(query)?)?,
+            inputs: { query: query.clone(), },
+            outputs: { plan, },
+        );
+
+        // unpack response
+        Ok(plan)
+    }
+
+    async fn issue_api_token_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        name: &str,
+        scopes: &FeatureSet,
+        rate_limit: u32,
+        expiration_time: Option<i64>,
+    ) -> Result<IssuedApiToken> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (token,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => ApiTokenIssue,
+            sign: self.ipiis.sign(
+                target,
+                IssueApiToken::new(name, scopes, rate_limit, expiration_time)?,
+            )?,
+            inputs: {
+                name: name.to_string(),
+                scopes: scopes.to_string(),
+            },
+            outputs: { token, },
+        );
+
+        // unpack response
+        Ok(token)
+    }
+
+    async fn get_api_token_page_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetApiTokens,
+    ) -> Result<Page<ApiTokenInfo>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (tokens,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => ApiTokenGetMany,
+            sign: self.ipiis.sign(target, *query)?,
+            inputs: { },
+            outputs: { tokens, },
+        );
+
+        // unpack response
+        Ok(tokens)
+    }
+
+    async fn revoke_api_token_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        id: i32,
+    ) -> Result<()> {
+        // next target
+        let target = self.target;
+
+        // external call
+        external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => ApiTokenRevoke,
+            sign: self.ipiis.sign(target, RevokeApiToken { id })?,
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn add_stop_word_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        kind: &Hash,
+        word: &TextHash,
+    ) -> Result<()> {
+        // next target
+        let target = self.target;
+
+        // external call
+        external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => StopWordAdd,
+            sign: self.ipiis.sign(
+                target,
+                AddStopWord {
+                    kind: *kind,
+                    word: *word,
+                },
+            )?,
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn list_stop_words_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        kind: &Hash,
+    ) -> Result<Vec<TextHash>> {
+        // next target
+        let target = self.target;
+
+        // external call
+        let (words,) = external_call!(
+            client: self.ipiis,
+            target: KIND.as_ref() => &target,
+            request: crate::io => StopWordGetMany,
+            sign: self.ipiis.sign(target, GetStopWords { kind: *kind })?,
+            inputs: { },
+            outputs: { words, },
+        );
+
+        // unpack response
+        Ok(words)
+    }
+}
+
+/// Talks to the primary IPDIS server of the account.
+#[async_trait]
+impl<IpiisClient> Ipdis for IpiisClient
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn ensure_registered(
+        &self,
+        guarantee: &AccountRef,
+        _guarantor: &AccountRef,
+    ) -> Result<()> {
+        let guarantee_now = self.account_me().account_ref();
+        if guarantee != &guarantee_now {
+            bail!("failed to authenticate the guarantee")
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_admin(&self, guarantee: &AccountRef, guarantor: &AccountRef) -> Result<()> {
+        self.ensure_registered(guarantee, guarantor).await
+    }
+
+    async fn get_kind_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetKind,
+    ) -> Result<Option<KindInfo>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_kind_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_kind_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetKinds,
+    ) -> Result<Page<KindInfo>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_kind_page_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn acquire_writer_lease_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &AcquireWriterLease,
+    ) -> Result<WriterLease> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .acquire_writer_lease_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn add_guarantee_unchecked(&self, guarantee: &GuaranteeSigned<AccountRef>) -> Result<()> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .add_guarantee_unchecked(guarantee)
+            .await
+    }
+
+    async fn link_account_successor_unchecked(
+        &self,
+        proof: &GuaranteeSigned<LinkAccountSuccessor>,
+    ) -> Result<()> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .link_account_successor_unchecked(proof)
+            .await
+    }
+
+    async fn get_account_chain_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetAccountChain,
+    ) -> Result<Vec<AccountRef>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_account_chain_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_dyn_path_record_unchecked<Path>(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_dyn_path_record_unchecked(guarantee, path)
+            .await
+    }
+
+    async fn get_dyn_path_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        paths: &[DynPath<()>],
+    ) -> Result<Vec<Option<GuarantorSigned<DynPath<Path>>>>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_dyn_path_many_unchecked(guarantee, paths)
+            .await
+    }
+
+    async fn get_members_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetMembers,
+    ) -> Result<Vec<Member>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_members_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_record_by_nonce_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<SignedRecord>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_record_by_nonce_unchecked(guarantee, nonce)
+            .await
+    }
+
+    async fn get_oplog_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetOplog,
+    ) -> Result<Oplog> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_oplog_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_inclusion_proof_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<InclusionProof>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_inclusion_proof_unchecked(guarantee, nonce)
+            .await
+    }
+
+    async fn put_dyn_path_fenced_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+    ) -> Result<PutReceipt> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .put_dyn_path_fenced_unchecked(path, metadata, on_behalf_of, fencing_token)
+            .await
+    }
+
+    async fn get_word_record_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_word_record_page_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_word_count_page_fresh_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+        max_staleness: Option<Duration>,
+    ) -> Result<Fresh<Page<GetWordsCountsOutput>>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_word_count_page_fresh_unchecked(guarantee, query, max_staleness)
+            .await
+    }
+
+    async fn get_word_count_page_batch_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        queries: &[GetWordsCounts],
+    ) -> Result<Vec<Page<GetWordsCountsOutput>>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_word_count_page_batch_unchecked(guarantee, queries)
+            .await
+    }
+
+    async fn get_word_count_all_langs_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountAllLangs,
+    ) -> Result<u32> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_word_count_all_langs_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_word_count_delta_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountDelta,
+    ) -> Result<WordCountDelta> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_word_count_delta_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_word_frequency_histogram_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordFrequencyHistogram,
+    ) -> Result<Vec<WordFrequencyBucket>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_word_frequency_histogram_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_idf_vector_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+    ) -> Result<IdfVector> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_idf_vector_unchecked(guarantee, words)
+            .await
+    }
+
+    async fn get_similar_documents_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+        top_k: u32,
+        owned: bool,
+    ) -> Result<Vec<SimilarDocument>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_similar_documents_unchecked(guarantee, words, top_k, owned)
+            .await
+    }
+
+    async fn query_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<Vec<WordQueryRow>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .query_words_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn put_word_normalized_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<PutReceipt> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .put_word_normalized_unchecked(
+                parent,
+                word,
+                metadata,
+                on_behalf_of,
+                fencing_token,
+                normalization,
+            )
+            .await
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Vec<PutReceipt>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .put_word_many_unchecked(parent, words, fencing_token, normalization)
+            .await
+    }
+}
+
+#[async_trait]
+impl<IpiisClient> IpdisAdmin for IpiisClient
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn set_read_only_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        enabled: bool,
+    ) -> Result<()> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .set_read_only_unchecked(guarantee, enabled)
+            .await
+    }
+
+    async fn get_server_diagnostics_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetServerDiagnostics,
+    ) -> Result<ServerDiagnostics> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_server_diagnostics_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_account_stats_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetAccountStats,
+    ) -> Result<AccountStats> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_account_stats_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn register_kind_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &RegisterKind,
+        name: &str,
+        description: &str,
+    ) -> Result<()> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .register_kind_unchecked(guarantee, query, name, description)
+            .await
+    }
+
+    async fn get_path_reference_count_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &Hash,
+    ) -> Result<u32> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_path_reference_count_unchecked(guarantee, path)
+            .await
+    }
+
+    async fn get_dyn_path_by_target_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetDynPathsByTarget,
+    ) -> Result<Page<GuarantorSigned<DynPath<Path>>>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_dyn_path_by_target_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_idf_log_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetIdfLogs,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_idf_log_page_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn explain_query_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<String> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .explain_query_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn issue_api_token_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        name: &str,
+        scopes: &FeatureSet,
+        rate_limit: u32,
+        expiration_time: Option<i64>,
+    ) -> Result<IssuedApiToken> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .issue_api_token_unchecked(guarantee, name, scopes, rate_limit, expiration_time)
+            .await
+    }
+
+    async fn get_api_token_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetApiTokens,
+    ) -> Result<Page<ApiTokenInfo>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .get_api_token_page_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn revoke_api_token_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        id: i32,
+    ) -> Result<()> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .revoke_api_token_unchecked(guarantee, id)
+            .await
+    }
+
+    async fn add_stop_word_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        kind: &Hash,
+        word: &TextHash,
+    ) -> Result<()> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .add_stop_word_unchecked(guarantee, kind, word)
+            .await
+    }
+
+    async fn list_stop_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        kind: &Hash,
+    ) -> Result<Vec<TextHash>> {
+        IpdisRemote::with_primary(self)
+            .await?
+            .list_stop_words_unchecked(guarantee, kind)
+            .await
+    }
+}
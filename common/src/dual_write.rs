@@ -0,0 +1,541 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::Result,
+        value::{hash::Hash, text::TextHash, uuid::Uuid},
+    },
+    path::{DynPath, Path},
+    word::{WordHash, WordKeyHash},
+};
+
+use crate::{
+    AccountStats, AcquireWriterLease, ApiTokenInfo, Delegation, FeatureSet, Fresh, GetAccountChain,
+    GetAccountStats, GetApiTokens, GetDynPathsByTarget, GetIdfLogs, GetKind, GetKinds, GetMembers,
+    GetOplog, GetServerDiagnostics, GetWordCountAllLangs, GetWordCountDelta,
+    GetWordFrequencyHistogram, GetWords, GetWordsCounts, GetWordsCountsOutput, IdfVector,
+    InclusionProof, Ipdis, IpdisAdmin, IssuedApiToken, KindInfo, LinkAccountSuccessor, Member,
+    Normalization, Oplog, Page, PutReceipt, RegisterKind, ServerDiagnostics, SignedRecord,
+    SimilarDocument, WithMetadata, WordCountDelta, WordFrequencyBucket, WordQuery, WordQueryRow,
+    WriterLease,
+};
+
+/// A client migrating the records from a backend to another, without downtime.
+///
+/// Writes are applied to the primary first, and then to the secondary;
+/// the write fails only if the primary fails.
+/// Reads are served by the primary only.
+///
+/// A write which has been applied to the primary but failed on the secondary is reported
+/// as a divergence, so that the records can be copied again before switching the backends.
+///
+/// Note that the pre-signed requests should be acceptable to both backends,
+/// e.g. by sharing the account of the guarantor.
+pub struct DualWriteIpdis<Primary, Secondary> {
+    primary: Primary,
+    secondary: Secondary,
+    divergences: AtomicU64,
+    reporter: Option<Box<dyn Fn(&Divergence) + Send + Sync>>,
+}
+
+/// A write which has been applied to the primary only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub method: &'static str,
+    pub error: String,
+}
+
+impl<Primary, Secondary> DualWriteIpdis<Primary, Secondary> {
+    pub fn new(primary: Primary, secondary: Secondary) -> Self {
+        Self {
+            primary,
+            secondary,
+            divergences: Default::default(),
+            reporter: None,
+        }
+    }
+
+    /// Calls the given function on every divergence.
+    pub fn with_reporter<F>(mut self, reporter: F) -> Self
+    where
+        F: Fn(&Divergence) + Send + Sync + 'static,
+    {
+        self.reporter = Some(Box::new(reporter));
+        self
+    }
+
+    pub fn primary(&self) -> &Primary {
+        &self.primary
+    }
+
+    pub fn secondary(&self) -> &Secondary {
+        &self.secondary
+    }
+
+    /// Returns the number of the divergences so far.
+    pub fn divergences(&self) -> u64 {
+        self.divergences.load(Ordering::SeqCst)
+    }
+
+    fn report(&self, method: &'static str, result: Result<()>) {
+        if let Err(error) = result {
+            self.divergences.fetch_add(1, Ordering::SeqCst);
+            if let Some(reporter) = &self.reporter {
+                reporter(&Divergence {
+                    method,
+                    error: error.to_string(),
+                });
+            }
+        }
+    }
+}
+
+macro_rules! dual_write {
+    ( $self:ident, $method:ident ( $( $arg:expr ),* ) ) => {{
+        let output = $self.primary.$method($( $arg ),*).await?;
+
+        let result = $self.secondary.$method($( $arg ),*).await.map(|_| ());
+        $self.report(stringify!($method), result);
+        Ok(output)
+    }};
+}
+
+#[async_trait]
+impl<Primary, Secondary> Ipdis for DualWriteIpdis<Primary, Secondary>
+where
+    Primary: Ipdis + Send + Sync,
+    Secondary: Ipdis + Send + Sync,
+{
+    async fn ensure_registered(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<()> {
+        self.primary.ensure_registered(guarantee, guarantor).await
+    }
+
+    async fn ensure_admin(&self, guarantee: &AccountRef, guarantor: &AccountRef) -> Result<()> {
+        self.primary.ensure_admin(guarantee, guarantor).await
+    }
+
+    async fn get_kind_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetKind,
+    ) -> Result<Option<KindInfo>> {
+        self.primary.get_kind_unchecked(guarantee, query).await
+    }
+
+    async fn get_kind_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetKinds,
+    ) -> Result<Page<KindInfo>> {
+        self.primary.get_kind_page_unchecked(guarantee, query).await
+    }
+
+    async fn acquire_writer_lease_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &AcquireWriterLease,
+    ) -> Result<WriterLease> {
+        self.primary
+            .acquire_writer_lease_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn add_guarantee_unchecked(&self, guarantee: &GuaranteeSigned<AccountRef>) -> Result<()> {
+        dual_write!(self, add_guarantee_unchecked(guarantee))
+    }
+
+    async fn link_account_successor_unchecked(
+        &self,
+        proof: &GuaranteeSigned<LinkAccountSuccessor>,
+    ) -> Result<()> {
+        dual_write!(self, link_account_successor_unchecked(proof))
+    }
+
+    async fn get_account_chain_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetAccountChain,
+    ) -> Result<Vec<AccountRef>> {
+        self.primary
+            .get_account_chain_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_dyn_path_record_unchecked<Path>(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        self.primary
+            .get_dyn_path_record_unchecked(guarantee, path)
+            .await
+    }
+
+    async fn get_dyn_path_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        paths: &[DynPath<()>],
+    ) -> Result<Vec<Option<GuarantorSigned<DynPath<Path>>>>> {
+        self.primary
+            .get_dyn_path_many_unchecked(guarantee, paths)
+            .await
+    }
+
+    async fn get_members_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetMembers,
+    ) -> Result<Vec<Member>> {
+        self.primary.get_members_unchecked(guarantee, query).await
+    }
+
+    async fn get_record_by_nonce_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<SignedRecord>> {
+        self.primary
+            .get_record_by_nonce_unchecked(guarantee, nonce)
+            .await
+    }
+
+    async fn get_oplog_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetOplog,
+    ) -> Result<Oplog> {
+        self.primary.get_oplog_unchecked(guarantee, query).await
+    }
+
+    async fn get_inclusion_proof_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<InclusionProof>> {
+        self.primary
+            .get_inclusion_proof_unchecked(guarantee, nonce)
+            .await
+    }
+
+    async fn put_dyn_path_fenced_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+    ) -> Result<PutReceipt> {
+        let output = self
+            .primary
+            .put_dyn_path_fenced_unchecked(path, metadata, on_behalf_of, fencing_token)
+            .await?;
+
+        // the writer leases are held on the primary, which has fenced the write already
+        let result = self
+            .secondary
+            .put_dyn_path_fenced_unchecked(path, metadata, on_behalf_of, None)
+            .await
+            .map(|_| ());
+        self.report("put_dyn_path_fenced_unchecked", result);
+        Ok(output)
+    }
+
+    async fn get_word_record_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>> {
+        self.primary
+            .get_word_record_page_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_word_count_page_fresh_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+        max_staleness: Option<Duration>,
+    ) -> Result<Fresh<Page<GetWordsCountsOutput>>> {
+        self.primary
+            .get_word_count_page_fresh_unchecked(guarantee, query, max_staleness)
+            .await
+    }
+
+    async fn get_word_count_page_batch_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        queries: &[GetWordsCounts],
+    ) -> Result<Vec<Page<GetWordsCountsOutput>>> {
+        self.primary
+            .get_word_count_page_batch_unchecked(guarantee, queries)
+            .await
+    }
+
+    async fn get_word_count_all_langs_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountAllLangs,
+    ) -> Result<u32> {
+        self.primary
+            .get_word_count_all_langs_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_word_count_delta_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountDelta,
+    ) -> Result<WordCountDelta> {
+        self.primary
+            .get_word_count_delta_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_word_frequency_histogram_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordFrequencyHistogram,
+    ) -> Result<Vec<WordFrequencyBucket>> {
+        self.primary
+            .get_word_frequency_histogram_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_idf_vector_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+    ) -> Result<IdfVector> {
+        self.primary
+            .get_idf_vector_unchecked(guarantee, words)
+            .await
+    }
+
+    async fn get_similar_documents_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+        top_k: u32,
+        owned: bool,
+    ) -> Result<Vec<SimilarDocument>> {
+        self.primary
+            .get_similar_documents_unchecked(guarantee, words, top_k, owned)
+            .await
+    }
+
+    async fn query_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<Vec<WordQueryRow>> {
+        self.primary.query_words_unchecked(guarantee, query).await
+    }
+
+    async fn put_word_normalized_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<PutReceipt> {
+        let output = self
+            .primary
+            .put_word_normalized_unchecked(
+                parent,
+                word,
+                metadata,
+                on_behalf_of,
+                fencing_token,
+                normalization,
+            )
+            .await?;
+
+        // the writer leases are held on the primary, which has fenced the write already
+        let result = self
+            .secondary
+            .put_word_normalized_unchecked(
+                parent,
+                word,
+                metadata,
+                on_behalf_of,
+                None,
+                normalization,
+            )
+            .await
+            .map(|_| ());
+        self.report("put_word_normalized_unchecked", result);
+        Ok(output)
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Vec<PutReceipt>> {
+        let output = self
+            .primary
+            .put_word_many_unchecked(parent, words, fencing_token, normalization)
+            .await?;
+
+        let result = self
+            .secondary
+            .put_word_many_unchecked(parent, words, fencing_token, normalization)
+            .await
+            .map(|_| ());
+        self.report("put_word_many_unchecked", result);
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl<Primary, Secondary> IpdisAdmin for DualWriteIpdis<Primary, Secondary>
+where
+    Primary: IpdisAdmin + Send + Sync,
+    Secondary: IpdisAdmin + Send + Sync,
+{
+    async fn set_read_only_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        enabled: bool,
+    ) -> Result<()> {
+        dual_write!(self, set_read_only_unchecked(guarantee, enabled))
+    }
+
+    async fn get_server_diagnostics_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetServerDiagnostics,
+    ) -> Result<ServerDiagnostics> {
+        self.primary
+            .get_server_diagnostics_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_account_stats_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetAccountStats,
+    ) -> Result<AccountStats> {
+        self.primary
+            .get_account_stats_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn register_kind_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &RegisterKind,
+        name: &str,
+        description: &str,
+    ) -> Result<()> {
+        dual_write!(
+            self,
+            register_kind_unchecked(guarantee, query, name, description)
+        )
+    }
+
+    async fn get_path_reference_count_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &Hash,
+    ) -> Result<u32> {
+        self.primary
+            .get_path_reference_count_unchecked(guarantee, path)
+            .await
+    }
+
+    async fn get_dyn_path_by_target_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetDynPathsByTarget,
+    ) -> Result<Page<GuarantorSigned<DynPath<Path>>>> {
+        self.primary
+            .get_dyn_path_by_target_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn get_idf_log_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetIdfLogs,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
+        self.primary
+            .get_idf_log_page_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn explain_query_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<String> {
+        self.primary.explain_query_unchecked(guarantee, query).await
+    }
+
+    // the API tokens are local to each backend, as their secrets are generated there
+
+    async fn issue_api_token_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        name: &str,
+        scopes: &FeatureSet,
+        rate_limit: u32,
+        expiration_time: Option<i64>,
+    ) -> Result<IssuedApiToken> {
+        self.primary
+            .issue_api_token_unchecked(guarantee, name, scopes, rate_limit, expiration_time)
+            .await
+    }
+
+    async fn get_api_token_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetApiTokens,
+    ) -> Result<Page<ApiTokenInfo>> {
+        self.primary
+            .get_api_token_page_unchecked(guarantee, query)
+            .await
+    }
+
+    async fn revoke_api_token_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        id: i32,
+    ) -> Result<()> {
+        self.primary.revoke_api_token_unchecked(guarantee, id).await
+    }
+
+    async fn add_stop_word_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        kind: &Hash,
+        word: &TextHash,
+    ) -> Result<()> {
+        dual_write!(self, add_stop_word_unchecked(guarantee, kind, word))
+    }
+
+    async fn list_stop_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        kind: &Hash,
+    ) -> Result<Vec<TextHash>> {
+        self.primary
+            .list_stop_words_unchecked(guarantee, kind)
+            .await
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt;
+
+use ipis::core::anyhow::Error;
+
+use crate::feature::Feature;
+
+/// the names of the payloads of `IpdisError::PayloadTooLarge`, which are decoded back by the name
+const PAYLOADS: &[&str] = &[
+    crate::METADATA_PAYLOAD,
+    crate::QUERIES_PAYLOAD,
+    crate::WORDS_PAYLOAD,
+    crate::kv::VALUE_PAYLOAD,
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IpdisError {
+    FeatureDisabled {
+        feature: Feature,
+    },
+    ReadOnly,
+    QueryTooLarge {
+        limit: u32,
+    },
+    PayloadTooLarge {
+        payload: &'static str,
+        limit: u64,
+        given: u64,
+    },
+    Busy {
+        resource: String,
+    },
+    RateLimited {
+        retry_after_ms: u64,
+    },
+    Duplicate {
+        resource: String,
+    },
+    Fenced {
+        resource: String,
+    },
+    LeaseTooLong {
+        limit_ms: u64,
+    },
+    StopWord {
+        word: String,
+    },
+}
+
+impl IpdisError {
+    /// Returns `true` if the same request may succeed when it is sent again later.
+    pub const fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::ReadOnly | Self::Busy { .. } | Self::RateLimited { .. }
+        )
+    }
+
+    /// Returns the stable code of the error, which is sent over the wire, see `encode`.
+    pub const fn code(&self) -> u32 {
+        match self {
+            Self::FeatureDisabled { .. } => 1,
+            Self::ReadOnly => 2,
+            Self::QueryTooLarge { .. } => 3,
+            Self::PayloadTooLarge { .. } => 4,
+            Self::Busy { .. } => 5,
+            Self::RateLimited { .. } => 6,
+            Self::Duplicate { .. } => 7,
+            Self::Fenced { .. } => 8,
+            Self::LeaseTooLong { .. } => 9,
+            Self::StopWord { .. } => 10,
+        }
+    }
+
+    /// Attaches the code and the fields of the typed error, if any, to the error,
+    /// so that the remote clients can decode it with `recover`.
+    ///
+    /// The errors of the other types are returned as they are.
+    pub fn encode(error: Error) -> Error {
+        let envelope = match error.chain().find_map(|error| error.downcast_ref::<Self>()) {
+            Some(typed) => typed.to_envelope(),
+            None => return error,
+        };
+        let message = error.to_string();
+        error.context(format!("{message} {envelope}"))
+    }
+
+    /// Recovers the typed error from the code, which has been attached by `encode`,
+    /// e.g. by a remote server, keeping the message as the context.
+    ///
+    /// The errors of the other types are returned as they are.
+    pub fn recover(error: Error) -> Error {
+        if error.downcast_ref::<Self>().is_some() {
+            return error;
+        }
+        match Self::decode(&format!("{error:#}")) {
+            Some(typed) => Error::new(typed).context(error.to_string()),
+            None => error,
+        }
+    }
+
+    /// Decodes the error from the code attached by `encode`, which may be embedded in a message.
+    pub fn decode(message: &str) -> Option<Self> {
+        let (_, rest) = message.split_once(ENVELOPE_PREFIX)?;
+        let (envelope, _) = rest.split_once(ENVELOPE_SUFFIX)?;
+
+        let mut fields = envelope.split(ENVELOPE_DELIMITER);
+        let code: u32 = fields.next()?.parse().ok()?;
+        let mut field = || fields.next();
+
+        let error = match code {
+            1 => Self::FeatureDisabled {
+                feature: field()?.parse().ok()?,
+            },
+            2 => Self::ReadOnly,
+            3 => Self::QueryTooLarge {
+                limit: field()?.parse().ok()?,
+            },
+            4 => {
+                let payload = field()?;
+                Self::PayloadTooLarge {
+                    payload: *PAYLOADS.iter().find(|name| **name == payload)?,
+                    limit: field()?.parse().ok()?,
+                    given: field()?.parse().ok()?,
+                }
+            }
+            5 => Self::Busy {
+                resource: decode_hex(field()?)?,
+            },
+            6 => Self::RateLimited {
+                retry_after_ms: field()?.parse().ok()?,
+            },
+            7 => Self::Duplicate {
+                resource: decode_hex(field()?)?,
+            },
+            8 => Self::Fenced {
+                resource: decode_hex(field()?)?,
+            },
+            9 => Self::LeaseTooLong {
+                limit_ms: field()?.parse().ok()?,
+            },
+            10 => Self::StopWord {
+                word: decode_hex(field()?)?,
+            },
+            _ => return None,
+        };
+        match field() {
+            Some(_) => None,
+            None => Some(error),
+        }
+    }
+
+    fn to_envelope(&self) -> String {
+        let fields = match self {
+            Self::FeatureDisabled { feature } => vec![feature.as_str().to_string()],
+            Self::ReadOnly => vec![],
+            Self::QueryTooLarge { limit } => vec![limit.to_string()],
+            Self::PayloadTooLarge {
+                payload,
+                limit,
+                given,
+            } => vec![payload.to_string(), limit.to_string(), given.to_string()],
+            Self::Busy { resource } | Self::Duplicate { resource } | Self::Fenced { resource } => {
+                vec![encode_hex(resource)]
+            }
+            Self::StopWord { word } => vec![encode_hex(word)],
+            Self::RateLimited { retry_after_ms } => vec![retry_after_ms.to_string()],
+            Self::LeaseTooLong { limit_ms } => vec![limit_ms.to_string()],
+        };
+
+        let mut envelope = format!("{ENVELOPE_PREFIX}{}", self.code());
+        for field in fields {
+            envelope.push(ENVELOPE_DELIMITER);
+            envelope.push_str(&field);
+        }
+        envelope.push_str(ENVELOPE_SUFFIX);
+        envelope
+    }
+}
+
+/// the envelope of the typed errors sent over the wire, e.g. `[ipdis-error:3:256]`
+const ENVELOPE_PREFIX: &str = "[ipdis-error:";
+const ENVELOPE_SUFFIX: &str = "]";
+const ENVELOPE_DELIMITER: char = ':';
+
+/// Encodes the free-form field, which may contain the delimiters of the envelope.
+fn encode_hex(s: &str) -> String {
+    s.bytes().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(s: &str) -> Option<String> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    let bytes = (0..s.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(s.get(index..index + 2)?, 16).ok())
+        .collect::<Option<_>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+impl fmt::Display for IpdisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FeatureDisabled { feature } => {
+                write!(f, "feature disabled: {feature}")
+            }
+            Self::ReadOnly => {
+                write!(f, "the server is in read-only mode; try again later")
+            }
+            Self::QueryTooLarge { limit } => {
+                write!(
+                    f,
+                    "query too large: up to {limit} rows are allowed per request"
+                )
+            }
+            Self::PayloadTooLarge {
+                payload,
+                limit,
+                given,
+            } => {
+                write!(
+                    f,
+                    "payload too large: up to {limit} {payload} are allowed per request, but given {given}"
+                )
+            }
+            Self::Busy { resource } => {
+                write!(
+                    f,
+                    "busy: {resource} is locked by another operation; try again later"
+                )
+            }
+            Self::RateLimited { retry_after_ms } => {
+                write!(
+                    f,
+                    "rate limited: too many requests; try again in {retry_after_ms} ms"
+                )
+            }
+            Self::Duplicate { resource } => {
+                write!(f, "duplicate: {resource} already exists")
+            }
+            Self::Fenced { resource } => {
+                write!(
+                    f,
+                    "fenced: the writer lease of {resource} is held by another writer, or has expired"
+                )
+            }
+            Self::LeaseTooLong { limit_ms } => {
+                write!(
+                    f,
+                    "lease too long: up to {limit_ms} ms are allowed per lease"
+                )
+            }
+            Self::StopWord { word } => {
+                write!(f, "stop word: {word} is rejected by the kind")
+            }
+        }
+    }
+}
+
+impl ::std::error::Error for IpdisError {}
@@ -0,0 +1,600 @@
+use std::time::Duration;
+
+use futures::future::{self, Either};
+use futures_timer::Delay;
+use ipiis_common::Ipiis;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{anyhow, bail, Error, Result},
+        value::{hash::Hash, text::TextHash, uuid::Uuid},
+    },
+    path::{DynPath, Path},
+    word::{WordHash, WordKeyHash},
+};
+
+use crate::{
+    AccountStats, AcquireWriterLease, ApiTokenInfo, Delegation, FeatureSet, Fresh, GetAccountChain,
+    GetAccountStats, GetApiTokens, GetDynPathsByTarget, GetIdfLogs, GetKind, GetKinds, GetMembers,
+    GetOplog, GetServerDiagnostics, GetWordCountAllLangs, GetWordCountDelta,
+    GetWordFrequencyHistogram, GetWords, GetWordsCounts, GetWordsCountsOutput, IdfVector,
+    InclusionProof, Ipdis, IpdisAdmin, IpdisError, IpdisRemote, IssuedApiToken, KindInfo,
+    LinkAccountSuccessor, Member, Normalization, Oplog, Page, PutReceipt, RegisterKind,
+    ServerDiagnostics, SignedRecord, SimilarDocument, WithMetadata, WordCountDelta,
+    WordFrequencyBucket, WordQuery, WordQueryRow, WriterLease,
+};
+
+/// A remote client, which serves the same logical index with several IPDIS servers.
+///
+/// Reads are retried against the next server on failures, including timeouts,
+/// except the typed errors which every server would return alike, see `IpdisError::is_retryable`.
+/// So are the idempotent writes, e.g. `set_read_only_unchecked`.
+/// The other writes may have been applied on any failure, so they are reported rather than repeated.
+///
+/// The addresses of the servers should be registered to the ipiis client beforehand.
+/// Note that the pre-signed requests (e.g. `put_word_with_metadata_unchecked`) are valid only
+/// for the server they have been signed for, so they are sent to it only, whether listed or not.
+pub struct IpdisFailover<IpiisClient> {
+    ipiis: IpiisClient,
+    targets: Vec<AccountRef>,
+    /// the accounts which may serve the cluster, see `Self::refresh_members`
+    cluster: Vec<AccountRef>,
+    timeout: Option<Duration>,
+}
+
+impl<IpiisClient> IpdisFailover<IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    /// Creates a client, which tries the servers in the given order.
+    pub fn new(ipiis: IpiisClient, targets: Vec<AccountRef>) -> Result<Self> {
+        if targets.is_empty() {
+            bail!("the failover client should have at least one server")
+        }
+
+        Ok(Self {
+            ipiis,
+            cluster: targets.clone(),
+            targets,
+            timeout: None,
+        })
+    }
+
+    /// Gives up each attempt after the given time.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Accepts the given accounts as the members of the cluster, besides the initial servers.
+    pub fn with_cluster_accounts(mut self, accounts: impl IntoIterator<Item = AccountRef>) -> Self {
+        self.cluster.extend(accounts);
+        self
+    }
+
+    pub fn ipiis(&self) -> &IpiisClient {
+        &self.ipiis
+    }
+
+    pub fn targets(&self) -> &[AccountRef] {
+        &self.targets
+    }
+
+    /// Replaces the servers with the members of the cluster, discovered through the current ones.
+    ///
+    /// Only the accounts of the cluster are accepted, so that a compromised server cannot
+    /// redirect the client elsewhere; see `Self::with_cluster_accounts`.
+    /// The servers are kept if no member has announced itself within the TTL.
+    pub async fn refresh_members(&mut self, namespace: Hash, ttl: Duration) -> Result<()> {
+        let query = GetMembers {
+            namespace,
+            ttl_secs: ttl.as_secs().try_into().unwrap_or(u32::MAX),
+        };
+
+        let members: Vec<_> = self
+            .get_members_unchecked(None, &query)
+            .await?
+            .into_iter()
+            .map(|member| member.account)
+            .filter(|account| self.cluster.contains(account))
+            .collect();
+        if !members.is_empty() {
+            self.targets = members;
+        }
+        Ok(())
+    }
+
+    async fn attempt<F, T>(&self, f: F) -> ::core::result::Result<T, Attempt>
+    where
+        F: ::core::future::Future<Output = Result<T>>,
+    {
+        match self.timeout {
+            // the timer of futures-timer runs on wasm32 as well, unlike the one of tokio
+            Some(timeout) => match future::select(Box::pin(f), Delay::new(timeout)).await {
+                Either::Left((result, _)) => result.map_err(Attempt::Failed),
+                Either::Right(_) => Err(Attempt::TimedOut),
+            },
+            None => f.await.map_err(Attempt::Failed),
+        }
+    }
+}
+
+enum Attempt {
+    Failed(Error),
+    TimedOut,
+}
+
+impl Attempt {
+    /// Returns `true` if the request may succeed on the next server.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Failed(error) => error
+                .downcast_ref::<IpdisError>()
+                .map_or(true, IpdisError::is_retryable),
+            Self::TimedOut => true,
+        }
+    }
+
+    /// Merges the failed attempts, keeping the last error typed.
+    fn into_error(mut attempts: Vec<(AccountRef, Self)>) -> Error {
+        let message = attempts
+            .iter()
+            .map(|(target, attempt)| match attempt {
+                Self::Failed(error) => format!("{target}: {error}"),
+                Self::TimedOut => format!("{target}: timed out"),
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        let message = format!("failed to request to the IPDIS servers: {message}");
+
+        match attempts.pop() {
+            Some((_, Self::Failed(error))) => error.context(message),
+            _ => anyhow!(message),
+        }
+    }
+}
+
+macro_rules! failover {
+    ( $self:ident, read, | $remote:ident | $call:expr ) => {
+        failover!(@impl $self, $self.targets.iter(), true, |$remote| $call)
+    };
+    ( $self:ident, idempotent, | $remote:ident | $call:expr ) => {
+        failover!(@impl $self, $self.targets.iter(), true, |$remote| $call)
+    };
+    ( $self:ident, write, | $remote:ident | $call:expr ) => {
+        failover!(@impl $self, $self.targets.iter(), false, |$remote| $call)
+    };
+    ( $self:ident, signed($target:expr), | $remote:ident | $call:expr ) => {
+        failover!(@impl $self, [$target].iter(), false, |$remote| $call)
+    };
+    ( @impl $self:ident, $targets:expr, $retry:expr, | $remote:ident | $call:expr ) => {{
+        let mut attempts = Vec::with_capacity($self.targets.len());
+        for target in $targets {
+            let $remote = IpdisRemote::new(&$self.ipiis, *target);
+            match $self.attempt($call).await {
+                Ok(value) => return Ok(value),
+                Err(attempt) => {
+                    let retry = $retry && attempt.is_retryable();
+                    attempts.push((*target, attempt));
+                    if !retry {
+                        break;
+                    }
+                }
+            }
+        }
+        Err(Attempt::into_error(attempts))
+    }};
+}
+
+#[async_trait]
+impl<IpiisClient> Ipdis for IpdisFailover<IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn ensure_registered(
+        &self,
+        guarantee: &AccountRef,
+        _guarantor: &AccountRef,
+    ) -> Result<()> {
+        let guarantee_now = self.ipiis.account_me().account_ref();
+        if guarantee != &guarantee_now {
+            bail!("failed to authenticate the guarantee")
+        }
+
+        Ok(())
+    }
+
+    async fn ensure_admin(&self, guarantee: &AccountRef, guarantor: &AccountRef) -> Result<()> {
+        self.ensure_registered(guarantee, guarantor).await
+    }
+
+    async fn get_kind_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetKind,
+    ) -> Result<Option<KindInfo>> {
+        failover!(self, read, |remote| remote
+            .get_kind_unchecked(guarantee, query))
+    }
+
+    async fn get_kind_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetKinds,
+    ) -> Result<Page<KindInfo>> {
+        failover!(self, read, |remote| remote
+            .get_kind_page_unchecked(guarantee, query))
+    }
+
+    async fn acquire_writer_lease_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &AcquireWriterLease,
+    ) -> Result<WriterLease> {
+        failover!(self, write, |remote| remote
+            .acquire_writer_lease_unchecked(guarantee, query))
+    }
+
+    async fn add_guarantee_unchecked(&self, guarantee: &GuaranteeSigned<AccountRef>) -> Result<()> {
+        failover!(self, signed(guarantee.data.guarantor), |remote| remote
+            .add_guarantee_unchecked(guarantee))
+    }
+
+    async fn link_account_successor_unchecked(
+        &self,
+        proof: &GuaranteeSigned<LinkAccountSuccessor>,
+    ) -> Result<()> {
+        failover!(self, signed(proof.data.guarantor), |remote| remote
+            .link_account_successor_unchecked(proof))
+    }
+
+    async fn get_account_chain_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetAccountChain,
+    ) -> Result<Vec<AccountRef>> {
+        failover!(self, read, |remote| remote
+            .get_account_chain_unchecked(guarantee, query))
+    }
+
+    async fn get_dyn_path_record_unchecked<Path>(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        failover!(self, read, |remote| remote
+            .get_dyn_path_record_unchecked(guarantee, path))
+    }
+
+    async fn get_dyn_path_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        paths: &[DynPath<()>],
+    ) -> Result<Vec<Option<GuarantorSigned<DynPath<Path>>>>> {
+        failover!(self, read, |remote| remote
+            .get_dyn_path_many_unchecked(guarantee, paths))
+    }
+
+    async fn get_members_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetMembers,
+    ) -> Result<Vec<Member>> {
+        failover!(self, read, |remote| remote
+            .get_members_unchecked(guarantee, query))
+    }
+
+    async fn get_record_by_nonce_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<SignedRecord>> {
+        failover!(self, read, |remote| remote
+            .get_record_by_nonce_unchecked(guarantee, nonce))
+    }
+
+    async fn get_oplog_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetOplog,
+    ) -> Result<Oplog> {
+        failover!(self, read, |remote| remote
+            .get_oplog_unchecked(guarantee, query))
+    }
+
+    async fn get_inclusion_proof_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<InclusionProof>> {
+        failover!(self, read, |remote| remote
+            .get_inclusion_proof_unchecked(guarantee, nonce))
+    }
+
+    async fn put_dyn_path_fenced_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+    ) -> Result<PutReceipt> {
+        failover!(self, signed(path.data.guarantor), |remote| remote
+            .put_dyn_path_fenced_unchecked(
+                path,
+                metadata,
+                on_behalf_of,
+                fencing_token
+            ))
+    }
+
+    async fn get_word_record_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>> {
+        failover!(self, read, |remote| remote
+            .get_word_record_page_unchecked(guarantee, query))
+    }
+
+    async fn get_word_count_page_fresh_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+        max_staleness: Option<Duration>,
+    ) -> Result<Fresh<Page<GetWordsCountsOutput>>> {
+        failover!(self, read, |remote| remote
+            .get_word_count_page_fresh_unchecked(
+                guarantee,
+                query,
+                max_staleness
+            ))
+    }
+
+    async fn get_word_count_page_batch_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        queries: &[GetWordsCounts],
+    ) -> Result<Vec<Page<GetWordsCountsOutput>>> {
+        failover!(self, read, |remote| remote
+            .get_word_count_page_batch_unchecked(guarantee, queries))
+    }
+
+    async fn get_word_count_all_langs_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountAllLangs,
+    ) -> Result<u32> {
+        failover!(self, read, |remote| remote
+            .get_word_count_all_langs_unchecked(guarantee, query))
+    }
+
+    async fn get_word_count_delta_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountDelta,
+    ) -> Result<WordCountDelta> {
+        failover!(self, read, |remote| remote
+            .get_word_count_delta_unchecked(guarantee, query))
+    }
+
+    async fn get_word_frequency_histogram_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordFrequencyHistogram,
+    ) -> Result<Vec<WordFrequencyBucket>> {
+        failover!(self, read, |remote| remote
+            .get_word_frequency_histogram_unchecked(guarantee, query))
+    }
+
+    async fn get_idf_vector_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+    ) -> Result<IdfVector> {
+        failover!(self, read, |remote| remote
+            .get_idf_vector_unchecked(guarantee, words))
+    }
+
+    async fn get_similar_documents_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+        top_k: u32,
+        owned: bool,
+    ) -> Result<Vec<SimilarDocument>> {
+        failover!(self, read, |remote| remote
+            .get_similar_documents_unchecked(guarantee, words, top_k, owned))
+    }
+
+    async fn query_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<Vec<WordQueryRow>> {
+        failover!(self, read, |remote| remote
+            .query_words_unchecked(guarantee, query))
+    }
+
+    async fn put_word_normalized_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<PutReceipt> {
+        failover!(self, signed(word.data.guarantor), |remote| remote
+            .put_word_normalized_unchecked(
+                parent,
+                word,
+                metadata,
+                on_behalf_of,
+                fencing_token,
+                normalization
+            ))
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Vec<PutReceipt>> {
+        // the words of a batch are signed for the same server
+        let target = match words.first() {
+            Some(word) => word.data.guarantor,
+            None => return Ok(Vec::new()),
+        };
+        failover!(self, signed(target), |remote| remote
+            .put_word_many_unchecked(
+                parent,
+                words,
+                fencing_token,
+                normalization
+            ))
+    }
+}
+
+#[async_trait]
+impl<IpiisClient> IpdisAdmin for IpdisFailover<IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn set_read_only_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        enabled: bool,
+    ) -> Result<()> {
+        failover!(self, idempotent, |remote| remote
+            .set_read_only_unchecked(guarantee, enabled))
+    }
+
+    async fn get_server_diagnostics_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetServerDiagnostics,
+    ) -> Result<ServerDiagnostics> {
+        failover!(self, read, |remote| remote
+            .get_server_diagnostics_unchecked(guarantee, query))
+    }
+
+    async fn get_account_stats_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetAccountStats,
+    ) -> Result<AccountStats> {
+        failover!(self, read, |remote| remote
+            .get_account_stats_unchecked(guarantee, query))
+    }
+
+    async fn register_kind_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &RegisterKind,
+        name: &str,
+        description: &str,
+    ) -> Result<()> {
+        failover!(self, idempotent, |remote| remote.register_kind_unchecked(
+            guarantee,
+            query,
+            name,
+            description
+        ))
+    }
+
+    async fn get_path_reference_count_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &Hash,
+    ) -> Result<u32> {
+        failover!(self, read, |remote| remote
+            .get_path_reference_count_unchecked(guarantee, path))
+    }
+
+    async fn get_dyn_path_by_target_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetDynPathsByTarget,
+    ) -> Result<Page<GuarantorSigned<DynPath<Path>>>> {
+        failover!(self, read, |remote| remote
+            .get_dyn_path_by_target_unchecked(guarantee, query))
+    }
+
+    async fn get_idf_log_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetIdfLogs,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
+        failover!(self, read, |remote| remote
+            .get_idf_log_page_unchecked(guarantee, query))
+    }
+
+    async fn explain_query_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<String> {
+        failover!(self, read, |remote| remote
+            .explain_query_unchecked(guarantee, query))
+    }
+
+    async fn issue_api_token_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        name: &str,
+        scopes: &FeatureSet,
+        rate_limit: u32,
+        expiration_time: Option<i64>,
+    ) -> Result<IssuedApiToken> {
+        failover!(self, write, |remote| remote.issue_api_token_unchecked(
+            guarantee,
+            name,
+            scopes,
+            rate_limit,
+            expiration_time
+        ))
+    }
+
+    async fn get_api_token_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetApiTokens,
+    ) -> Result<Page<ApiTokenInfo>> {
+        failover!(self, read, |remote| remote
+            .get_api_token_page_unchecked(guarantee, query))
+    }
+
+    async fn revoke_api_token_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        id: i32,
+    ) -> Result<()> {
+        failover!(self, idempotent, |remote| remote
+            .revoke_api_token_unchecked(guarantee, id))
+    }
+
+    async fn add_stop_word_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        kind: &Hash,
+        word: &TextHash,
+    ) -> Result<()> {
+        failover!(self, idempotent, |remote| remote
+            .add_stop_word_unchecked(guarantee, kind, word))
+    }
+
+    async fn list_stop_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        kind: &Hash,
+    ) -> Result<Vec<TextHash>> {
+        failover!(self, read, |remote| remote
+            .list_stop_words_unchecked(guarantee, kind))
+    }
+}
@@ -0,0 +1,118 @@
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+use ipis::core::anyhow::{bail, Error, Result};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Feature {
+    Guarantee,
+    DynPathGet,
+    DynPathPut,
+    WordGet,
+    WordPut,
+    Delete,
+}
+
+impl Feature {
+    pub const ALL: &'static [Self] = &[
+        Self::Guarantee,
+        Self::DynPathGet,
+        Self::DynPathPut,
+        Self::WordGet,
+        Self::WordPut,
+        Self::Delete,
+    ];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Guarantee => "guarantee",
+            Self::DynPathGet => "dyn-path-get",
+            Self::DynPathPut => "dyn-path-put",
+            Self::WordGet => "word-get",
+            Self::WordPut => "word-put",
+            Self::Delete => "delete",
+        }
+    }
+
+    /// Returns `true` if the feature modifies the stored records.
+    pub const fn is_write(&self) -> bool {
+        matches!(
+            self,
+            Self::Guarantee | Self::DynPathPut | Self::WordPut | Self::Delete,
+        )
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for Feature {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Self::ALL.iter().find(|feature| feature.as_str() == s) {
+            Some(feature) => Ok(*feature),
+            None => bail!("unknown feature: {s:?}"),
+        }
+    }
+}
+
+/// A set of features, parsed from a comma-separated list.
+///
+/// The special value `write` expands to every feature which modifies the records,
+/// so that a read-only mirror node can be configured with a single word.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeatureSet(BTreeSet<Feature>);
+
+impl FeatureSet {
+    pub fn contains(&self, feature: &Feature) -> bool {
+        self.0.contains(feature)
+    }
+
+    pub fn insert(&mut self, feature: Feature) -> bool {
+        self.0.insert(feature)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Feature> {
+        self.0.iter()
+    }
+}
+
+impl fmt::Display for FeatureSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, feature) in self.iter().enumerate() {
+            if index > 0 {
+                f.write_str(",")?;
+            }
+            feature.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<Feature> for FeatureSet {
+    fn from_iter<T: IntoIterator<Item = Feature>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl FromStr for FeatureSet {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut features = Self::default();
+        for name in s.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            match name {
+                "write" => features
+                    .0
+                    .extend(Feature::ALL.iter().filter(|feature| feature.is_write())),
+                name => {
+                    features.insert(name.parse()?);
+                }
+            }
+        }
+        Ok(features)
+    }
+}
@@ -0,0 +1,153 @@
+//! The generators of the records for the tests and the benchmarks, shared across the backends.
+//!
+//! The records generated from the same seed are identical, but their signatures are not,
+//! as the nonces and the dates of the signatures are chosen when they are signed.
+
+use ipiis_common::Ipiis;
+use ipis::{
+    core::{
+        account::{AccountRef, GuaranteeSigned},
+        anyhow::Result,
+        value::{hash::Hash, text::Text},
+    },
+    path::{DynPath, Path},
+    word::{Word, WordHash, WordKey},
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::Ipdis;
+
+/// the syllables of the generated words
+const SYLLABLES: &[&str] = &[
+    "ba", "ce", "di", "fo", "gu", "ha", "ke", "li", "mo", "nu", "pa", "re", "si", "to", "vu", "za",
+];
+
+/// Generates the records of a namespace and a kind from a seed.
+pub struct Fixtures {
+    rng: ChaCha8Rng,
+    namespace: String,
+    kind: String,
+    vocabulary: Vec<String>,
+}
+
+impl Fixtures {
+    pub const DEFAULT_NAMESPACE: &'static str = "ipdis-fixtures";
+    pub const DEFAULT_KIND: &'static str = "ipdis-fixtures";
+    pub const DEFAULT_VOCABULARY_SIZE: usize = 1024;
+
+    pub fn new(seed: u64) -> Self {
+        Self::with_namespace(seed, Self::DEFAULT_NAMESPACE, Self::DEFAULT_KIND)
+    }
+
+    pub fn with_namespace(seed: u64, namespace: &str, kind: &str) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let vocabulary = (0..Self::DEFAULT_VOCABULARY_SIZE)
+            .map(|_| {
+                let len = rng.gen_range(1..=4);
+                (0..len)
+                    .map(|_| SYLLABLES[rng.gen_range(0..SYLLABLES.len())])
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            rng,
+            namespace: namespace.to_string(),
+            kind: kind.to_string(),
+            vocabulary,
+        }
+    }
+
+    /// Returns a word of the vocabulary, where the former ones are more frequent as in the texts.
+    pub fn text(&mut self) -> Text {
+        // squaring the uniform distribution skews it towards the front
+        let skew: f64 = self.rng.gen::<f64>().powi(2);
+        let index = ((self.vocabulary.len() as f64) * skew) as usize;
+        Text::with_en_us(&self.vocabulary[index.min(self.vocabulary.len() - 1)])
+    }
+
+    /// Returns a static path of a random content.
+    pub fn path(&mut self) -> Path {
+        Path {
+            value: Hash::with_str(&self.rng.gen::<u64>().to_string()),
+            len: self.rng.gen_range(1..=65536),
+        }
+    }
+
+    pub fn word(&mut self) -> WordHash {
+        Word {
+            key: WordKey {
+                namespace: self.namespace.clone(),
+                text: self.text(),
+            },
+            kind: self.kind.clone(),
+            relpath: self.rng.gen(),
+            path: self.path(),
+        }
+        .into()
+    }
+
+    pub fn dyn_path(&mut self) -> DynPath<Path> {
+        DynPath {
+            namespace: Hash::with_str(&self.namespace),
+            kind: Hash::with_str(&self.kind),
+            word: Hash::with_str(&self.text().msg),
+            path: self.path(),
+        }
+    }
+
+    /// Generates the words, signed by the client as the guarantee of the target guarantor.
+    pub fn signed_words<IpiisClient>(
+        &mut self,
+        ipiis: &IpiisClient,
+        target: AccountRef,
+        len: usize,
+    ) -> Result<Vec<GuaranteeSigned<WordHash>>>
+    where
+        IpiisClient: Ipiis,
+    {
+        (0..len).map(|_| ipiis.sign(target, self.word())).collect()
+    }
+
+    /// Generates the dynamic paths, signed by the client as the guarantee of the target guarantor.
+    pub fn signed_dyn_paths<IpiisClient>(
+        &mut self,
+        ipiis: &IpiisClient,
+        target: AccountRef,
+        len: usize,
+    ) -> Result<Vec<GuaranteeSigned<DynPath<Path>>>>
+    where
+        IpiisClient: Ipiis,
+    {
+        (0..len)
+            .map(|_| ipiis.sign(target, self.dyn_path()))
+            .collect()
+    }
+}
+
+/// Puts the words in order, stopping at the first failure.
+pub async fn put_words<T>(
+    ipdis: &T,
+    parent: &Hash,
+    words: &[GuaranteeSigned<WordHash>],
+) -> Result<()>
+where
+    T: Ipdis + ?Sized,
+{
+    for word in words {
+        ipdis.put_word_unchecked(parent, word).await?;
+    }
+    Ok(())
+}
+
+/// Puts the dynamic paths in order, stopping at the first failure.
+pub async fn put_dyn_paths<T>(ipdis: &T, paths: &[GuaranteeSigned<DynPath<Path>>]) -> Result<()>
+where
+    T: Ipdis + ?Sized,
+{
+    for path in paths {
+        ipdis.put_dyn_path_unchecked(path).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! An append-only journal, which lets the embedded (i.e. non-SQL) backends recover after a crash.
+//!
+//! The entries are archived with rkyv, and each of them is prefixed with its length and its CRC-32.
+//! A torn or corrupted write at the tail (e.g. by a crash during an append) is dropped on recovery,
+//! as it has never been acknowledged.
+//! The journal grows with every write, so the backends should compact it periodically
+//! into a snapshot of the entries which are still alive.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use ipis::core::anyhow::{anyhow, Result};
+use rkyv::{
+    ser::serializers::AllocSerializer, validation::validators::DefaultValidator, AlignedVec,
+    Archive, Deserialize, Infallible, Serialize,
+};
+
+/// the scratch space of the serializer, in bytes
+const SCRATCH_SPACE: usize = 4096;
+
+/// the size of the length prefix of an entry, in bytes
+const LEN_SIZE: usize = ::core::mem::size_of::<u32>();
+
+/// the size of the prefix of an entry, i.e. its length and its checksum, in bytes
+const HEADER_SIZE: usize = LEN_SIZE + ::core::mem::size_of::<u32>();
+
+/// When the appended entries are synchronized to the disk.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// Synchronizes every entry before the append returns, which survives the power failures.
+    Always,
+    /// Synchronizes at most once in the interval, so a crash may lose the latest entries.
+    Interval(Duration),
+    /// Leaves it to the operating system, which survives the process crashes only.
+    Never,
+}
+
+impl Default for FsyncPolicy {
+    fn default() -> Self {
+        Self::Interval(Duration::from_secs(1))
+    }
+}
+
+impl ::core::str::FromStr for FsyncPolicy {
+    type Err = ::ipis::core::anyhow::Error;
+
+    /// Parses `always`, `never`, or the interval in milliseconds (e.g. `1000`).
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => s
+                .parse()
+                .map(|ms| Self::Interval(Duration::from_millis(ms)))
+                .map_err(|_| anyhow!("unknown fsync policy: {s:?}")),
+        }
+    }
+}
+
+/// A journal of the entries of type `T`, which is exclusively owned by a backend.
+pub struct Journal<T> {
+    path: PathBuf,
+    file: BufWriter<File>,
+    fsync: FsyncPolicy,
+    last_sync: Instant,
+    /// the number of the entries in the journal, including the ones made obsolete
+    len: usize,
+    _type: PhantomData<T>,
+}
+
+impl<T> Journal<T>
+where
+    T: Archive + Serialize<AllocSerializer<SCRATCH_SPACE>>,
+    T::Archived: for<'a> ::bytecheck::CheckBytes<DefaultValidator<'a>> + Deserialize<T, Infallible>,
+{
+    /// Opens the journal, creating it if missing, and returns the recovered entries in order.
+    pub fn open(path: impl Into<PathBuf>, fsync: FsyncPolicy) -> Result<(Self, Vec<T>)> {
+        let path = path.into();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .open(&path)?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        let (entries, valid_len) = decode_all::<T>(&bytes);
+
+        // drop the torn tail, so that the next entries are appended right after the valid ones
+        if valid_len < bytes.len() {
+            file.set_len(valid_len as u64)?;
+            file.sync_all()?;
+        }
+        file.seek(SeekFrom::Start(valid_len as u64))?;
+
+        let journal = Self {
+            path,
+            file: BufWriter::new(file),
+            fsync,
+            last_sync: Instant::now(),
+            len: entries.len(),
+            _type: PhantomData,
+        };
+        Ok((journal, entries))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends an entry, which is synchronized according to the fsync policy.
+    pub fn append(&mut self, entry: &T) -> Result<()> {
+        let bytes = encode(entry)?;
+        self.file.write_all(&bytes)?;
+        self.len += 1;
+
+        match self.fsync {
+            FsyncPolicy::Always => self.flush(),
+            FsyncPolicy::Interval(interval) if self.last_sync.elapsed() >= interval => self.flush(),
+            FsyncPolicy::Interval(_) => Ok(()),
+            FsyncPolicy::Never => self.file.flush().map_err(Into::into),
+        }
+    }
+
+    /// Writes the buffered entries, and synchronizes them to the disk.
+    pub fn flush(&mut self) -> Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()?;
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+
+    /// Replaces the journal with the given entries, e.g. a snapshot of the live records.
+    ///
+    /// The snapshot is written aside and renamed over the journal,
+    /// so a crash during the compaction leaves either of them intact.
+    pub fn compact<'a, I>(&mut self, entries: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: 'a,
+    {
+        self.flush()?;
+
+        let tmp = self.path.with_extension("compact");
+        let mut len = 0;
+        {
+            let mut file = BufWriter::new(File::create(&tmp)?);
+            for entry in entries {
+                file.write_all(&encode(entry)?)?;
+                len += 1;
+            }
+            file.flush()?;
+            file.get_ref().sync_all()?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        sync_parent(&self.path)?;
+
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        file.seek(SeekFrom::End(0))?;
+        self.file = BufWriter::new(file);
+        self.last_sync = Instant::now();
+        self.len = len;
+        Ok(())
+    }
+}
+
+impl<T> Drop for Journal<T> {
+    fn drop(&mut self) {
+        // the appended entries are kept unless the policy is to sync them rarely
+        if self.file.flush().is_ok() && self.fsync != FsyncPolicy::Never {
+            let _ = self.file.get_ref().sync_data();
+        }
+    }
+}
+
+fn encode<T>(entry: &T) -> Result<Vec<u8>>
+where
+    T: Serialize<AllocSerializer<SCRATCH_SPACE>>,
+{
+    let archived = ::rkyv::to_bytes::<_, SCRATCH_SPACE>(entry)
+        .map_err(|error| anyhow!("failed to archive the journal entry: {error}"))?;
+    let len: u32 = archived
+        .len()
+        .try_into()
+        .map_err(|_| anyhow!("the journal entry is too large: {} bytes", archived.len()))?;
+
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + archived.len());
+    bytes.extend_from_slice(&len.to_le_bytes());
+    bytes.extend_from_slice(&::crc32fast::hash(&archived).to_le_bytes());
+    bytes.extend_from_slice(&archived);
+    Ok(bytes)
+}
+
+/// Synchronizes the directory of the file, so that a rename over it survives the power failures.
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(parent)?.sync_all().map_err(Into::into)
+}
+
+/// The directories cannot be opened as files on the other platforms, whose renames are durable.
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Decodes the entries up to the first torn or malformed one,
+/// returning them along with the length of the valid bytes.
+fn decode_all<T>(bytes: &[u8]) -> (Vec<T>, usize)
+where
+    T: Archive,
+    T::Archived: for<'a> ::bytecheck::CheckBytes<DefaultValidator<'a>> + Deserialize<T, Infallible>,
+{
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    while let Some((entry, len)) = bytes.get(offset..).and_then(decode_one::<T>) {
+        entries.push(entry);
+        offset += len;
+    }
+    (entries, offset)
+}
+
+fn decode_one<T>(bytes: &[u8]) -> Option<(T, usize)>
+where
+    T: Archive,
+    T::Archived: for<'a> ::bytecheck::CheckBytes<DefaultValidator<'a>> + Deserialize<T, Infallible>,
+{
+    let len = u32::from_le_bytes(bytes.get(..LEN_SIZE)?.try_into().ok()?) as usize;
+    let crc = u32::from_le_bytes(bytes.get(LEN_SIZE..HEADER_SIZE)?.try_into().ok()?);
+    let payload = bytes.get(HEADER_SIZE..HEADER_SIZE.checked_add(len)?)?;
+    if ::crc32fast::hash(payload) != crc {
+        return None;
+    }
+
+    let mut aligned = AlignedVec::with_capacity(payload.len());
+    aligned.extend_from_slice(payload);
+
+    let entry = ::rkyv::check_archived_root::<T>(&aligned)
+        .ok()?
+        .deserialize(&mut Infallible)
+        .expect("infallible");
+    Some((entry, HEADER_SIZE + len))
+}
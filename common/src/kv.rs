@@ -0,0 +1,162 @@
+//! A typed store of the small values (e.g. the configs) addressed by names.
+//!
+//! A value is archived with rkyv and stored by its content, e.g. in ipsis,
+//! and then a dynamic path of the given kind refers to it by the hash of its name.
+//! So the latest value of a name is resolved as any other dynamic path.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use ipiis_common::Ipiis;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::AccountRef,
+        anyhow::{anyhow, bail, Result},
+        value::hash::Hash,
+    },
+    path::{DynPath, Path},
+};
+use rkyv::{
+    ser::serializers::AllocSerializer, validation::validators::DefaultValidator, AlignedVec,
+    Archive, Deserialize, Infallible, Serialize,
+};
+
+use crate::{ensure_payload_len, Ipdis};
+
+/// the scratch space of the serializer, in bytes
+const SCRATCH_SPACE: usize = 4096;
+
+/// the maximum size of an archived value, in bytes
+pub const MAX_VALUE_LEN: usize = 64 * 1024;
+
+/// the name of the values in `IpdisError::PayloadTooLarge`
+pub const VALUE_PAYLOAD: &str = "value bytes";
+
+/// Stores the archived values addressed by their contents, e.g. in ipsis.
+#[async_trait]
+pub trait ValueStore {
+    async fn put(&self, path: &Path, value: &[u8]) -> Result<()>;
+
+    async fn get(&self, path: &Path) -> Result<Option<Vec<u8>>>;
+}
+
+/// A store kept in memory, which is lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryValueStore {
+    values: Mutex<HashMap<Hash, Vec<u8>>>,
+}
+
+#[async_trait]
+impl ValueStore for MemoryValueStore {
+    async fn put(&self, path: &Path, value: &[u8]) -> Result<()> {
+        self.values
+            .lock()
+            .map_err(|_| anyhow!("the value store has been poisoned"))?
+            .insert(path.value, value.to_vec());
+        Ok(())
+    }
+
+    async fn get(&self, path: &Path) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .values
+            .lock()
+            .map_err(|_| anyhow!("the value store has been poisoned"))?
+            .get(&path.value)
+            .cloned())
+    }
+}
+
+/// Returns the dynamic path of the name, without the value it refers to.
+pub fn key(namespace: Hash, kind: Hash, name: &str) -> DynPath<()> {
+    DynPath {
+        namespace,
+        kind,
+        word: Hash::with_str(name),
+        path: (),
+    }
+}
+
+/// Archives the value, and stores it by its content.
+pub async fn put_value<V, S>(store: &S, value: &V) -> Result<Path>
+where
+    V: Serialize<AllocSerializer<SCRATCH_SPACE>>,
+    S: ValueStore + Send + Sync,
+{
+    let bytes = ::rkyv::to_bytes::<_, SCRATCH_SPACE>(value)
+        .map_err(|error| anyhow!("failed to archive the value: {error}"))?;
+    ensure_payload_len(VALUE_PAYLOAD, Some(bytes.len()), MAX_VALUE_LEN)?;
+
+    let path = Path {
+        value: Hash::with_bytes(&bytes),
+        len: bytes.len().try_into()?,
+    };
+    store.put(&path, &bytes).await?;
+    Ok(path)
+}
+
+/// Stores the value, and then points the key (see `key`) to it.
+///
+/// The value should be small, as it is loaded as a whole; see `MAX_VALUE_LEN`.
+pub async fn put_kv<V, T, S, IpiisClient>(
+    ipdis: &T,
+    ipiis: &IpiisClient,
+    store: &S,
+    target: AccountRef,
+    key: DynPath<()>,
+    value: &V,
+) -> Result<Path>
+where
+    V: Serialize<AllocSerializer<SCRATCH_SPACE>>,
+    T: Ipdis + Send + Sync,
+    IpiisClient: Ipiis + Send + Sync,
+    S: ValueStore + Send + Sync,
+{
+    let path = put_value(store, value).await?;
+
+    let dyn_path = DynPath {
+        namespace: key.namespace,
+        kind: key.kind,
+        word: key.word,
+        path,
+    };
+    ipdis
+        .put_dyn_path_unchecked(&ipiis.sign(target, dyn_path)?)
+        .await?;
+    Ok(path)
+}
+
+/// Resolves the key (see `key`), and then loads the value it points to.
+///
+/// The loaded bytes are verified against the path, so the store needs not be trusted.
+pub async fn get_kv<R, T, S>(
+    ipdis: &T,
+    store: &S,
+    guarantee: Option<&AccountRef>,
+    key: DynPath<()>,
+) -> Result<Option<R>>
+where
+    R: Archive,
+    R::Archived: for<'a> ::bytecheck::CheckBytes<DefaultValidator<'a>> + Deserialize<R, Infallible>,
+    T: Ipdis + Send + Sync,
+    S: ValueStore + Send + Sync,
+{
+    let path = match ipdis.get_dyn_path_unchecked(guarantee, &key).await? {
+        Some(dyn_path) => dyn_path.data.data.data.path,
+        None => return Ok(None),
+    };
+
+    let bytes = match store.get(&path).await? {
+        Some(bytes) => bytes,
+        None => bail!("the value is missing in the store: {}", path.value),
+    };
+    if Hash::with_bytes(&bytes) != path.value || bytes.len() as u64 != path.len {
+        bail!("the value does not match its path: {}", path.value)
+    }
+
+    let mut aligned = AlignedVec::with_capacity(bytes.len());
+    aligned.extend_from_slice(&bytes);
+
+    ::rkyv::check_archived_root::<R>(&aligned)
+        .map_err(|error| anyhow!("malformed value: {error}"))
+        .map(|archived| Some(archived.deserialize(&mut Infallible).expect("infallible")))
+}
@@ -0,0 +1,76 @@
+use ipiis_common::Ipiis;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        anyhow::Result,
+        value::{hash::Hash, text::Text},
+    },
+    path::Path,
+    word::{Word, WordHash, WordKey},
+};
+
+use crate::{
+    normalize::{DefaultNormalizer, Normalizer},
+    Ipdis, KIND,
+};
+
+/// the language tag of the texts whose language cannot be determined (ISO 639-2)
+pub const UNDETERMINED: &str = "und";
+
+/// Creates a text of the undetermined language.
+pub fn undetermined(msg: impl ToString) -> Result<Text> {
+    Ok(Text {
+        msg: msg.to_string(),
+        lang: UNDETERMINED.parse()?,
+    })
+}
+
+/// Helpers for the clients which cannot detect the languages reliably.
+///
+/// The words of the undetermined language are still counted along with the others
+/// by `get_word_count_all_langs_unchecked`.
+#[async_trait]
+pub trait IpdisLang {
+    /// Puts the word of the undetermined language, which refers to the given static path.
+    async fn put_word_undetermined(
+        &self,
+        namespace: &str,
+        msg: &str,
+        kind: &str,
+        parent: &Hash,
+        path: &Path,
+    ) -> Result<WordHash>;
+}
+
+#[async_trait]
+impl<IpiisClient> IpdisLang for IpiisClient
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn put_word_undetermined(
+        &self,
+        namespace: &str,
+        msg: &str,
+        kind: &str,
+        parent: &Hash,
+        path: &Path,
+    ) -> Result<WordHash> {
+        let word: WordHash = Word {
+            key: WordKey {
+                namespace: namespace.to_string(),
+                text: DefaultNormalizer::default().normalize(undetermined(msg)?),
+            },
+            kind: kind.to_string(),
+            relpath: true,
+            path: *path,
+        }
+        .into();
+
+        // sign as guarantee
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+        let signed = self.sign(target, word)?;
+
+        self.put_word_unchecked(parent, &signed).await?;
+        Ok(word)
+    }
+}
@@ -1,23 +1,119 @@
+pub mod archived;
+#[cfg(feature = "client")]
+mod client;
+pub mod dual_write;
+mod error;
+#[cfg(feature = "client")]
+pub mod failover;
+mod feature;
+#[cfg(feature = "test-util")]
+pub mod fixtures;
+pub mod journal;
+pub mod kv;
+#[cfg(feature = "client")]
+pub mod lang;
+pub mod membership;
+#[cfg(feature = "test-util")]
+pub mod memory;
+pub mod merkle;
+#[cfg(feature = "client")]
+pub mod ngram;
+pub mod normalize;
+#[cfg(feature = "client")]
+pub mod pipeline;
+pub mod query;
+pub mod replay;
+#[cfg(feature = "client")]
+pub mod tokenize;
+
+#[cfg(feature = "client")]
+pub use self::client::IpdisRemote;
+pub use self::{
+    error::IpdisError,
+    feature::{Feature, FeatureSet},
+    normalize::Normalization,
+    query::{WordQuery, WordQueryGroupBy, WordQueryRow},
+};
+
+use std::time::Duration;
+
 use bytecheck::CheckBytes;
-use ipiis_common::{define_io, external_call, Ipiis, ServerResult};
+use ipiis_common::{define_io, ServerResult};
 use ipis::{
     async_trait::async_trait,
     core::{
         account::{AccountRef, GuaranteeSigned, GuarantorSigned},
-        anyhow::{bail, Result},
+        anyhow::{anyhow, bail, Result},
+        signature::{Signature, Verifier},
         signed::IsSigned,
-        value::hash::Hash,
+        value::{hash::Hash, text::TextHash, uuid::Uuid},
     },
     path::{DynPath, Path},
     word::{WordHash, WordKeyHash},
 };
-use rkyv::{Archive, Deserialize, Serialize};
+use rkyv::{ser::serializers::AllocSerializer, Archive, Deserialize, Serialize};
 
+/// The data plane of IPDIS, i.e. the records and the queries on them.
 #[async_trait]
 pub trait Ipdis {
     async fn ensure_registered(&self, guarantee: &AccountRef, guarantor: &AccountRef)
         -> Result<()>;
 
+    async fn ensure_admin(&self, guarantee: &AccountRef, guarantor: &AccountRef) -> Result<()>;
+
+    async fn get_kind(&self, query: &GuaranteeSigned<GetKind>) -> Result<Option<KindInfo>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.get_kind_unchecked(Some(guarantee), &query.data).await
+    }
+
+    async fn get_kind_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetKind,
+    ) -> Result<Option<KindInfo>>;
+
+    /// Enumerates the registered kinds, ordered by their names.
+    async fn get_kind_page(&self, query: &GuaranteeSigned<GetKinds>) -> Result<Page<KindInfo>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.get_kind_page_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    async fn get_kind_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetKinds,
+    ) -> Result<Page<KindInfo>>;
+
+    /// Acquires the exclusive lease of writing the records of the kind,
+    /// or renews the held one if its fencing token is given.
+    ///
+    /// Only the admins may acquire the leases, as a lease fences all the other writers of the kind.
+    /// An expired lease may be taken over by any admin, with a new fencing token.
+    async fn acquire_writer_lease(
+        &self,
+        query: &GuaranteeSigned<AcquireWriterLease>,
+    ) -> Result<WriterLease> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.acquire_writer_lease_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    async fn acquire_writer_lease_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &AcquireWriterLease,
+    ) -> Result<WriterLease>;
+
     async fn add_guarantee(&self, target: &GuaranteeSigned<AccountRef>) -> Result<()> {
         let guarantee = &target.guarantee.account;
         let guarantor = &target.data.guarantor;
@@ -29,6 +125,50 @@ pub trait Ipdis {
 
     async fn add_guarantee_unchecked(&self, guarantee: &GuaranteeSigned<AccountRef>) -> Result<()>;
 
+    async fn link_account_successor(
+        &self,
+        proof: &GuaranteeSigned<LinkAccountSuccessor>,
+    ) -> Result<()> {
+        let successor = &proof.guarantee.account;
+        let guarantor = &proof.data.guarantor;
+        proof.data.data.validate(successor)?;
+
+        // the successor inherits the registration of its predecessor
+        let predecessor = &proof.data.data.predecessor.guarantee.account;
+        self.ensure_registered(predecessor, guarantor).await?;
+
+        self.link_account_successor_unchecked(proof).await
+    }
+
+    /// Links the rotated account to its successor, which is then treated as the same principal.
+    ///
+    /// The predecessor is retired, so that a leaked old key cannot be used anymore.
+    async fn link_account_successor_unchecked(
+        &self,
+        proof: &GuaranteeSigned<LinkAccountSuccessor>,
+    ) -> Result<()>;
+
+    async fn get_account_chain(
+        &self,
+        query: &GuaranteeSigned<GetAccountChain>,
+    ) -> Result<Vec<AccountRef>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.get_account_chain_unchecked(Some(guarantee), &query.data.data)
+            .await
+    }
+
+    /// Returns the chain of the successions containing the account, oldest first.
+    ///
+    /// An account which has never been rotated forms a chain of itself.
+    async fn get_account_chain_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetAccountChain,
+    ) -> Result<Vec<AccountRef>>;
+
     async fn get_dyn_path<Path>(
         &self,
         path: &GuaranteeSigned<DynPath<Path>>,
@@ -49,18 +189,210 @@ pub trait Ipdis {
         guarantee: Option<&AccountRef>,
         path: &DynPath<Path>,
     ) -> Result<Option<GuarantorSigned<DynPath<::ipis::path::Path>>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        self.get_dyn_path_record_unchecked(guarantee, path)
+            .await
+            .map(|record| record.map(|record| record.data))
+    }
+
+    async fn get_dyn_path_record<Path>(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        let guarantee = &path.guarantee.account;
+        let guarantor = &path.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.get_dyn_path_record_unchecked(Some(guarantee), &path.data)
+            .await
+    }
+
+    async fn get_dyn_path_record_unchecked<Path>(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
     where
         Path: Copy + Send + Sync;
 
+    /// Returns the latest dynamic path of each of the given ones, in the same order.
+    async fn get_dyn_path_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        paths: &[DynPath<()>],
+    ) -> Result<Vec<Option<GuarantorSigned<DynPath<Path>>>>> {
+        let mut records = Vec::with_capacity(paths.len());
+        for path in paths {
+            records.push(self.get_dyn_path_unchecked(guarantee, path).await?);
+        }
+        Ok(records)
+    }
+
+    async fn get_members(&self, query: &GuaranteeSigned<GetMembers>) -> Result<Vec<Member>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.get_members_unchecked(Some(guarantee), &query.data.data)
+            .await
+    }
+
+    /// Returns the nodes of the cluster which have announced themselves recently, latest first.
+    ///
+    /// See `membership` for the announcements.
+    async fn get_members_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetMembers,
+    ) -> Result<Vec<Member>>;
+
+    /// Returns exactly the signed record put with the nonce, e.g. for the audits and the disputes.
+    async fn get_record_by_nonce(
+        &self,
+        query: &GuaranteeSigned<GetRecordByNonce>,
+    ) -> Result<Option<SignedRecord>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.get_record_by_nonce_unchecked(Some(guarantee), &query.data.data.nonce)
+            .await
+    }
+
+    async fn get_record_by_nonce_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<SignedRecord>>;
+
+    /// Returns the accepted writes after the watermark, in the total order of their acceptance,
+    /// which is permitted to the admins only.
+    async fn get_oplog(&self, query: &GuaranteeSigned<GetOplog>) -> Result<Oplog> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.get_oplog_unchecked(Some(guarantee), &query.data.data)
+            .await
+    }
+
+    async fn get_oplog_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetOplog,
+    ) -> Result<Oplog>;
+
+    /// Returns the proof that the record of the nonce has been accepted,
+    /// against the latest root of the oplog published by the server.
+    ///
+    /// Returns `None` if the record has not been logged, or no root covers it yet.
+    async fn get_inclusion_proof(
+        &self,
+        query: &GuaranteeSigned<GetInclusionProof>,
+    ) -> Result<Option<InclusionProof>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.get_inclusion_proof_unchecked(Some(guarantee), &query.data.data.nonce)
+            .await
+    }
+
+    async fn get_inclusion_proof_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        nonce: &Uuid,
+    ) -> Result<Option<InclusionProof>>;
+
+    /// Puts the path, discarding the receipt.
     async fn put_dyn_path(&self, path: &GuaranteeSigned<DynPath<Path>>) -> Result<()> {
+        self.put_dyn_path_with_metadata(path, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// Puts the path, discarding the receipt.
+    async fn put_dyn_path_unchecked(&self, path: &GuaranteeSigned<DynPath<Path>>) -> Result<()> {
+        self.put_dyn_path_with_metadata_unchecked(path, None)
+            .await
+            .map(|_| ())
+    }
+
+    async fn put_dyn_path_with_metadata(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+    ) -> Result<PutReceipt> {
+        let guarantee = &path.guarantee.account;
+        let guarantor = &path.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.put_dyn_path_with_metadata_unchecked(path, metadata)
+            .await
+    }
+
+    async fn put_dyn_path_with_metadata_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+    ) -> Result<PutReceipt> {
+        self.put_dyn_path_delegated_unchecked(path, metadata, None)
+            .await
+    }
+
+    /// Puts the path on behalf of the given account,
+    /// where the guarantee should be a gateway permitted to delegate the writes.
+    async fn put_dyn_path_delegated(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: &GuaranteeSigned<Delegation>,
+    ) -> Result<PutReceipt> {
         let guarantee = &path.guarantee.account;
         let guarantor = &path.data.guarantor;
         self.ensure_registered(guarantee, guarantor).await?;
 
-        self.put_dyn_path_unchecked(path).await
+        self.put_dyn_path_delegated_unchecked(path, metadata, Some(on_behalf_of))
+            .await
+    }
+
+    async fn put_dyn_path_delegated_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+    ) -> Result<PutReceipt> {
+        self.put_dyn_path_fenced_unchecked(path, metadata, on_behalf_of, None)
+            .await
+    }
+
+    /// Puts the path under the writer lease of its kind, identified by the fencing token.
+    async fn put_dyn_path_fenced(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        fencing_token: u64,
+    ) -> Result<PutReceipt> {
+        let guarantee = &path.guarantee.account;
+        let guarantor = &path.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.put_dyn_path_fenced_unchecked(path, metadata, None, Some(fencing_token))
+            .await
     }
 
-    async fn put_dyn_path_unchecked(&self, path: &GuaranteeSigned<DynPath<Path>>) -> Result<()>;
+    async fn put_dyn_path_fenced_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+    ) -> Result<PutReceipt>;
 
     async fn get_word_latest(
         &self,
@@ -84,6 +416,7 @@ pub trait Ipdis {
             parent: GetWordsParent::None,
             start_index: 0,
             end_index: 1,
+            with_total: false,
         };
 
         self.get_word_many_unchecked(guarantee, &query)
@@ -95,19 +428,92 @@ pub trait Ipdis {
         &self,
         query: &GuaranteeSigned<GetWords>,
     ) -> Result<Vec<GuarantorSigned<WordHash>>> {
+        self.get_word_page(query).await.map(|page| page.items)
+    }
+
+    async fn get_word_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Vec<GuarantorSigned<WordHash>>> {
+        self.get_word_page_unchecked(guarantee, query)
+            .await
+            .map(|page| page.items)
+    }
+
+    async fn get_word_page(
+        &self,
+        query: &GuaranteeSigned<GetWords>,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
         let guarantee = &query.guarantee.account;
         let guarantor = &query.data.guarantor;
         self.ensure_registered(guarantee, guarantor).await?;
 
-        self.get_word_many_unchecked(Some(guarantee), &query.data)
+        self.get_word_page_unchecked(Some(guarantee), &query.data)
             .await
     }
 
-    async fn get_word_many_unchecked(
+    async fn get_word_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
+        self.get_word_record_page_unchecked(guarantee, query)
+            .await
+            .map(|page| page.map(|record| record.data))
+    }
+
+    async fn get_word_record_page(
+        &self,
+        query: &GuaranteeSigned<GetWords>,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.get_word_record_page_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    async fn get_word_record_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>>;
+
+    /// Visits all the records matching the query, fetching a page of the query's size at a time,
+    /// so that only one page is materialized at once.
+    async fn for_each_word_record_unchecked<F>(
         &self,
         guarantee: Option<&AccountRef>,
         query: &GetWords,
-    ) -> Result<Vec<GuarantorSigned<WordHash>>>;
+        mut f: F,
+    ) -> Result<()>
+    where
+        F: FnMut(WithMetadata<GuarantorSigned<WordHash>>) -> Result<()> + Send,
+    {
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+        let page_size = query.end_index - query.start_index;
+
+        let mut query = *query;
+        loop {
+            let page = self
+                .get_word_record_page_unchecked(guarantee, &query)
+                .await?;
+            let next_cursor = page.next_cursor;
+            page.items.into_iter().try_for_each(&mut f)?;
+
+            match next_cursor {
+                Some(start_index) => {
+                    query.start_index = start_index;
+                    query.end_index = start_index.saturating_add(page_size);
+                }
+                None => break Ok(()),
+            }
+        }
+    }
 
     async fn get_word_count(
         &self,
@@ -134,6 +540,7 @@ pub trait Ipdis {
             owned,
             start_index: 0,
             end_index: 1,
+            with_total: false,
         };
 
         self.get_word_count_many_unchecked(guarantee, &query)
@@ -145,234 +552,1701 @@ pub trait Ipdis {
         &self,
         query: &GuaranteeSigned<GetWordsCounts>,
     ) -> Result<Vec<GetWordsCountsOutput>> {
+        self.get_word_count_page(query).await.map(|page| page.items)
+    }
+
+    async fn get_word_count_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+    ) -> Result<Vec<GetWordsCountsOutput>> {
+        self.get_word_count_page_unchecked(guarantee, query)
+            .await
+            .map(|page| page.items)
+    }
+
+    async fn get_word_count_page(
+        &self,
+        query: &GuaranteeSigned<GetWordsCounts>,
+    ) -> Result<Page<GetWordsCountsOutput>> {
         let guarantee = &query.guarantee.account;
         let guarantor = &query.data.guarantor;
         self.ensure_registered(guarantee, guarantor).await?;
 
-        self.get_word_count_many_unchecked(Some(guarantee), &query.data)
+        self.get_word_count_page_unchecked(Some(guarantee), &query.data)
             .await
     }
 
-    async fn get_word_count_many_unchecked(
+    async fn get_word_count_page_unchecked(
         &self,
         guarantee: Option<&AccountRef>,
         query: &GetWordsCounts,
-    ) -> Result<Vec<GetWordsCountsOutput>>;
+    ) -> Result<Page<GetWordsCountsOutput>> {
+        self.get_word_count_page_fresh_unchecked(guarantee, query, None)
+            .await
+            .map(|page| page.data)
+    }
 
-    async fn put_word(&self, parent: &Hash, word: &GuaranteeSigned<WordHash>) -> Result<()> {
-        let guarantee = &word.guarantee.account;
-        let guarantor = &word.data.guarantor;
+    async fn get_word_count_page_fresh(
+        &self,
+        query: &GuaranteeSigned<GetWordsCounts>,
+        max_staleness: Option<Duration>,
+    ) -> Result<Fresh<Page<GetWordsCountsOutput>>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
         self.ensure_registered(guarantee, guarantor).await?;
 
-        self.put_word_unchecked(parent, word).await
+        self.get_word_count_page_fresh_unchecked(Some(guarantee), &query.data, max_staleness)
+            .await
     }
 
-    async fn put_word_unchecked(
+    /// Returns the counts along with when they have been computed.
+    ///
+    /// The cached counts are returned only if they have been computed within `max_staleness`,
+    /// or whenever cached if it is `None`.
+    async fn get_word_count_page_fresh_unchecked(
         &self,
-        parent: &Hash,
-        word: &GuaranteeSigned<WordHash>,
-    ) -> Result<()>;
-}
+        guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+        max_staleness: Option<Duration>,
+    ) -> Result<Fresh<Page<GetWordsCountsOutput>>>;
 
-#[async_trait]
-impl<IpiisClient> Ipdis for IpiisClient
-where
-    IpiisClient: Ipiis + Send + Sync,
-{
-    async fn ensure_registered(
+    async fn get_word_count_page_batch_unchecked(
         &self,
-        guarantee: &AccountRef,
-        _guarantor: &AccountRef,
-    ) -> Result<()> {
-        let guarantee_now = self.account_me().account_ref();
-        if guarantee != &guarantee_now {
-            bail!("failed to authenticate the guarantee")
+        guarantee: Option<&AccountRef>,
+        queries: &[GetWordsCounts],
+    ) -> Result<Vec<Page<GetWordsCountsOutput>>> {
+        let mut pages = Vec::with_capacity(queries.len());
+        for query in queries {
+            pages.push(self.get_word_count_page_unchecked(guarantee, query).await?);
         }
-
-        Ok(())
+        Ok(pages)
     }
 
-    async fn add_guarantee_unchecked(&self, guarantee: &GuaranteeSigned<AccountRef>) -> Result<()> {
-        // next target
-        let target = self.get_account_primary(KIND.as_ref()).await?;
+    /// Sums the counts of the message in all the languages, e.g. the undetermined one.
+    async fn get_word_count_all_langs_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountAllLangs,
+    ) -> Result<u32>;
 
-        // external call
-        external_call!(
-            client: self,
-            target: KIND.as_ref() => &target,
-            request: crate::io => GuaranteePut,
-            sign: *guarantee,
-            inputs: { },
-            outputs: { },
-        );
+    /// Returns the changes of the word counts after the watermark, merged per word,
+    /// so that the external indexes can be synchronized incrementally.
+    ///
+    /// The exact changes are rejected for the other accounts if the counts are noised.
+    async fn get_word_count_delta(
+        &self,
+        query: &GuaranteeSigned<GetWordCountDelta>,
+    ) -> Result<WordCountDelta> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
 
-        // unpack response
-        Ok(())
+        self.get_word_count_delta_unchecked(Some(guarantee), &query.data.data)
+            .await
     }
 
-    async fn get_dyn_path_unchecked<Path>(
+    async fn get_word_count_delta_unchecked(
         &self,
-        _guarantee: Option<&AccountRef>,
-        path: &DynPath<Path>,
-    ) -> Result<Option<GuarantorSigned<DynPath<::ipis::path::Path>>>>
-    where
-        Path: Copy + Send + Sync,
-    {
-        // next target
-        let target = self.get_account_primary(KIND.as_ref()).await?;
+        guarantee: Option<&AccountRef>,
+        query: &GetWordCountDelta,
+    ) -> Result<WordCountDelta>;
 
-        // external call
-        let (path,) = external_call!(
-            client: self,
-            target: KIND.as_ref() => &target,
-            request: crate::io => DynPathGet,
-            sign: self.sign(target, (*path).remove_path())?,
-            inputs: { },
-            outputs: { path, },
-        );
+    async fn get_word_frequency_histogram(
+        &self,
+        query: &GuaranteeSigned<GetWordFrequencyHistogram>,
+    ) -> Result<Vec<WordFrequencyBucket>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
 
-        // unpack response
-        Ok(path)
+        self.get_word_frequency_histogram_unchecked(Some(guarantee), &query.data.data)
+            .await
+    }
+
+    /// Counts the distinct words of the kind by the decimal orders of their counts,
+    /// i.e. `1`, `2..=10`, `11..=100`, ..., where the last bucket is open-ended,
+    /// e.g. to tune the cutoffs of IDF or to spot the polluted indexes.
+    async fn get_word_frequency_histogram_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordFrequencyHistogram,
+    ) -> Result<Vec<WordFrequencyBucket>>;
+
+    /// Counts the documents (i.e. the parents) containing each word of the same namespace,
+    /// along with the number of all the documents of the namespace.
+    async fn get_idf_vector_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+    ) -> Result<IdfVector>;
+
+    /// Ranks the documents (i.e. the parents) sharing the words of the same namespace
+    /// by the overlap weighted with the inverse document frequencies, returning the top `top_k`.
+    ///
+    /// If `owned`, only the words of the guarantee are ranked, whose frequencies are exact;
+    /// otherwise the frequencies are noised as `get_idf_vector_unchecked` does.
+    async fn get_similar_documents_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[WordKeyHash],
+        top_k: u32,
+        owned: bool,
+    ) -> Result<Vec<SimilarDocument>>;
+
+    /// Runs the analytics over the words of the kind, as composed with the builder.
+    ///
+    /// Querying the words owned by the other accounts is permitted to the admins only.
+    async fn query_words(
+        &self,
+        sign: &GuaranteeSigned<QueryWords>,
+        query: &WordQuery,
+    ) -> Result<Vec<WordQueryRow>> {
+        let guarantee = &sign.guarantee.account;
+        let guarantor = &sign.data.guarantor;
+        match &query.owned_by {
+            Some(account) if account != guarantee => {
+                self.ensure_admin(guarantee, guarantor).await?
+            }
+            _ => self.ensure_registered(guarantee, guarantor).await?,
+        }
+        sign.data.data.validate(query)?;
+
+        self.query_words_unchecked(Some(guarantee), query).await
+    }
+
+    async fn query_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<Vec<WordQueryRow>>;
+
+    /// Puts the word, discarding the receipt.
+    async fn put_word(&self, parent: &Hash, word: &GuaranteeSigned<WordHash>) -> Result<()> {
+        self.put_word_with_metadata(parent, word, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// Puts the word, discarding the receipt.
+    async fn put_word_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+    ) -> Result<()> {
+        self.put_word_with_metadata_unchecked(parent, word, None)
+            .await
+            .map(|_| ())
+    }
+
+    async fn put_word_with_metadata(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+    ) -> Result<PutReceipt> {
+        let guarantee = &word.guarantee.account;
+        let guarantor = &word.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.put_word_with_metadata_unchecked(parent, word, metadata)
+            .await
+    }
+
+    async fn put_word_with_metadata_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+    ) -> Result<PutReceipt> {
+        self.put_word_delegated_unchecked(parent, word, metadata, None)
+            .await
+    }
+
+    /// Puts the word on behalf of the given account,
+    /// where the guarantee should be a gateway permitted to delegate the writes.
+    async fn put_word_delegated(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: &GuaranteeSigned<Delegation>,
+    ) -> Result<PutReceipt> {
+        let guarantee = &word.guarantee.account;
+        let guarantor = &word.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.put_word_delegated_unchecked(parent, word, metadata, Some(on_behalf_of))
+            .await
+    }
+
+    async fn put_word_delegated_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+    ) -> Result<PutReceipt> {
+        self.put_word_fenced_unchecked(parent, word, metadata, on_behalf_of, None)
+            .await
+    }
+
+    /// Puts the word under the writer lease of its kind, identified by the fencing token.
+    async fn put_word_fenced(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        fencing_token: u64,
+    ) -> Result<PutReceipt> {
+        let guarantee = &word.guarantee.account;
+        let guarantor = &word.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.put_word_fenced_unchecked(parent, word, metadata, None, Some(fencing_token))
+            .await
+    }
+
+    async fn put_word_fenced_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+    ) -> Result<PutReceipt> {
+        self.put_word_normalized_unchecked(
+            parent,
+            word,
+            metadata,
+            on_behalf_of,
+            fencing_token,
+            None,
+        )
+        .await
+    }
+
+    /// Puts the word, declaring the normalization of its text.
+    ///
+    /// The words of a kind with a normalization policy are rejected
+    /// unless they declare the same normalization; see `RegisterKind`.
+    async fn put_word_normalized(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        normalization: Normalization,
+    ) -> Result<PutReceipt> {
+        let guarantee = &word.guarantee.account;
+        let guarantor = &word.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.put_word_normalized_unchecked(parent, word, metadata, None, None, Some(normalization))
+            .await
+    }
+
+    async fn put_word_normalized_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<PutReceipt>;
+
+    /// Puts the words of a guarantee sharing the parent at once, e.g. the n-grams of a text.
+    ///
+    /// The words are stored in a single transaction, so either all or none of them are put.
+    /// The fencing token and the normalization are declared for all of them,
+    /// as `put_word_fenced` and `put_word_normalized` do.
+    async fn put_word_many(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Vec<PutReceipt>> {
+        let word = match words.first() {
+            Some(word) => word,
+            None => return Ok(vec![]),
+        };
+        ensure_same_guarantee(words)?;
+
+        let guarantee = &word.guarantee.account;
+        let guarantor = &word.data.guarantor;
+        self.ensure_registered(guarantee, guarantor).await?;
+
+        self.put_word_many_unchecked(parent, words, fencing_token, normalization)
+            .await
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Vec<PutReceipt>>;
+}
+
+/// The administrative operations, e.g. the maintenance, the diagnostics and the policies,
+/// which a read-only or a data-plane-only backend need not implement.
+#[async_trait]
+pub trait IpdisAdmin: Ipdis {
+    async fn set_read_only(&self, query: &GuaranteeSigned<SetReadOnly>) -> Result<()> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.set_read_only_unchecked(Some(guarantee), query.data.data.enabled)
+            .await
+    }
+
+    async fn set_read_only_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        enabled: bool,
+    ) -> Result<()>;
+
+    async fn get_server_diagnostics(
+        &self,
+        query: &GuaranteeSigned<GetServerDiagnostics>,
+    ) -> Result<ServerDiagnostics> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.get_server_diagnostics_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    async fn get_server_diagnostics_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetServerDiagnostics,
+    ) -> Result<ServerDiagnostics>;
+
+    /// Returns the activity of the account, which is permitted to the account itself and the admins.
+    async fn get_account_stats(
+        &self,
+        query: &GuaranteeSigned<GetAccountStats>,
+    ) -> Result<AccountStats> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        if guarantee == &query.data.data.account {
+            self.ensure_registered(guarantee, guarantor).await?;
+        } else {
+            self.ensure_admin(guarantee, guarantor).await?;
+        }
+
+        self.get_account_stats_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    async fn get_account_stats_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetAccountStats,
+    ) -> Result<AccountStats>;
+
+    /// Describes the kind, overwriting the previous description if any.
+    async fn register_kind(
+        &self,
+        sign: &GuaranteeSigned<DescribeKind>,
+        name: &str,
+        description: &str,
+    ) -> Result<()> {
+        let guarantee = &sign.guarantee.account;
+        let guarantor = &sign.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+        sign.data.data.validate(name, description)?;
+
+        self.register_kind_unchecked(Some(guarantee), &sign.data.data.query, name, description)
+            .await
+    }
+
+    async fn register_kind_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &RegisterKind,
+        name: &str,
+        description: &str,
+    ) -> Result<()>;
+
+    async fn get_path_reference_count(
+        &self,
+        query: &GuaranteeSigned<GetPathReferenceCount>,
+    ) -> Result<u32> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.get_path_reference_count_unchecked(Some(guarantee), &query.data.data.path)
+            .await
+    }
+
+    /// Returns the number of the active dynamic paths and words of all accounts,
+    /// which refer to the given static path.
+    async fn get_path_reference_count_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &Hash,
+    ) -> Result<u32>;
+
+    async fn get_dyn_path_by_target(
+        &self,
+        query: &GuaranteeSigned<GetDynPathsByTarget>,
+    ) -> Result<Page<GuarantorSigned<DynPath<Path>>>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.get_dyn_path_by_target_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    /// Returns the active dynamic paths of all accounts which refer to the given static path,
+    /// latest first.
+    async fn get_dyn_path_by_target_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetDynPathsByTarget,
+    ) -> Result<Page<GuarantorSigned<DynPath<Path>>>>;
+
+    async fn get_idf_log_page(
+        &self,
+        query: &GuaranteeSigned<GetIdfLogs>,
+    ) -> Result<Page<GuarantorSigned<WordHash>>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.get_idf_log_page_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    /// Returns the live words of all accounts created in the range, oldest first,
+    /// which are read from both the hot table and the tiered segments.
+    async fn get_idf_log_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetIdfLogs,
+    ) -> Result<Page<GuarantorSigned<WordHash>>>;
+
+    /// A hook for the garbage collectors of the contents, e.g. ipsis,
+    /// so that no content referred by the index is deleted.
+    async fn is_path_referenced_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &Hash,
+    ) -> Result<bool> {
+        self.get_path_reference_count_unchecked(guarantee, path)
+            .await
+            .map(|count| count > 0)
+    }
+
+    /// Runs the query as `query_words` with `EXPLAIN (ANALYZE, BUFFERS)`, and returns the plan,
+    /// which is permitted to the admins only.
+    async fn explain_query(
+        &self,
+        sign: &GuaranteeSigned<QueryWords>,
+        query: &WordQuery,
+    ) -> Result<String> {
+        let guarantee = &sign.guarantee.account;
+        let guarantor = &sign.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+        sign.data.data.validate(query)?;
+
+        self.explain_query_unchecked(Some(guarantee), query).await
+    }
+
+    async fn explain_query_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &WordQuery,
+    ) -> Result<String>;
+
+    /// Issues a token of the public query tier, which is permitted the given read features.
+    ///
+    /// The secret of the token is returned once, as only its hash is stored.
+    async fn issue_api_token(
+        &self,
+        sign: &GuaranteeSigned<IssueApiToken>,
+        name: &str,
+        scopes: &FeatureSet,
+    ) -> Result<IssuedApiToken> {
+        let guarantee = &sign.guarantee.account;
+        let guarantor = &sign.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+        sign.data.data.validate(name, scopes)?;
+
+        self.issue_api_token_unchecked(
+            Some(guarantee),
+            name,
+            scopes,
+            sign.data.data.rate_limit,
+            sign.data.data.expiration_time,
+        )
+        .await
+    }
+
+    async fn issue_api_token_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        name: &str,
+        scopes: &FeatureSet,
+        rate_limit: u32,
+        expiration_time: Option<i64>,
+    ) -> Result<IssuedApiToken>;
+
+    async fn get_api_token_page(
+        &self,
+        query: &GuaranteeSigned<GetApiTokens>,
+    ) -> Result<Page<ApiTokenInfo>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.get_api_token_page_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    /// Enumerates the issued API tokens, oldest first, without their secrets.
+    async fn get_api_token_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetApiTokens,
+    ) -> Result<Page<ApiTokenInfo>>;
+
+    async fn revoke_api_token(&self, query: &GuaranteeSigned<RevokeApiToken>) -> Result<()> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.revoke_api_token_unchecked(Some(guarantee), query.data.data.id)
+            .await
+    }
+
+    async fn revoke_api_token_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        id: i32,
+    ) -> Result<()>;
+
+    async fn add_stop_word(&self, query: &GuaranteeSigned<AddStopWord>) -> Result<()> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.add_stop_word_unchecked(
+            Some(guarantee),
+            &query.data.data.kind,
+            &query.data.data.word,
+        )
+        .await
+    }
+
+    /// Registers the stop word of the kind and the language.
+    ///
+    /// The counts of the word are deleted if the stop words are not counted.
+    async fn add_stop_word_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        kind: &Hash,
+        word: &TextHash,
+    ) -> Result<()>;
+
+    async fn list_stop_words(
+        &self,
+        query: &GuaranteeSigned<GetStopWords>,
+    ) -> Result<Vec<TextHash>> {
+        let guarantee = &query.guarantee.account;
+        let guarantor = &query.data.guarantor;
+        self.ensure_admin(guarantee, guarantor).await?;
+
+        self.list_stop_words_unchecked(Some(guarantee), &query.data.data.kind)
+            .await
+    }
+
+    /// Lists the stop words of the kind, in all the languages.
+    async fn list_stop_words_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        kind: &Hash,
+    ) -> Result<Vec<TextHash>>;
+}
+
+define_io! {
+    ReadOnlySet {
+        inputs: { },
+        input_sign: GuaranteeSigned<SetReadOnly>,
+        outputs: { },
+        output_sign: GuarantorSigned<SetReadOnly>,
+        generics: { },
+    },
+    DiagnosticsGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetServerDiagnostics>,
+        outputs: {
+            diagnostics: ServerDiagnostics,
+        },
+        output_sign: GuarantorSigned<GetServerDiagnostics>,
+        generics: { },
+    },
+    AccountStatsGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetAccountStats>,
+        outputs: {
+            stats: AccountStats,
+        },
+        output_sign: GuarantorSigned<GetAccountStats>,
+        generics: { },
+    },
+    KindRegister {
+        inputs: {
+            name: String,
+            description: String,
+        },
+        input_sign: GuaranteeSigned<DescribeKind>,
+        outputs: { },
+        output_sign: GuarantorSigned<DescribeKind>,
+        generics: { },
+    },
+    KindGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetKind>,
+        outputs: {
+            info: Option<KindInfo>,
+        },
+        output_sign: GuarantorSigned<GetKind>,
+        generics: { },
+    },
+    KindGetMany {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetKinds>,
+        outputs: {
+            kinds: Page<KindInfo>,
+        },
+        output_sign: GuarantorSigned<GetKinds>,
+        generics: { },
+    },
+    WriterLeaseAcquire {
+        inputs: { },
+        input_sign: GuaranteeSigned<AcquireWriterLease>,
+        outputs: {
+            lease: WriterLease,
+        },
+        output_sign: GuarantorSigned<AcquireWriterLease>,
+        generics: { },
+    },
+    GuaranteePut {
+        inputs: { },
+        input_sign: GuaranteeSigned<AccountRef>,
+        outputs: { },
+        output_sign: GuarantorSigned<AccountRef>,
+        generics: { },
+    },
+    AccountSuccessorLink {
+        inputs: { },
+        input_sign: GuaranteeSigned<LinkAccountSuccessor>,
+        outputs: { },
+        output_sign: GuarantorSigned<LinkAccountSuccessor>,
+        generics: { },
+    },
+    AccountChainGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetAccountChain>,
+        outputs: {
+            chain: Vec<AccountRef>,
+        },
+        output_sign: GuarantorSigned<GetAccountChain>,
+        generics: { },
+    },
+    DynPathGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<DynPath<()>>,
+        outputs: {
+            path: Option<WithMetadata<GuarantorSigned<DynPath<Path>>>>,
+        },
+        output_sign: GuarantorSigned<DynPath<()>>,
+        generics: { },
+    },
+    DynPathGetByTarget {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetDynPathsByTarget>,
+        outputs: {
+            paths: Page<GuarantorSigned<DynPath<Path>>>,
+        },
+        output_sign: GuarantorSigned<GetDynPathsByTarget>,
+        generics: { },
+    },
+    DynPathGetMany {
+        inputs: {
+            paths: Vec<DynPath<()>>,
+        },
+        input_sign: GuaranteeSigned<GetDynPathsMany>,
+        outputs: {
+            paths: Vec<Option<GuarantorSigned<DynPath<Path>>>>,
+        },
+        output_sign: GuarantorSigned<GetDynPathsMany>,
+        generics: { },
+    },
+    MembersGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetMembers>,
+        outputs: {
+            members: Vec<Member>,
+        },
+        output_sign: GuarantorSigned<GetMembers>,
+        generics: { },
+    },
+    PathReferenceCountGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetPathReferenceCount>,
+        outputs: {
+            count: u32,
+        },
+        output_sign: GuarantorSigned<GetPathReferenceCount>,
+        generics: { },
+    },
+    RecordGetByNonce {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetRecordByNonce>,
+        outputs: {
+            record: Option<SignedRecord>,
+        },
+        output_sign: GuarantorSigned<GetRecordByNonce>,
+        generics: { },
+    },
+    OplogGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetOplog>,
+        outputs: {
+            oplog: Oplog,
+        },
+        output_sign: GuarantorSigned<GetOplog>,
+        generics: { },
+    },
+    InclusionProofGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetInclusionProof>,
+        outputs: {
+            proof: Option<InclusionProof>,
+        },
+        output_sign: GuarantorSigned<GetInclusionProof>,
+        generics: { },
+    },
+    DynPathPut {
+        inputs: {
+            metadata: Option<Vec<u8>>,
+            on_behalf_of: Option<GuaranteeSigned<Delegation>>,
+            fencing_token: Option<u64>,
+        },
+        input_sign: GuaranteeSigned<DynPath<Path>>,
+        outputs: {
+            receipt: PutReceipt,
+        },
+        output_sign: GuarantorSigned<DynPath<Path>>,
+        generics: { },
+    },
+    WordGetMany {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetWords>,
+        outputs: {
+            words: Page<WithMetadata<GuarantorSigned<WordHash>>>,
+        },
+        output_sign: GuarantorSigned<GetWords>,
+        generics: { },
+    },
+    WordPut {
+        inputs: {
+            parent: Hash,
+            metadata: Option<Vec<u8>>,
+            on_behalf_of: Option<GuaranteeSigned<Delegation>>,
+            fencing_token: Option<u64>,
+            normalization: Option<Normalization>,
+        },
+        input_sign: GuaranteeSigned<WordHash>,
+        outputs: {
+            receipt: PutReceipt,
+        },
+        output_sign: GuarantorSigned<WordHash>,
+        generics: { },
+    },
+    WordCountGetMany {
+        inputs: {
+            max_staleness_ms: Option<u64>,
+        },
+        input_sign: GuaranteeSigned<GetWordsCounts>,
+        outputs: {
+            counts: Fresh<Page<GetWordsCountsOutput>>,
+        },
+        output_sign: GuarantorSigned<GetWordsCounts>,
+        generics: { },
+    },
+    WordPutMany {
+        inputs: {
+            parent: Hash,
+            words: Vec<GuaranteeSigned<WordHash>>,
+            fencing_token: Option<u64>,
+            normalization: Option<Normalization>,
+        },
+        input_sign: GuaranteeSigned<PutWordsBatch>,
+        outputs: {
+            receipts: Vec<PutReceipt>,
+        },
+        output_sign: GuarantorSigned<PutWordsBatch>,
+        generics: { },
+    },
+    WordCountGetBatch {
+        inputs: {
+            queries: Vec<GetWordsCounts>,
+        },
+        input_sign: GuaranteeSigned<GetWordsCountsBatch>,
+        outputs: {
+            pages: Vec<Page<GetWordsCountsOutput>>,
+        },
+        output_sign: GuarantorSigned<GetWordsCountsBatch>,
+        generics: { },
+    },
+    WordCountGetAllLangs {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetWordCountAllLangs>,
+        outputs: {
+            count: u32,
+        },
+        output_sign: GuarantorSigned<GetWordCountAllLangs>,
+        generics: { },
+    },
+    WordCountDeltaGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetWordCountDelta>,
+        outputs: {
+            delta: WordCountDelta,
+        },
+        output_sign: GuarantorSigned<GetWordCountDelta>,
+        generics: { },
+    },
+    WordFrequencyHistogramGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetWordFrequencyHistogram>,
+        outputs: {
+            buckets: Vec<WordFrequencyBucket>,
+        },
+        output_sign: GuarantorSigned<GetWordFrequencyHistogram>,
+        generics: { },
+    },
+    IdfVectorGet {
+        inputs: {
+            words: Vec<WordKeyHash>,
+        },
+        input_sign: GuaranteeSigned<GetIdfVector>,
+        outputs: {
+            vector: IdfVector,
+        },
+        output_sign: GuarantorSigned<GetIdfVector>,
+        generics: { },
+    },
+    SimilarDocumentsGet {
+        inputs: {
+            words: Vec<WordKeyHash>,
+        },
+        input_sign: GuaranteeSigned<GetSimilarDocuments>,
+        outputs: {
+            documents: Vec<SimilarDocument>,
+        },
+        output_sign: GuarantorSigned<GetSimilarDocuments>,
+        generics: { },
+    },
+    WordQueryGet {
+        inputs: {
+            query: WordQuery,
+        },
+        input_sign: GuaranteeSigned<QueryWords>,
+        outputs: {
+            rows: Vec<WordQueryRow>,
+        },
+        output_sign: GuarantorSigned<QueryWords>,
+        generics: { },
+    },
+    QueryExplain {
+        inputs: {
+            query: WordQuery,
+        },
+        input_sign: GuaranteeSigned<QueryWords>,
+        outputs: {
+            plan: String,
+        },
+        output_sign: GuarantorSigned<QueryWords>,
+        generics: { },
+    },
+    IdfLogsGet {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetIdfLogs>,
+        outputs: {
+            words: Page<GuarantorSigned<WordHash>>,
+        },
+        output_sign: GuarantorSigned<GetIdfLogs>,
+        generics: { },
+    },
+    DynPathGetWithToken {
+        inputs: {
+            token: String,
+        },
+        input_sign: GuaranteeSigned<DynPath<()>>,
+        outputs: {
+            path: Option<WithMetadata<GuarantorSigned<DynPath<Path>>>>,
+        },
+        output_sign: GuarantorSigned<DynPath<()>>,
+        generics: { },
+    },
+    WordGetManyWithToken {
+        inputs: {
+            token: String,
+        },
+        input_sign: GuaranteeSigned<GetWords>,
+        outputs: {
+            words: Page<WithMetadata<GuarantorSigned<WordHash>>>,
+        },
+        output_sign: GuarantorSigned<GetWords>,
+        generics: { },
+    },
+    ApiTokenIssue {
+        inputs: {
+            name: String,
+            scopes: String,
+        },
+        input_sign: GuaranteeSigned<IssueApiToken>,
+        outputs: {
+            token: IssuedApiToken,
+        },
+        output_sign: GuarantorSigned<IssueApiToken>,
+        generics: { },
+    },
+    ApiTokenGetMany {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetApiTokens>,
+        outputs: {
+            tokens: Page<ApiTokenInfo>,
+        },
+        output_sign: GuarantorSigned<GetApiTokens>,
+        generics: { },
+    },
+    ApiTokenRevoke {
+        inputs: { },
+        input_sign: GuaranteeSigned<RevokeApiToken>,
+        outputs: { },
+        output_sign: GuarantorSigned<RevokeApiToken>,
+        generics: { },
+    },
+    StopWordAdd {
+        inputs: { },
+        input_sign: GuaranteeSigned<AddStopWord>,
+        outputs: { },
+        output_sign: GuarantorSigned<AddStopWord>,
+        generics: { },
+    },
+    StopWordGetMany {
+        inputs: { },
+        input_sign: GuaranteeSigned<GetStopWords>,
+        outputs: {
+            words: Vec<TextHash>,
+        },
+        output_sign: GuarantorSigned<GetStopWords>,
+        generics: { },
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct SetReadOnly {
+    pub enabled: bool,
+}
+
+impl IsSigned for SetReadOnly {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetServerDiagnostics {
+    /// the maximum number of the slowest recent queries to be returned
+    pub slow_queries: u32,
+}
+
+impl IsSigned for GetServerDiagnostics {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct ServerDiagnostics {
+    pub connections_total: u32,
+    pub connections_active: u32,
+    pub requests_waiting: u32,
+    /// the number of the requests waiting for their turn in the queue
+    pub requests_queued: u32,
+    /// the longest time which the recent requests have been queued, in microseconds
+    pub queue_time_us: u64,
+    /// sorted by the elapsed time, slowest first
+    pub slow_queries: Vec<SlowQuery>,
+    /// resident memory of the server process in bytes, if available
+    pub memory_usage: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct SlowQuery {
+    pub name: String,
+    /// hash of the query parameters, so that no raw record is leaked
+    pub params: Hash,
+    pub elapsed_us: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetAccountStats {
+    pub account: AccountRef,
+}
+
+impl IsSigned for GetAccountStats {}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct AccountStats {
+    /// the number of the words written by the account
+    pub words: u64,
+    /// the number of the dynamic paths written by the account
+    pub dyn_paths: u64,
+    /// the unix timestamp of the first record, in seconds
+    pub first_activity: Option<i64>,
+    /// the unix timestamp of the last record, in seconds
+    pub last_activity: Option<i64>,
+    /// the size of the records in the storage, in bytes
+    pub storage_bytes: u64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct RegisterKind {
+    pub kind: Hash,
+    /// the version of the schema of the records of the kind, defined by the application
+    pub schema_version: u32,
+    /// the normalization which the words of the kind should declare, if enforced
+    pub normalization: Option<Normalization>,
+}
+
+impl IsSigned for RegisterKind {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct DescribeKind {
+    pub query: RegisterKind,
+    /// the hash of the name and the description of the kind, which are sent along with the sign
+    pub hash: Hash,
+}
+
+impl IsSigned for DescribeKind {}
+
+impl DescribeKind {
+    pub fn new(query: RegisterKind, name: &str, description: &str) -> Result<Self> {
+        Ok(Self {
+            query,
+            hash: Self::hash(name, description)?,
+        })
+    }
+
+    /// Ensures that the name and the description sent along with the sign are the signed ones.
+    pub fn validate(&self, name: &str, description: &str) -> Result<()> {
+        if Self::hash(name, description)? != self.hash {
+            bail!("malformed query: the description of the kind is not signed")
+        }
+        Ok(())
+    }
+
+    fn hash(name: &str, description: &str) -> Result<Hash> {
+        hash_batch(&[(name.to_string(), description.to_string())])
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetKind {
+    pub kind: Hash,
+}
+
+impl IsSigned for GetKind {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetKinds {
+    pub start_index: u32,
+    pub end_index: u32,
+}
+
+impl IsSigned for GetKinds {}
+
+/// The human-meaningful description of a kind hash.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct KindInfo {
+    pub kind: Hash,
+    pub name: String,
+    pub description: String,
+    pub schema_version: u32,
+    pub normalization: Option<Normalization>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct AcquireWriterLease {
+    pub kind: Hash,
+    /// how long the lease is held unless renewed, in milliseconds
+    pub ttl_ms: u64,
+    /// the fencing token of the lease to be renewed, or `None` to acquire a new one
+    pub token: Option<u64>,
+}
+
+impl IsSigned for AcquireWriterLease {}
+
+/// The exclusive lease of writing the records of a kind.
+///
+/// The fencing token grows with every new lease of the kind,
+/// so that the writes of a previous holder are rejected once its lease has been taken over.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct WriterLease {
+    pub kind: Hash,
+    pub holder: AccountRef,
+    /// the token which should accompany the writes while the lease is held
+    pub token: u64,
+    /// the unix timestamp when the lease expires, in milliseconds
+    pub expiration_time: i64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct IssueApiToken {
+    /// the maximum number of the requests per minute, on each node
+    pub rate_limit: u32,
+    /// the unix timestamp when the token expires, in milliseconds, or `None` to never expire
+    pub expiration_time: Option<i64>,
+    /// the hash of the name and the scopes of the token, which are sent along with the sign
+    pub hash: Hash,
+}
+
+impl IsSigned for IssueApiToken {}
+
+impl IssueApiToken {
+    pub fn new(
+        name: &str,
+        scopes: &FeatureSet,
+        rate_limit: u32,
+        expiration_time: Option<i64>,
+    ) -> Result<Self> {
+        Ok(Self {
+            rate_limit,
+            expiration_time,
+            hash: Self::hash(name, scopes)?,
+        })
+    }
+
+    /// Ensures that the name and the scopes sent along with the sign are the signed ones.
+    pub fn validate(&self, name: &str, scopes: &FeatureSet) -> Result<()> {
+        if Self::hash(name, scopes)? != self.hash {
+            bail!("malformed query: the scopes of the API token are not signed")
+        }
+        Ok(())
+    }
+
+    fn hash(name: &str, scopes: &FeatureSet) -> Result<Hash> {
+        hash_batch(&[(name.to_string(), scopes.to_string())])
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetApiTokens {
+    /// inclusive left bound
+    pub start_index: u32,
+    /// exclusive right bound
+    pub end_index: u32,
+}
+
+impl IsSigned for GetApiTokens {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct RevokeApiToken {
+    pub id: i32,
+}
+
+impl IsSigned for RevokeApiToken {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct AddStopWord {
+    pub kind: Hash,
+    pub word: TextHash,
+}
+
+impl IsSigned for AddStopWord {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetStopWords {
+    pub kind: Hash,
+}
+
+impl IsSigned for GetStopWords {}
+
+/// An API token of the public query tier, as listed to the admins, without its secret.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct ApiTokenInfo {
+    pub id: i32,
+    pub name: String,
+    /// the read features permitted to the token, as parsed by `FeatureSet`
+    pub scopes: String,
+    /// the maximum number of the requests per minute, on each node
+    pub rate_limit: u32,
+    /// the unix timestamp when the token was issued, in milliseconds
+    pub created_time: i64,
+    /// the unix timestamp when the token expires, in milliseconds, if any
+    pub expiration_time: Option<i64>,
+}
+
+/// A newly issued API token, along with its secret, which is shown only once.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct IssuedApiToken {
+    pub token: String,
+    pub info: ApiTokenInfo,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetDynPathsByTarget {
+    /// the hash of the static path
+    pub path: Hash,
+    pub start_index: u32,
+    pub end_index: u32,
+}
+
+impl IsSigned for GetDynPathsByTarget {}
+
+/// The words created in `since..until`, i.e. the logs from which the IDF vectors are computed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetIdfLogs {
+    /// the unix timestamp of the oldest word, in milliseconds
+    pub since: i64,
+    /// the unix timestamp after the newest word, in milliseconds
+    pub until: i64,
+    pub start_index: u32,
+    pub end_index: u32,
+}
+
+impl IsSigned for GetIdfLogs {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetDynPathsMany {
+    /// the number of the paths, which are sent along with the sign
+    pub len: u32,
+    /// the hash of the paths, so that they cannot be replaced in transit
+    pub hash: Hash,
+}
+
+impl IsSigned for GetDynPathsMany {}
+
+impl GetDynPathsMany {
+    pub fn new(paths: &[DynPath<()>]) -> Result<Self> {
+        Ok(Self {
+            len: paths.len().try_into()?,
+            hash: hash_batch(paths)?,
+        })
+    }
+
+    /// Ensures that the paths sent along with the sign are the signed ones.
+    pub fn validate(&self, paths: &[DynPath<()>]) -> Result<()> {
+        ensure_batch("paths", self.len, &self.hash, paths)
+    }
+}
+
+/// A succession of a rotated account, which is signed by the successor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct LinkAccountSuccessor {
+    /// the successor signed by the predecessor, which proves that the old key hands over
+    pub predecessor: GuaranteeSigned<AccountRef>,
+}
+
+impl IsSigned for LinkAccountSuccessor {}
+
+impl LinkAccountSuccessor {
+    /// Ensures that the predecessor hands over to the given successor, rather than to itself.
+    pub fn validate(&self, successor: &AccountRef) -> Result<()> {
+        if &self.predecessor.data.data != successor {
+            bail!("the proof of the succession is signed for another successor")
+        }
+        if &self.predecessor.guarantee.account == successor {
+            bail!("an account cannot succeed itself")
+        }
+        Ok(())
+    }
+}
+
+/// A write on behalf of the principal, which is signed by the gateway putting the record.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct Delegation {
+    pub principal: AccountRef,
+    /// the nonce of the delegated record, so that the delegation cannot be replayed on the others
+    pub nonce: Uuid,
+}
+
+impl IsSigned for Delegation {}
+
+impl Delegation {
+    /// Ensures that the delegation is signed for the given record.
+    pub fn validate(&self, nonce: &Uuid) -> Result<()> {
+        if &self.nonce != nonce {
+            bail!("the delegation is signed for another record")
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetAccountChain {
+    pub account: AccountRef,
+}
+
+impl IsSigned for GetAccountChain {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetMembers {
+    /// the namespace of the cluster
+    pub namespace: Hash,
+    /// how recently the members should have announced themselves, in seconds
+    pub ttl_secs: u32,
+}
+
+impl IsSigned for GetMembers {}
+
+/// A node of the cluster, which has announced itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct Member {
+    pub account: AccountRef,
+    /// when the node has announced itself last, in milliseconds since the epoch
+    pub last_seen_ms: i64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetRecordByNonce {
+    /// the nonce of the record, which has been returned on put
+    pub nonce: Uuid,
+}
+
+impl IsSigned for GetRecordByNonce {}
+
+/// A record signed by both the guarantee and the guarantor, of any table.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub enum SignedRecord {
+    DynPath(GuarantorSigned<DynPath<Path>>),
+    Word(GuarantorSigned<WordHash>),
+}
+
+impl SignedRecord {
+    /// Returns the hash of the archived record, which is logged in the oplog.
+    pub fn hash(&self) -> Result<Hash> {
+        ::rkyv::to_bytes::<_, 4096>(self)
+            .map(|bytes| Hash::with_bytes(&bytes))
+            .map_err(|error| anyhow!("failed to archive the record: {error}"))
     }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetOplog {
+    /// the watermark returned by the previous call, or `0` to start from the beginning
+    pub since_seq: u64,
+    /// the maximum number of the entries to be returned at once
+    pub limit: u32,
+}
+
+impl IsSigned for GetOplog {}
+
+/// The accepted writes, in the total order of their acceptance.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct Oplog {
+    pub entries: Vec<OplogEntry>,
+    /// the watermark to be given to the next call
+    pub until_seq: u64,
+    /// whether there are more entries after the watermark
+    pub has_more: bool,
+}
+
+/// An accepted write, whose record can be fetched and verified by its nonce.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct OplogEntry {
+    pub seq: u64,
+    pub nonce: Uuid,
+    /// the hash of the record; see `SignedRecord::hash`
+    pub hash: Hash,
+    /// the signature of the server over the record
+    pub signature: Signature,
+}
+
+/// A commitment to the first entries of the oplog, which is signed by the server.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct OplogRoot {
+    /// the number of the entries covered by the root
+    pub tree_size: u64,
+    /// the root of the Merkle tree over the entries; see `merkle`
+    pub root: Hash,
+}
 
-    async fn put_dyn_path_unchecked(&self, path: &GuaranteeSigned<DynPath<Path>>) -> Result<()> {
-        // next target
-        let target = self.get_account_primary(KIND.as_ref()).await?;
-
-        // external call
-        external_call!(
-            client: self,
-            target: KIND.as_ref() => &target,
-            request: crate::io => DynPathPut,
-            sign: *path,
-            inputs: { },
-            outputs: { },
-        );
-
-        // unpack response
-        Ok(())
+impl IsSigned for OplogRoot {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetInclusionProof {
+    pub nonce: Uuid,
+}
+
+impl IsSigned for GetInclusionProof {}
+
+/// The proof that an entry is included in the oplog committed by a signed root.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct InclusionProof {
+    pub entry: OplogEntry,
+    /// the position of the entry in the oplog, from `0`
+    pub index: u64,
+    /// the siblings of the path from the entry up to the root, deepest first
+    pub path: Vec<Hash>,
+    pub root: GuaranteeSigned<OplogRoot>,
+}
+
+impl InclusionProof {
+    /// Ensures that the root is signed by the server, and that it commits the entry.
+    ///
+    /// Note that the entry should be checked against the record, e.g. with `SignedRecord::hash`.
+    pub fn verify(&self, server: &AccountRef) -> Result<()> {
+        if &self.root.guarantee.account != server {
+            bail!("the root of the oplog is not signed by the server")
+        }
+        self.root.verify(None)?;
+
+        let root = &self.root.data.data;
+        match merkle::root_from_path(
+            merkle::leaf(&self.entry.hash),
+            self.index,
+            root.tree_size,
+            &self.path,
+        ) {
+            Some(computed) if computed == root.root => Ok(()),
+            _ => bail!("the entry is not included in the root of the oplog"),
+        }
     }
+}
 
-    async fn get_word_many_unchecked(
-        &self,
-        _guarantee: Option<&AccountRef>,
-        query: &GetWords,
-    ) -> Result<Vec<GuarantorSigned<WordHash>>> {
-        // next target
-        let target = self.get_account_primary(KIND.as_ref()).await?;
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetPathReferenceCount {
+    /// the hash of the static path
+    pub path: Hash,
+}
+
+impl IsSigned for GetPathReferenceCount {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// the `start_index` of the next page, if any
+    pub next_cursor: Option<u32>,
+    /// the number of all items matching the query, if known
+    pub total: Option<u32>,
+}
 
-        // external call
-        let (words,) = external_call!(
-            client: self,
-            target: KIND.as_ref() => &target,
-            request: crate::io => WordGetMany,
-            sign: self.sign(target, *query)?,
-            inputs: { },
-            outputs: { words, },
-        );
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, start_index: u32, end_index: u32, total: Option<u32>) -> Self {
+        let next_index = start_index.saturating_add(items.len().try_into().unwrap_or(u32::MAX));
+        let has_next = match total {
+            Some(total) => next_index < total,
+            None => next_index >= end_index,
+        };
 
-        // unpack response
-        Ok(words)
+        Self {
+            items,
+            next_cursor: if has_next { Some(next_index) } else { None },
+            total,
+        }
     }
 
-    async fn get_word_count_many_unchecked(
-        &self,
-        _guarantee: Option<&AccountRef>,
-        query: &GetWordsCounts,
-    ) -> Result<Vec<GetWordsCountsOutput>> {
-        // next target
-        let target = self.get_account_primary(KIND.as_ref()).await?;
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            total: self.total,
+        }
+    }
+}
 
-        // external call
-        let (counts,) = external_call!(
-            client: self,
-            target: KIND.as_ref() => &target,
-            request: crate::io => WordCountGetMany,
-            sign: self.sign(target, *query)?,
-            inputs: { },
-            outputs: { counts, },
-        );
+/// A response along with its freshness, which may have been served from the cache.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct Fresh<T> {
+    pub data: T,
+    /// the unix timestamp when the data has been computed, in milliseconds
+    pub computed_at: i64,
+    pub from_cache: bool,
+}
 
-        // unpack response
-        Ok(counts)
+impl<T> Fresh<T> {
+    /// Returns the time elapsed since the data has been computed.
+    pub fn staleness(&self) -> Duration {
+        self.staleness_at(::ipis::core::chrono::Utc::now().timestamp_millis())
     }
 
-    async fn put_word_unchecked(
-        &self,
-        parent: &Hash,
-        word: &GuaranteeSigned<WordHash>,
-    ) -> Result<()> {
-        // next target
-        let target = self.get_account_primary(KIND.as_ref()).await?;
-
-        // external call
-        external_call!(
-            client: self,
-            target: KIND.as_ref() => &target,
-            request: crate::io => WordPut,
-            sign: *word,
-            inputs: {
-                parent: *parent,
-            },
-            outputs: { },
-        );
-
-        // unpack response
-        Ok(())
+    /// Returns the time elapsed since the data has been computed until `now`,
+    /// the unix timestamp in milliseconds, e.g. of the server's clock.
+    pub fn staleness_at(&self, now: i64) -> Duration {
+        Duration::from_millis(now.saturating_sub(self.computed_at).max(0) as u64)
     }
 }
 
-define_io! {
-    GuaranteePut {
-        inputs: { },
-        input_sign: GuaranteeSigned<AccountRef>,
-        outputs: { },
-        output_sign: GuarantorSigned<AccountRef>,
-        generics: { },
-    },
-    DynPathGet {
-        inputs: { },
-        input_sign: GuaranteeSigned<DynPath<()>>,
-        outputs: {
-            path: Option<GuarantorSigned<DynPath<Path>>>,
-        },
-        output_sign: GuarantorSigned<DynPath<()>>,
-        generics: { },
-    },
-    DynPathPut {
-        inputs: { },
-        input_sign: GuaranteeSigned<DynPath<Path>>,
-        outputs: { },
-        output_sign: GuarantorSigned<DynPath<Path>>,
-        generics: { },
-    },
-    WordGetMany {
-        inputs: { },
-        input_sign: GuaranteeSigned<GetWords>,
-        outputs: {
-            words: Vec<GuarantorSigned<WordHash>>,
-        },
-        output_sign: GuarantorSigned<GetWords>,
-        generics: { },
-    },
-    WordPut {
-        inputs: {
-            parent: Hash,
-        },
-        input_sign: GuaranteeSigned<WordHash>,
-        outputs: { },
-        output_sign: GuarantorSigned<WordHash>,
-        generics: { },
-    },
-    WordCountGetMany {
-        inputs: { },
-        input_sign: GuaranteeSigned<GetWordsCounts>,
-        outputs: {
-            counts: Vec<GetWordsCountsOutput>,
-        },
-        output_sign: GuarantorSigned<GetWordsCounts>,
-        generics: { },
-    },
+/// The acknowledgement of a put, which is signed by the server along with the record.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct PutReceipt {
+    /// the nonce of the record, with which the record can be fetched later
+    pub nonce: Uuid,
+    /// the sequence number of the record in its table
+    pub seq: u64,
+    /// the signature of the server over the record
+    pub guarantor_signature: Signature,
+    /// the unix timestamp when the record has been stored, in milliseconds
+    pub server_time: i64,
+}
+
+/// the maximum size of the metadata attached to a record, in bytes
+pub const MAX_METADATA_LEN: usize = 1024;
+
+/// A record along with the metadata attached by the application, e.g. a score or a source document id.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct WithMetadata<T> {
+    pub data: T,
+    pub metadata: Option<Vec<u8>>,
+}
+
+pub fn ensure_metadata_len(metadata: Option<&[u8]>) -> Result<()> {
+    ensure_payload_len(
+        METADATA_PAYLOAD,
+        metadata.map(<[u8]>::len),
+        MAX_METADATA_LEN,
+    )
+}
+
+/// the name of the metadata in `IpdisError::PayloadTooLarge`
+pub const METADATA_PAYLOAD: &str = "metadata bytes";
+
+/// the name of the batched queries in `IpdisError::PayloadTooLarge`
+pub const QUERIES_PAYLOAD: &str = "queries";
+
+/// the name of the words of a request in `IpdisError::PayloadTooLarge`
+pub const WORDS_PAYLOAD: &str = "words";
+
+/// Rejects the payload longer than the limit with `IpdisError::PayloadTooLarge`.
+pub fn ensure_payload_len(payload: &'static str, len: Option<usize>, limit: usize) -> Result<()> {
+    match len {
+        Some(len) if len > limit => bail!(IpdisError::PayloadTooLarge {
+            payload,
+            limit: limit as u64,
+            given: len as u64,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Hashes the inputs sent along with a batch sign.
+pub fn hash_batch<T>(inputs: &[T]) -> Result<Hash>
+where
+    T: Clone,
+    Vec<T>: Serialize<AllocSerializer<4096>>,
+{
+    ::rkyv::to_bytes::<_, 4096>(&inputs.to_vec())
+        .map(|bytes| Hash::with_bytes(&bytes))
+        .map_err(|error| anyhow!("failed to archive the batch: {error}"))
+}
+
+/// Ensures that the inputs sent along with a batch sign are the signed ones,
+/// so that they cannot be replaced in transit.
+pub fn ensure_batch<T>(payload: &'static str, len: u32, hash: &Hash, inputs: &[T]) -> Result<()>
+where
+    T: Clone,
+    Vec<T>: Serialize<AllocSerializer<4096>>,
+{
+    if inputs.len() != len as usize {
+        bail!("malformed batch: the number of the {payload} is not signed")
+    }
+    if &hash_batch(inputs)? != hash {
+        bail!("malformed batch: the {payload} are not signed")
+    }
+    Ok(())
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
@@ -385,6 +2259,8 @@ pub struct GetWords {
     pub start_index: u32,
     /// exclusive right bound
     pub end_index: u32,
+    /// whether to count all the matching items as the `total` of the page, which scans them all
+    pub with_total: bool,
 }
 
 impl IsSigned for GetWords {}
@@ -408,10 +2284,88 @@ pub struct GetWordsCounts {
     pub start_index: u32,
     /// exclusive right bound
     pub end_index: u32,
+    /// whether to count all the matching items as the `total` of the page, which scans them all
+    pub with_total: bool,
 }
 
 impl IsSigned for GetWordsCounts {}
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetWordsCountsBatch {
+    /// the number of the queries, which are sent along with the sign
+    pub len: u32,
+    /// the hash of the queries, so that they cannot be replaced in transit
+    pub hash: Hash,
+}
+
+impl IsSigned for GetWordsCountsBatch {}
+
+impl GetWordsCountsBatch {
+    pub fn new(queries: &[GetWordsCounts]) -> Result<Self> {
+        Ok(Self {
+            len: queries.len().try_into()?,
+            hash: hash_batch(queries)?,
+        })
+    }
+
+    /// Ensures that the queries sent along with the sign are the signed ones.
+    pub fn validate(&self, queries: &[GetWordsCounts]) -> Result<()> {
+        ensure_batch("queries", self.len, &self.hash, queries)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct PutWordsBatch {
+    /// the number of the words, which are sent along with the sign
+    pub len: u32,
+    /// the hash of the words, the fencing token and the normalization,
+    /// so that none of them can be replaced in transit
+    pub hash: Hash,
+}
+
+impl IsSigned for PutWordsBatch {}
+
+impl PutWordsBatch {
+    pub fn new(
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Self> {
+        Ok(Self {
+            len: words.len().try_into()?,
+            hash: Self::hash(words, fencing_token, normalization)?,
+        })
+    }
+
+    /// Ensures that the words and the options sent along with the sign are the signed ones.
+    pub fn validate(
+        &self,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<()> {
+        if words.len() != self.len as usize {
+            bail!("malformed batch: the number of the {WORDS_PAYLOAD} is not signed")
+        }
+        if Self::hash(words, fencing_token, normalization)? != self.hash {
+            bail!("malformed batch: the {WORDS_PAYLOAD} are not signed")
+        }
+        Ok(())
+    }
+
+    fn hash(
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        normalization: Option<Normalization>,
+    ) -> Result<Hash> {
+        hash_batch(&[(hash_batch(words)?, fencing_token, normalization)])
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
 #[archive(compare(PartialEq))]
 #[archive_attr(derive(CheckBytes, Debug, PartialEq))]
@@ -432,6 +2386,259 @@ pub struct GetWordKeyHash {
 
 impl IsSigned for GetWordKeyHash {}
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetWordCountAllLangs {
+    pub namespace: Hash,
+    pub kind: Hash,
+    /// the hash of the message, regardless of the language
+    pub msg: Hash,
+    pub owned: bool,
+}
+
+impl IsSigned for GetWordCountAllLangs {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetWordCountDelta {
+    pub kind: Hash,
+    /// the watermark returned by the previous call, or `0` to start from the beginning
+    pub since_seq: u64,
+    /// the maximum number of the changes to be merged at once
+    pub limit: u32,
+}
+
+impl IsSigned for GetWordCountDelta {}
+
+/// The changes of the word counts, merged per word.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct WordCountDelta {
+    /// the words whose counts have been changed, in no particular order
+    pub items: Vec<WordCountDeltaItem>,
+    /// the watermark to be given to the next call
+    pub until_seq: u64,
+    /// whether there are more changes after the watermark
+    pub has_more: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct WordCountDeltaItem {
+    pub word: WordKeyHash,
+    /// the difference of the count, which is negative if the word has been deleted
+    pub delta: i64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetWordFrequencyHistogram {
+    pub kind: Hash,
+    /// the number of the buckets, up to `MAX_HISTOGRAM_BUCKETS`
+    pub buckets: u32,
+}
+
+impl IsSigned for GetWordFrequencyHistogram {}
+
+/// the maximum number of the buckets of a histogram, whose bounds fit in `u64`
+pub const MAX_HISTOGRAM_BUCKETS: u32 = 20;
+
+impl GetWordFrequencyHistogram {
+    pub fn validate(&self) -> Result<()> {
+        if self.buckets == 0 || self.buckets > MAX_HISTOGRAM_BUCKETS {
+            bail!(
+                "malformed histogram: the buckets should be between 1 and {MAX_HISTOGRAM_BUCKETS}"
+            )
+        }
+        Ok(())
+    }
+}
+
+/// The distinct words whose counts are in the range.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct WordFrequencyBucket {
+    pub min_count: u64,
+    /// the inclusive upper bound, or `None` for the last bucket
+    pub max_count: Option<u64>,
+    pub words: u64,
+}
+
+impl WordFrequencyBucket {
+    /// Returns the empty buckets of the histogram, in order.
+    pub fn empty(buckets: u32) -> Vec<Self> {
+        (0..buckets)
+            .map(|index| Self {
+                min_count: match index {
+                    0 => 1,
+                    _ => 10u64.pow(index - 1) + 1,
+                },
+                max_count: if index + 1 < buckets {
+                    Some(10u64.pow(index))
+                } else {
+                    None
+                },
+                words: 0,
+            })
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetIdfVector {
+    /// the number of the words, which are sent along with the sign
+    pub len: u32,
+    /// the hash of the words, so that they cannot be replaced in transit
+    pub hash: Hash,
+}
+
+impl IsSigned for GetIdfVector {}
+
+impl GetIdfVector {
+    pub fn new(words: &[WordKeyHash]) -> Result<Self> {
+        Ok(Self {
+            len: words.len().try_into()?,
+            hash: hash_batch(words)?,
+        })
+    }
+
+    /// Ensures that the words sent along with the sign are the signed ones.
+    pub fn validate(&self, words: &[WordKeyHash]) -> Result<()> {
+        ensure_batch("words", self.len, &self.hash, words)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct IdfVector {
+    /// the number of all the documents of the namespace
+    pub corpus_size: u32,
+    /// the number of the documents containing each word, in the order of the query
+    pub document_counts: Vec<u32>,
+}
+
+impl IdfVector {
+    /// Returns the smoothed inverse document frequencies, i.e. `ln((1 + N) / (1 + df)) + 1`.
+    pub fn idf(&self) -> Vec<f64> {
+        let corpus_size = f64::from(self.corpus_size);
+        self.document_counts
+            .iter()
+            .map(|&count| ((1.0 + corpus_size) / (1.0 + f64::from(count))).ln() + 1.0)
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetSimilarDocuments {
+    /// the number of the words, which are sent along with the sign
+    pub len: u32,
+    /// the hash of the words, so that they cannot be replaced in transit
+    pub hash: Hash,
+    pub top_k: u32,
+    /// whether to rank the documents of the guarantee's words only
+    pub owned: bool,
+}
+
+impl IsSigned for GetSimilarDocuments {}
+
+impl GetSimilarDocuments {
+    pub fn new(words: &[WordKeyHash], top_k: u32, owned: bool) -> Result<Self> {
+        Ok(Self {
+            len: words.len().try_into()?,
+            hash: hash_batch(words)?,
+            top_k,
+            owned,
+        })
+    }
+
+    /// Ensures that the words sent along with the sign are the signed ones.
+    pub fn validate(&self, words: &[WordKeyHash]) -> Result<()> {
+        ensure_batch("words", self.len, &self.hash, words)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct SimilarDocument {
+    /// the parent of the words
+    pub document: Hash,
+    /// the number of the shared words
+    pub overlap: u32,
+    /// the sum of the inverse document frequencies of the shared words
+    pub score: f64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct QueryWords {
+    /// the kind of the query, which is sent along with the sign
+    pub kind: Hash,
+    /// the hash of the whole query, so that none of the filters can be replaced in transit
+    pub hash: Hash,
+}
+
+impl IsSigned for QueryWords {}
+
+impl QueryWords {
+    pub fn new(query: &WordQuery) -> Result<Self> {
+        Ok(Self {
+            kind: query.kind,
+            hash: hash_batch(::core::slice::from_ref(query))?,
+        })
+    }
+
+    /// Ensures that the query sent along with the sign is the signed one.
+    pub fn validate(&self, query: &WordQuery) -> Result<()> {
+        if self.kind != query.kind {
+            bail!("malformed query: the kind is not signed")
+        }
+        if hash_batch(::core::slice::from_ref(query))? != self.hash {
+            bail!("malformed query: the query is not signed")
+        }
+        Ok(())
+    }
+}
+
+/// Ensures that all the records are signed by the same guarantee, for the same guarantor.
+pub fn ensure_same_guarantee<T>(records: &[GuaranteeSigned<T>]) -> Result<()> {
+    let record = match records.first() {
+        Some(record) => record,
+        None => return Ok(()),
+    };
+    if records.iter().any(|other| {
+        other.guarantee.account != record.guarantee.account
+            || other.data.guarantor != record.data.guarantor
+    }) {
+        bail!("malformed batch: all the records should be of the same guarantee")
+    }
+    Ok(())
+}
+
+/// Ensures that all the words share a namespace, which is returned.
+pub fn ensure_same_namespace(words: &[WordKeyHash]) -> Result<Option<Hash>> {
+    let namespace = match words.first() {
+        Some(word) => word.namespace,
+        None => return Ok(None),
+    };
+    if words.iter().any(|word| word.namespace != namespace) {
+        bail!("malformed words: all the words should be of the same namespace")
+    }
+    Ok(Some(namespace))
+}
+
 ::ipis::lazy_static::lazy_static! {
     pub static ref KIND: Option<::ipis::core::value::hash::Hash> = Some(
         ::ipis::core::value::hash::Hash::with_str("__ipis__ipdis__"),
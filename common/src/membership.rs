@@ -0,0 +1,53 @@
+//! The membership of the IPDIS nodes serving the same cluster.
+//!
+//! Each node announces itself periodically by putting a dynamic path of a well-known kind,
+//! whose word is derived from the account of the node.
+//! The clients then discover the nodes with `Ipdis::get_members_unchecked`,
+//! rather than being configured with static lists of the servers.
+//! Each node may announce itself only to itself, and only the members of the cluster are discovered.
+//! Note that the addresses of the nodes are resolved by ipiis, as for any other account.
+
+use ipiis_common::Ipiis;
+use ipis::{
+    core::{account::AccountRef, anyhow::Result, value::hash::Hash},
+    path::{DynPath, Path},
+};
+
+use crate::Ipdis;
+
+::ipis::lazy_static::lazy_static! {
+    /// the kind of the announcements
+    pub static ref KIND: Hash = Hash::with_str("__ipis__ipdis__members__");
+}
+
+/// Returns the dynamic path announcing the node in the cluster.
+pub fn announcement(namespace: Hash, account: &AccountRef) -> DynPath<Path> {
+    DynPath {
+        namespace,
+        kind: *KIND,
+        word: Hash::with_str(&account.to_string()),
+        // every announcement of the cluster refers to the same path
+        path: Path {
+            value: namespace,
+            len: 0,
+        },
+    }
+}
+
+/// Announces the node of the given IPIIS client to the server, which should be repeated
+/// more often than the TTL of the membership queries.
+pub async fn announce<T, IpiisClient>(
+    ipdis: &T,
+    ipiis: &IpiisClient,
+    target: AccountRef,
+    namespace: Hash,
+) -> Result<()>
+where
+    T: Ipdis + Send + Sync,
+    IpiisClient: Ipiis + Send + Sync,
+{
+    let account = ipiis.account_me().account_ref();
+    let path = ipiis.sign(target, announcement(namespace, &account))?;
+
+    ipdis.put_dyn_path_unchecked(&path).await
+}
@@ -0,0 +1,490 @@
+//! A backend keeping the records in memory, e.g. for the examples and the tests
+//! which cannot afford a database.
+//!
+//! Only the guarantees, the dynamic paths and the words are stored, which are lost when
+//! the process exits. The other operations, e.g. the kinds, the oplog and the analytics,
+//! are rejected, as are the delegated and the fenced writes.
+
+use std::{
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+
+use ipiis_common::Ipiis;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{anyhow, bail, Result},
+        value::{hash::Hash, uuid::Uuid},
+    },
+    path::{DynPath, Path},
+    word::{WordHash, WordKeyHash},
+};
+
+use crate::{
+    ensure_metadata_len, ensure_same_guarantee, AcquireWriterLease, Delegation, Fresh,
+    GetAccountChain, GetKind, GetKinds, GetMembers, GetOplog, GetWordCountAllLangs,
+    GetWordCountDelta, GetWordFrequencyHistogram, GetWordKeyHash, GetWords, GetWordsCounts,
+    GetWordsCountsOutput, GetWordsParent, IdfVector, InclusionProof, Ipdis, KindInfo,
+    LinkAccountSuccessor, Member, Normalization, Oplog, Page, PutReceipt, SignedRecord,
+    SimilarDocument, WithMetadata, WordCountDelta, WordFrequencyBucket, WordQuery, WordQueryRow,
+    WriterLease,
+};
+
+/// The records of a backend kept in memory, signed by the account of the given client.
+pub struct IpdisMemory<IpiisClient> {
+    ipiis: IpiisClient,
+    guarantees: Mutex<Vec<AccountRef>>,
+    dyn_paths: Mutex<Vec<WithMetadata<GuarantorSigned<DynPath<Path>>>>>,
+    words: Mutex<Vec<WordRecord>>,
+}
+
+struct WordRecord {
+    parent: Hash,
+    record: WithMetadata<GuarantorSigned<WordHash>>,
+}
+
+impl<IpiisClient> AsRef<IpiisClient> for IpdisMemory<IpiisClient> {
+    fn as_ref(&self) -> &IpiisClient {
+        &self.ipiis
+    }
+}
+
+impl<IpiisClient> IpdisMemory<IpiisClient> {
+    pub fn new(ipiis: IpiisClient) -> Self {
+        Self {
+            ipiis,
+            guarantees: Default::default(),
+            dyn_paths: Default::default(),
+            words: Default::default(),
+        }
+    }
+}
+
+fn lock<'a, T>(store: &'a Mutex<T>, name: &str) -> Result<MutexGuard<'a, T>> {
+    store
+        .lock()
+        .map_err(|_| anyhow!("the {name} store has been poisoned"))
+}
+
+fn unsupported<T>(method: &str) -> Result<T> {
+    bail!("{method} is not supported by the in-memory backend")
+}
+
+fn now() -> i64 {
+    ::ipis::core::chrono::Utc::now().timestamp_millis()
+}
+
+#[async_trait]
+impl<IpiisClient> Ipdis for IpdisMemory<IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn ensure_registered(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<()> {
+        let guarantor_now = self.ipiis.account_me().account_ref();
+        if guarantor != &guarantor_now {
+            bail!("failed to authenticate the guarantor")
+        }
+
+        // skip authentication for self-authentication
+        if guarantee == guarantor || lock(&self.guarantees, "guarantee")?.contains(guarantee) {
+            Ok(())
+        } else {
+            bail!("failed to authenticate the guarantee")
+        }
+    }
+
+    async fn ensure_admin(&self, guarantee: &AccountRef, guarantor: &AccountRef) -> Result<()> {
+        let guarantor_now = self.ipiis.account_me().account_ref();
+        if guarantor != &guarantor_now {
+            bail!("failed to authenticate the guarantor")
+        }
+
+        // there are no admins but the guarantor itself
+        if guarantee == guarantor {
+            Ok(())
+        } else {
+            bail!("failed to authenticate the admin")
+        }
+    }
+
+    async fn get_kind_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &GetKind,
+    ) -> Result<Option<KindInfo>> {
+        unsupported("get_kind")
+    }
+
+    async fn get_kind_page_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &GetKinds,
+    ) -> Result<Page<KindInfo>> {
+        unsupported("get_kind_page")
+    }
+
+    async fn acquire_writer_lease_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &AcquireWriterLease,
+    ) -> Result<WriterLease> {
+        unsupported("acquire_writer_lease")
+    }
+
+    async fn add_guarantee_unchecked(&self, guarantee: &GuaranteeSigned<AccountRef>) -> Result<()> {
+        let guarantee = self.ipiis.sign_as_guarantor(*guarantee)?;
+
+        let mut guarantees = lock(&self.guarantees, "guarantee")?;
+        if !guarantees.contains(&guarantee.guarantee.account) {
+            guarantees.push(guarantee.guarantee.account);
+        }
+        Ok(())
+    }
+
+    async fn link_account_successor_unchecked(
+        &self,
+        _proof: &GuaranteeSigned<LinkAccountSuccessor>,
+    ) -> Result<()> {
+        unsupported("link_account_successor")
+    }
+
+    async fn get_account_chain_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &GetAccountChain,
+    ) -> Result<Vec<AccountRef>> {
+        unsupported("get_account_chain")
+    }
+
+    async fn get_dyn_path_record_unchecked<Path>(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+    ) -> Result<Option<WithMetadata<GuarantorSigned<DynPath<::ipis::path::Path>>>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        let guarantor = self.ipiis.account_me().account_ref();
+        let guarantee = guarantee.unwrap_or(&guarantor);
+
+        // the latest one wins
+        Ok(lock(&self.dyn_paths, "dynamic path")?
+            .iter()
+            .rev()
+            .find(|record| {
+                let data = &record.data.data.data.data;
+                &record.data.guarantee.account == guarantee
+                    && data.namespace == path.namespace
+                    && data.kind == path.kind
+                    && data.word == path.word
+            })
+            .cloned())
+    }
+
+    async fn get_members_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &GetMembers,
+    ) -> Result<Vec<Member>> {
+        unsupported("get_members")
+    }
+
+    async fn get_record_by_nonce_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _nonce: &Uuid,
+    ) -> Result<Option<SignedRecord>> {
+        unsupported("get_record_by_nonce")
+    }
+
+    async fn get_oplog_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &GetOplog,
+    ) -> Result<Oplog> {
+        unsupported("get_oplog")
+    }
+
+    async fn get_inclusion_proof_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _nonce: &Uuid,
+    ) -> Result<Option<InclusionProof>> {
+        unsupported("get_inclusion_proof")
+    }
+
+    async fn put_dyn_path_fenced_unchecked(
+        &self,
+        path: &GuaranteeSigned<DynPath<Path>>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+    ) -> Result<PutReceipt> {
+        if on_behalf_of.is_some() {
+            return unsupported("put_dyn_path_delegated");
+        }
+        if fencing_token.is_some() {
+            return unsupported("put_dyn_path_fenced");
+        }
+        ensure_metadata_len(metadata)?;
+
+        let path = self.ipiis.sign_as_guarantor(*path)?;
+        let server_time = now();
+
+        let mut dyn_paths = lock(&self.dyn_paths, "dynamic path")?;
+        let receipt = PutReceipt {
+            nonce: path.nonce.0,
+            seq: (dyn_paths.len() + 1).try_into()?,
+            guarantor_signature: path.guarantor.signature,
+            server_time,
+        };
+        dyn_paths.push(WithMetadata {
+            data: path,
+            metadata: metadata.map(<[u8]>::to_vec),
+        });
+        Ok(receipt)
+    }
+
+    async fn get_word_record_page_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<WithMetadata<GuarantorSigned<WordHash>>>> {
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+
+        let guarantor = self.ipiis.account_me().account_ref();
+        let guarantee = guarantee.unwrap_or(&guarantor);
+
+        let words = lock(&self.words, "word")?;
+        let records: Vec<_> = words
+            .iter()
+            .rev()
+            .filter(|word| &word.record.data.guarantee.account == guarantee)
+            .filter(|word| {
+                let key = &word.record.data.data.data.data.key;
+                let msg = match query.parent {
+                    GetWordsParent::None => &key.text.msg,
+                    GetWordsParent::Duplicated => &word.parent,
+                };
+                key.namespace == query.word.namespace
+                    && key.text.lang == query.word.text.lang
+                    && msg == &query.word.text.msg
+            })
+            .collect();
+
+        let total = if query.with_total {
+            Some(records.len().try_into()?)
+        } else {
+            None
+        };
+        let items = records
+            .into_iter()
+            .skip(query.start_index as usize)
+            .take((query.end_index - query.start_index) as usize)
+            .map(|word| word.record.clone())
+            .collect();
+
+        Ok(Page::new(items, query.start_index, query.end_index, total))
+    }
+
+    async fn get_word_count_page_fresh_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+        _max_staleness: Option<Duration>,
+    ) -> Result<Fresh<Page<GetWordsCountsOutput>>> {
+        if query.end_index <= query.start_index {
+            bail!("malformed index: end_index should be bigger than start_index")
+        }
+
+        let guarantor = self.ipiis.account_me().account_ref();
+        let guarantee = guarantee.unwrap_or(&guarantor);
+
+        // the counts are computed on every call, as there is no cache
+        let computed_at = now();
+
+        // the words are counted per kind, latest first
+        let mut counts: Vec<GetWordsCountsOutput> = vec![];
+        for word in lock(&self.words, "word")?.iter().rev() {
+            if query.owned && &word.record.data.guarantee.account != guarantee {
+                continue;
+            }
+
+            let data = &word.record.data.data.data.data;
+            let msg = if query.parent {
+                &word.parent
+            } else {
+                &data.key.text.msg
+            };
+            if data.key.namespace != query.word.namespace
+                || data.key.text.lang != query.word.text.lang
+                || msg != &query.word.text.msg
+            {
+                continue;
+            }
+
+            let key = GetWordKeyHash {
+                key: data.key,
+                kind: data.kind,
+            };
+            match counts.iter_mut().find(|count| count.word == key) {
+                Some(count) => count.count += 1,
+                None => counts.push(GetWordsCountsOutput {
+                    word: key,
+                    count: 1,
+                }),
+            }
+        }
+
+        let total = if query.with_total {
+            Some(counts.len().try_into()?)
+        } else {
+            None
+        };
+        let items = counts
+            .into_iter()
+            .skip(query.start_index as usize)
+            .take((query.end_index - query.start_index) as usize)
+            .collect();
+
+        Ok(Fresh {
+            data: Page::new(items, query.start_index, query.end_index, total),
+            computed_at,
+            from_cache: false,
+        })
+    }
+
+    async fn get_word_count_all_langs_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &GetWordCountAllLangs,
+    ) -> Result<u32> {
+        unsupported("get_word_count_all_langs")
+    }
+
+    async fn get_word_count_delta_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &GetWordCountDelta,
+    ) -> Result<WordCountDelta> {
+        unsupported("get_word_count_delta")
+    }
+
+    async fn get_word_frequency_histogram_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &GetWordFrequencyHistogram,
+    ) -> Result<Vec<WordFrequencyBucket>> {
+        unsupported("get_word_frequency_histogram")
+    }
+
+    async fn get_idf_vector_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _words: &[WordKeyHash],
+    ) -> Result<IdfVector> {
+        unsupported("get_idf_vector")
+    }
+
+    async fn get_similar_documents_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _words: &[WordKeyHash],
+        _top_k: u32,
+        _owned: bool,
+    ) -> Result<Vec<SimilarDocument>> {
+        unsupported("get_similar_documents")
+    }
+
+    async fn query_words_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        _query: &WordQuery,
+    ) -> Result<Vec<WordQueryRow>> {
+        unsupported("query_words")
+    }
+
+    async fn put_word_normalized_unchecked(
+        &self,
+        parent: &Hash,
+        word: &GuaranteeSigned<WordHash>,
+        metadata: Option<&[u8]>,
+        on_behalf_of: Option<&GuaranteeSigned<Delegation>>,
+        fencing_token: Option<u64>,
+        _normalization: Option<Normalization>,
+    ) -> Result<PutReceipt> {
+        if on_behalf_of.is_some() {
+            return unsupported("put_word_delegated");
+        }
+        if fencing_token.is_some() {
+            return unsupported("put_word_fenced");
+        }
+
+        self.put_words(parent, ::core::slice::from_ref(word), metadata)
+            .map(|mut receipts| receipts.remove(0))
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        fencing_token: Option<u64>,
+        _normalization: Option<Normalization>,
+    ) -> Result<Vec<PutReceipt>> {
+        if fencing_token.is_some() {
+            return unsupported("put_word_fenced");
+        }
+        ensure_same_guarantee(words)?;
+
+        self.put_words(parent, words, None)
+    }
+}
+
+impl<IpiisClient> IpdisMemory<IpiisClient>
+where
+    IpiisClient: Ipiis,
+{
+    /// Puts the words at once, as the normalizations are not checked without the kinds.
+    fn put_words(
+        &self,
+        parent: &Hash,
+        words: &[GuaranteeSigned<WordHash>],
+        metadata: Option<&[u8]>,
+    ) -> Result<Vec<PutReceipt>> {
+        ensure_metadata_len(metadata)?;
+
+        // sign all the words first, so that either all or none of them are put
+        let signed = words
+            .iter()
+            .map(|word| self.ipiis.sign_as_guarantor(*word))
+            .collect::<Result<Vec<_>>>()?;
+        let server_time = now();
+
+        let mut records = lock(&self.words, "word")?;
+        let mut receipts = Vec::with_capacity(signed.len());
+        for word in signed {
+            receipts.push(PutReceipt {
+                nonce: word.nonce.0,
+                seq: (records.len() + 1).try_into()?,
+                guarantor_signature: word.guarantor.signature,
+                server_time,
+            });
+            records.push(WordRecord {
+                parent: *parent,
+                record: WithMetadata {
+                    data: word,
+                    metadata: metadata.map(<[u8]>::to_vec),
+                },
+            });
+        }
+        Ok(receipts)
+    }
+}
@@ -0,0 +1,73 @@
+//! A Merkle tree over the oplog, which commits the server to the order of the accepted writes.
+//!
+//! The tree follows RFC 6962, where the leaves and the inner nodes are hashed apart
+//! so that an inner node cannot be passed off as a leaf.
+//! The hashes are combined by their string forms, which are easy to reproduce elsewhere:
+//!
+//! - leaf: `Hash::with_str("leaf:{hash of the record}")`
+//! - node: `Hash::with_str("node:{left}:{right}")`
+
+use ipis::core::value::hash::Hash;
+
+/// Returns the hash of the leaf of the record of the given hash.
+pub fn leaf(hash: &Hash) -> Hash {
+    Hash::with_str(&format!("leaf:{hash}"))
+}
+
+/// Returns the hash of the inner node of the given children.
+pub fn node(left: &Hash, right: &Hash) -> Hash {
+    Hash::with_str(&format!("node:{left}:{right}"))
+}
+
+/// Returns the root of the tree over the leaves, in order.
+pub fn root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => Hash::with_str(""),
+        1 => leaves[0],
+        len => {
+            let (left, right) = leaves.split_at(split(len));
+            node(&root(left), &root(right))
+        }
+    }
+}
+
+/// Returns the siblings of the path from the leaf of the index up to the root, deepest first.
+pub fn path(leaves: &[Hash], index: usize) -> Vec<Hash> {
+    if leaves.len() <= 1 {
+        return vec![];
+    }
+
+    let (left, right) = leaves.split_at(split(leaves.len()));
+    let (mut path, sibling) = if index < left.len() {
+        (self::path(left, index), root(right))
+    } else {
+        (self::path(right, index - left.len()), root(left))
+    };
+    path.push(sibling);
+    path
+}
+
+/// Recomputes the root of the tree of the size from the leaf of the index and its path,
+/// or returns `None` if the path does not fit the tree.
+pub fn root_from_path(leaf: Hash, index: u64, size: u64, path: &[Hash]) -> Option<Hash> {
+    if index >= size {
+        return None;
+    }
+    if size == 1 {
+        return path.is_empty().then_some(leaf);
+    }
+
+    let (sibling, path) = path.split_last()?;
+    let left = split(size.try_into().ok()?) as u64;
+    if index < left {
+        root_from_path(leaf, index, left, path).map(|child| node(&child, sibling))
+    } else {
+        root_from_path(leaf, index - left, size - left, path).map(|child| node(sibling, &child))
+    }
+}
+
+/// Returns the size of the left subtree, i.e. the largest power of two less than the length.
+fn split(len: usize) -> usize {
+    debug_assert!(len > 1);
+    1 << (usize::BITS - (len - 1).leading_zeros() - 1)
+}
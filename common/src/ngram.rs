@@ -0,0 +1,164 @@
+use ipiis_common::Ipiis;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        anyhow::{bail, Result},
+        value::{hash::Hash, text::Text},
+    },
+    path::Path,
+    word::{Word, WordHash, WordKey},
+};
+
+use crate::{
+    normalize::{DefaultNormalizer, Normalization, Normalizer},
+    tokenize::tokenize,
+    GetWordsCounts, Ipdis, KIND,
+};
+
+/// Phrase search on top of the word index, by registering the n-grams of the texts.
+#[async_trait]
+pub trait IpdisNgram {
+    /// Puts all the words and the n-grams (up to `n` tokens) of the text,
+    /// which refer to the given static path.
+    ///
+    /// The text is split by the tokenizer of its language; see `tokenize::register_tokenizer`.
+    ///
+    /// All of them share the hash of the whole normalized text as a parent,
+    /// which is returned, and are put at once,
+    /// declaring `Normalization::NfcLowercase` and the fencing token of the kind's lease, if any.
+    async fn put_text_indexed(
+        &self,
+        namespace: &str,
+        text: &Text,
+        kind: &str,
+        path: &Path,
+        n: usize,
+        fencing_token: Option<u64>,
+    ) -> Result<Hash>;
+
+    /// Estimates the number of the puts of the phrase.
+    ///
+    /// A phrase longer than `n` tokens is joined from its consecutive n-grams,
+    /// so the result is the upper bound, i.e. the least count of them.
+    async fn get_phrase_count(
+        &self,
+        namespace: &str,
+        phrase: &Text,
+        n: usize,
+        owned: bool,
+    ) -> Result<u32>;
+}
+
+#[async_trait]
+impl<IpiisClient> IpdisNgram for IpiisClient
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    async fn put_text_indexed(
+        &self,
+        namespace: &str,
+        text: &Text,
+        kind: &str,
+        path: &Path,
+        n: usize,
+        fencing_token: Option<u64>,
+    ) -> Result<Hash> {
+        if n == 0 {
+            bail!("the n-grams should have at least one token")
+        }
+
+        let text = DefaultNormalizer::default().normalize(text.clone());
+        let tokens = tokenize(&text);
+        let parent = Hash::with_str(&tokens.join(" "));
+
+        // sign as guarantee
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        let words = ngrams(&text, &tokens, n)
+            .into_iter()
+            .map(|gram| {
+                let word: WordHash = Word {
+                    key: WordKey {
+                        namespace: namespace.to_string(),
+                        text: gram,
+                    },
+                    kind: kind.to_string(),
+                    relpath: true,
+                    path: *path,
+                }
+                .into();
+                self.sign(target, word)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // all or none of them are put, so that the phrases are not partially indexed
+        self.put_word_many_unchecked(
+            &parent,
+            &words,
+            fencing_token,
+            Some(Normalization::NfcLowercase),
+        )
+        .await?;
+        Ok(parent)
+    }
+
+    async fn get_phrase_count(
+        &self,
+        namespace: &str,
+        phrase: &Text,
+        n: usize,
+        owned: bool,
+    ) -> Result<u32> {
+        if n == 0 {
+            bail!("the n-grams should have at least one token")
+        }
+
+        let phrase = DefaultNormalizer::default().normalize(phrase.clone());
+        let tokens = tokenize(&phrase);
+        if tokens.is_empty() {
+            return Ok(0);
+        }
+
+        // the consecutive n-grams covering the whole phrase
+        let queries: Vec<_> = tokens
+            .windows(n.min(tokens.len()))
+            .map(|window| {
+                let mut text = phrase.clone();
+                text.msg = window.join(" ");
+
+                GetWordsCounts {
+                    word: WordKey {
+                        namespace: namespace.to_string(),
+                        text,
+                    }
+                    .into(),
+                    parent: false,
+                    owned,
+                    start_index: 0,
+                    end_index: 1,
+                    with_total: false,
+                }
+            })
+            .collect();
+
+        let pages = self
+            .get_word_count_page_batch_unchecked(None, &queries)
+            .await?;
+        Ok(pages
+            .into_iter()
+            .map(|page| page.items.first().map(|record| record.count).unwrap_or(0))
+            .min()
+            .unwrap_or(0))
+    }
+}
+
+fn ngrams(text: &Text, tokens: &[String], n: usize) -> Vec<Text> {
+    (1..=n.min(tokens.len()))
+        .flat_map(|len| tokens.windows(len))
+        .map(|window| {
+            let mut gram = text.clone();
+            gram.msg = window.join(" ");
+            gram
+        })
+        .collect()
+}
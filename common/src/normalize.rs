@@ -0,0 +1,190 @@
+use std::{fmt, str::FromStr};
+
+use bytecheck::CheckBytes;
+use ipis::{
+    core::{
+        anyhow::{bail, Error, Result},
+        value::text::Text,
+    },
+    word::{Word, WordKey},
+};
+use rkyv::{Archive, Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+/// Normalizes the text of the words before hashing,
+/// so that the equivalent words map to the same `WordHash` across the clients.
+pub trait Normalizer {
+    fn normalize(&self, text: Text) -> Text;
+}
+
+/// Applies the normalizers in order.
+impl<A, B> Normalizer for (A, B)
+where
+    A: Normalizer,
+    B: Normalizer,
+{
+    fn normalize(&self, text: Text) -> Text {
+        self.1.normalize(self.0.normalize(text))
+    }
+}
+
+/// The normalizer used by the convenience wrappers, e.g. the bindings.
+pub type DefaultNormalizer = (Nfc, Lowercase);
+
+/// The normalization of the texts of the words, which is declared by the writers
+/// and enforced per kind, so that the counts within a kind remain comparable.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub enum Normalization {
+    /// the texts are hashed as given
+    Raw,
+    /// the unicode characters are composed canonically
+    Nfc,
+    /// the same as `DefaultNormalizer`
+    NfcLowercase,
+}
+
+impl Normalization {
+    pub const ALL: &'static [Self] = &[Self::Raw, Self::Nfc, Self::NfcLowercase];
+
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Nfc => "nfc",
+            Self::NfcLowercase => "nfc-lowercase",
+        }
+    }
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl FromStr for Normalization {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Self::ALL
+            .iter()
+            .find(|normalization| normalization.as_str() == s)
+        {
+            Some(normalization) => Ok(*normalization),
+            None => bail!("unknown normalization: {s:?}"),
+        }
+    }
+}
+
+/// Applies the declared normalization, so that the writers can follow the policy of a kind.
+impl Normalizer for Normalization {
+    fn normalize(&self, text: Text) -> Text {
+        match self {
+            Self::Raw => text,
+            Self::Nfc => Nfc.normalize(text),
+            Self::NfcLowercase => DefaultNormalizer::default().normalize(text),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lowercase;
+
+impl Normalizer for Lowercase {
+    fn normalize(&self, mut text: Text) -> Text {
+        text.msg = text.msg.to_lowercase();
+        text
+    }
+}
+
+/// Composes the unicode characters canonically (NFC).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Nfc;
+
+impl Normalizer for Nfc {
+    fn normalize(&self, mut text: Text) -> Text {
+        text.msg = text.msg.nfc().collect();
+        text
+    }
+}
+
+/// Stems each whitespace-separated token, choosing the algorithm by the language of the text.
+///
+/// The texts in the unsupported languages are left unchanged.
+#[cfg(feature = "stemming")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stemmer;
+
+#[cfg(feature = "stemming")]
+impl Stemmer {
+    fn algorithm(lang: &str) -> Option<::rust_stemmers::Algorithm> {
+        use rust_stemmers::Algorithm;
+
+        // the primary language subtag, e.g. "en" of "en-US"
+        let primary = lang.split(['-', '_']).next()?.to_lowercase();
+        Some(match primary.as_str() {
+            "ar" => Algorithm::Arabic,
+            "da" => Algorithm::Danish,
+            "de" => Algorithm::German,
+            "el" => Algorithm::Greek,
+            "en" => Algorithm::English,
+            "es" => Algorithm::Spanish,
+            "fi" => Algorithm::Finnish,
+            "fr" => Algorithm::French,
+            "hu" => Algorithm::Hungarian,
+            "it" => Algorithm::Italian,
+            "nl" => Algorithm::Dutch,
+            "no" | "nb" | "nn" => Algorithm::Norwegian,
+            "pt" => Algorithm::Portuguese,
+            "ro" => Algorithm::Romanian,
+            "ru" => Algorithm::Russian,
+            "sv" => Algorithm::Swedish,
+            "ta" => Algorithm::Tamil,
+            "tr" => Algorithm::Turkish,
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(feature = "stemming")]
+impl Normalizer for Stemmer {
+    fn normalize(&self, mut text: Text) -> Text {
+        if let Some(algorithm) = Self::algorithm(&text.lang.to_string()) {
+            let stemmer = ::rust_stemmers::Stemmer::create(algorithm);
+            text.msg = text
+                .msg
+                .split_whitespace()
+                .map(|token| stemmer.stem(token))
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+        text
+    }
+}
+
+pub trait Normalized {
+    fn normalized<N>(self, normalizer: &N) -> Self
+    where
+        N: Normalizer + ?Sized;
+}
+
+impl Normalized for WordKey {
+    fn normalized<N>(mut self, normalizer: &N) -> Self
+    where
+        N: Normalizer + ?Sized,
+    {
+        self.text = normalizer.normalize(self.text);
+        self
+    }
+}
+
+impl Normalized for Word {
+    fn normalized<N>(mut self, normalizer: &N) -> Self
+    where
+        N: Normalizer + ?Sized,
+    {
+        self.key = self.key.normalized(normalizer);
+        self
+    }
+}
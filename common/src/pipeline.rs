@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use futures::{
+    channel::{mpsc, oneshot},
+    stream, FutureExt, StreamExt,
+};
+use ipiis_common::Ipiis;
+use ipis::{
+    core::anyhow::{anyhow, Error, Result},
+    word::WordKeyHash,
+};
+
+use crate::{GetWordsCounts, GetWordsCountsOutput, Ipdis, IpdisError, Page};
+
+type Reply = oneshot::Sender<Result<Page<GetWordsCountsOutput>>>;
+
+/// A remote client which coalesces the concurrent word count queries into batches,
+/// so that many small queries share a single round trip.
+///
+/// The batches are sent by the `IpdisPipelineDispatcher` returned along with it,
+/// which should be spawned by the caller.
+pub struct IpdisPipeline {
+    sender: mpsc::UnboundedSender<(GetWordsCounts, Reply)>,
+}
+
+impl IpdisPipeline {
+    pub const DEFAULT_MAX_BATCH: usize = 256;
+
+    /// the default number of the batches sent at once, see `IpdisPipelineDispatcher::with_max_in_flight`
+    pub const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+    pub fn new<IpiisClient>(ipiis: Arc<IpiisClient>) -> (Self, IpdisPipelineDispatcher<IpiisClient>)
+    where
+        IpiisClient: Ipiis + Send + Sync + 'static,
+    {
+        Self::with_max_batch(ipiis, Self::DEFAULT_MAX_BATCH)
+    }
+
+    pub fn with_max_batch<IpiisClient>(
+        ipiis: Arc<IpiisClient>,
+        max_batch: usize,
+    ) -> (Self, IpdisPipelineDispatcher<IpiisClient>)
+    where
+        IpiisClient: Ipiis + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::unbounded();
+        let dispatcher = IpdisPipelineDispatcher {
+            ipiis,
+            receiver,
+            max_batch: max_batch.max(1),
+            max_in_flight: Self::DEFAULT_MAX_IN_FLIGHT,
+        };
+
+        (Self { sender }, dispatcher)
+    }
+
+    pub async fn get_word_count_unchecked(&self, word: &WordKeyHash, owned: bool) -> Result<u32> {
+        let query = GetWordsCounts {
+            word: *word,
+            parent: false,
+            owned,
+            start_index: 0,
+            end_index: 1,
+            with_total: false,
+        };
+
+        self.get_word_count_page_unchecked(&query)
+            .await
+            .map(|mut page| page.items.pop().map(|record| record.count).unwrap_or(0))
+    }
+
+    pub async fn get_word_count_page_unchecked(
+        &self,
+        query: &GetWordsCounts,
+    ) -> Result<Page<GetWordsCountsOutput>> {
+        let (reply, response) = oneshot::channel();
+        self.sender
+            .unbounded_send((*query, reply))
+            .map_err(|_| anyhow!("the pipeline has been closed"))?;
+
+        response
+            .await
+            .map_err(|_| anyhow!("the pipeline has been closed"))?
+    }
+}
+
+/// Sends the queued queries of an `IpdisPipeline` in batches.
+///
+/// Each batch is sent as soon as the previous queue has been drained,
+/// without waiting for the replies of the in-flight batches, up to the limit.
+pub struct IpdisPipelineDispatcher<IpiisClient> {
+    ipiis: Arc<IpiisClient>,
+    receiver: mpsc::UnboundedReceiver<(GetWordsCounts, Reply)>,
+    max_batch: usize,
+    max_in_flight: usize,
+}
+
+impl<IpiisClient> IpdisPipelineDispatcher<IpiisClient>
+where
+    IpiisClient: Ipiis + Send + Sync + 'static,
+{
+    /// Limits the number of the batches sent at once, so the queries are queued beyond it.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
+
+    /// Sends the batches until the pipeline is dropped.
+    pub async fn run(self) {
+        let Self {
+            ipiis,
+            receiver,
+            max_batch,
+            max_in_flight,
+        } = self;
+
+        let batches = stream::unfold(receiver, move |mut receiver| async move {
+            let first = receiver.next().await?;
+
+            // collect the queued queries without waiting
+            let mut batch = vec![first];
+            while batch.len() < max_batch {
+                match receiver.next().now_or_never() {
+                    Some(Some(request)) => batch.push(request),
+                    _ => break,
+                }
+            }
+            Some((batch, receiver))
+        });
+
+        batches
+            .for_each_concurrent(Some(max_in_flight), |batch| send_batch(&*ipiis, batch))
+            .await
+    }
+}
+
+/// Sends the batch, replying to the queries in the order of the pages.
+async fn send_batch<IpiisClient>(ipiis: &IpiisClient, batch: Vec<(GetWordsCounts, Reply)>)
+where
+    IpiisClient: Ipiis + Send + Sync,
+{
+    let (queries, replies): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+
+    let error = match ipiis
+        .get_word_count_page_batch_unchecked(None, &queries)
+        .await
+    {
+        Ok(pages) if pages.len() == replies.len() => {
+            for (reply, page) in replies.into_iter().zip(pages) {
+                let _ = reply.send(Ok(page));
+            }
+            return;
+        }
+        Ok(pages) => anyhow!(
+            "malformed batch response: expected {} pages, but given {}",
+            replies.len(),
+            pages.len(),
+        ),
+        Err(error) => error,
+    };
+    for reply in replies {
+        let _ = reply.send(Err(share_error(&error)));
+    }
+}
+
+/// Copies the error for each query of the batch, keeping it typed, e.g. to be retried.
+fn share_error(error: &Error) -> Error {
+    let message = format!("{error:#}");
+    match error.downcast_ref::<IpdisError>() {
+        Some(typed) => Error::new(typed.clone()).context(message),
+        None => anyhow!(message),
+    }
+}
@@ -0,0 +1,130 @@
+use bytecheck::CheckBytes;
+use ipis::core::{
+    account::AccountRef,
+    anyhow::{bail, Result},
+    chrono::{DateTime, Utc},
+    value::hash::Hash,
+};
+use rkyv::{Archive, Deserialize, Serialize};
+
+/// A composable analytics query over the words of a kind, which is compiled by the backend.
+///
+/// ```ignore
+/// let query = WordQuery::kind(kind)
+///     .lang("en-US")
+///     .between(since, until)
+///     .group_by_day()
+///     .owned_by(account);
+/// ```
+///
+/// Only the filters and the groupings defined here can be expressed,
+/// so that no raw SQL travels from the clients.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct WordQuery {
+    pub kind: Hash,
+    pub namespace: Option<Hash>,
+    pub lang: Option<String>,
+    /// the inclusive lower bound of the created dates, in unix milliseconds
+    pub since: Option<i64>,
+    /// the exclusive upper bound of the created dates, in unix milliseconds
+    pub until: Option<i64>,
+    /// counts the words put by the account only
+    pub owned_by: Option<AccountRef>,
+    pub group_by: WordQueryGroupBy,
+    /// the maximum number of the rows
+    pub limit: u32,
+}
+
+impl WordQuery {
+    /// the number of the rows returned by default
+    pub const DEFAULT_LIMIT: u32 = 100;
+
+    /// Counts all the words of the kind.
+    pub fn kind(kind: Hash) -> Self {
+        Self {
+            kind,
+            namespace: None,
+            lang: None,
+            since: None,
+            until: None,
+            owned_by: None,
+            group_by: WordQueryGroupBy::None,
+            limit: Self::DEFAULT_LIMIT,
+        }
+    }
+
+    pub fn namespace(self, namespace: Hash) -> Self {
+        Self {
+            namespace: Some(namespace),
+            ..self
+        }
+    }
+
+    pub fn lang(self, lang: impl ToString) -> Self {
+        Self {
+            lang: Some(lang.to_string()),
+            ..self
+        }
+    }
+
+    /// Counts the words created in `[since, until)`.
+    pub fn between(self, since: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        Self {
+            since: Some(since.timestamp_millis()),
+            until: Some(until.timestamp_millis()),
+            ..self
+        }
+    }
+
+    /// Counts the words per day of their created dates, in UTC.
+    pub fn group_by_day(self) -> Self {
+        Self {
+            group_by: WordQueryGroupBy::Day,
+            ..self
+        }
+    }
+
+    pub fn owned_by(self, account: AccountRef) -> Self {
+        Self {
+            owned_by: Some(account),
+            ..self
+        }
+    }
+
+    pub fn limit(self, limit: u32) -> Self {
+        Self { limit, ..self }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let (Some(since), Some(until)) = (self.since, self.until) {
+            if until <= since {
+                bail!("malformed range: until should be later than since")
+            }
+        }
+        if self.limit == 0 {
+            bail!("malformed limit: it should be positive")
+        }
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub enum WordQueryGroupBy {
+    /// returns a single row
+    None,
+    /// returns a row per day, ordered by the day
+    Day,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct WordQueryRow {
+    /// the start of the day in unix milliseconds, if grouped by day
+    pub day: Option<i64>,
+    pub count: u32,
+}
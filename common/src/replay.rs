@@ -0,0 +1,289 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard},
+};
+
+use bytecheck::CheckBytes;
+
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuarantorSigned},
+        anyhow::{anyhow, bail, Result},
+        signature::Verifier,
+        value::hash::Hash,
+    },
+    path::{DynPath, Path},
+    word::{WordHash, WordKeyHash},
+};
+use rkyv::{
+    ser::serializers::AllocSerializer, validation::validators::DefaultValidator, AlignedVec,
+    Archive, Deserialize, Infallible, Serialize,
+};
+
+use crate::{
+    journal::{FsyncPolicy, Journal},
+    Ipdis,
+};
+
+/// the scratch space of the serializer, in bytes
+const SCRATCH_SPACE: usize = 4096;
+
+/// Stores the archived responses addressed by the hashes of their queries, e.g. in ipsis.
+#[async_trait]
+pub trait ReplayStore {
+    async fn put(&self, query: &Hash, response: &[u8]) -> Result<()>;
+
+    async fn get(&self, query: &Hash) -> Result<Option<Vec<u8>>>;
+}
+
+/// A store kept in memory, which is lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryReplayStore {
+    responses: Mutex<HashMap<Hash, Vec<u8>>>,
+}
+
+#[async_trait]
+impl ReplayStore for MemoryReplayStore {
+    async fn put(&self, query: &Hash, response: &[u8]) -> Result<()> {
+        self.responses
+            .lock()
+            .map_err(|_| anyhow!("the replay store has been poisoned"))?
+            .insert(*query, response.to_vec());
+        Ok(())
+    }
+
+    async fn get(&self, query: &Hash) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .responses
+            .lock()
+            .map_err(|_| anyhow!("the replay store has been poisoned"))?
+            .get(query)
+            .cloned())
+    }
+}
+
+/// A store journaled to a file, which survives the restarts of the process, e.g. of an embedded node.
+///
+/// The journal is compacted once most of its entries have been superseded.
+pub struct JournalReplayStore {
+    inner: Mutex<JournalReplayStoreInner>,
+}
+
+struct JournalReplayStoreInner {
+    journal: Journal<ReplayEntry>,
+    responses: HashMap<Hash, Vec<u8>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+struct ReplayEntry {
+    query: Hash,
+    response: Vec<u8>,
+}
+
+/// the number of the superseded entries which are kept in the journal before compacted
+const COMPACTION_SLACK: usize = 1024;
+
+impl JournalReplayStore {
+    /// Opens the store, recovering the responses kept in the journal.
+    pub fn open(path: impl Into<PathBuf>, fsync: FsyncPolicy) -> Result<Self> {
+        let (journal, entries) = Journal::open(path, fsync)?;
+        let responses = entries
+            .into_iter()
+            .map(|entry| (entry.query, entry.response))
+            .collect();
+
+        Ok(Self {
+            inner: Mutex::new(JournalReplayStoreInner { journal, responses }),
+        })
+    }
+
+    /// Synchronizes the kept responses to the disk, regardless of the fsync policy.
+    pub fn flush(&self) -> Result<()> {
+        self.lock()?.journal.flush()
+    }
+
+    /// Rewrites the journal with the latest responses only.
+    pub fn compact(&self) -> Result<()> {
+        self.lock()?.compact()
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, JournalReplayStoreInner>> {
+        self.inner
+            .lock()
+            .map_err(|_| anyhow!("the replay store has been poisoned"))
+    }
+}
+
+impl JournalReplayStoreInner {
+    fn compact(&mut self) -> Result<()> {
+        let entries: Vec<_> = self
+            .responses
+            .iter()
+            .map(|(query, response)| ReplayEntry {
+                query: *query,
+                response: response.clone(),
+            })
+            .collect();
+        self.journal.compact(&entries)
+    }
+}
+
+#[async_trait]
+impl ReplayStore for JournalReplayStore {
+    async fn put(&self, query: &Hash, response: &[u8]) -> Result<()> {
+        let mut inner = self.lock()?;
+        inner.journal.append(&ReplayEntry {
+            query: *query,
+            response: response.to_vec(),
+        })?;
+        inner.responses.insert(*query, response.to_vec());
+
+        if inner.journal.len() > 2 * inner.responses.len() + COMPACTION_SLACK {
+            inner.compact()?;
+        }
+        Ok(())
+    }
+
+    async fn get(&self, query: &Hash) -> Result<Option<Vec<u8>>> {
+        Ok(self.lock()?.responses.get(query).cloned())
+    }
+}
+
+/// Returns the hash of the query, by which its response is stored.
+pub fn query_hash<Q>(method: &str, guarantee: Option<&AccountRef>, query: &Q) -> Result<Hash>
+where
+    Q: Serialize<AllocSerializer<SCRATCH_SPACE>>,
+{
+    let guarantee = ::rkyv::to_bytes::<_, SCRATCH_SPACE>(&guarantee.copied())
+        .map_err(|error| anyhow!("failed to archive the guarantee: {error}"))?;
+    let query = ::rkyv::to_bytes::<_, SCRATCH_SPACE>(query)
+        .map_err(|error| anyhow!("failed to archive the query: {error}"))?;
+
+    let mut bytes = Vec::with_capacity(method.len() + 1 + guarantee.len() + query.len());
+    bytes.extend_from_slice(method.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(&guarantee);
+    bytes.extend_from_slice(&query);
+    Ok(Hash::with_bytes(&bytes))
+}
+
+/// A client keeping the server-signed responses, which are replayed when the server is unreachable.
+///
+/// Only the signed responses are kept, so that a replayed response is verified
+/// against the signatures of the server rather than trusting the store.
+/// A replayed response may be stale, as it is the latest one seen by this client.
+pub struct IpdisReplay<T, S> {
+    inner: T,
+    store: S,
+}
+
+impl<T, S> IpdisReplay<T, S> {
+    pub fn new(inner: T, store: S) -> Self {
+        Self { inner, store }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<T, S> IpdisReplay<T, S>
+where
+    T: Ipdis + Send + Sync,
+    S: ReplayStore + Send + Sync,
+{
+    /// Returns the verified response of the query, which has been stored before.
+    pub async fn replay<R>(&self, query: &Hash) -> Result<Option<GuarantorSigned<R>>>
+    where
+        GuarantorSigned<R>: Archive + Verifier,
+        <GuarantorSigned<R> as Archive>::Archived: for<'a> ::bytecheck::CheckBytes<DefaultValidator<'a>>
+            + Deserialize<GuarantorSigned<R>, Infallible>,
+    {
+        let bytes = match self.store.get(query).await? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        let mut aligned = AlignedVec::with_capacity(bytes.len());
+        aligned.extend_from_slice(&bytes);
+
+        let response: GuarantorSigned<R> =
+            ::rkyv::check_archived_root::<GuarantorSigned<R>>(&aligned)
+                .map_err(|error| anyhow!("malformed replayed response: {error}"))?
+                .deserialize(&mut Infallible)
+                .expect("infallible");
+
+        response.verify(None)?;
+        Ok(Some(response))
+    }
+
+    async fn keep<R>(&self, query: &Hash, response: &GuarantorSigned<R>) -> Result<()>
+    where
+        GuarantorSigned<R>: Serialize<AllocSerializer<SCRATCH_SPACE>>,
+    {
+        let bytes = ::rkyv::to_bytes::<_, SCRATCH_SPACE>(response)
+            .map_err(|error| anyhow!("failed to archive the response: {error}"))?;
+        self.store.put(query, &bytes).await
+    }
+
+    /// Returns the latest dynamic path, or the replayed one if the server is unreachable.
+    pub async fn get_dyn_path_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<()>,
+    ) -> Result<Option<GuarantorSigned<DynPath<Path>>>> {
+        let query = query_hash("get_dyn_path", guarantee, path)?;
+
+        match self.inner.get_dyn_path_unchecked(guarantee, path).await {
+            Ok(response) => {
+                if let Some(response) = &response {
+                    self.keep(&query, response).await?;
+                }
+                Ok(response)
+            }
+            Err(error) => match self.replay::<DynPath<Path>>(&query).await? {
+                Some(response) => {
+                    if &response.data.data.data.remove_path() != path {
+                        bail!("the replayed response does not match the query")
+                    }
+                    Ok(Some(response))
+                }
+                None => Err(error),
+            },
+        }
+    }
+
+    /// Returns the latest word, or the replayed one if the server is unreachable.
+    pub async fn get_word_latest_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        word: &WordKeyHash,
+    ) -> Result<Option<GuarantorSigned<WordHash>>> {
+        let query = query_hash("get_word_latest", guarantee, word)?;
+
+        match self.inner.get_word_latest_unchecked(guarantee, word).await {
+            Ok(response) => {
+                if let Some(response) = &response {
+                    self.keep(&query, response).await?;
+                }
+                Ok(response)
+            }
+            Err(error) => match self.replay::<WordHash>(&query).await? {
+                Some(response) => {
+                    if &response.data.data.data.key != word {
+                        bail!("the replayed response does not match the query")
+                    }
+                    Ok(Some(response))
+                }
+                None => Err(error),
+            },
+        }
+    }
+}
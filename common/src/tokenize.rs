@@ -0,0 +1,68 @@
+//! The tokenizers of the text-indexing helpers, chosen by the languages of the texts.
+//!
+//! The languages without separators between the words (e.g. CJK) need their own segmentation,
+//! which the other crates can provide by registering a tokenizer with `register_tokenizer`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use ipis::core::value::text::Text;
+
+use crate::lang::UNDETERMINED;
+
+/// Splits the message of a text into the tokens, e.g. the words.
+pub trait Tokenizer: Send + Sync {
+    fn tokenize(&self, msg: &str) -> Vec<String>;
+}
+
+/// Splits the message by the whitespaces, which fits most of the languages written in latin.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Whitespace;
+
+impl Tokenizer for Whitespace {
+    fn tokenize(&self, msg: &str) -> Vec<String> {
+        msg.split_whitespace().map(ToString::to_string).collect()
+    }
+}
+
+::ipis::lazy_static::lazy_static! {
+    /// the registered tokenizers by the language tags
+    static ref TOKENIZERS: RwLock<HashMap<String, Arc<dyn Tokenizer>>> = RwLock::new(
+        [("en", Whitespace), (UNDETERMINED, Whitespace)]
+            .into_iter()
+            .map(|(lang, tokenizer)| (lang.to_string(), Arc::new(tokenizer) as Arc<dyn Tokenizer>))
+            .collect(),
+    );
+}
+
+/// Registers the tokenizer of the language, replacing the previous one if any.
+///
+/// The language is either a primary subtag (e.g. "ja"), or a full tag (e.g. "zh-Hant")
+/// which takes precedence over its primary subtag.
+pub fn register_tokenizer(lang: &str, tokenizer: Box<dyn Tokenizer>) {
+    TOKENIZERS
+        .write()
+        .unwrap_or_else(|error| error.into_inner())
+        .insert(lang.to_lowercase(), tokenizer.into());
+}
+
+/// Returns the tokenizer of the language, falling back to the one of the undetermined language.
+pub fn tokenizer(lang: &str) -> Arc<dyn Tokenizer> {
+    let lang = lang.to_lowercase();
+    // the primary language subtag, e.g. "en" of "en-US"
+    let primary = lang.split(['-', '_']).next().unwrap_or_default();
+
+    let tokenizers = TOKENIZERS.read().unwrap_or_else(|error| error.into_inner());
+    [lang.as_str(), primary, UNDETERMINED]
+        .into_iter()
+        .find_map(|lang| tokenizers.get(lang))
+        .cloned()
+        .unwrap_or_else(|| Arc::new(Whitespace))
+}
+
+/// Splits the message of the text with the tokenizer of its language.
+pub fn tokenize(text: &Text) -> Vec<String> {
+    tokenizer(&text.lang.to_string()).tokenize(&text.msg)
+}
@@ -1,7 +1,90 @@
+use std::path::PathBuf;
+
+use clap::Parser;
 use ipdis_api::server::IpdisServer;
 use ipis::{env::Infer, tokio};
 
+/// InterPlanetary Dictionary Server
+#[derive(Parser)]
+#[clap(name = "ipdis-server", version, about)]
+struct Args {
+    /// the port to listen on
+    #[clap(long, env = "ipiis_server_port", default_value_t = 5001)]
+    port: u16,
+
+    #[clap(long, env = "DATABASE_URL")]
+    database_url: String,
+
+    /// the number of the database connections
+    #[clap(long, env = "ipdis_pool_size", default_value_t = 4)]
+    pool_size: u32,
+
+    /// the PEM-encoded certificate chain of the server
+    #[clap(long, env = "ipdis_tls_cert", requires = "tls_key")]
+    tls_cert: Option<PathBuf>,
+
+    /// the PEM-encoded private key of the server
+    #[clap(long, env = "ipdis_tls_key", requires = "tls_cert")]
+    tls_key: Option<PathBuf>,
+
+    /// the PEM-encoded CA certificates to require and verify the client certificates with
+    #[clap(long, env = "ipdis_tls_client_ca", requires = "tls_cert")]
+    tls_client_ca: Option<PathBuf>,
+
+    /// the accounts which are permitted to send requests; all accounts are permitted if empty
+    #[clap(long, env = "ipdis_allowed_accounts", use_value_delimiter = true)]
+    allowed_accounts: Vec<String>,
+
+    /// the accounts which are permitted to call the admin APIs
+    #[clap(long, env = "ipdis_admin_accounts", use_value_delimiter = true)]
+    admin_accounts: Vec<String>,
+
+    /// the other nodes of the cluster, whose announcements are discovered besides the admins'
+    #[clap(long, env = "ipdis_cluster_accounts", use_value_delimiter = true)]
+    cluster_accounts: Vec<String>,
+
+    /// the interval of deleting the expired records in seconds
+    #[clap(long, env = "ipdis_gc_interval_secs")]
+    gc_interval_secs: Option<u64>,
+}
+
+impl Args {
+    /// Exports the arguments as the environment variables, so that they can be inferred.
+    fn export(&self) {
+        fn set_var(key: &str, value: impl ToString) {
+            ::std::env::set_var(key, value.to_string())
+        }
+
+        set_var("ipiis_server_port", self.port);
+        set_var("DATABASE_URL", &self.database_url);
+        set_var("ipdis_pool_size", self.pool_size);
+        if let Some(path) = &self.tls_cert {
+            set_var("ipdis_tls_cert", path.display());
+        }
+        if let Some(path) = &self.tls_key {
+            set_var("ipdis_tls_key", path.display());
+        }
+        if let Some(path) = &self.tls_client_ca {
+            set_var("ipdis_tls_client_ca", path.display());
+        }
+        if !self.allowed_accounts.is_empty() {
+            set_var("ipdis_allowed_accounts", self.allowed_accounts.join(","));
+        }
+        set_var("ipdis_admin_accounts", self.admin_accounts.join(","));
+        set_var("ipdis_cluster_accounts", self.cluster_accounts.join(","));
+        if let Some(secs) = self.gc_interval_secs {
+            set_var("ipdis_gc_interval_secs", secs);
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
-    IpdisServer::infer().await.run().await
+    let _telemetry = ipdis_api::telemetry::init().expect("failed to initialize the telemetry");
+
+    Args::parse().export();
+
+    let server = IpdisServer::infer().await;
+    server.spawn_background_tasks();
+    server.run().await
 }